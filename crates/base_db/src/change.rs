@@ -13,8 +13,11 @@
 use std::fmt;
 use std::sync::Arc;
 
+use fxhash::FxHashMap;
 use vfs::FileId;
 
+use crate::encoding::decode_file_bytes;
+use crate::encoding::encode_file_text;
 use crate::input::AppStructure;
 use crate::SourceDatabaseExt;
 use crate::SourceRoot;
@@ -25,6 +28,9 @@ use crate::SourceRootId;
 pub struct Change {
     pub roots: Option<Vec<SourceRoot>>,
     pub files_changed: Vec<(FileId, Option<Arc<String>>)>,
+    /// Raw contents for the entries of `files_changed` that were
+    /// set with `change_file_bytes`.
+    pub file_bytes: FxHashMap<FileId, Arc<Vec<u8>>>,
     pub app_structure: Option<AppStructure>,
 }
 
@@ -54,9 +60,23 @@ impl Change {
     }
 
     pub fn change_file(&mut self, file_id: FileId, new_text: Option<Arc<String>>) {
+        self.file_bytes.remove(&file_id);
         self.files_changed.push((file_id, new_text))
     }
 
+    /// Change a file from its raw contents, decoding them according
+    /// to the `coding:` directive of the file.
+    pub fn change_file_bytes(&mut self, file_id: FileId, new_bytes: Option<Arc<Vec<u8>>>) {
+        let new_text = new_bytes
+            .as_ref()
+            .map(|bytes| Arc::new(decode_file_bytes(bytes)));
+        self.files_changed.push((file_id, new_text));
+        match new_bytes {
+            Some(bytes) => self.file_bytes.insert(file_id, bytes),
+            None => self.file_bytes.remove(&file_id),
+        };
+    }
+
     pub fn set_app_structure(&mut self, a: AppStructure) {
         self.app_structure = Some(a);
     }
@@ -81,6 +101,11 @@ impl Change {
         for (file_id, text) in self.files_changed {
             // XXX: can't actually remove the file, just reset the text
             let text = text.unwrap_or_default();
+            let bytes = match self.file_bytes.get(&file_id) {
+                Some(bytes) => bytes.clone(),
+                None => Arc::new(encode_file_text(&text).into_owned()),
+            };
+            db.set_file_bytes(file_id, bytes);
             db.set_file_text(file_id, text);
             res.push(file_id);
         }
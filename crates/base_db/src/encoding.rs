@@ -0,0 +1,134 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Source file encodings, following the rules of `epp:read_encoding/1`:
+//! a `coding: <encoding>` comment in the first two lines of the file
+//! selects the encoding, and the default is utf8.
+
+use std::borrow::Cow;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileEncoding {
+    Utf8,
+    Latin1,
+}
+
+/// Find the encoding declared by a `coding:` directive, if any.
+pub fn declared_encoding(text: &[u8]) -> Option<FileEncoding> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"^\s*%.*?coding\s*[:=]\s*([-a-zA-Z0-9_]+)").unwrap();
+    }
+    let encoding = text.split(|byte| *byte == b'\n').take(2).find_map(|line| {
+        RE.captures(&String::from_utf8_lossy(line))
+            .map(|c| c[1].to_string())
+    })?;
+    match encoding.to_lowercase().as_str() {
+        "latin1" | "latin-1" | "iso-8859-1" => Some(FileEncoding::Latin1),
+        "utf8" | "utf-8" => Some(FileEncoding::Utf8),
+        _ => None,
+    }
+}
+
+/// Decode the raw contents of a source file into text.
+///
+/// Files without a directive that are not valid utf8 are still decoded
+/// as latin1, this mostly affects old code from OTP or yaws.
+pub fn decode_file_bytes(bytes: &[u8]) -> String {
+    match declared_encoding(bytes) {
+        Some(FileEncoding::Latin1) => decode_latin1(bytes),
+        _ => match std::str::from_utf8(bytes) {
+            Ok(text) => text.to_string(),
+            Err(_) => decode_latin1(bytes),
+        },
+    }
+}
+
+/// The inverse of `decode_file_bytes`, so that text edited in memory
+/// keeps the encoding it declares.
+pub fn encode_file_text(text: &str) -> Cow<[u8]> {
+    match declared_encoding(text.as_bytes()) {
+        Some(FileEncoding::Latin1) if !text.is_ascii() => Cow::Owned(
+            text.chars()
+                .map(|ch| u8::try_from(u32::from(ch)).unwrap_or(b'?'))
+                .collect(),
+        ),
+        _ => Cow::Borrowed(text.as_bytes()),
+    }
+}
+
+fn decode_latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| *byte as char).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utf8_by_default() {
+        let bytes = "-module(main).\nfoo() -> \"é\".\n".as_bytes();
+        assert_eq!(None, declared_encoding(bytes));
+        assert_eq!(
+            "-module(main).\nfoo() -> \"é\".\n",
+            decode_file_bytes(bytes)
+        );
+    }
+
+    #[test]
+    fn latin1_directive() {
+        let bytes = b"%% -*- coding: latin-1 -*-\n-module(main).\nfoo() -> \"\xe9\".\n";
+        assert_eq!(Some(FileEncoding::Latin1), declared_encoding(bytes));
+        let text = decode_file_bytes(bytes);
+        assert_eq!(
+            "%% -*- coding: latin-1 -*-\n-module(main).\nfoo() -> \"é\".\n",
+            text
+        );
+        assert_eq!(&bytes[..], &*encode_file_text(&text));
+    }
+
+    #[test]
+    fn latin1_directive_is_respected_for_valid_utf8() {
+        // "é" encoded as utf8 is two valid latin1 characters
+        let bytes = "%% coding: latin1\nfoo() -> \"é\".\n".as_bytes();
+        assert_eq!(
+            "%% coding: latin1\nfoo() -> \"\u{c3}\u{a9}\".\n",
+            decode_file_bytes(bytes)
+        );
+    }
+
+    #[test]
+    fn directive_only_in_first_two_lines() {
+        let bytes = b"-module(main).\n\n%% coding: latin1\nfoo() -> \"\xe9\".\n";
+        assert_eq!(None, declared_encoding(bytes));
+        // Still decoded as latin1, as it is not valid utf8
+        assert_eq!(
+            "-module(main).\n\n%% coding: latin1\nfoo() -> \"é\".\n",
+            decode_file_bytes(bytes)
+        );
+    }
+
+    #[test]
+    fn directive_only_in_comments() {
+        let bytes = "-define(ENC, \"coding: latin1\").\nfoo() -> \"é\".\n".as_bytes();
+        assert_eq!(None, declared_encoding(bytes));
+        assert_eq!(
+            "-define(ENC, \"coding: latin1\").\nfoo() -> \"é\".\n",
+            decode_file_bytes(bytes)
+        );
+    }
+
+    #[test]
+    fn directive_after_shebang() {
+        let bytes = b"#!/usr/bin/env escript\n%% coding: latin1\nmain(_) -> \"\xe9\".\n";
+        assert_eq!(Some(FileEncoding::Latin1), declared_encoding(bytes));
+    }
+}
@@ -804,12 +804,15 @@ bar() -> ?FOO.
                                 extra_src_dirs: [],
                                 macros: [],
                                 parse_transforms: [],
+                                compile_options: [],
                                 app_type: App,
                                 ebin_path: Some(
                                     AbsPathBuf(
                                         "/ebin",
                                     ),
                                 ),
+                                escript_modules: false,
+                                version: None,
                             },
                         ),
                         SourceRootId(
@@ -844,12 +847,17 @@ bar() -> ?FOO.
                                 extra_src_dirs: [],
                                 macros: [],
                                 parse_transforms: [],
+                                compile_options: [],
                                 app_type: Otp,
                                 ebin_path: Some(
                                     AbsPathBuf(
                                         "/opt/lib/comp-1.3/ebin",
                                     ),
                                 ),
+                                escript_modules: false,
+                                version: Some(
+                                    "1.3",
+                                ),
                             },
                         ),
                         SourceRootId(
@@ -877,12 +885,15 @@ bar() -> ?FOO.
                                 extra_src_dirs: [],
                                 macros: [],
                                 parse_transforms: [],
+                                compile_options: [],
                                 app_type: App,
                                 ebin_path: Some(
                                     AbsPathBuf(
                                         "/ebin",
                                     ),
                                 ),
+                                escript_modules: false,
+                                version: None,
                             },
                         ),
                         SourceRootId(
@@ -911,6 +922,7 @@ bar() -> ?FOO.
                                     1,
                                 ),
                             ),
+                            sibling_project_ids: [],
                             app_roots: AppRoots {
                                 otp: Some(
                                     AppRoots {
@@ -940,6 +952,12 @@ bar() -> ?FOO.
                             eqwalizer_config: EqwalizerConfig {
                                 enable_all: false,
                             },
+                            generated_config: GeneratedConfig {
+                                markers: [
+                                    "@generated",
+                                ],
+                                scan_bytes: 2001,
+                            },
                         },
                         ProjectId(
                             1,
@@ -959,6 +977,7 @@ bar() -> ?FOO.
                                     1,
                                 ),
                             ),
+                            sibling_project_ids: [],
                             app_roots: AppRoots {
                                 otp: None,
                                 app_map: {
@@ -972,6 +991,12 @@ bar() -> ?FOO.
                             eqwalizer_config: EqwalizerConfig {
                                 enable_all: false,
                             },
+                            generated_config: GeneratedConfig {
+                                markers: [
+                                    "@generated",
+                                ],
+                                scan_bytes: 2001,
+                            },
                         },
                     },
                 },
@@ -1040,12 +1065,15 @@ foo() -> ?BAR.
                                 extra_src_dirs: [],
                                 macros: [],
                                 parse_transforms: [],
+                                compile_options: [],
                                 app_type: App,
                                 ebin_path: Some(
                                     AbsPathBuf(
                                         "/extra/ebin",
                                     ),
                                 ),
+                                escript_modules: false,
+                                version: None,
                             },
                         ),
                         SourceRootId(
@@ -1071,6 +1099,7 @@ foo() -> ?BAR.
                                     1,
                                 ),
                             ),
+                            sibling_project_ids: [],
                             app_roots: AppRoots {
                                 otp: None,
                                 app_map: {
@@ -1084,6 +1113,12 @@ foo() -> ?BAR.
                             eqwalizer_config: EqwalizerConfig {
                                 enable_all: false,
                             },
+                            generated_config: GeneratedConfig {
+                                markers: [
+                                    "@generated",
+                                ],
+                                scan_bytes: 2001,
+                            },
                         },
                         ProjectId(
                             1,
@@ -1099,6 +1134,7 @@ foo() -> ?BAR.
                                     1,
                                 ),
                             ),
+                            sibling_project_ids: [],
                             app_roots: AppRoots {
                                 otp: None,
                                 app_map: {},
@@ -1106,6 +1142,12 @@ foo() -> ?BAR.
                             eqwalizer_config: EqwalizerConfig {
                                 enable_all: false,
                             },
+                            generated_config: GeneratedConfig {
+                                markers: [
+                                    "@generated",
+                                ],
+                                scan_bytes: 2001,
+                            },
                         },
                     },
                 },
@@ -13,6 +13,7 @@ use std::path::Path;
 use std::sync::Arc;
 
 use elp_project_model::buck::EqwalizerConfig;
+use elp_project_model::buck::GeneratedConfig;
 use elp_project_model::AppName;
 use elp_project_model::AppType;
 use elp_project_model::Project;
@@ -106,8 +107,13 @@ pub struct ProjectData {
     pub deps_ebins: Vec<AbsPathBuf>,
     pub build_info_path: Option<AbsPathBuf>,
     pub otp_project_id: Option<ProjectId>,
+    /// Other projects of the same workspace, searched in order for modules
+    /// that are not defined in this project
+    pub sibling_project_ids: Vec<ProjectId>,
     pub app_roots: AppRoots,
     pub eqwalizer_config: EqwalizerConfig,
+    /// How generated files are recognised, see `SourceDatabase::is_generated`
+    pub generated_config: GeneratedConfig,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -120,8 +126,24 @@ pub struct AppData {
     pub extra_src_dirs: Vec<String>,
     pub macros: Vec<eetf::Term>,
     pub parse_transforms: Vec<eetf::Term>,
+    /// See `ProjectAppData::compile_options`
+    pub compile_options: Vec<eetf::Term>,
     pub app_type: AppType,
     pub ebin_path: Option<AbsPathBuf>,
+    /// Whether `.escript` files are indexed as modules, see
+    /// `ProjectAppData::escript_modules`
+    pub escript_modules: bool,
+    /// See `ProjectAppData::version`
+    pub version: Option<String>,
+}
+
+/// An OTP application available to a project
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OtpApp {
+    pub name: AppName,
+    /// The `include` directory of the application, if it has any header,
+    /// as used by `-include_lib("app/include/...")`
+    pub include_dir: Option<AbsPathBuf>,
 }
 
 impl AppData {
@@ -237,6 +259,9 @@ pub struct ProjectApps<'a> {
     /// Sometimes we don't have an OTP project because we are explicitly
     /// opting out of using it, e.g. for eqWAlizer compatibility
     pub otp_project_id: Option<ProjectId>,
+    /// Whether modules can be resolved across projects, e.g. for a
+    /// monorepo with several build projects
+    pub search_sibling_projects: bool,
     // We store the original projects to we can make the AppStructure later
     projects: Vec<Project>,
 }
@@ -276,10 +301,18 @@ impl<'a> ProjectApps<'a> {
         ProjectApps {
             all_apps,
             otp_project_id,
+            search_sibling_projects: false,
             projects,
         }
     }
 
+    /// Resolve modules not found in a project in the other, non-OTP,
+    /// projects, in the order they were given.
+    pub fn with_sibling_projects(mut self) -> ProjectApps<'a> {
+        self.search_sibling_projects = true;
+        self
+    }
+
     pub fn app_structure(&self) -> AppStructure {
         let mut app_structure = AppStructure::default();
         let mut app_idx = 0;
@@ -319,9 +352,12 @@ impl<'a> ProjectApps<'a> {
                     extra_src_dirs: app.extra_src_dirs.clone(),
                     macros: app.macros.clone(),
                     parse_transforms: app.parse_transforms.clone(),
+                    compile_options: app.compile_options.clone(),
                     app_type: app.app_type,
                     src_path: app.abs_src_dirs.clone(),
                     ebin_path: app.ebin.clone(),
+                    escript_modules: app.escript_modules,
+                    version: app.version.clone(),
                 };
                 app_structure.add_app_data(root_id, Some(input_data));
             }
@@ -332,14 +368,26 @@ impl<'a> ProjectApps<'a> {
                 app_roots.set_otp(otp_root.clone());
             }
 
+            let sibling_project_ids =
+                if self.search_sibling_projects && self.otp_project_id != Some(project_id) {
+                    (0..self.projects.len() as u32)
+                        .map(ProjectId)
+                        .filter(|id| *id != project_id && Some(*id) != self.otp_project_id)
+                        .collect()
+                } else {
+                    vec![]
+                };
+
             let project_data = ProjectData {
                 source_roots: project_source_roots,
                 root_dir: project.root().into_owned(),
                 deps_ebins: project.deps_ebins(),
                 build_info_path: project.build_info_file(),
                 otp_project_id: self.otp_project_id,
+                sibling_project_ids,
                 app_roots,
                 eqwalizer_config: project.eqwalizer_config(),
+                generated_config: project.generated_config(),
             };
             app_structure.add_project_data(project_id, project_data);
         }
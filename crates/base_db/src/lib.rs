@@ -9,15 +9,21 @@
 
 use std::sync::Arc;
 
+use elp_project_model::is_escript_shebang;
 use elp_project_model::AppName;
 use elp_syntax::ast::SourceFile;
+use elp_syntax::unescape;
 use elp_syntax::Parse;
+use elp_syntax::SmolStr;
 use elp_syntax::TextRange;
 use elp_syntax::TextSize;
 
 mod change;
+mod encoding;
 mod input;
 mod module_index;
+#[cfg(test)]
+mod test_db;
 
 // ---------------------------------------------------------------------
 // Public API
@@ -27,11 +33,14 @@ pub mod test_fixture;
 pub mod test_utils;
 pub use change::Change;
 pub use elp_project_model::AppType;
+pub use encoding::decode_file_bytes;
+pub use encoding::encode_file_text;
 pub use input::AppData;
 pub use input::AppRoots;
 pub use input::AppStructure;
 pub use input::FileSource;
 pub use input::IncludeOtp;
+pub use input::OtpApp;
 pub use input::ProjectApps;
 pub use input::ProjectData;
 pub use input::ProjectId;
@@ -118,34 +127,98 @@ pub trait SourceDatabase: FileLoader + salsa::Database {
     fn include_files_revision(&self) -> u64;
 
     /// Returns a map from module name to FileId of the containing file.
+    /// Modules of `project_id` itself take precedence over those of its
+    /// sibling projects, which take precedence over OTP.
     fn module_index(&self, project_id: ProjectId) -> Arc<ModuleIndex>;
 
+    /// Like `module_index`, but ignoring the sibling projects of
+    /// `project_id`.
+    fn project_module_index(&self, project_id: ProjectId) -> Arc<ModuleIndex>;
+
+    /// The OTP applications available to `project_id`, sorted by name.
+    /// Empty if OTP is not included.
+    fn otp_apps(&self, project_id: ProjectId) -> Arc<Vec<OtpApp>>;
+
+    /// The project listing `source_root_id` among its source roots, or
+    /// `None` for the out-of-project root. A root shared between projects
+    /// resolves to the project recorded in its `AppData`.
+    fn source_root_project(&self, source_root_id: SourceRootId) -> Option<ProjectId>;
+
     /// Parse the file_id to AST
     fn parse(&self, file_id: FileId) -> Parse<SourceFile>;
 
+    /// Whether one of the generated markers of the project of `file_id`
+    /// occurs at the start of the file, `@generated` by default.
     fn is_generated(&self, file_id: FileId) -> bool;
 
+    /// Whether `file_id` is in one of the extra source directories of its
+    /// application, such as `test`. Only depends on the source root of the
+    /// file and on the application data, so edits to the text of any file
+    /// reuse the memoized result.
     fn is_test_suite_or_test_helper(&self, file_id: FileId) -> Option<bool>;
 
     fn file_app_type(&self, file_id: FileId) -> Option<AppType>;
 
     fn file_app_name(&self, file_id: FileId) -> Option<AppName>;
+
+    /// The version of the application of `file_id`, if known.
+    fn file_app_version(&self, file_id: FileId) -> Option<SmolStr>;
+
+    /// Whether two files belong to the same application. A header belongs
+    /// to the application it is defined in, not to the ones including it.
+    /// Files without an application only belong with themselves.
+    fn same_app(&self, file_a: FileId, file_b: FileId) -> bool;
 }
 
 fn module_index(db: &dyn SourceDatabase, project_id: ProjectId) -> Arc<ModuleIndex> {
+    let project_data = db.project_data(project_id);
+    if project_data.sibling_project_ids.is_empty() {
+        return db.project_module_index(project_id);
+    }
+    let mut builder = module_index_builder(db, project_id);
+    for &sibling_id in &project_data.sibling_project_ids {
+        if sibling_id != project_id {
+            builder.add_sibling(db.project_module_index(sibling_id));
+        }
+    }
+    builder.build()
+}
+
+fn project_module_index(db: &dyn SourceDatabase, project_id: ProjectId) -> Arc<ModuleIndex> {
+    module_index_builder(db, project_id).build()
+}
+
+fn module_index_builder(db: &dyn SourceDatabase, project_id: ProjectId) -> module_index::Builder {
     let mut builder = ModuleIndex::builder();
 
     let project_data = db.project_data(project_id);
+    let mut escripts = Vec::new();
     for &source_root_id in &project_data.source_roots {
         if let Some(app_data) = db.app_data(source_root_id) {
             let source_root = db.source_root(source_root_id);
             for (file_id, file_source, path) in source_root.iter_app_files(&app_data) {
-                if let Some((name, Some("erl"))) = path.name_and_extension() {
-                    builder.insert(file_id, file_source, ModuleName::new(name));
+                match path.name_and_extension() {
+                    Some((name, Some("erl"))) => {
+                        builder.insert(file_id, file_source, ModuleName::new(name))
+                    }
+                    Some((name, Some("escript"))) if app_data.escript_modules => {
+                        escripts.push((file_id, file_source, ModuleName::new(name)))
+                    }
+                    Some((name, None))
+                        if app_data.escript_modules
+                            && is_escript_shebang(db.file_text(file_id).as_bytes()) =>
+                    {
+                        escripts.push((file_id, file_source, ModuleName::new(name)))
+                    }
+                    _ => {}
                 }
             }
         }
     }
+    // Added last, so that an `.erl` module of the same name wins
+    for (file_id, file_source, name) in escripts {
+        builder.insert_if_absent(file_id, file_source, name);
+    }
 
     project_data
         .otp_project_id
@@ -158,7 +231,52 @@ fn module_index(db: &dyn SourceDatabase, project_id: ProjectId) -> Arc<ModuleInd
             }
         });
 
-    builder.build()
+    builder
+}
+
+fn otp_apps(db: &dyn SourceDatabase, project_id: ProjectId) -> Arc<Vec<OtpApp>> {
+    let otp_project_id = match db.project_data(project_id).otp_project_id {
+        Some(otp_project_id) => otp_project_id,
+        None => return Arc::default(),
+    };
+    let mut apps: Vec<OtpApp> = db
+        .project_data(otp_project_id)
+        .source_roots
+        .iter()
+        .filter_map(|&source_root_id| {
+            let app_data = db.app_data(source_root_id)?;
+            let source_root = db.source_root(source_root_id);
+            let include_dir = app_data.dir.join("include");
+            let has_headers = source_root.iter().any(|file_id| {
+                source_root
+                    .path_for_file(&file_id)
+                    .and_then(|path| path.as_path())
+                    .map_or(false, |path| path.as_ref().starts_with(&include_dir))
+            });
+            Some(OtpApp {
+                name: app_data.name.clone(),
+                include_dir: has_headers.then_some(include_dir),
+            })
+        })
+        .collect();
+    apps.sort_by(|a, b| a.name.0.cmp(&b.name.0));
+    Arc::new(apps)
+}
+
+fn source_root_project(db: &dyn SourceDatabase, source_root_id: SourceRootId) -> Option<ProjectId> {
+    let project_id = db.app_data(source_root_id)?.project_id;
+    let project_data = db.project_data(project_id);
+    if project_data.source_roots.contains(&source_root_id) {
+        return Some(project_id);
+    }
+    // Otherwise the root can only be listed by a project this one refers
+    // to, so there is no need to scan them all
+    project_data
+        .otp_project_id
+        .into_iter()
+        .chain(project_data.sibling_project_ids.iter().copied())
+        .filter(|&id| db.project_data(id).source_roots.contains(&source_root_id))
+        .min()
 }
 
 fn parse(db: &dyn SourceDatabase, file_id: FileId) -> Parse<SourceFile> {
@@ -168,7 +286,23 @@ fn parse(db: &dyn SourceDatabase, file_id: FileId) -> Parse<SourceFile> {
 
 fn is_generated(db: &dyn SourceDatabase, file_id: FileId) -> bool {
     let contents = db.file_text(file_id);
-    contents[0..(2001.min(contents.len()))].contains(&format!("{}generated", "@"))
+    let config = db
+        .app_data(db.file_source_root(file_id))
+        .map(|app_data| {
+            db.project_data(app_data.project_id)
+                .generated_config
+                .clone()
+        })
+        .unwrap_or_default();
+    let mut end = config.scan_bytes.min(contents.len());
+    while !contents.is_char_boundary(end) {
+        end -= 1;
+    }
+    let scanned = &contents[..end];
+    config
+        .markers
+        .iter()
+        .any(|marker| scanned.contains(marker.as_str()))
 }
 
 fn is_test_suite_or_test_helper(db: &dyn SourceDatabase, file_id: FileId) -> Option<bool> {
@@ -193,12 +327,36 @@ fn file_app_name(db: &dyn SourceDatabase, file_id: FileId) -> Option<AppName> {
     Some(app_data.name.clone())
 }
 
+fn file_app_version(db: &dyn SourceDatabase, file_id: FileId) -> Option<SmolStr> {
+    let app_data = db.app_data(db.file_source_root(file_id))?;
+    app_data.version.as_deref().map(SmolStr::new)
+}
+
+fn same_app(db: &dyn SourceDatabase, file_a: FileId, file_b: FileId) -> bool {
+    if file_a == file_b {
+        return true;
+    }
+    let app_a = db.app_data(db.file_source_root(file_a));
+    let app_b = db.app_data(db.file_source_root(file_b));
+    match (app_a, app_b) {
+        (Some(app_a), Some(app_b)) => {
+            app_a.project_id == app_b.project_id && app_a.name == app_b.name
+        }
+        _ => false,
+    }
+}
+
 /// We don't want to give HIR knowledge of source roots, hence we extract these
 /// methods into a separate DB.
 #[salsa::query_group(SourceDatabaseExtStorage)]
 pub trait SourceDatabaseExt: SourceDatabase {
     #[salsa::input]
     fn file_text(&self, file_id: FileId) -> Arc<String>;
+
+    /// Raw contents of the file, `file_text` is decoded from these
+    /// according to the `coding:` directive of the file.
+    #[salsa::input]
+    fn file_bytes(&self, file_id: FileId) -> Arc<Vec<u8>>;
 }
 
 /// Silly workaround for cyclic deps between the traits
@@ -211,7 +369,7 @@ impl<T: SourceDatabaseExt> FileLoader for FileLoaderDelegate<&'_ T> {
 }
 
 /// If the `input` string represents an atom, and needs quoting, quote
-/// it.
+/// it, escaping any quote or backslash.
 pub fn to_quoted_string(input: &str) -> String {
     fn is_valid_atom(input: &str) -> bool {
         let mut chars = input.chars();
@@ -221,6 +379,322 @@ pub fn to_quoted_string(input: &str) -> String {
     if is_valid_atom(input) {
         input.to_string()
     } else {
-        format!("'{}'", &input)
+        format!("'{}'", input.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+/// The inverse of `to_quoted_string`: if `input` is a quoted atom, strip
+/// the quotes and unescape it, otherwise return it as is.
+pub fn from_quoted_string(input: &str) -> String {
+    let is_quoted = input.len() >= 2 && input.starts_with('\'') && input.ends_with('\'');
+    match unescape::unescape_string(input) {
+        Some(unescaped) if is_quoted => unescaped.into_owned(),
+        _ => input.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_project_model::otp::Otp;
+    use elp_project_model::rebar::RebarProject;
+    use elp_project_model::Project;
+    use elp_project_model::ProjectAppData;
+    use elp_project_model::ProjectBuildData;
+
+    use super::*;
+    use crate::fixture::WithFixture;
+    use crate::test_db::TestDB;
+
+    /// A database with a project for each of `projects`, given as the name
+    /// of its single application, rooted at `/<name>`, and its modules.
+    fn with_projects(
+        projects: &[(&str, &[&str])],
+        sibling_projects: bool,
+    ) -> (TestDB, Vec<FileId>) {
+        let otp = Otp {
+            lib_dir: AbsPathBuf::assert("/otp".into()),
+            apps: Default::default(),
+        };
+        let mut change = Change::new();
+        let mut roots = Vec::new();
+        let mut files = Vec::new();
+        let mut all_projects = Vec::new();
+        for (name, modules) in projects {
+            let dir = AbsPathBuf::assert(format!("/{name}").into());
+            let mut file_set = FileSet::default();
+            for module in *modules {
+                let file_id = FileId(files.len() as u32);
+                file_set.insert(
+                    file_id,
+                    VfsPath::from(dir.join(format!("src/{module}.erl"))),
+                );
+                change.change_file(file_id, Some(Arc::new(format!("-module({module}).\n"))));
+                files.push(file_id);
+            }
+            roots.push(SourceRoot::new(file_set));
+            let app = ProjectAppData::fixture_app_data(
+                AppName(name.to_string()),
+                dir.clone(),
+                vec![],
+                vec![dir.join("src")],
+                vec![],
+            );
+            let rebar_project =
+                RebarProject::new(vec![app], vec![], dir, Default::default(), &otp.lib_dir);
+            let mut project = Project::empty(otp.clone());
+            project.project_build_data = ProjectBuildData::Rebar(rebar_project);
+            all_projects.push(project);
+        }
+        let mut project_apps = ProjectApps::new(&all_projects, IncludeOtp::No);
+        if sibling_projects {
+            project_apps = project_apps.with_sibling_projects();
+        }
+        change.set_app_structure(project_apps.app_structure());
+        change.set_roots(roots);
+        let mut db = TestDB::default();
+        change.apply(&mut db);
+        (db, files)
+    }
+
+    #[test]
+    fn module_index_with_sibling_projects() {
+        let projects: &[(&str, &[&str])] =
+            &[("a", &["a_mod", "shared"]), ("b", &["b_mod", "shared"])];
+        let (db, files) = with_projects(projects, true);
+
+        let index = db.module_index(ProjectId(0));
+        assert_eq!(index.file_for_module("a_mod"), Some(files[0]));
+        assert_eq!(index.file_for_module("shared"), Some(files[1]));
+        assert_eq!(index.file_for_module("b_mod"), Some(files[2]));
+
+        let index = db.module_index(ProjectId(1));
+        assert_eq!(index.file_for_module("shared"), Some(files[3]));
+        assert_eq!(index.file_for_module("a_mod"), Some(files[0]));
+        assert_eq!(
+            index.module_for_file(files[0]).map(|name| name.as_str()),
+            Some("a_mod")
+        );
+        let mut all = index.all_modules();
+        all.sort();
+        assert_eq!(
+            all.iter().map(|name| name.as_str()).collect::<Vec<_>>(),
+            vec!["a_mod", "b_mod", "shared"]
+        );
+    }
+
+    #[test]
+    fn module_index_without_sibling_projects() {
+        let projects: &[(&str, &[&str])] = &[("a", &["a_mod"]), ("b", &["b_mod"])];
+        let (db, files) = with_projects(projects, false);
+
+        let index = db.module_index(ProjectId(0));
+        assert_eq!(index.file_for_module("a_mod"), Some(files[0]));
+        assert_eq!(index.file_for_module("b_mod"), None);
+        assert_eq!(index.all_modules().len(), 1);
+    }
+
+    #[test]
+    fn lib_header_same_app() {
+        let (db, files) = TestDB::with_many_files(
+            r#"
+//- /main/src/module.erl app:main
+-include_lib("another/include/header.hrl").
+//- /main/src/other.erl app:main
+//- /another-app/include/header.hrl app:another
+"#,
+        );
+        let (module, other, header) = (files[0], files[1], files[2]);
+        assert!(db.same_app(module, other));
+        assert!(db.same_app(header, header));
+        assert!(!db.same_app(module, header));
+        assert!(!db.same_app(header, other));
+    }
+
+    #[test]
+    fn otp_apps() {
+        let (db, files) = TestDB::with_many_files(
+            r#"
+//- /src/module.erl
+-module(module).
+//- /opt/lib/kernel-8.3/include/file.hrl otp_app:/opt/lib/kernel-8.3
+//- /opt/lib/stdlib-3.17/src/lists.erl otp_app:/opt/lib/stdlib-3.17
+-module(lists).
+"#,
+        );
+        let project_id = db
+            .app_data(db.file_source_root(files[0]))
+            .unwrap()
+            .project_id;
+        let apps: Vec<_> = db
+            .otp_apps(project_id)
+            .iter()
+            .map(|app| {
+                let include_dir = app
+                    .include_dir
+                    .as_ref()
+                    .map(|dir| dir.display().to_string());
+                (app.name.0.clone(), include_dir)
+            })
+            .collect();
+        assert_eq!(
+            apps,
+            vec![
+                (
+                    "kernel".to_string(),
+                    Some("/opt/lib/kernel-8.3/include".to_string())
+                ),
+                ("stdlib".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn otp_apps_without_otp() {
+        let projects: &[(&str, &[&str])] = &[("a", &["a_mod"])];
+        let (db, _files) = with_projects(projects, false);
+        assert!(db.otp_apps(ProjectId(0)).is_empty());
+    }
+
+    #[test]
+    fn quoted_string_round_trip() {
+        let fragments = [
+            "", "a", "foo", "Foo", "_", "@", "1", " ", "foo bar", "é", "日本", "'", "\\", "\\'",
+            "\n", "-",
+        ];
+        for first in fragments {
+            for second in fragments {
+                let atom = format!("{first}{second}");
+                let quoted = to_quoted_string(&atom);
+                assert_eq!(from_quoted_string(&quoted), atom, "quoted as {quoted}");
+            }
+        }
+    }
+
+    #[test]
+    fn quoted_string_escapes() {
+        assert_eq!(to_quoted_string("it's"), "'it\\'s'");
+        assert_eq!(to_quoted_string("a\\b"), "'a\\\\b'");
+        assert_eq!(from_quoted_string("foo"), "foo");
+        assert_eq!(from_quoted_string("'"), "'");
+        assert_eq!(from_quoted_string("'foo bar'"), "foo bar");
+        assert_eq!(from_quoted_string("'\\101\\x42\\x{43}\\s'"), "ABC ");
+        assert_eq!(from_quoted_string("'\\1012'"), "A2");
+        assert_eq!(from_quoted_string("'\\^a\\^Z'"), "\x01\x1a");
+    }
+
+    #[test]
+    fn change_file_bytes() {
+        let latin1 = b"%% coding: latin1\n-module(a).\nfoo() -> \"\xe9\".\n";
+        let mut change = Change::new();
+        change.change_file_bytes(FileId(0), Some(Arc::new(latin1.to_vec())));
+        change.change_file(FileId(1), Some(Arc::new("-module(b).\n".to_string())));
+        let mut db = TestDB::default();
+        change.apply(&mut db);
+
+        assert_eq!(
+            db.file_text(FileId(0)).as_str(),
+            "%% coding: latin1\n-module(a).\nfoo() -> \"é\".\n"
+        );
+        assert_eq!(db.file_bytes(FileId(0)).as_slice(), &latin1[..]);
+        assert_eq!(db.file_bytes(FileId(1)).as_slice(), b"-module(b).\n");
+    }
+
+    #[test]
+    fn is_generated_with_project_markers() {
+        let (mut db, file_id) = TestDB::with_single_file(
+            r#"
+%% @autogenerated by protoc
+-module(test).
+"#,
+        );
+        assert!(!db.is_generated(file_id));
+
+        let project_id = db
+            .app_data(db.file_source_root(file_id))
+            .unwrap()
+            .project_id;
+        let mut project_data = (*db.project_data(project_id)).clone();
+        project_data.generated_config.markers = vec!["@autogenerated".to_string()];
+        db.set_project_data(project_id, Arc::new(project_data.clone()));
+        assert!(db.is_generated(file_id));
+
+        project_data.generated_config.scan_bytes = 5;
+        db.set_project_data(project_id, Arc::new(project_data));
+        assert!(!db.is_generated(file_id));
+    }
+
+    #[test]
+    fn module_index_with_escripts() {
+        let (mut db, files) = TestDB::with_many_files(
+            r#"
+//- /src/tool.erl
+-module(tool).
+//- /src/tool.escript
+main(_) -> ok.
+//- /src/runner.escript
+main(_) -> ok.
+//- /src/deploy
+#!/usr/bin/env escript
+main(_) -> ok.
+//- /src/notes
+Not an escript.
+"#,
+        );
+        let source_root_id = db.file_source_root(files[0]);
+        let mut app_data = (*db.app_data(source_root_id).unwrap()).clone();
+        let project_id = app_data.project_id;
+        assert_eq!(db.module_index(project_id).file_for_module("runner"), None);
+
+        app_data.escript_modules = true;
+        db.set_app_data(source_root_id, Some(Arc::new(app_data)));
+        let module_index = db.module_index(project_id);
+        assert_eq!(module_index.file_for_module("tool"), Some(files[0]));
+        assert_eq!(module_index.file_for_module("runner"), Some(files[2]));
+        assert_eq!(module_index.file_for_module("deploy"), Some(files[3]));
+        assert_eq!(module_index.file_for_module("notes"), None);
+    }
+
+    #[test]
+    fn file_app_version() {
+        let (db, files) = TestDB::with_many_files(
+            r#"
+//- /versioned/src/versioned.erl app:versioned vsn:1.2.0
+-module(versioned).
+//- /unversioned/src/unversioned.erl app:unversioned
+-module(unversioned).
+//- /opt/lib/stdlib-3.17/src/lists.erl otp_app:/opt/lib/stdlib-3.17
+-module(lists).
+"#,
+        );
+        assert_eq!(db.file_app_version(files[0]).as_deref(), Some("1.2.0"));
+        assert_eq!(db.file_app_version(files[1]), None);
+        assert_eq!(db.file_app_version(files[2]).as_deref(), Some("3.17"));
+    }
+
+    #[test]
+    fn source_root_project() {
+        let (mut db, files) = TestDB::with_many_files(
+            r#"
+//- /my_app/src/my_app.erl app:my_app
+-module(my_app).
+//- /opt/lib/stdlib-3.17/src/lists.erl otp_app:/opt/lib/stdlib-3.17
+-module(lists).
+"#,
+        );
+        let app_root = db.file_source_root(files[0]);
+        let otp_root = db.file_source_root(files[1]);
+        let project_id = db.app_data(app_root).unwrap().project_id;
+        let otp_project_id = db.app_data(otp_root).unwrap().project_id;
+        assert_ne!(project_id, otp_project_id);
+        assert_eq!(db.source_root_project(app_root), Some(project_id));
+        assert_eq!(db.source_root_project(otp_root), Some(otp_project_id));
+        // The out-of-project root comes after the app roots
+        assert_eq!(db.source_root_project(SourceRootId(2)), None);
+
+        // A shared root keeps resolving to the project of its app data
+        let mut project_data = (*db.project_data(project_id)).clone();
+        project_data.source_roots.push(otp_root);
+        db.set_project_data(project_id, Arc::new(project_data));
+        assert_eq!(db.source_root_project(otp_root), Some(otp_project_id));
     }
 }
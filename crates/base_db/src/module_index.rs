@@ -15,6 +15,7 @@ use std::sync::Arc;
 
 use elp_syntax::SmolStr;
 use fxhash::FxHashMap;
+use fxhash::FxHashSet;
 
 use crate::to_quoted_string;
 use crate::FileId;
@@ -59,8 +60,15 @@ pub struct ModuleIndex {
     /// - Some(There(_)): There's OTP's module index
     /// - Some(Here): This index is itself OTP
     otp: Option<OtpModuleIndex>,
+    /// Indexes of the sibling projects, searched in order for modules not
+    /// defined in this project, before OTP. These only contain the modules
+    /// of the sibling projects themselves.
+    siblings: Vec<Arc<ModuleIndex>>,
     mod2file: FxHashMap<ModuleName, (FileSource, FileId)>,
     file2mod: FxHashMap<FileId, ModuleName>,
+    /// The modules by lowercase name, more than one when they only differ
+    /// in case
+    lowercase2mod: FxHashMap<String, Vec<ModuleName>>,
 }
 
 impl fmt::Debug for ModuleIndex {
@@ -87,6 +95,11 @@ impl ModuleIndex {
     {
         self.mod2file
             .get(name)
+            .or_else(|| {
+                self.siblings
+                    .iter()
+                    .find_map(|sibling| sibling.mod2file.get(name))
+            })
             .map(|(_source, id)| *id)
             .or_else(|| {
                 self.otp.as_ref().and_then(|otp| match otp {
@@ -96,10 +109,59 @@ impl ModuleIndex {
             })
     }
 
+    /// The name under which the module `name` is indexed, matching it
+    /// exactly if possible and ignoring case otherwise, since on
+    /// case-insensitive file systems the file of a module may not have the
+    /// case used to refer to it. Returns `None` if only a case-insensitive
+    /// match is possible, but it is ambiguous.
+    pub fn get_ignore_case(&self, name: &str) -> Option<&ModuleName> {
+        self.get_exact(name)
+            .or_else(|| self.get_lowercase(&name.to_lowercase()))
+    }
+
+    fn get_exact(&self, name: &str) -> Option<&ModuleName> {
+        self.mod2file
+            .get_key_value(name)
+            .or_else(|| {
+                self.siblings
+                    .iter()
+                    .find_map(|sibling| sibling.mod2file.get_key_value(name))
+            })
+            .map(|(name, _)| name)
+            .or_else(|| match &self.otp {
+                Some(OtpModuleIndex::There(index)) => index.get_exact(name),
+                Some(OtpModuleIndex::Here) | None => None,
+            })
+    }
+
+    fn get_lowercase(&self, lowercase: &str) -> Option<&ModuleName> {
+        let names = self.lowercase2mod.get(lowercase).or_else(|| {
+            self.siblings
+                .iter()
+                .find_map(|sibling| sibling.lowercase2mod.get(lowercase))
+        });
+        match names {
+            Some(names) => match names.as_slice() {
+                [name] => Some(name),
+                _ => None,
+            },
+            None => match &self.otp {
+                Some(OtpModuleIndex::There(index)) => index.get_lowercase(lowercase),
+                Some(OtpModuleIndex::Here) | None => None,
+            },
+        }
+    }
+
     pub fn file_source_for_file(&self, file_id: FileId) -> Option<FileSource> {
         self.file2mod
             .get(&file_id)
             .and_then(|name| self.mod2file.get(name).map(|(source, _id)| *source))
+            .or_else(|| {
+                self.siblings.iter().find_map(|sibling| {
+                    let name = sibling.file2mod.get(&file_id)?;
+                    sibling.mod2file.get(name).map(|(source, _id)| *source)
+                })
+            })
             .or_else(|| {
                 self.otp.as_ref().and_then(|otp| match otp {
                     OtpModuleIndex::There(index) => index.file_source_for_file(file_id),
@@ -109,15 +171,22 @@ impl ModuleIndex {
     }
 
     pub fn module_for_file(&self, file_id: FileId) -> Option<&ModuleName> {
-        self.file2mod.get(&file_id).map_or_else(
-            || {
-                self.otp.as_ref().and_then(|otp| match otp {
-                    OtpModuleIndex::There(index) => index.module_for_file(file_id),
-                    OtpModuleIndex::Here => None,
-                })
-            },
-            Some,
-        )
+        self.file2mod
+            .get(&file_id)
+            .or_else(|| {
+                self.siblings
+                    .iter()
+                    .find_map(|sibling| sibling.file2mod.get(&file_id))
+            })
+            .map_or_else(
+                || {
+                    self.otp.as_ref().and_then(|otp| match otp {
+                        OtpModuleIndex::There(index) => index.module_for_file(file_id),
+                        OtpModuleIndex::Here => None,
+                    })
+                },
+                Some,
+            )
     }
 
     /// Iterate over project-owned modules, without OTP
@@ -134,21 +203,25 @@ impl ModuleIndex {
         self.mod2file.len()
     }
 
-    /// All project-owned modules and OTP modules
+    /// All project-owned modules, then the modules of sibling projects
+    /// and OTP, each name listed once as it would be resolved
     pub fn all_modules(&self) -> Modules {
-        match &self.otp {
-            Some(OtpModuleIndex::There(otp)) => self
-                .mod2file
-                .iter()
-                .chain(otp.mod2file.iter())
-                .map(|(m, _f)| m.clone())
-                .collect::<Vec<_>>(),
-            Some(_) | None => self
-                .mod2file
-                .iter()
-                .map(|(m, _f)| m.clone())
-                .collect::<Vec<_>>(),
-        }
+        let otp = match &self.otp {
+            Some(OtpModuleIndex::There(otp)) => Some(otp.mod2file.keys()),
+            Some(_) | None => None,
+        };
+        let mut seen = FxHashSet::default();
+        self.mod2file
+            .keys()
+            .chain(
+                self.siblings
+                    .iter()
+                    .flat_map(|sibling| sibling.mod2file.keys()),
+            )
+            .chain(otp.into_iter().flatten())
+            .filter(|name| seen.insert(*name))
+            .cloned()
+            .collect::<Vec<_>>()
     }
 }
 
@@ -164,6 +237,7 @@ pub enum OtpModuleIndex {
 pub struct Builder(
     FxHashMap<ModuleName, (FileSource, FileId)>,
     Option<OtpModuleIndex>,
+    Vec<Arc<ModuleIndex>>,
 );
 
 impl Builder {
@@ -171,6 +245,11 @@ impl Builder {
         self.0.insert(name, (source, file_id));
     }
 
+    /// Like `insert`, unless a module of the same name was already inserted
+    pub fn insert_if_absent(&mut self, file_id: FileId, source: FileSource, name: ModuleName) {
+        self.0.entry(name).or_insert((source, file_id));
+    }
+
     /// Use a given, existing index as OTP
     pub fn set_otp(&mut self, otp: Arc<ModuleIndex>) {
         self.1 = Some(OtpModuleIndex::There(otp))
@@ -181,17 +260,119 @@ impl Builder {
         self.1 = Some(OtpModuleIndex::Here)
     }
 
+    /// Fall back to the modules of a sibling project, after the ones added
+    /// so far. Only the modules owned by `sibling` are used.
+    pub fn add_sibling(&mut self, sibling: Arc<ModuleIndex>) {
+        self.2.push(sibling)
+    }
+
     pub fn build(self) -> Arc<ModuleIndex> {
         let file2mod = self
             .0
             .iter()
             .map(|(name, (_source, file))| (*file, name.clone()))
             .collect::<FxHashMap<_, _>>();
+        let mut lowercase2mod: FxHashMap<String, Vec<ModuleName>> = FxHashMap::default();
+        for name in self.0.keys() {
+            lowercase2mod
+                .entry(name.to_lowercase())
+                .or_default()
+                .push(name.clone());
+        }
 
         Arc::new(ModuleIndex {
             otp: self.1,
+            siblings: self.2,
             mod2file: self.0,
             file2mod,
+            lowercase2mod,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(otp: Option<Arc<ModuleIndex>>, modules: &[(&str, u32)]) -> Arc<ModuleIndex> {
+        let mut builder = ModuleIndex::builder();
+        for (name, file_id) in modules {
+            builder.insert(FileId(*file_id), FileSource::Src, ModuleName::new(name));
+        }
+        if let Some(otp) = otp {
+            builder.set_otp(otp);
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn own_project_before_siblings_before_otp() {
+        let otp = index(None, &[("lists", 0), ("b", 1)]);
+        let sibling = index(Some(otp.clone()), &[("b", 10), ("shared", 11)]);
+
+        let mut builder = ModuleIndex::builder();
+        builder.insert(FileId(20), FileSource::Src, ModuleName::new("a"));
+        builder.insert(FileId(21), FileSource::Src, ModuleName::new("shared"));
+        builder.set_otp(otp);
+        builder.add_sibling(sibling);
+        let index = builder.build();
+
+        assert_eq!(index.file_for_module("a"), Some(FileId(20)));
+        assert_eq!(index.file_for_module("shared"), Some(FileId(21)));
+        assert_eq!(index.file_for_module("b"), Some(FileId(10)));
+        assert_eq!(index.file_for_module("lists"), Some(FileId(0)));
+        assert_eq!(index.file_for_module("missing"), None);
+        assert_eq!(
+            index.module_for_file(FileId(11)).map(|name| name.as_str()),
+            Some("shared")
+        );
+        assert_eq!(
+            index.file_source_for_file(FileId(10)),
+            Some(FileSource::Src)
+        );
+
+        let mut all = index.all_modules();
+        all.sort();
+        assert_eq!(
+            all.iter().map(|name| name.as_str()).collect::<Vec<_>>(),
+            vec!["a", "b", "lists", "shared"]
+        );
+    }
+
+    #[test]
+    fn get_ignore_case() {
+        let otp = index(None, &[("lists", 0)]);
+        let index = index(
+            Some(otp),
+            &[
+                ("MyModule", 1),
+                ("mymodule", 2),
+                ("Other", 3),
+                ("OTHER", 4),
+                ("Single", 5),
+            ],
+        );
+
+        // Exact matches are preferred, so modules only differing in case
+        // don't collide
+        let found = |name| index.get_ignore_case(name).map(|name| name.as_str());
+        assert_eq!(found("MyModule"), Some("MyModule"));
+        assert_eq!(found("mymodule"), Some("mymodule"));
+        assert_eq!(found("single"), Some("Single"));
+        assert_eq!(found("LISTS"), Some("lists"));
+        assert_eq!(found("other"), None);
+        assert_eq!(found("missing"), None);
+        assert_eq!(
+            index
+                .get_ignore_case("MyModule")
+                .and_then(|name| index.file_for_module(name)),
+            Some(FileId(1))
+        );
+        assert_eq!(
+            index
+                .get_ignore_case("mymodule")
+                .and_then(|name| index.file_for_module(name)),
+            Some(FileId(2))
+        );
+    }
+}
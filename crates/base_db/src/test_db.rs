@@ -0,0 +1,40 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Database used for testing `base_db`.
+
+use std::fmt;
+use std::panic;
+use std::sync::Arc;
+
+use crate::FileId;
+use crate::FileLoader;
+use crate::FileLoaderDelegate;
+
+#[salsa::database(crate::SourceDatabaseExtStorage, crate::SourceDatabaseStorage)]
+#[derive(Default)]
+pub(crate) struct TestDB {
+    storage: salsa::Storage<TestDB>,
+}
+
+impl salsa::Database for TestDB {}
+
+impl fmt::Debug for TestDB {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestDB").finish()
+    }
+}
+
+impl panic::RefUnwindSafe for TestDB {}
+
+impl FileLoader for TestDB {
+    fn file_text(&self, file_id: FileId) -> Arc<String> {
+        FileLoaderDelegate(self).file_text(file_id)
+    }
+}
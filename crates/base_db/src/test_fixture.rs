@@ -50,6 +50,14 @@
 //! "
 //! ```
 //!
+//! Define macros as the build system would, with `-D`
+//! ```
+//! "
+//! //- /src/main.erl macros:DEBUG,TEST
+//! -module(main).
+//! "
+//! ```
+//!
 //! Example setting up multi-app project, and OTP
 //! ```
 //! "
@@ -146,6 +154,7 @@ impl Fixture {
     //- /module.erl app:foo
     //- /opt/lib/comp-1.3/include/comp.hrl otp_app:/opt/lib/comp-1.3
     //- /my_app/test/file_SUITE.erl extra:test
+    //- /my_app/src/file.erl vsn:1.2.0
     fn parse_meta_line(meta: &str) -> Fixture {
         assert!(meta.starts_with("//-"));
         let meta = meta["//-".len()..].trim();
@@ -162,6 +171,8 @@ impl Fixture {
         let mut include_dirs = Vec::new();
         let mut extra_dirs = Vec::new();
         let mut otp = None;
+        let mut macros = Vec::new();
+        let mut version = None;
 
         for component in components[1..].iter() {
             let (key, value) = component
@@ -189,6 +200,15 @@ impl Fixture {
                     let dir = value.to_string();
                     extra_dirs.push(dir);
                 }
+                "macros" => {
+                    // Macros defined by the build system, as with `-D`
+                    macros.extend(
+                        value
+                            .split(',')
+                            .map(|name| eetf::Term::Atom(eetf::Atom::from(name))),
+                    );
+                }
+                "vsn" => version = Some(value.to_string()),
                 _ => panic!("bad component: {:?}", component),
             }
         }
@@ -210,13 +230,11 @@ impl Fixture {
                     }
                 }
             }
-            Some(ProjectAppData::fixture_app_data(
-                app_name,
-                dir,
-                include_dirs,
-                src_dirs,
-                extra_dirs,
-            ))
+            let mut app_data =
+                ProjectAppData::fixture_app_data(app_name, dir, include_dirs, src_dirs, extra_dirs);
+            app_data.macros = macros;
+            app_data.version = version;
+            Some(app_data)
         };
 
         Fixture {
@@ -337,8 +355,11 @@ bar() -> ok.
                 abs_src_dirs: [],
                 macros: [],
                 parse_transforms: [],
+                compile_options: [],
                 app_type: App,
                 include_path: [],
+                escript_modules: false,
+                version: None,
             }"#]]
         .assert_eq(format!("{:#?}", meta0.app_data.as_ref().unwrap()).as_str());
     }
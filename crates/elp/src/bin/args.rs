@@ -163,6 +163,40 @@ pub struct EqwalizeStats {
     pub include_generated: bool,
 }
 
+#[derive(Clone, Debug, Bpaf)]
+pub struct EqwalizeReport {
+    /// Path to directory with project (defaults to `.`)
+    #[bpaf(argument("PROJECT"), fallback(PathBuf::from(".")))]
+    pub project: PathBuf,
+    /// Rebar3 profile to pickup (default is test)
+    #[bpaf(long("as"), argument("PROFILE"), fallback("test".to_string()))]
+    pub profile: String,
+    /// Run with rebar
+    pub rebar: bool,
+    /// Also report on opted-in generated modules from project
+    pub include_generated: bool,
+}
+
+#[derive(Clone, Debug, Bpaf)]
+pub struct MigrateRecord {
+    /// Path to directory with project (defaults to `.`)
+    #[bpaf(argument("PROJECT"), fallback(PathBuf::from(".")))]
+    pub project: PathBuf,
+    /// Rebar3 profile to pickup (default is test)
+    #[bpaf(long("as"), argument("PROFILE"), fallback("test".to_string()))]
+    pub profile: String,
+    /// Run with rebar
+    pub rebar: bool,
+    /// Print the changes as a diff instead of modifying the files
+    pub dry_run: bool,
+    /// Module the record is defined in or included by
+    #[bpaf(positional::< String > ("MODULE"), complete(module_completer))]
+    pub module: String,
+    /// Record to rewrite into a map
+    #[bpaf(positional::< String > ("RECORD"))]
+    pub record: String,
+}
+
 #[derive(Clone, Debug, Bpaf)]
 pub struct BuildInfo {
     /// Path to directory with project (defaults to `.`)
@@ -258,6 +292,8 @@ pub enum Command {
     EqwalizeTarget(EqwalizeTarget),
     EqwalizeApp(EqwalizeApp),
     EqwalizeStats(EqwalizeStats),
+    EqwalizeReport(EqwalizeReport),
+    MigrateRecord(MigrateRecord),
     BuildInfo(BuildInfo),
     GenerateCompletions(GenerateCompletions),
     RunServer(RunServer),
@@ -326,6 +362,18 @@ pub fn command() -> impl Parser<Command> {
         .command("eqwalize-stats")
         .help("Return statistics about code quality for eqWAlizer");
 
+    let eqwalize_report = eqwalize_report()
+        .map(Command::EqwalizeReport)
+        .to_options()
+        .command("eqwalize-report")
+        .help("Report the forms eqWAlizer considers valid and invalid, as JSON");
+
+    let migrate_record = migrate_record()
+        .map(Command::MigrateRecord)
+        .to_options()
+        .command("migrate-record")
+        .help("Rewrite a record and all its usages in a project into maps");
+
     let build_info = build_info()
         .map(Command::BuildInfo)
         .to_options()
@@ -376,6 +424,8 @@ pub fn command() -> impl Parser<Command> {
         version,
         shell,
         eqwalize_stats,
+        eqwalize_report,
+        migrate_record,
     ])
     .fallback(Help())
 }
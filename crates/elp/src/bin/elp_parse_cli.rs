@@ -245,7 +245,7 @@ fn do_parse_one(
 ) -> Result<Option<(String, Vec<Diagnostic>)>> {
     let url = file_id_to_url(vfs, file_id);
     let mut diagnostics = db.diagnostics(config, file_id, include_generated)?;
-    let erlang_service_diagnostics = db.erlang_service_diagnostics(file_id)?;
+    let erlang_service_diagnostics = db.erlang_service_diagnostics(config, file_id)?;
     diagnostics.extend(
         erlang_service_diagnostics
             .into_iter()
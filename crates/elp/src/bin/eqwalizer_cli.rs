@@ -24,6 +24,7 @@ use elp_ide::elp_ide_db::elp_base_db::FileSource;
 use elp_ide::elp_ide_db::elp_base_db::IncludeOtp;
 use elp_ide::elp_ide_db::elp_base_db::ModuleName;
 use elp_ide::elp_ide_db::elp_base_db::VfsPath;
+use elp_ide::elp_ide_db::eqwalizer_report::ProjectReport;
 use elp_ide::elp_ide_db::EqwalizerDiagnostics;
 use elp_ide::elp_ide_db::EqwalizerStats;
 use elp_ide::erlang_service;
@@ -41,6 +42,7 @@ use crate::args::Eqwalize;
 use crate::args::EqwalizeAll;
 use crate::args::EqwalizeApp;
 use crate::args::EqwalizePassthrough;
+use crate::args::EqwalizeReport;
 use crate::args::EqwalizeStats;
 use crate::args::EqwalizeTarget;
 use crate::erlang_service_cli;
@@ -297,6 +299,37 @@ pub fn eqwalize_stats(args: &EqwalizeStats, cli: &mut dyn Cli) -> Result<()> {
     Ok(())
 }
 
+pub fn eqwalize_report(args: &EqwalizeReport, cli: &mut dyn Cli) -> Result<()> {
+    let config = DiscoverConfig::new(args.rebar, &args.profile);
+    let loaded = load::load_project_at(cli, &args.project, config, IncludeOtp::Yes)?;
+    build::compile_deps(&loaded, cli)?;
+    let analysis = &loaded.analysis();
+    let module_index = analysis.module_index(loaded.project_id)?;
+    let include_generated = args.include_generated;
+    let project_id = loaded.project_id;
+    let pb = cli.progress(module_index.len_own() as u64, "Computing report");
+    let modules = module_index
+        .iter_own()
+        .par_bridge()
+        .progress_with(pb.clone())
+        .map_with(analysis.clone(), |analysis, (_name, _source, file_id)| {
+            if should_eqwalize(analysis, file_id, include_generated) {
+                analysis
+                    .eqwalizer_report(project_id, file_id)
+                    .expect("cancelled")
+                    .map(|report| (*report).clone())
+            } else {
+                None
+            }
+        })
+        .flatten()
+        .collect();
+    pb.finish();
+    let report = ProjectReport::new(modules);
+    cli.write(serde_json::to_string(&report)?.as_bytes())?;
+    Ok(())
+}
+
 fn eqwalize(
     EqwalizerInternalArgs {
         analysis,
@@ -29,6 +29,7 @@ mod elp_parse_cli;
 mod eqwalizer_cli;
 mod erlang_service_cli;
 mod lint_cli;
+mod migrate_cli;
 mod reporting;
 mod shell;
 
@@ -70,12 +71,14 @@ fn try_main(cli: &mut dyn Cli, args: Args) -> Result<()> {
         args::Command::EqwalizeAll(args) => eqwalizer_cli::eqwalize_all(&args, cli)?,
         args::Command::EqwalizeApp(args) => eqwalizer_cli::eqwalize_app(&args, cli)?,
         args::Command::EqwalizeStats(args) => eqwalizer_cli::eqwalize_stats(&args, cli)?,
+        args::Command::EqwalizeReport(args) => eqwalizer_cli::eqwalize_report(&args, cli)?,
         args::Command::EqwalizeTarget(args) => eqwalizer_cli::eqwalize_target(&args, cli)?,
         args::Command::EqwalizePassthrough(args) => {
             eqwalizer_cli::eqwalize_passthrough(&args, cli)?
         }
         args::Command::BuildInfo(args) => build_info_cli::save_build_info(args)?,
         args::Command::Lint(args) => lint_cli::lint_all(&args, cli)?,
+        args::Command::MigrateRecord(args) => migrate_cli::migrate_record(&args, cli)?,
         args::Command::GenerateCompletions(args) => {
             let instructions = args::gen_completions(&args.shell);
             writeln!(cli, "#Please run this:\n{}", instructions)?
@@ -366,6 +369,61 @@ mod tests {
         );
     }
 
+    #[test_case(false ; "rebar")]
+    #[test_case(true  ; "buck")]
+    fn eqwalize_report_app_a_mod2(buck: bool) {
+        if !buck || cfg!(feature = "buck") {
+            let (mut args, _path) = add_project(args_vec!["eqwalize-report"], "standard", None);
+            if !buck {
+                args.push("--rebar".into());
+            }
+            let (stdout, stderr, code) = elp(args);
+            assert_eq!(code, 0, "stderr:\n{}", stderr);
+            let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+            assert_eq!(report["version"], 1);
+
+            let modules = report["modules"].as_array().unwrap();
+            let names: Vec<&str> = modules
+                .iter()
+                .map(|module| module["module"].as_str().unwrap())
+                .collect();
+            let mut sorted = names.clone();
+            sorted.sort();
+            assert_eq!(names, sorted);
+
+            let module = modules
+                .iter()
+                .find(|module| module["module"] == "app_a_mod2")
+                .unwrap();
+            assert_eq!(module["error"], serde_json::Value::Null);
+            assert_eq!(
+                module["invalid_forms"],
+                serde_json::json!([{
+                    "kind": "type",
+                    "id": "invalid/0",
+                    "location": {"line": 22, "column": 1},
+                    "reason": "NonProductiveRecursiveTypeAlias",
+                    "references": [],
+                }])
+            );
+            let valid_forms = module["valid_forms"].as_array().unwrap();
+            for expected in [
+                serde_json::json!({
+                    "kind": "type",
+                    "id": "alias/1",
+                    "location": {"line": 21, "column": 1},
+                }),
+                serde_json::json!({
+                    "kind": "spec",
+                    "id": "id/1",
+                    "location": {"line": 24, "column": 1},
+                }),
+            ] {
+                assert!(valid_forms.contains(&expected), "missing {}", expected);
+            }
+        }
+    }
+
     #[test_case(false ; "rebar")]
     #[test_case(true  ; "buck")]
     fn parse_all_diagnostics1(buck: bool) {
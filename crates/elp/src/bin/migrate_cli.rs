@@ -0,0 +1,76 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::fs;
+use std::io::Write;
+
+use anyhow::bail;
+use anyhow::Result;
+use elp::build::load;
+use elp::cli::Cli;
+use elp_ide::diff::diff_from_textedit;
+use elp_ide::elp_ide_db::elp_base_db::IncludeOtp;
+use elp_ide::RecordMigrationError;
+use elp_project_model::DiscoverConfig;
+
+use crate::args::MigrateRecord;
+
+pub fn migrate_record(args: &MigrateRecord, cli: &mut dyn Cli) -> Result<()> {
+    let config = DiscoverConfig::new(args.rebar, &args.profile);
+    let loaded = load::load_project_at(cli, &args.project, config, IncludeOtp::Yes)?;
+    let analysis = loaded.analysis();
+    let file_id = match analysis.module_file_id(loaded.project_id, &args.module)? {
+        Some(file_id) => file_id,
+        None => bail!("Module {} not found", args.module),
+    };
+    let source_change = match analysis.migrate_record_to_map(file_id, &args.record)? {
+        Ok(source_change) => source_change,
+        Err(RecordMigrationError::UnknownRecord) => {
+            bail!("Record {} not found in module {}", args.record, args.module)
+        }
+        Err(RecordMigrationError::Unmigratable(sites)) => {
+            writeln!(
+                cli,
+                "Record {} cannot be migrated, because of these usages:",
+                args.record
+            )?;
+            for site in sites {
+                let line_index = analysis.line_index(site.range.file_id)?;
+                let start = line_index.line_col(site.range.range.start());
+                writeln!(
+                    cli,
+                    "  {}:{}:{}: {}",
+                    loaded.vfs.file_path(site.range.file_id),
+                    start.line + 1,
+                    start.col_utf16 + 1,
+                    site.reason
+                )?;
+            }
+            bail!("Migration of record {} blocked", args.record)
+        }
+    };
+
+    let mut edits: Vec<_> = source_change.source_file_edits.into_iter().collect();
+    edits.sort_by_key(|(file_id, _)| loaded.vfs.file_path(*file_id).to_string());
+    for (file_id, edit) in edits {
+        let original = analysis.file_text(file_id)?;
+        let mut actual = original.to_string();
+        edit.apply(&mut actual);
+        let path = loaded.vfs.file_path(file_id);
+        if args.dry_run {
+            if let (_, Some(unified)) = diff_from_textedit(&original, &actual) {
+                writeln!(cli, "--- {path}\n{unified}")?;
+            }
+        } else if let Some(abs_path) = path.as_path() {
+            fs::write(abs_path, actual)?;
+            writeln!(cli, "Updated {path}")?;
+        }
+    }
+    Ok(())
+}
@@ -17,7 +17,7 @@ use anyhow::Result;
 use elp::build::load;
 use elp::build::types::LoadResult;
 use elp::cli::Cli;
-use elp::document::Document;
+use elp_ide::elp_ide_db::elp_base_db::decode_file_bytes;
 use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
 use elp_ide::elp_ide_db::elp_base_db::IncludeOtp;
 use elp_ide::elp_ide_db::elp_base_db::SourceDatabase;
@@ -230,10 +230,12 @@ fn process_changes_to_vfs_store(loaded: &mut LoadResult) -> bool {
         }
         if file.exists() {
             let bytes = loaded.vfs.file_contents(file.file_id).to_vec();
-            let document = Document::from_bytes(bytes);
-            raw_database.set_file_text(file.file_id, Arc::new(document.content));
+            let text = decode_file_bytes(&bytes);
+            raw_database.set_file_text(file.file_id, Arc::new(text));
+            raw_database.set_file_bytes(file.file_id, Arc::new(bytes));
         } else {
             raw_database.set_file_text(file.file_id, Default::default());
+            raw_database.set_file_bytes(file.file_id, Default::default());
         };
     }
 
@@ -16,6 +16,7 @@ use std::sync::Arc;
 use anyhow::Result;
 use crossbeam_channel::unbounded;
 use crossbeam_channel::Receiver;
+use elp_ide::elp_ide_db::elp_base_db::decode_file_bytes;
 use elp_ide::elp_ide_db::elp_base_db::loader;
 use elp_ide::elp_ide_db::elp_base_db::loader::Handle;
 use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
@@ -145,19 +146,9 @@ fn load_database(
     for file in changes {
         if file.exists() {
             let contents = vfs.file_contents(file.file_id).to_vec();
-            match String::from_utf8(contents) {
-                Ok(text) => {
-                    db.set_file_text(file.file_id, Arc::new(text));
-                }
-                Err(err) => {
-                    // Fall back to lossy latin1 loading of files.
-                    // This should only affect files from yaws, and
-                    // possibly OTP that are latin1 encoded.
-                    let contents = err.into_bytes();
-                    let text = contents.into_iter().map(|byte| byte as char).collect();
-                    db.set_file_text(file.file_id, Arc::new(text));
-                }
-            }
+            let text = decode_file_bytes(&contents);
+            db.set_file_text(file.file_id, Arc::new(text));
+            db.set_file_bytes(file.file_id, Arc::new(contents));
         }
     }
 
@@ -35,11 +35,51 @@ config_data! {
       /// Whether to show experimental ELP diagnostics that might
       /// have more false positives than usual.
       diagnostics_enableExperimental: bool = json! { false },
+      /// Whether to report `catch Expr` expressions, suggesting
+      /// `try ... catch ... end` instead.
+      diagnostics_bareCatch_enable: bool = json! { false },
       /// List of ELP diagnostics to disable.
       diagnostics_disabled: FxHashSet<String> = json! { [] },
+      /// Whether to report functions defined after the last `-export`
+      /// which are not exported, in modules exporting most of their
+      /// functions.
+      diagnostics_missingExport_enable: bool = json! { false },
+      /// Minimum ratio of exported functions for a module to be checked
+      /// by `#elp.diagnostics.missingExport.enable#`.
+      diagnostics_missingExport_threshold: f64 = json! { 0.8 },
+      /// Whether to report `==` and `/=` comparisons on values which do
+      /// not look like numbers, suggesting `=:=` and `=/=` instead.
+      diagnostics_strictComparison_enable: bool = json! { false },
+      /// Whether to report `try ... catch` clauses which catch any
+      /// exception and neither log nor re-raise it.
+      diagnostics_swallowedException_enable: bool = json! { false },
+      /// Whether to report functions taking many arguments, suggesting
+      /// to pass a record or a map instead.
+      diagnostics_tooManyArguments_enable: bool = json! { false },
+      /// Maximum number of arguments of a function not reported by
+      /// `#elp.diagnostics.tooManyArguments.enable#`.
+      diagnostics_tooManyArguments_max: usize = json! { 8 },
+      /// Whether to report `-export_type` entries naming a type which is not
+      /// defined.
+      diagnostics_undefinedExportedType_enable: bool = json! { false },
+      /// Whether to report `-import` attributes naming an unknown module, or
+      /// a function which the module does not define or export.
+      diagnostics_unresolvedImport_enable: bool = json! { false },
+      /// Whether to report exported functions which are not called anywhere
+      /// in the project. Only applies to modules of project applications.
+      diagnostics_unusedExport_enable: bool = json! { false },
+      /// Whether to report the compiler warnings of modules compiled with
+      /// `warnings_as_errors` as errors, as the compiler does.
+      diagnostics_warningsAsErrors_enable: bool = json! { false },
+      /// Whether go to definition on the module argument of calls like
+      /// `gen_server:start_link/3` or `spawn/3` navigates to that module.
+      gotoDefinition_spawnModules_enable: bool = json! { false },
       /// Whether to show function parameter name inlay hints at the call
       /// site.
       inlayHints_parameterHints_enable: bool = json! { false },
+      /// Whether to show the expansion of short macros after their
+      /// use, or the replacement text of macros with arguments.
+      inlayHints_macroExpansionHints_enable: bool = json! { false },
       /// Whether to show Code Lenses in Erlang files.
       lens_enable: bool = json! { false },
       /// Whether to show the `Run` lenses. Only applies when
@@ -50,6 +90,10 @@ config_data! {
       lens_debug_enable: bool = json! { false },
       /// Configure LSP-based logging using env_logger syntax.
       log: String = json! { "error" },
+      /// Whether modules not found in a project are resolved in the other
+      /// projects of the workspace, in the order they were opened. Applies
+      /// when a project is opened.
+      projects_siblingModules_enable: bool = json! { false },
       /// Whether to show Signature Help.
       signatureHelp_enable: bool = json! { false },
   }
@@ -167,6 +211,18 @@ impl Config {
         )
     }
 
+    pub fn semantic_tokens_multiline(&self) -> bool {
+        try_or!(
+            self.caps
+                .text_document
+                .as_ref()?
+                .semantic_tokens
+                .as_ref()?
+                .multiline_token_support?,
+            false
+        )
+    }
+
     fn experimental(&self, index: &'static str) -> bool {
         try_or!(
             self.caps.experimental.as_ref()?.get(index)?.as_bool()?,
@@ -176,7 +232,7 @@ impl Config {
 
     pub fn diagnostics(&self) -> DiagnosticsConfig {
         // Look up disabled diagnostics using both label and code.
-        DiagnosticsConfig::new(
+        let mut config = DiagnosticsConfig::new(
             !self.data.diagnostics_enableExperimental,
             self.data
                 .diagnostics_disabled
@@ -184,7 +240,21 @@ impl Config {
                 .filter_map(DiagnosticCode::maybe_from_string)
                 .collect(),
             vec![],
-        )
+        );
+        config.enable_strict_comparison = self.data.diagnostics_strictComparison_enable;
+        config.enable_unused_export = self.data.diagnostics_unusedExport_enable;
+        config.enable_swallowed_exception = self.data.diagnostics_swallowedException_enable;
+        config.enable_bare_catch = self.data.diagnostics_bareCatch_enable;
+        config.enable_undefined_exported_type = self.data.diagnostics_undefinedExportedType_enable;
+        config.enable_unresolved_import = self.data.diagnostics_unresolvedImport_enable;
+        config.enable_warnings_as_errors = self.data.diagnostics_warningsAsErrors_enable;
+        if self.data.diagnostics_missingExport_enable {
+            config.missing_export_threshold = Some(self.data.diagnostics_missingExport_threshold);
+        }
+        if self.data.diagnostics_tooManyArguments_enable {
+            config.max_function_arguments = Some(self.data.diagnostics_tooManyArguments_max);
+        }
+        config
     }
 
     pub fn code_action_group(&self) -> bool {
@@ -205,6 +275,14 @@ impl Config {
         }
     }
 
+    pub fn goto_definition_spawn_modules(&self) -> bool {
+        self.data.gotoDefinition_spawnModules_enable
+    }
+
+    pub fn sibling_modules(&self) -> bool {
+        self.data.projects_siblingModules_enable
+    }
+
     pub fn signature_help(&self) -> bool {
         self.data.signatureHelp_enable
     }
@@ -227,6 +305,7 @@ impl Config {
     pub fn inlay_hints(&self) -> InlayHintsConfig {
         InlayHintsConfig {
             parameter_hints: self.data.inlayHints_parameterHints_enable,
+            macro_expansion_hints: self.data.inlayHints_macroExpansionHints_enable,
         }
     }
 
@@ -352,6 +431,11 @@ fn field_props(
 
     match ty {
         "bool" => set!("type": "boolean"),
+        "f64" => set!("type": "number"),
+        "usize" => set! {
+            "type": "integer",
+            "minimum": 0,
+        },
         "String" => set!("type": "string"),
         "Vec<String>" => set! {
             "type": "array",
@@ -420,7 +504,7 @@ mod tests {
 
         let s = remove_ws(&schema);
 
-        expect![[r#""elp.ai.enable":{"default":false,"markdownDescription":"EnablesupportforAI-basedcompletions.","type":"boolean"},"elp.diagnostics.disabled":{"default":[],"items":{"type":"string"},"markdownDescription":"ListofELPdiagnosticstodisable.","type":"array","uniqueItems":true},"elp.diagnostics.enableExperimental":{"default":false,"markdownDescription":"WhethertoshowexperimentalELPdiagnosticsthatmight\nhavemorefalsepositivesthanusual.","type":"boolean"},"elp.inlayHints.parameterHints.enable":{"default":false,"markdownDescription":"Whethertoshowfunctionparameternameinlayhintsatthecall\nsite.","type":"boolean"},"elp.lens.debug.enable":{"default":false,"markdownDescription":"Whethertoshowthe`Debug`lenses.Onlyapplieswhen\n`#elp.lens.enable#`isset.","type":"boolean"},"elp.lens.enable":{"default":false,"markdownDescription":"WhethertoshowCodeLensesinErlangfiles.","type":"boolean"},"elp.lens.run.enable":{"default":false,"markdownDescription":"Whethertoshowthe`Run`lenses.Onlyapplieswhen\n`#elp.lens.enable#`isset.","type":"boolean"},"elp.log":{"default":"error","markdownDescription":"ConfigureLSP-basedloggingusingenv_loggersyntax.","type":"string"},"elp.signatureHelp.enable":{"default":false,"markdownDescription":"WhethertoshowSignatureHelp.","type":"boolean"},"#]]
+        expect![[r#""elp.ai.enable":{"default":false,"markdownDescription":"EnablesupportforAI-basedcompletions.","type":"boolean"},"elp.diagnostics.bareCatch.enable":{"default":false,"markdownDescription":"Whethertoreport`catchExpr`expressions,suggesting\n`try...catch...end`instead.","type":"boolean"},"elp.diagnostics.disabled":{"default":[],"items":{"type":"string"},"markdownDescription":"ListofELPdiagnosticstodisable.","type":"array","uniqueItems":true},"elp.diagnostics.enableExperimental":{"default":false,"markdownDescription":"WhethertoshowexperimentalELPdiagnosticsthatmight\nhavemorefalsepositivesthanusual.","type":"boolean"},"elp.diagnostics.missingExport.enable":{"default":false,"markdownDescription":"Whethertoreportfunctionsdefinedafterthelast`-export`\nwhicharenotexported,inmodulesexportingmostoftheir\nfunctions.","type":"boolean"},"elp.diagnostics.missingExport.threshold":{"default":0.8,"markdownDescription":"Minimumratioofexportedfunctionsforamoduletobechecked\nby`#elp.diagnostics.missingExport.enable#`.","type":"number"},"elp.diagnostics.strictComparison.enable":{"default":false,"markdownDescription":"Whethertoreport`==`and`/=`comparisonsonvalueswhichdo\nnotlooklikenumbers,suggesting`=:=`and`=/=`instead.","type":"boolean"},"elp.diagnostics.swallowedException.enable":{"default":false,"markdownDescription":"Whethertoreport`try...catch`clauseswhichcatchany\nexceptionandneitherlognorre-raiseit.","type":"boolean"},"elp.diagnostics.tooManyArguments.enable":{"default":false,"markdownDescription":"Whethertoreportfunctionstakingmanyarguments,suggesting\ntopassarecordoramapinstead.","type":"boolean"},"elp.diagnostics.tooManyArguments.max":{"default":8,"markdownDescription":"Maximumnumberofargumentsofafunctionnotreportedby\n`#elp.diagnostics.tooManyArguments.enable#`.","minimum":0,"type":"integer"},"elp.diagnostics.undefinedExportedType.enable":{"default":false,"markdownDescription":"Whethertoreport`-export_type`entriesnamingatypewhichisnot\ndefined.","type":"boolean"},"elp.diagnostics.unresolvedImport.enable":{"default":false,"markdownDescription":"Whethertoreport`-import`attributesnaminganunknownmodule,or\nafunctionwhichthemoduledoesnotdefineorexport.","type":"boolean"},"elp.diagnostics.unusedExport.enable":{"default":false,"markdownDescription":"Whethertoreportexportedfunctionswhicharenotcalledanywhere\nintheproject.Onlyappliestomodulesofprojectapplications.","type":"boolean"},"elp.diagnostics.warningsAsErrors.enable":{"default":false,"markdownDescription":"Whethertoreportthecompilerwarningsofmodulescompiledwith\n`warnings_as_errors`aserrors,asthecompilerdoes.","type":"boolean"},"elp.gotoDefinition.spawnModules.enable":{"default":false,"markdownDescription":"Whethergotodefinitiononthemoduleargumentofcallslike\n`gen_server:start_link/3`or`spawn/3`navigatestothatmodule.","type":"boolean"},"elp.inlayHints.macroExpansionHints.enable":{"default":false,"markdownDescription":"Whethertoshowtheexpansionofshortmacrosaftertheir\nuse,orthereplacementtextofmacroswitharguments.","type":"boolean"},"elp.inlayHints.parameterHints.enable":{"default":false,"markdownDescription":"Whethertoshowfunctionparameternameinlayhintsatthecall\nsite.","type":"boolean"},"elp.lens.debug.enable":{"default":false,"markdownDescription":"Whethertoshowthe`Debug`lenses.Onlyapplieswhen\n`#elp.lens.enable#`isset.","type":"boolean"},"elp.lens.enable":{"default":false,"markdownDescription":"WhethertoshowCodeLensesinErlangfiles.","type":"boolean"},"elp.lens.run.enable":{"default":false,"markdownDescription":"Whethertoshowthe`Run`lenses.Onlyapplieswhen\n`#elp.lens.enable#`isset.","type":"boolean"},"elp.log":{"default":"error","markdownDescription":"ConfigureLSP-basedloggingusingenv_loggersyntax.","type":"string"},"elp.projects.siblingModules.enable":{"default":false,"markdownDescription":"Whethermodulesnotfoundinaprojectareresolvedintheother\nprojectsoftheworkspace,intheordertheywereopened.Applies\nwhenaprojectisopened.","type":"boolean"},"elp.signatureHelp.enable":{"default":false,"markdownDescription":"WhethertoshowSignatureHelp.","type":"boolean"},"#]]
         .assert_eq(s.as_str());
 
         expect![[r#"
@@ -429,6 +513,11 @@ mod tests {
               "markdownDescription": "Enable support for AI-based completions.",
               "type": "boolean"
             },
+            "elp.diagnostics.bareCatch.enable": {
+              "default": false,
+              "markdownDescription": "Whether to report `catch Expr` expressions, suggesting\n`try ... catch ... end` instead.",
+              "type": "boolean"
+            },
             "elp.diagnostics.disabled": {
               "default": [],
               "items": {
@@ -443,6 +532,67 @@ mod tests {
               "markdownDescription": "Whether to show experimental ELP diagnostics that might\nhave more false positives than usual.",
               "type": "boolean"
             },
+            "elp.diagnostics.missingExport.enable": {
+              "default": false,
+              "markdownDescription": "Whether to report functions defined after the last `-export`\nwhich are not exported, in modules exporting most of their\nfunctions.",
+              "type": "boolean"
+            },
+            "elp.diagnostics.missingExport.threshold": {
+              "default": 0.8,
+              "markdownDescription": "Minimum ratio of exported functions for a module to be checked\nby `#elp.diagnostics.missingExport.enable#`.",
+              "type": "number"
+            },
+            "elp.diagnostics.strictComparison.enable": {
+              "default": false,
+              "markdownDescription": "Whether to report `==` and `/=` comparisons on values which do\nnot look like numbers, suggesting `=:=` and `=/=` instead.",
+              "type": "boolean"
+            },
+            "elp.diagnostics.swallowedException.enable": {
+              "default": false,
+              "markdownDescription": "Whether to report `try ... catch` clauses which catch any\nexception and neither log nor re-raise it.",
+              "type": "boolean"
+            },
+            "elp.diagnostics.tooManyArguments.enable": {
+              "default": false,
+              "markdownDescription": "Whether to report functions taking many arguments, suggesting\nto pass a record or a map instead.",
+              "type": "boolean"
+            },
+            "elp.diagnostics.tooManyArguments.max": {
+              "default": 8,
+              "markdownDescription": "Maximum number of arguments of a function not reported by\n`#elp.diagnostics.tooManyArguments.enable#`.",
+              "minimum": 0,
+              "type": "integer"
+            },
+            "elp.diagnostics.undefinedExportedType.enable": {
+              "default": false,
+              "markdownDescription": "Whether to report `-export_type` entries naming a type which is not\ndefined.",
+              "type": "boolean"
+            },
+            "elp.diagnostics.unresolvedImport.enable": {
+              "default": false,
+              "markdownDescription": "Whether to report `-import` attributes naming an unknown module, or\na function which the module does not define or export.",
+              "type": "boolean"
+            },
+            "elp.diagnostics.unusedExport.enable": {
+              "default": false,
+              "markdownDescription": "Whether to report exported functions which are not called anywhere\nin the project. Only applies to modules of project applications.",
+              "type": "boolean"
+            },
+            "elp.diagnostics.warningsAsErrors.enable": {
+              "default": false,
+              "markdownDescription": "Whether to report the compiler warnings of modules compiled with\n`warnings_as_errors` as errors, as the compiler does.",
+              "type": "boolean"
+            },
+            "elp.gotoDefinition.spawnModules.enable": {
+              "default": false,
+              "markdownDescription": "Whether go to definition on the module argument of calls like\n`gen_server:start_link/3` or `spawn/3` navigates to that module.",
+              "type": "boolean"
+            },
+            "elp.inlayHints.macroExpansionHints.enable": {
+              "default": false,
+              "markdownDescription": "Whether to show the expansion of short macros after their\nuse, or the replacement text of macros with arguments.",
+              "type": "boolean"
+            },
             "elp.inlayHints.parameterHints.enable": {
               "default": false,
               "markdownDescription": "Whether to show function parameter name inlay hints at the call\nsite.",
@@ -468,6 +618,11 @@ mod tests {
               "markdownDescription": "Configure LSP-based logging using env_logger syntax.",
               "type": "string"
             },
+            "elp.projects.siblingModules.enable": {
+              "default": false,
+              "markdownDescription": "Whether modules not found in a project are resolved in the other\nprojects of the workspace, in the order they were opened. Applies\nwhen a project is opened.",
+              "type": "boolean"
+            },
             "elp.signatureHelp.enable": {
               "default": false,
               "markdownDescription": "Whether to show Signature Help.",
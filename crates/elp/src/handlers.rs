@@ -243,6 +243,21 @@ pub(crate) fn handle_expand_macro(
     }
 }
 
+pub(crate) fn handle_matching_clause(
+    snap: Snapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<Option<lsp_ext::MatchingClauseLocation>> {
+    let _p = profile::span("handle_matching_clause");
+    let position = from_proto::file_position(&snap, params)?;
+    match snap.analysis.matching_clause(position)? {
+        Some(it) => Ok(Some(lsp_ext::MatchingClauseLocation {
+            location: to_proto::location_from_nav(&snap, it.target)?,
+            ambiguous: it.ambiguous,
+        })),
+        None => Ok(None),
+    }
+}
+
 pub(crate) fn pong(_: Snapshot, _: Vec<String>) -> Result<String> {
     Ok("pong".to_string())
 }
@@ -296,9 +311,17 @@ pub(crate) fn handle_goto_definition(
 ) -> Result<Option<lsp_types::GotoDefinitionResponse>> {
     let _p = profile::span("handle_goto_definition");
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
-    let nav_info = match snap.analysis.goto_definition(position)? {
-        None => return Ok(None),
+    let spawn_module = if snap.config.goto_definition_spawn_modules() {
+        snap.analysis.goto_spawn_module(position)?
+    } else {
+        None
+    };
+    let nav_info = match spawn_module {
         Some(it) => it,
+        None => match snap.analysis.goto_definition(position)? {
+            None => return Ok(None),
+            Some(it) => it,
+        },
     };
     let src = FileRange {
         file_id: position.file_id,
@@ -376,6 +399,7 @@ pub(crate) fn handle_completion(
             position: None,
             sort_text: Some("\0".to_string()),
             deprecated: false,
+            detail: None,
         });
     } else {
         for c in completions.iter_mut() {
@@ -404,14 +428,19 @@ pub(crate) fn handle_completion_resolve(
     if let Some(data) = original_completion.clone().data {
         let data: lsp_ext::CompletionData = serde_json::from_value(data)?;
         if let Ok(position) = from_proto::file_position(&snap, data.position) {
-            if let Ok(Some(res)) = snap.analysis.get_docs_at_position(position) {
-                let docs = res.0.markdown_text().to_string();
-                let documentation =
-                    lsp_types::Documentation::MarkupContent(lsp_types::MarkupContent {
-                        kind: lsp_types::MarkupKind::Markdown,
-                        value: docs,
-                    });
-                original_completion.documentation = Some(documentation)
+            if let Ok(Some(docs)) = snap.analysis.get_completion_docs(position) {
+                if let Some(doc) = docs.documentation {
+                    let documentation =
+                        lsp_types::Documentation::MarkupContent(lsp_types::MarkupContent {
+                            kind: lsp_types::MarkupKind::Markdown,
+                            value: doc.markdown_text().to_string(),
+                        });
+                    original_completion.documentation = Some(documentation)
+                }
+                // Keep the detail computed upfront, such as the other arities
+                if original_completion.detail.is_none() {
+                    original_completion.detail = docs.detail;
+                }
             }
         }
     }
@@ -488,7 +517,7 @@ pub(crate) fn handle_hover(snap: Snapshot, params: HoverParams) -> Result<Option
     let _p = profile::span("handle_hover");
     let position = from_proto::file_position(&snap, params.text_document_position_params)?;
 
-    let docs = snap.analysis.get_docs_at_position(position)?;
+    let docs = snap.analysis.get_hover_docs_at_position(position)?;
 
     to_proto::hover_response(&snap, docs)
 }
@@ -667,7 +696,12 @@ pub(crate) fn handle_semantic_tokens_full(
     let line_index = snap.analysis.line_index(file_id)?;
 
     let highlights = snap.analysis.highlight(file_id)?;
-    let semantic_tokens = to_proto::semantic_tokens(&text, &line_index, highlights);
+    let semantic_tokens = to_proto::semantic_tokens(
+        &text,
+        &line_index,
+        highlights,
+        snap.config.semantic_tokens_multiline(),
+    );
 
     // Unconditionally cache the tokens
     snap.semantic_tokens_cache
@@ -688,7 +722,12 @@ pub(crate) fn handle_semantic_tokens_full_delta(
     let line_index = snap.analysis.line_index(file_id)?;
 
     let highlights = snap.analysis.highlight(file_id)?;
-    let semantic_tokens = to_proto::semantic_tokens(&text, &line_index, highlights);
+    let semantic_tokens = to_proto::semantic_tokens(
+        &text,
+        &line_index,
+        highlights,
+        snap.config.semantic_tokens_multiline(),
+    );
 
     let mut cache = snap.semantic_tokens_cache.lock();
     let cached_tokens = cache.entry(params.text_document.uri).or_default();
@@ -717,7 +756,12 @@ pub(crate) fn handle_semantic_tokens_range(
     let line_index = snap.analysis.line_index(frange.file_id)?;
 
     let highlights = snap.analysis.highlight_range(frange)?;
-    let semantic_tokens = to_proto::semantic_tokens(&text, &line_index, highlights);
+    let semantic_tokens = to_proto::semantic_tokens(
+        &text,
+        &line_index,
+        highlights,
+        snap.config.semantic_tokens_multiline(),
+    );
     Ok(Some(semantic_tokens.into()))
 }
 
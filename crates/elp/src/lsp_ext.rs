@@ -62,6 +62,25 @@ pub struct ExpandedMacro {
     pub expansion: String,
 }
 
+// ---------------------------------------------------------------------
+
+pub enum MatchingClause {}
+
+impl Request for MatchingClause {
+    type Params = TextDocumentPositionParams;
+    type Result = Option<MatchingClauseLocation>;
+    const METHOD: &'static str = "elp/matchingClause";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchingClauseLocation {
+    pub location: lsp_types::Location,
+    /// Whether the clause may not be the one selected at runtime, as its
+    /// guards can't be evaluated statically
+    pub ambiguous: bool,
+}
+
 // ---------------------------------------------------------------------
 pub enum StatusNotification {}
 
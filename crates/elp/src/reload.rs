@@ -54,10 +54,11 @@ impl ProjectFolders {
                 };
                 let dir_entry = loader::Entry::Directories(dirs);
                 match app.app_type {
-                    AppType::App => vec![
-                        dir_entry,
-                        loader::Entry::Files(vec![app.dir.join(".eqwalizer")]),
-                    ],
+                    AppType::App => {
+                        let mut files = vec![app.dir.join(".eqwalizer")];
+                        files.extend(app.extensionless_escripts());
+                        vec![dir_entry, loader::Entry::Files(files)]
+                    }
                     _ => vec![dir_entry],
                 }
             })
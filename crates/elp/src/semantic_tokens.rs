@@ -11,6 +11,7 @@
 
 use std::ops;
 
+use lsp_types::Position;
 use lsp_types::Range;
 use lsp_types::SemanticToken;
 use lsp_types::SemanticTokenModifier;
@@ -53,7 +54,7 @@ define_semantic_token_types![
         // NUMBER,
         // OPERATOR,
         // PARAMETER,
-        // PROPERTY,
+        PROPERTY,
         STRING,
         STRUCT,
         TYPE_PARAMETER,
@@ -131,8 +132,24 @@ impl SemanticTokensBuilder {
 
     /// Push a new token onto the builder
     pub(crate) fn push(&mut self, range: Range, token_index: u32, modifier_bitset: u32) {
-        let mut push_line = range.start.line;
-        let mut push_char = range.start.character;
+        // A token cannot be multiline
+        let token_len = range.end.character - range.start.character;
+        self.push_with_len(range.start, token_len, token_index, modifier_bitset);
+    }
+
+    /// Push a new token onto the builder, with an explicit length in
+    /// UTF-16 code units. Only clients supporting multiline tokens accept a
+    /// token extending past the end of its starting line, in which case the
+    /// length includes the line terminators it spans.
+    pub(crate) fn push_with_len(
+        &mut self,
+        start: Position,
+        token_len: u32,
+        token_index: u32,
+        modifier_bitset: u32,
+    ) {
+        let mut push_line = start.line;
+        let mut push_char = start.character;
 
         if !self.data.is_empty() {
             push_line -= self.prev_line;
@@ -141,9 +158,6 @@ impl SemanticTokensBuilder {
             }
         }
 
-        // A token cannot be multiline
-        let token_len = range.end.character - range.start.character;
-
         let token = SemanticToken {
             delta_line: push_line,
             delta_start: push_char,
@@ -154,8 +168,10 @@ impl SemanticTokensBuilder {
 
         self.data.push(token);
 
-        self.prev_line = range.start.line;
-        self.prev_char = range.start.character;
+        // Deltas are relative to the start of the previous token, even when
+        // it spans several lines
+        self.prev_line = start.line;
+        self.prev_char = start.character;
     }
 
     pub(crate) fn build(self) -> SemanticTokens {
@@ -342,4 +358,21 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_builder_multiline_token() {
+        let mut builder = SemanticTokensBuilder::new("1".to_string());
+        builder.push(Range::new(Position::new(0, 4), Position::new(0, 7)), 1, 0);
+        // A token starting at (0, 10), spanning to (2, 3), 20 code units long
+        builder.push_with_len(Position::new(0, 10), 20, 2, 0);
+        builder.push(Range::new(Position::new(2, 5), Position::new(2, 8)), 3, 0);
+        assert_eq!(
+            builder.build().data,
+            vec![
+                from((0, 4, 3, 1, 0)),
+                from((0, 6, 20, 2, 0)),
+                from((2, 5, 3, 3, 0)),
+            ]
+        );
+    }
 }
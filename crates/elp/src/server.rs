@@ -18,6 +18,7 @@ use crossbeam_channel::select;
 use crossbeam_channel::Receiver;
 use dispatch::NotificationDispatcher;
 use elp_ai::AiCompletion;
+use elp_ide::elp_ide_db::elp_base_db::decode_file_bytes;
 use elp_ide::elp_ide_db::elp_base_db::loader;
 use elp_ide::elp_ide_db::elp_base_db::AbsPath;
 use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
@@ -494,6 +495,7 @@ impl Server {
             .on::<request::InlayHintRequest>(handlers::handle_inlay_hints)
             .on::<request::InlayHintResolveRequest>(handlers::handle_inlay_hints_resolve)
             .on::<lsp_ext::ExpandMacro>(handlers::handle_expand_macro)
+            .on::<lsp_ext::MatchingClause>(handlers::handle_matching_clause)
             .on::<lsp_ext::Ping>(handlers::pong)
             .on::<lsp_ext::ExternalDocs>(handlers::handle_external_docs)
             .finish();
@@ -693,12 +695,19 @@ impl Server {
             }
             if file.exists() {
                 let bytes = vfs.file_contents(file.file_id).to_vec();
-                let document = Document::from_bytes(bytes);
-                let (text, line_ending) = LineEndings::normalize(document.content);
+                // Open documents hold the text sent by the client, other
+                // files are decoded according to their `coding:` directive
+                let text = if self.open_document_versions.read().contains_key(&file_path) {
+                    Document::from_bytes(bytes.clone()).content
+                } else {
+                    decode_file_bytes(&bytes)
+                };
+                let (text, line_ending) = LineEndings::normalize(text);
                 self.line_ending_map
                     .write()
                     .insert(file.file_id, line_ending);
                 raw_database.set_file_text(file.file_id, Arc::new(text));
+                raw_database.set_file_bytes(file.file_id, Arc::new(bytes));
                 // causes us to remove stale squiggles from the UI
                 self.diagnostics.set_eqwalizer(file.file_id, vec![]);
             } else {
@@ -706,6 +715,7 @@ impl Server {
 
                 // We can't actually delete things from salsa, just set it to empty
                 raw_database.set_file_text(file.file_id, Default::default());
+                raw_database.set_file_bytes(file.file_id, Default::default());
             };
         }
 
@@ -865,7 +875,10 @@ impl Server {
         let raw_db = self.analysis_host.raw_database_mut();
         raw_db.clear_erlang_services();
 
-        let project_apps = ProjectApps::new(&projects, IncludeOtp::Yes);
+        let mut project_apps = ProjectApps::new(&projects, IncludeOtp::Yes);
+        if self.config.sibling_modules() {
+            project_apps = project_apps.with_sibling_projects();
+        }
         let folders = ProjectFolders::new(&project_apps);
         project_apps.app_structure().apply(raw_db);
 
@@ -15,6 +15,7 @@ use anyhow::Result;
 use elp_ai::AiCompletion;
 use elp_ai::CompletionReceiver;
 use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
+use elp_ide::elp_ide_db::elp_base_db::AnchoredPathBuf;
 use elp_ide::elp_ide_db::elp_base_db::FileId;
 use elp_ide::elp_ide_db::elp_base_db::FilePosition;
 use elp_ide::elp_ide_db::elp_base_db::ProjectId;
@@ -22,6 +23,7 @@ use elp_ide::elp_ide_db::elp_base_db::Vfs;
 use elp_ide::elp_ide_db::elp_base_db::VfsPath;
 use elp_ide::elp_ide_db::EqwalizerDiagnostics;
 use elp_ide::Analysis;
+use elp_ide::Cancellable;
 use elp_log::timeit_with_telemetry;
 use elp_project_model::Project;
 use fxhash::FxHashMap;
@@ -39,6 +41,7 @@ use crate::convert;
 use crate::line_endings::LineEndings;
 use crate::server::file_id_to_path;
 use crate::server::file_id_to_url;
+use crate::to_proto;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TelemetryData {
@@ -126,6 +129,20 @@ impl Snapshot {
         file_id_to_url(&self.vfs.read(), id)
     }
 
+    /// The url of a path relative to the directory of its anchor file. Fails
+    /// if the anchor is not a file on disk, or the path leaves its root.
+    pub(crate) fn anchored_path(&self, path: &AnchoredPathBuf) -> Result<Url> {
+        let mut base = self.vfs.read().file_path(path.anchor);
+        base.pop();
+        let joined = base
+            .join(&path.path)
+            .with_context(|| format!("cannot resolve {} relative to {}", path.path, base))?;
+        let abs_path = joined
+            .as_path()
+            .with_context(|| format!("not a file system path: {}", joined))?;
+        Ok(convert::url_from_abs_path(abs_path))
+    }
+
     pub(crate) fn url_file_version(&self, url: &Url) -> Option<i32> {
         let path = convert::vfs_path(url).ok()?;
         Some(*self.open_document_versions.read().get(&path)?)
@@ -181,16 +198,23 @@ impl Snapshot {
             .eqwalizer_diagnostics(project_id, vec![file_id])
             .ok()?;
         match &*diags {
-            EqwalizerDiagnostics::Diagnostics(diags) => Some(
-                diags
-                    .iter()
-                    .flat_map(|(_, diags)| {
-                        diags.iter().map(|d| {
-                            convert::eqwalizer_to_lsp_diagnostic(d, &line_index, eqwalizer_enabled)
-                        })
-                    })
-                    .collect(),
-            ),
+            // A cancelled conversion discards all of them, so that the
+            // previous diagnostics are kept rather than a partial list.
+            EqwalizerDiagnostics::Diagnostics(diags) => diags
+                .iter()
+                .flat_map(|(_, diags)| diags)
+                .map(|d| {
+                    to_proto::eqwalizer_diagnostic(
+                        self,
+                        project_id,
+                        file_id,
+                        &line_index,
+                        d,
+                        eqwalizer_enabled,
+                    )
+                })
+                .collect::<Cancellable<Vec<_>>>()
+                .ok(),
             EqwalizerDiagnostics::NoAst { .. } => Some(vec![]),
             EqwalizerDiagnostics::Error(err) => {
                 log::error!("EqWAlizer failed for {:?}: {}", file_id, err);
@@ -231,7 +255,10 @@ impl Snapshot {
         let url = file_id_to_url(&self.vfs.read(), file_id);
         let line_index = self.analysis.line_index(file_id).ok()?;
 
-        let diags = &*self.analysis.erlang_service_diagnostics(file_id).ok()?;
+        let diags = &*self
+            .analysis
+            .erlang_service_diagnostics(&self.config.diagnostics(), file_id)
+            .ok()?;
 
         Some(
             diags
@@ -22,8 +22,11 @@ use elp_ide::elp_ide_db::docs::Doc;
 use elp_ide::elp_ide_db::elp_base_db::FileId;
 use elp_ide::elp_ide_db::elp_base_db::FilePosition;
 use elp_ide::elp_ide_db::elp_base_db::FileRange;
+use elp_ide::elp_ide_db::elp_base_db::ProjectId;
 use elp_ide::elp_ide_db::rename::RenameError;
+use elp_ide::elp_ide_db::source_change::FileSystemEdit;
 use elp_ide::elp_ide_db::source_change::SourceChange;
+use elp_ide::elp_ide_db::EqwalizerDiagnostic;
 use elp_ide::elp_ide_db::LineIndex;
 use elp_ide::elp_ide_db::ReferenceCategory;
 use elp_ide::elp_ide_db::SymbolKind;
@@ -43,6 +46,7 @@ use elp_ide::Runnable;
 use elp_ide::SignatureHelp;
 use elp_ide::TextRange;
 use elp_ide::TextSize;
+use elp_ide::TRANSITIVE_INVALID_CODE;
 use elp_project_model::ProjectBuildData;
 use lsp_types::CompletionItemTag;
 use lsp_types::Hover;
@@ -52,6 +56,7 @@ use lsp_types::MarkupKind;
 use text_edit::Indel;
 use text_edit::TextEdit;
 
+use crate::convert;
 use crate::line_endings::LineEndings;
 use crate::lsp_ext;
 use crate::lsp_ext::CompletionData;
@@ -79,7 +84,7 @@ pub(crate) fn symbol_kind(symbol_kind: SymbolKind) -> lsp_types::SymbolKind {
         SymbolKind::Define => lsp_types::SymbolKind::CONSTANT,
         SymbolKind::File => lsp_types::SymbolKind::FILE,
         SymbolKind::Module => lsp_types::SymbolKind::MODULE,
-        SymbolKind::RecordField => lsp_types::SymbolKind::STRUCT,
+        SymbolKind::RecordField => lsp_types::SymbolKind::FIELD,
         SymbolKind::Variable => lsp_types::SymbolKind::VARIABLE,
         SymbolKind::Callback => lsp_types::SymbolKind::FUNCTION,
     }
@@ -145,7 +150,22 @@ pub(crate) fn workspace_edit(
             edits: edit.edits.into_iter().map(From::from).collect(),
         });
     }
-    let document_changes = lsp_types::DocumentChanges::Edits(edits);
+    let document_changes = if source_change.file_system_edits.is_empty() {
+        lsp_types::DocumentChanges::Edits(edits)
+    } else {
+        // Text edits refer to files by their original url, so they have
+        // to be applied before any file is moved
+        let mut ops: Vec<_> = edits
+            .into_iter()
+            .map(lsp_types::DocumentChangeOperation::Edit)
+            .collect();
+        for op in source_change.file_system_edits {
+            ops.push(lsp_types::DocumentChangeOperation::Op(resource_op(
+                snap, op,
+            )?));
+        }
+        lsp_types::DocumentChanges::Operations(ops)
+    };
     let workspace_edit = lsp_types::WorkspaceEdit {
         changes: None,
         document_changes: Some(document_changes),
@@ -154,6 +174,27 @@ pub(crate) fn workspace_edit(
     Ok(workspace_edit)
 }
 
+fn resource_op(snap: &Snapshot, file_system_edit: FileSystemEdit) -> Result<lsp_types::ResourceOp> {
+    let op = match file_system_edit {
+        FileSystemEdit::CreateFile { dst, .. } => {
+            lsp_types::ResourceOp::Create(lsp_types::CreateFile {
+                uri: snap.anchored_path(&dst)?,
+                options: None,
+                annotation_id: None,
+            })
+        }
+        FileSystemEdit::MoveFile { src, dst } => {
+            lsp_types::ResourceOp::Rename(lsp_types::RenameFile {
+                old_uri: snap.file_id_to_url(src),
+                new_uri: snap.anchored_path(&dst)?,
+                options: None,
+                annotation_id: None,
+            })
+        }
+    };
+    Ok(op)
+}
+
 pub(crate) fn code_action_kind(kind: AssistKind) -> lsp_types::CodeActionKind {
     match kind {
         AssistKind::None | AssistKind::Generate => lsp_types::CodeActionKind::EMPTY,
@@ -209,6 +250,47 @@ pub(crate) fn location(snap: &Snapshot, file_range: FileRange) -> Cancellable<ls
     Ok(loc)
 }
 
+/// An eqWAlizer diagnostic, with the invalid forms depended on by a
+/// `TransitiveInvalid` diagnostic as related information. References
+/// which can't be resolved are listed in the message instead.
+pub(crate) fn eqwalizer_diagnostic(
+    snap: &Snapshot,
+    project_id: ProjectId,
+    file_id: FileId,
+    line_index: &LineIndex,
+    d: &EqwalizerDiagnostic,
+    eqwalizer_enabled: bool,
+) -> Cancellable<lsp_types::Diagnostic> {
+    let mut diagnostic = convert::eqwalizer_to_lsp_diagnostic(d, line_index, eqwalizer_enabled);
+    if d.code != TRANSITIVE_INVALID_CODE {
+        return Ok(diagnostic);
+    }
+    let mut related_information = Vec::new();
+    let mut unresolved = Vec::new();
+    for reference in snap
+        .analysis
+        .eqwalizer_invalid_references(project_id, file_id, d.range)?
+    {
+        match reference.target {
+            Some(target) => related_information.push(lsp_types::DiagnosticRelatedInformation {
+                location: location(snap, target)?,
+                message: format!("{} is invalid", reference.name),
+            }),
+            None => unresolved.push(reference.name),
+        }
+    }
+    if !unresolved.is_empty() {
+        diagnostic.message.push_str(&format!(
+            "\n        Depends on invalid {}",
+            unresolved.join(", ")
+        ));
+    }
+    if !related_information.is_empty() {
+        diagnostic.related_information = Some(related_information);
+    }
+    Ok(diagnostic)
+}
+
 /// Prefer using `location_link`, if the client has the cap.
 pub(crate) fn location_from_nav(
     snap: &Snapshot,
@@ -347,10 +429,12 @@ fn completion_item(snap: &Snapshot, c: Completion) -> lsp_types::CompletionItem
         kind: Some(match c.kind {
             Attribute => K::KEYWORD,
             Behavior => K::INTERFACE,
+            File => K::FILE,
             Function => K::FUNCTION,
             Keyword => K::KEYWORD,
             Macro => K::CONSTANT,
             Module => K::MODULE,
+            MapKey => K::PROPERTY,
             Operator => K::OPERATOR,
             RecordField => K::FIELD,
             Record => K::STRUCT,
@@ -358,7 +442,7 @@ fn completion_item(snap: &Snapshot, c: Completion) -> lsp_types::CompletionItem
             Variable => K::VARIABLE,
             AiAssist => K::EVENT,
         }),
-        detail: None,
+        detail: c.detail,
         documentation: None,
         deprecated: Some(c.deprecated),
         preselect: None,
@@ -407,7 +491,9 @@ fn completion_item_data(snap: &Snapshot, pos: Option<FilePosition>) -> Option<Co
 
 pub(crate) fn folding_range(line_index: &LineIndex, fold: Fold) -> lsp_types::FoldingRange {
     let kind = match fold.kind {
-        FoldKind::Function | FoldKind::Record => Some(lsp_types::FoldingRangeKind::Region),
+        FoldKind::Function | FoldKind::Record | FoldKind::Region => {
+            Some(lsp_types::FoldingRangeKind::Region)
+        }
     };
 
     let range = range(line_index, fold.range);
@@ -429,12 +515,16 @@ pub(crate) fn call_hierarchy_item(
 ) -> Result<lsp_types::CallHierarchyItem> {
     let name = target.name.to_string();
     let kind = lsp_types::SymbolKind::FUNCTION;
+    let detail = snap.analysis.function_signature(FilePosition {
+        file_id: target.file_id,
+        offset: target.focus_range.unwrap_or(target.full_range).start(),
+    })?;
     let (uri, range, selection_range) = location_info(snap, target)?;
     Ok(lsp_types::CallHierarchyItem {
         name,
         kind,
         tags: None,
-        detail: None,
+        detail,
         uri,
         range,
         selection_range,
@@ -507,6 +597,7 @@ pub(crate) fn semantic_tokens(
     text: &str,
     line_index: &LineIndex,
     highlights: Vec<HlRange>,
+    multiline_support: bool,
 ) -> lsp_types::SemanticTokens {
     let id = TOKEN_RESULT_COUNTER
         .fetch_add(1, Ordering::SeqCst)
@@ -522,6 +613,19 @@ pub(crate) fn semantic_tokens(
         let token_index = semantic_tokens::type_index(ty);
         let modifier_bitset = mods.0;
 
+        if multiline_support {
+            let mut text_range = highlight_range.range;
+            if text[text_range].ends_with('\n') {
+                text_range =
+                    TextRange::new(text_range.start(), text_range.end() - TextSize::of('\n'));
+            }
+            let start = position(line_index, text_range.start());
+            // The length of a multiline token counts the line terminators
+            let token_len = text[text_range].encode_utf16().count() as u32;
+            builder.push_with_len(start, token_len, token_index, modifier_bitset);
+            continue;
+        }
+
         for mut text_range in line_index.lines(highlight_range.range) {
             if text[text_range].ends_with('\n') {
                 // Temporary for T148094436
@@ -556,7 +660,7 @@ fn semantic_token_type_and_modifiers(
             SymbolKind::Module => semantic_tokens::NAMESPACE,
             SymbolKind::Function => semantic_tokens::FUNCTION,
             SymbolKind::Record => semantic_tokens::STRUCT,
-            SymbolKind::RecordField => semantic_tokens::STRUCT,
+            SymbolKind::RecordField => semantic_tokens::PROPERTY,
             SymbolKind::Type => semantic_tokens::TYPE_PARAMETER,
             SymbolKind::Define => semantic_tokens::MACRO,
             SymbolKind::Variable => semantic_tokens::VARIABLE,
@@ -709,6 +813,7 @@ pub(crate) fn inlay_hint(
 ) -> Cancellable<lsp_types::InlayHint> {
     match inlay_hint.kind {
         InlayKind::Parameter => inlay_hint.label.append_str(":"),
+        InlayKind::MacroExpansion => inlay_hint.label.prepend_str("= "),
     }
 
     let (label, tooltip) = inlay_hint_label(snap, inlay_hint.label)?;
@@ -718,16 +823,19 @@ pub(crate) fn inlay_hint(
             // before annotated thing
             InlayKind::Parameter => position(line_index, inlay_hint.range.start()),
             // after annotated thing
-            // _ => position(line_index, inlay_hint.range.end()),
+            InlayKind::MacroExpansion => position(line_index, inlay_hint.range.end()),
         },
         padding_left: Some(match inlay_hint.kind {
             InlayKind::Parameter => false,
+            InlayKind::MacroExpansion => true,
         }),
         padding_right: Some(match inlay_hint.kind {
             InlayKind::Parameter => true,
+            InlayKind::MacroExpansion => false,
         }),
         kind: match inlay_hint.kind {
             InlayKind::Parameter => Some(lsp_types::InlayHintKind::PARAMETER),
+            InlayKind::MacroExpansion => None,
         },
         text_edits: None,
         data: None,
@@ -835,3 +943,205 @@ pub(crate) fn document_symbol(
 }
 
 // ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use elp_ai::AiCompletion;
+    use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
+    use elp_ide::elp_ide_db::elp_base_db::AnchoredPathBuf;
+    use elp_ide::elp_ide_db::elp_base_db::FileId;
+    use elp_ide::elp_ide_db::elp_base_db::ProjectId;
+    use elp_ide::elp_ide_db::elp_base_db::SourceDatabaseExt;
+    use elp_ide::elp_ide_db::elp_base_db::Vfs;
+    use elp_ide::elp_ide_db::elp_base_db::VfsPath;
+    use elp_ide::elp_ide_db::source_change::FileSystemEdit;
+    use elp_ide::elp_ide_db::source_change::SourceChange;
+    use elp_ide::elp_ide_db::EqwalizerDiagnostic;
+    use elp_ide::elp_ide_db::LineIndex;
+    use elp_ide::elp_ide_db::SymbolKind;
+    use elp_ide::AnalysisHost;
+    use elp_ide::HlRange;
+    use elp_ide::HlTag;
+    use elp_ide::TextRange;
+    use elp_ide::TextSize;
+    use expect_test::expect;
+    use fxhash::FxHashMap;
+    use lsp_types::ClientCapabilities;
+    use parking_lot::Mutex;
+    use parking_lot::RwLock;
+    use text_edit::TextEdit;
+
+    use crate::config::Config;
+    use crate::line_endings::LineEndings;
+    use crate::snapshot::Snapshot;
+
+    /// A snapshot of `files`, given as their path and text
+    fn snapshot(files: &[(VfsPath, &str)]) -> (Snapshot, Vec<FileId>) {
+        let mut vfs = Vfs::default();
+        let mut host = AnalysisHost::default();
+        let mut line_endings = FxHashMap::default();
+        let file_ids = files
+            .iter()
+            .map(|(path, text)| {
+                vfs.set_file_contents(path.clone(), Some(text.as_bytes().to_vec()));
+                let file_id = vfs.file_id(path).unwrap();
+                host.raw_database_mut()
+                    .set_file_text(file_id, Arc::new(text.to_string()));
+                line_endings.insert(file_id, LineEndings::Unix);
+                file_id
+            })
+            .collect();
+        let config = Config::new(
+            AbsPathBuf::assert(PathBuf::from("/")),
+            ClientCapabilities::default(),
+        );
+        let snap = Snapshot::new(
+            Arc::new(config),
+            host.analysis(),
+            Arc::new(RwLock::new(vfs)),
+            Arc::default(),
+            Arc::new(RwLock::new(line_endings)),
+            Arc::default(),
+            Arc::new(Mutex::new(AiCompletion::disabled())),
+        );
+        (snap, file_ids)
+    }
+
+    fn path(path: &str) -> VfsPath {
+        VfsPath::from(AbsPathBuf::assert(PathBuf::from(path)))
+    }
+
+    #[test]
+    fn workspace_edit_with_file_operations() {
+        let (snap, files) = snapshot(&[(path("/proj/src/foo.erl"), "-module(foo).\n")]);
+        let mut edit = TextEdit::builder();
+        edit.replace(TextRange::new(8.into(), 11.into()), "bar".to_string());
+        let mut source_change = SourceChange::from_text_edit(files[0], edit.finish());
+        source_change.push_file_system_edit(FileSystemEdit::MoveFile {
+            src: files[0],
+            dst: AnchoredPathBuf {
+                anchor: files[0],
+                path: "bar.erl".to_string(),
+            },
+        });
+        source_change.push_file_system_edit(FileSystemEdit::CreateFile {
+            dst: AnchoredPathBuf {
+                anchor: files[0],
+                path: "../include/bar.hrl".to_string(),
+            },
+            initial_contents: String::new(),
+        });
+
+        let edit = super::workspace_edit(&snap, source_change).unwrap();
+        let actual = serde_json::to_string_pretty(&edit.document_changes).unwrap();
+        // The text edit comes first, as it refers to the file before its move
+        expect![[r#"
+            [
+              {
+                "textDocument": {
+                  "uri": "file:///proj/src/foo.erl",
+                  "version": null
+                },
+                "edits": [
+                  {
+                    "range": {
+                      "start": {
+                        "line": 0,
+                        "character": 8
+                      },
+                      "end": {
+                        "line": 0,
+                        "character": 11
+                      }
+                    },
+                    "newText": "bar"
+                  }
+                ]
+              },
+              {
+                "kind": "rename",
+                "oldUri": "file:///proj/src/foo.erl",
+                "newUri": "file:///proj/src/bar.erl"
+              },
+              {
+                "kind": "create",
+                "uri": "file:///proj/include/bar.hrl"
+              }
+            ]"#]]
+        .assert_eq(&actual);
+    }
+
+    #[test]
+    fn workspace_edit_with_virtual_anchor() {
+        let virtual_path = VfsPath::new_virtual_path("/src/foo.erl".to_string());
+        let (snap, files) = snapshot(&[(virtual_path, "-module(foo).\n")]);
+        let mut source_change = SourceChange::default();
+        source_change.push_file_system_edit(FileSystemEdit::CreateFile {
+            dst: AnchoredPathBuf {
+                anchor: files[0],
+                path: "bar.erl".to_string(),
+            },
+            initial_contents: String::new(),
+        });
+        let err = super::workspace_edit(&snap, source_change).unwrap_err();
+        expect!["not a file system path: /src/bar.erl"].assert_eq(&err.to_string());
+    }
+
+    #[test]
+    fn eqwalizer_diagnostic_without_invalid_references() {
+        let text = "-module(foo).\nfoo() -> error.\n";
+        let (snap, files) = snapshot(&[(path("/proj/src/foo.erl"), text)]);
+        let diagnostic = EqwalizerDiagnostic {
+            range: TextRange::new(23.into(), 28.into()),
+            message: "Expression has type: 'error'".to_string(),
+            uri: "https://fb.me/eqwalizer_errors#incompatible_types".to_string(),
+            code: "incompatible_types".to_string(),
+            expression: Some("'error'".to_string()),
+            explanation: None,
+        };
+        // The file belongs to no project: looking up its eqWAlizer report
+        // would fail, so only `TransitiveInvalid` diagnostics may do so.
+        let actual = super::eqwalizer_diagnostic(
+            &snap,
+            ProjectId(0),
+            files[0],
+            &LineIndex::new(text),
+            &diagnostic,
+            true,
+        )
+        .unwrap();
+        assert_eq!(actual.related_information, None);
+        expect![[r#"
+            `'error'`.
+            Expression has type: 'error'
+                    See https://fb.me/eqwalizer_errors#incompatible_types"#]]
+        .assert_eq(&actual.message);
+    }
+
+    #[test]
+    fn semantic_tokens_spanning_lines() {
+        let text = "foo() ->\n    \"ab\n cd\".\n";
+        let start = TextSize::from(text.find('"').unwrap() as u32);
+        let end = TextSize::from(text.rfind('"').unwrap() as u32 + 1);
+        let highlight = HlRange {
+            range: TextRange::new(start, end),
+            highlight: HlTag::Symbol(SymbolKind::Variable).into(),
+            binding_hash: None,
+        };
+        let line_index = LineIndex::new(text);
+        let tokens = |multiline_support| {
+            super::semantic_tokens(text, &line_index, vec![highlight], multiline_support)
+                .data
+                .iter()
+                .map(|token| (token.delta_line, token.delta_start, token.length))
+                .collect::<Vec<_>>()
+        };
+        // One token per line, without the line terminator
+        assert_eq!(tokens(false), vec![(1, 4, 3), (1, 0, 4)]);
+        // A single token, whose length counts the line terminator
+        assert_eq!(tokens(true), vec![(1, 4, 8)]);
+    }
+}
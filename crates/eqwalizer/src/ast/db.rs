@@ -18,6 +18,7 @@ use fxhash::FxHashSet;
 
 use super::contractivity::StubContractivityChecker;
 use super::expand::StubExpander;
+use super::report::ModuleReport;
 use super::stub::ModuleStub;
 use super::trans_valid::TransitiveChecker;
 use super::variance_check::VarianceChecker;
@@ -104,6 +105,9 @@ pub trait EqwalizerASTDatabase: EqwalizerErlASTStorage + SourceDatabase {
         project_id: ProjectId,
         module: ModuleName,
     ) -> Result<Arc<Vec<u8>>, Error>;
+
+    #[salsa::invoke(super::report::module_report)]
+    fn module_report(&self, project_id: ProjectId, module: ModuleName) -> Arc<ModuleReport>;
 }
 
 fn from_beam(db: &dyn EqwalizerASTDatabase, project_id: ProjectId, module: ModuleName) -> bool {
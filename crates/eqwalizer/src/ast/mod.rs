@@ -37,6 +37,7 @@ pub mod form;
 pub mod guard;
 pub mod invalid_diagnostics;
 pub mod pat;
+pub mod report;
 pub mod stub;
 pub mod subst;
 pub mod trans_valid;
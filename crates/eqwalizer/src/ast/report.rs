@@ -0,0 +1,321 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! A JSON report of the forms of each module that eqWAlizer considers
+//! valid or invalid, once stubs have been transitively validated.
+//!
+//! The schema is meant to be consumed by CI tooling: any incompatible
+//! change to it must bump `REPORT_VERSION`.
+
+use std::sync::Arc;
+
+use elp_base_db::ModuleName;
+use elp_base_db::ProjectId;
+use elp_syntax::SmolStr;
+use serde::Serialize;
+
+use super::db::EqwalizerASTDatabase;
+use super::form::InvalidForm;
+use super::invalid_diagnostics::Invalid;
+use super::Pos;
+
+pub const REPORT_VERSION: u32 = 1;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProjectReport {
+    pub version: u32,
+    pub modules: Vec<ModuleReport>,
+}
+
+impl ProjectReport {
+    pub fn new(mut modules: Vec<ModuleReport>) -> ProjectReport {
+        modules.sort_by(|a, b| a.module.cmp(&b.module));
+        ProjectReport {
+            version: REPORT_VERSION,
+            modules,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ModuleReport {
+    pub module: SmolStr,
+    pub valid_forms: Vec<FormReport>,
+    pub invalid_forms: Vec<InvalidFormReport>,
+    /// Set if the stub of the module could not be computed, in which
+    /// case no forms are reported.
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum FormKind {
+    Type,
+    Opaque,
+    Spec,
+    Record,
+    Callback,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FormReport {
+    pub kind: FormKind,
+    /// `name/arity` for types, specs and callbacks, the name for records
+    pub id: SmolStr,
+    pub location: Location,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InvalidFormReport {
+    pub kind: FormKind,
+    pub id: SmolStr,
+    pub location: Location,
+    /// The kind of `Invalid` diagnostic, e.g. `TransitiveInvalid`
+    pub reason: SmolStr,
+    /// For `TransitiveInvalid`, the invalid forms this one depends on
+    pub references: Vec<SmolStr>,
+}
+
+/// One-based line and column. Columns count characters, not bytes. Both
+/// are 0 if the source of the module could not be found.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl Location {
+    fn from_pos(pos: &Pos, text: Option<&str>) -> Location {
+        match pos {
+            Pos::LineAndColumn(pos) => Location {
+                line: pos.line,
+                column: pos.column,
+            },
+            Pos::TextRange(range) => match text {
+                Some(text) => Location::from_offset(text, range.start_byte as usize),
+                None => Location { line: 0, column: 0 },
+            },
+        }
+    }
+
+    fn from_offset(text: &str, offset: usize) -> Location {
+        let mut offset = offset.min(text.len());
+        while !text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &text[..offset];
+        let line_start = before.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+        Location {
+            line: before.matches('\n').count() as u32 + 1,
+            column: before[line_start..].chars().count() as u32 + 1,
+        }
+    }
+}
+
+/// The report for a single module, based on its transitively validated stub.
+pub(crate) fn module_report(
+    db: &dyn EqwalizerASTDatabase,
+    project_id: ProjectId,
+    module: ModuleName,
+) -> Arc<ModuleReport> {
+    let name = SmolStr::new(module.as_str());
+    let stub = match db.transitive_stub(project_id, module.clone()) {
+        Ok(stub) => stub,
+        Err(err) => {
+            return Arc::new(ModuleReport {
+                module: name,
+                valid_forms: vec![],
+                invalid_forms: vec![],
+                error: Some(err.to_string()),
+            });
+        }
+    };
+    let text = db
+        .module_index(project_id)
+        .file_for_module(&module)
+        .map(|file_id| db.file_text(file_id));
+    let location = |pos: &Pos| Location::from_pos(pos, text.as_deref().map(String::as_str));
+
+    let mut valid_forms: Vec<FormReport> = Vec::new();
+    let mut add_valid = |kind, id: String, pos: &Pos| {
+        valid_forms.push(FormReport {
+            kind,
+            id: id.into(),
+            location: location(pos),
+        })
+    };
+    for decl in stub.types.values() {
+        add_valid(FormKind::Type, decl.id.to_string(), &decl.location);
+    }
+    for decl in stub.private_opaques.values() {
+        add_valid(FormKind::Opaque, decl.id.to_string(), &decl.location);
+    }
+    for decl in stub.public_opaques.values() {
+        add_valid(FormKind::Opaque, decl.id.to_string(), &decl.location);
+    }
+    for spec in stub.specs.values() {
+        add_valid(FormKind::Spec, spec.id.to_string(), &spec.location);
+    }
+    for spec in stub.overloaded_specs.values() {
+        add_valid(FormKind::Spec, spec.id.to_string(), &spec.location);
+    }
+    for record in stub.records.values() {
+        add_valid(FormKind::Record, record.name.to_string(), &record.location);
+    }
+    for callback in &stub.callbacks {
+        add_valid(
+            FormKind::Callback,
+            callback.id.to_string(),
+            &callback.location,
+        );
+    }
+    valid_forms.sort_by(|a, b| (a.location, a.kind, &a.id).cmp(&(b.location, b.kind, &b.id)));
+
+    let mut invalid_forms: Vec<InvalidFormReport> = stub
+        .invalid_forms
+        .iter()
+        .map(|form| {
+            let (kind, id, pos, invalid) = match form {
+                InvalidForm::InvalidTypeDecl(decl) => (
+                    FormKind::Type,
+                    decl.id.to_string(),
+                    &decl.location,
+                    &decl.te,
+                ),
+                InvalidForm::InvalidFunSpec(spec) => (
+                    FormKind::Spec,
+                    spec.id.to_string(),
+                    &spec.location,
+                    &spec.te,
+                ),
+                InvalidForm::InvalidRecDecl(decl) => (
+                    FormKind::Record,
+                    decl.name.to_string(),
+                    &decl.location,
+                    &decl.te,
+                ),
+                InvalidForm::InvalidConvertTypeInRecDecl(decl) => (
+                    FormKind::Record,
+                    decl.name.to_string(),
+                    &decl.location,
+                    &decl.te,
+                ),
+            };
+            let (reason, references) = invalid_reason(invalid);
+            InvalidFormReport {
+                kind,
+                id: id.into(),
+                location: location(pos),
+                reason: reason.into(),
+                references,
+            }
+        })
+        .collect();
+    invalid_forms.sort_by(|a, b| (a.location, a.kind, &a.id).cmp(&(b.location, b.kind, &b.id)));
+
+    Arc::new(ModuleReport {
+        module: name,
+        valid_forms,
+        invalid_forms,
+        error: None,
+    })
+}
+
+fn invalid_reason(invalid: &Invalid) -> (&'static str, Vec<SmolStr>) {
+    match invalid {
+        Invalid::UnknownId(_) => ("UnknownId", vec![]),
+        Invalid::RecursiveConstraint(_) => ("RecursiveConstraint", vec![]),
+        Invalid::TyVarWithMultipleConstraints(_) => ("TyVarWithMultipleConstraints", vec![]),
+        Invalid::TypeVarInRecordField(_) => ("TypeVarInRecordField", vec![]),
+        Invalid::UnboundTyVarInTyDecl(_) => ("UnboundTyVarInTyDecl", vec![]),
+        Invalid::RepeatedTyVarInTyDecl(_) => ("RepeatedTyVarInTyDecl", vec![]),
+        Invalid::NonProductiveRecursiveTypeAlias(_) => ("NonProductiveRecursiveTypeAlias", vec![]),
+        Invalid::TransitiveInvalid(invalid) => {
+            let mut references = invalid.references.clone();
+            references.sort();
+            ("TransitiveInvalid", references)
+        }
+        Invalid::AliasWithNonCovariantParam(_) => ("AliasWithNonCovariantParam", vec![]),
+        Invalid::BadMapKey(_) => ("BadMapKey", vec![]),
+    }
+}
+
+/// The report for all of `modules`, sorted by module name.
+pub fn project_report(
+    db: &dyn EqwalizerASTDatabase,
+    project_id: ProjectId,
+    modules: Vec<ModuleName>,
+) -> ProjectReport {
+    ProjectReport::new(
+        modules
+            .into_iter()
+            .map(|module| (*db.module_report(project_id, module)).clone())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_syntax::SmolStr;
+
+    use super::Location;
+    use super::ModuleReport;
+    use super::ProjectReport;
+    use super::REPORT_VERSION;
+    use crate::ast::LineAndColumn;
+    use crate::ast::Pos;
+    use crate::ast::TextRange;
+
+    fn location(line: u32, column: u32) -> Location {
+        Location { line, column }
+    }
+
+    #[test]
+    fn from_offset() {
+        let text = "-module(m).\n\nfoo() -> 'é', ok.\n";
+        assert_eq!(Location::from_offset(text, 0), location(1, 1));
+        assert_eq!(Location::from_offset(text, 11), location(1, 12));
+        assert_eq!(Location::from_offset(text, 12), location(2, 1));
+        assert_eq!(Location::from_offset(text, 13), location(3, 1));
+        // Columns count characters: `ok` follows a two-byte character
+        let ok = text.find("ok").unwrap();
+        assert_eq!(Location::from_offset(text, ok), location(3, 15));
+        // Inside a character, and past the end of the text
+        let accent = text.find('é').unwrap();
+        assert_eq!(Location::from_offset(text, accent + 1), location(3, 11));
+        assert_eq!(Location::from_offset(text, 1000), location(4, 1));
+    }
+
+    #[test]
+    fn from_pos() {
+        let text = "-module(m).\nfoo() -> ok.\n";
+        let range = Pos::TextRange(TextRange {
+            start_byte: 12,
+            end_byte: 24,
+        });
+        assert_eq!(Location::from_pos(&range, Some(text)), location(2, 1));
+        assert_eq!(Location::from_pos(&range, None), location(0, 0));
+        let line_and_column = Pos::LineAndColumn(LineAndColumn { line: 7, column: 3 });
+        assert_eq!(Location::from_pos(&line_and_column, None), location(7, 3));
+    }
+
+    #[test]
+    fn project_report_sorted() {
+        let module = |name: &str| ModuleReport {
+            module: SmolStr::new(name),
+            valid_forms: vec![],
+            invalid_forms: vec![],
+            error: None,
+        };
+        let report = ProjectReport::new(vec![module("b"), module("a"), module("c")]);
+        assert_eq!(report.version, REPORT_VERSION);
+        let names: Vec<&str> = report.modules.iter().map(|m| m.module.as_str()).collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+    }
+}
@@ -41,6 +41,7 @@ use crate::FormList;
 use crate::Function;
 use crate::FunctionId;
 use crate::InFile;
+use crate::MatchDiscard;
 use crate::Pat;
 use crate::PatId;
 use crate::RecordFieldBody;
@@ -66,6 +67,9 @@ pub mod scope;
 mod tests;
 mod tree_print;
 
+pub(crate) use lower::lower_literal;
+pub use lower::MacroExpansion;
+
 #[derive(Debug, PartialEq, Eq, Default)]
 pub struct Body {
     pub exprs: Arena<Expr>,
@@ -163,6 +167,36 @@ impl Body {
             .find_map(|(k, v)| if v == expr { Some(k.clone()) } else { None })
     }
 
+    /// Whether `expr_id` is a match whose left-hand side is `_` or a
+    /// variable starting with `_`, looking through macros.
+    pub fn match_discard(
+        &self,
+        db: &dyn MinInternDatabase,
+        expr_id: ExprId,
+    ) -> Option<MatchDiscard> {
+        match &self[expr_id] {
+            Expr::Match { lhs, .. } => self.pat_discard(db, *lhs),
+            _ => None,
+        }
+    }
+
+    fn pat_discard(&self, db: &dyn MinInternDatabase, pat_id: PatId) -> Option<MatchDiscard> {
+        match &self[pat_id] {
+            Pat::Var(var) => {
+                let name = db.lookup_var(*var);
+                if name.as_str() == "_" {
+                    Some(MatchDiscard::Wildcard)
+                } else if name.as_str().starts_with('_') {
+                    Some(MatchDiscard::Named(*var))
+                } else {
+                    None
+                }
+            }
+            Pat::MacroCall { expansion, .. } => self.pat_discard(db, *expansion),
+            _ => None,
+        }
+    }
+
     pub fn fold_expr<'a, T>(
         &self,
         strategy: Strategy,
@@ -191,14 +225,26 @@ impl FunctionBody {
         db: &dyn MinDefDatabase,
         function_id: InFile<FunctionId>,
     ) -> (Arc<FunctionBody>, Arc<BodySourceMap>) {
+        let (body, source_map) =
+            Self::lower_with_macro_expansion(db, function_id, MacroExpansion::All);
+        (Arc::new(body), Arc::new(source_map))
+    }
+
+    /// Lower the function outside of salsa, choosing which macro calls
+    /// get expanded. Used to inspect a body "as written".
+    pub fn lower_with_macro_expansion(
+        db: &dyn MinDefDatabase,
+        function_id: InFile<FunctionId>,
+        macro_expansion: MacroExpansion,
+    ) -> (FunctionBody, BodySourceMap) {
         let form_list = db.file_form_list(function_id.file_id);
         let function = &form_list[function_id.value];
         let function_ast = function.form_id.get(&function_id.file_syntax(db.upcast()));
 
-        let mut ctx = lower::Ctx::new(db, function_id.file_id);
+        let mut ctx =
+            lower::Ctx::new(db, function_id.file_id).with_macro_expansion(macro_expansion);
         ctx.set_function_info(&function.name);
-        let (body, source_map) = ctx.lower_function(&function_ast);
-        (Arc::new(body), Arc::new(source_map))
+        ctx.lower_function(&function_ast)
     }
 
     pub fn print(&self, db: &dyn MinInternDatabase, form: &Function) -> String {
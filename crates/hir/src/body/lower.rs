@@ -56,6 +56,7 @@ use crate::NameArity;
 use crate::Pat;
 use crate::PatId;
 use crate::ReceiveAfter;
+use crate::ReceiveTimeout;
 use crate::Record;
 use crate::RecordBody;
 use crate::RecordFieldBody;
@@ -76,22 +77,52 @@ struct MacroStackEntry {
     parent_id: usize,
 }
 
+/// Controls which macro calls are expanded during lowering.
+///
+/// Anything other than `All` is only meant for debugging macro-related
+/// confusion: a call that is not expanded is still resolved and recorded
+/// in the source map, but is lowered to a `MacroCall` wrapping a
+/// `Missing` expansion, with its arguments lowered as they are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacroExpansion {
+    /// Expand every macro call (the default)
+    All,
+    /// Expand only built-in macros like `?MODULE` or `?LINE`
+    BuiltInOnly,
+    /// Treat every macro call as opaque
+    None,
+}
+
 pub struct Ctx<'a> {
     db: &'a dyn MinDefDatabase,
     original_file_id: FileId,
     macro_stack: Vec<MacroStackEntry>,
     macro_stack_id: usize,
+    macro_expansion: MacroExpansion,
+    /// The name and arity of the function being lowered, for
+    /// `?FUNCTION_NAME` and `?FUNCTION_ARITY`. Outside of a function, such
+    /// as in a spec or a record default, these lower to missing.
     function_info: Option<(Atom, u32)>,
     body: Body,
     source_map: BodySourceMap,
 }
 
+/// A macro that expands to a value known to ELP, rather than to source code
+#[derive(Debug, Clone, Copy)]
+enum MacroValue {
+    BuiltIn(BuiltInMacro),
+    /// Defined by the build system or a `-compile({d, ...})` option
+    Predefined(Atom),
+}
+
 #[derive(Debug)]
 enum MacroReplacement {
-    BuiltIn(BuiltInMacro),
+    Value(MacroValue),
     Ast(ast::MacroDefReplacement),
-    BuiltInArgs(BuiltInMacro, MacroCallArgs),
+    ValueArgs(MacroValue, MacroCallArgs),
     AstArgs(ast::MacroDefReplacement, MacroCallArgs),
+    /// Resolved, but not expanded, see `MacroExpansion`
+    Opaque,
 }
 
 impl<'a> Ctx<'a> {
@@ -106,12 +137,18 @@ impl<'a> Ctx<'a> {
                 parent_id: 0,
             }],
             macro_stack_id: 0,
+            macro_expansion: MacroExpansion::All,
             function_info: None,
             body: Body::default(),
             source_map: BodySourceMap::default(),
         }
     }
 
+    pub fn with_macro_expansion(mut self, macro_expansion: MacroExpansion) -> Self {
+        self.macro_expansion = macro_expansion;
+        self
+    }
+
     pub fn set_function_info(&mut self, info: &NameArity) {
         let name = self.db.atom(info.name().clone());
         let arity = info.arity();
@@ -170,10 +207,12 @@ impl<'a> Ctx<'a> {
         record: &Record,
         ast: &ast::RecordDecl,
     ) -> (RecordBody, BodySourceMap) {
+        // The form list skips fields without a name, skip them here too so
+        // that each field body is lowered from its own declaration
         let fields = record
             .fields
             .clone()
-            .zip(ast.fields())
+            .zip(ast.fields().filter(|field| field.name().is_some()))
             .map(|(field_id, field)| {
                 let expr = field
                     .expr()
@@ -277,10 +316,12 @@ impl<'a> Ctx<'a> {
                                 .flat_map(|clause| this.lower_clause_or_macro(clause))
                                 .collect(),
                             // no built-in macro makes sense in this place
-                            MacroReplacement::Ast(_) | MacroReplacement::BuiltIn(_) => vec![],
+                            MacroReplacement::Ast(_) | MacroReplacement::Value(_) => vec![],
                             // args make no sense here
-                            MacroReplacement::AstArgs(_, _)
-                            | MacroReplacement::BuiltInArgs(_, _) => vec![],
+                            MacroReplacement::AstArgs(_, _) | MacroReplacement::ValueArgs(_, _) => {
+                                vec![]
+                            }
+                            MacroReplacement::Opaque => vec![],
                         }
                     })
                     .into_iter()
@@ -451,6 +492,69 @@ impl<'a> Ctx<'a> {
         }
     }
 
+    /// Lower the replacement of a macro call, as given by `resolve_macro`
+    fn lower_pat_macro_replacement(
+        &mut self,
+        expr: &ast::Expr,
+        source: ExprSource,
+        replacement: MacroReplacement,
+    ) -> Option<PatId> {
+        match replacement {
+            MacroReplacement::Value(value) => self
+                .lower_macro_value(value)
+                .map(|literal| {
+                    let pat_id = self.alloc_pat(Pat::Literal(literal), Some(expr));
+                    self.record_pat_source(pat_id, source);
+                    pat_id
+                }),
+            MacroReplacement::Ast(ast::MacroDefReplacement::Expr(macro_expr)) => {
+                let pat_id = self.lower_pat(&macro_expr);
+                self.record_pat_source(pat_id, source);
+                Some(pat_id)
+            }
+            MacroReplacement::Ast(_)
+            // calls are not allowed in patterns
+            | MacroReplacement::ValueArgs(_, _)
+            | MacroReplacement::AstArgs(_, _) => None,
+            MacroReplacement::Opaque => {
+                let pat_id = self.alloc_pat(Pat::Missing, None);
+                self.record_pat_source(pat_id, source);
+                Some(pat_id)
+            }
+        }
+    }
+
+    /// The macro call `expr`, given the lowering of its replacement if any
+    fn alloc_pat_macro_call(
+        &mut self,
+        expr: &ast::Expr,
+        call: &ast::MacroCallExpr,
+        expansion: Option<PatId>,
+    ) -> PatId {
+        match expansion {
+            Some(expansion) => {
+                let args = call
+                    .args()
+                    .iter()
+                    .flat_map(|args| args.args())
+                    .map(|expr| self.lower_optional_expr(expr.expr()))
+                    .collect();
+                self.alloc_pat(Pat::MacroCall { expansion, args }, Some(expr))
+            }
+            None => {
+                let _ = call
+                    .args()
+                    .iter()
+                    .flat_map(|args| args.args())
+                    .for_each(|expr| {
+                        let _ = self.lower_optional_pat(expr.expr());
+                        let _ = self.lower_optional_pat(expr.guard());
+                    });
+                self.alloc_pat(Pat::Missing, Some(expr))
+            }
+        }
+    }
+
     fn lower_pat_max(&mut self, expr_max: &ast::ExprMax, expr: &ast::Expr) -> PatId {
         match expr_max {
             ast::ExprMax::AnonymousFun(fun) => {
@@ -557,6 +661,8 @@ impl<'a> Ctx<'a> {
                     list,
                     |this| this.alloc_pat(Pat::Missing, None),
                     |this, expr| this.lower_pat(expr),
+                    Self::lower_pat_macro_replacement,
+                    Self::alloc_pat_macro_call,
                 );
                 self.alloc_pat(Pat::List { pats, tail }, Some(expr))
             }
@@ -565,47 +671,14 @@ impl<'a> Ctx<'a> {
                 let _ = self.lower_lc_exprs(lc.lc_exprs());
                 self.alloc_pat(Pat::Missing, Some(expr))
             }
-            ast::ExprMax::MacroCallExpr(call) => self
-                .resolve_macro(call, |this, source, replacement| match replacement {
-                    MacroReplacement::BuiltIn(built_in) => this
-                        .lower_built_in_macro(built_in)
-                        .map(|literal| {
-                            let pat_id = this.alloc_pat(Pat::Literal(literal), Some(expr));
-                            this.record_pat_source(pat_id, source);
-                            pat_id
-                        }),
-                    MacroReplacement::Ast(ast::MacroDefReplacement::Expr(macro_expr)) => {
-                        let pat_id = this.lower_pat(&macro_expr);
-                        this.record_pat_source(pat_id, source);
-                        Some(pat_id)
-                    }
-                    MacroReplacement::Ast(_)
-                    // calls are not allowed in patterns
-                    | MacroReplacement::BuiltInArgs(_, _)
-                    | MacroReplacement::AstArgs(_, _) => None,
-                })
-                .flatten()
-                .map(|expansion| {
-                    let args = call
-                        .args()
-                        .iter()
-                        .flat_map(|args| args.args())
-                        .map(|expr| self.lower_optional_expr(expr.expr()))
-                        .collect();
-                    let expr_id = self.alloc_pat(Pat::MacroCall { expansion, args }, Some(expr));
-                    expr_id
-                })
-                .unwrap_or_else(|| {
-                    let _ = call
-                        .args()
-                        .iter()
-                        .flat_map(|args| args.args())
-                        .for_each(|expr| {
-                            let _ = self.lower_optional_pat(expr.expr());
-                            let _ = self.lower_optional_pat(expr.guard());
-                        });
-                    self.alloc_pat(Pat::Missing, Some(expr))
-                }),
+            ast::ExprMax::MacroCallExpr(call) => {
+                let expansion = self
+                    .resolve_macro(call, |this, source, replacement| {
+                        this.lower_pat_macro_replacement(expr, source, replacement)
+                    })
+                    .flatten();
+                self.alloc_pat_macro_call(expr, call, expansion)
+            }
             ast::ExprMax::MacroString(_) => self.alloc_pat(Pat::Missing, Some(expr)),
             ExprMax::MapComprehension(map_comp) => {
                 self.lower_optional_pat(map_comp.expr().and_then(|mf| mf.key()));
@@ -645,10 +718,17 @@ impl<'a> Ctx<'a> {
                 });
                 self.alloc_pat(Pat::Missing, Some(expr))
             }
-            ast::ExprMax::String(str) => {
-                let value = lower_str(str).map_or(Pat::Missing, Pat::Literal);
-                self.alloc_pat(value, Some(expr))
-            }
+            ast::ExprMax::String(str) => match lower_binary_sigil(str) {
+                Some(value) => {
+                    let elem = self.alloc_pat(Pat::Literal(Literal::String(value)), None);
+                    let segs = vec![self.utf8_binary_seg(elem)];
+                    self.alloc_pat(Pat::Binary { segs }, Some(expr))
+                }
+                None => {
+                    let value = lower_str(str).map_or(Pat::Missing, Pat::Literal);
+                    self.alloc_pat(value, Some(expr))
+                }
+            },
             ast::ExprMax::TryExpr(try_expr) => {
                 let _ = try_expr.exprs().for_each(|expr| {
                     self.lower_pat(&expr);
@@ -761,9 +841,18 @@ impl<'a> Ctx<'a> {
                 self.alloc_expr(Expr::Match { lhs, rhs }, Some(expr))
             }
             ast::Expr::Pipe(pipe) => {
-                let _ = self.lower_optional_expr(pipe.lhs());
-                let _ = self.lower_optional_expr(pipe.rhs());
-                self.alloc_expr(Expr::Missing, Some(expr))
+                // Pipes within a list, including macro replacements
+                // `H | T`, are handled by `lower_list`. This is one on its
+                // own, which stands for the cons `[H | T]`
+                let head = self.lower_optional_expr(pipe.lhs());
+                let tail = self.lower_optional_expr(pipe.rhs());
+                self.alloc_expr(
+                    Expr::List {
+                        exprs: vec![head],
+                        tail: Some(tail),
+                    },
+                    Some(expr),
+                )
             }
             ast::Expr::RangeType(range) => {
                 let _ = self.lower_optional_expr(range.lhs());
@@ -781,11 +870,10 @@ impl<'a> Ctx<'a> {
                         Some((name, value))
                     })
                     .collect();
-                if let Some(name) = name {
-                    self.alloc_expr(Expr::Record { name, fields }, Some(expr))
-                } else {
-                    self.alloc_expr(Expr::Missing, Some(expr))
-                }
+                // Keep the fields addressable while the name is being typed,
+                // or when it comes from a macro which can't be resolved
+                let name = name.unwrap_or_else(|| self.db.atom(Name::MISSING));
+                self.alloc_expr(Expr::Record { name, fields }, Some(expr))
             }
             ast::Expr::RecordFieldExpr(field) => {
                 let base = self.lower_optional_expr(field.expr().map(Into::into));
@@ -839,14 +927,14 @@ impl<'a> Ctx<'a> {
                 }
             }
             ast::Expr::Remote(remote) => {
-                let _ = self.lower_optional_expr(
+                let module = self.lower_optional_expr(
                     remote
                         .module()
                         .and_then(|module| module.module())
                         .map(Into::into),
                 );
-                let _ = self.lower_optional_expr(remote.fun().map(Into::into));
-                self.alloc_expr(Expr::Missing, Some(expr))
+                let name = self.lower_optional_expr(remote.fun().map(Into::into));
+                self.alloc_expr(Expr::RemoteRef { module, name }, Some(expr))
             }
             ast::Expr::UnaryOpExpr(unary_op) => {
                 let operand = self.lower_optional_expr(unary_op.operand());
@@ -880,8 +968,8 @@ impl<'a> Ctx<'a> {
             },
             Some(ast::Expr::ExprMax(ast::ExprMax::MacroCallExpr(call))) => self
                 .resolve_macro(call, |this, source, replacement| match replacement {
-                    MacroReplacement::BuiltIn(built_in) => {
-                        this.lower_built_in_macro(built_in).map(|literal| {
+                    MacroReplacement::Value(value) => {
+                        this.lower_macro_value(value).map(|literal| {
                             let name = this.alloc_expr(Expr::Literal(literal), None);
                             this.record_expr_source(name, source);
                             CallTarget::Local { name }
@@ -892,7 +980,12 @@ impl<'a> Ctx<'a> {
                     }
                     MacroReplacement::Ast(_) => None,
                     // This would mean double parens in the call - invalid
-                    MacroReplacement::BuiltInArgs(_, _) | MacroReplacement::AstArgs(_, _) => None,
+                    MacroReplacement::ValueArgs(_, _) | MacroReplacement::AstArgs(_, _) => None,
+                    MacroReplacement::Opaque => {
+                        let name = this.alloc_expr(Expr::Missing, None);
+                        this.record_expr_source(name, source);
+                        Some(CallTarget::Local { name })
+                    }
                 })
                 .flatten()
                 .unwrap_or_else(|| {
@@ -917,6 +1010,88 @@ impl<'a> Ctx<'a> {
         }
     }
 
+    /// Lower the replacement of a macro call, as given by `resolve_macro`
+    fn lower_expr_macro_replacement(
+        &mut self,
+        source: ExprSource,
+        replacement: MacroReplacement,
+    ) -> Option<ExprId> {
+        match replacement {
+            MacroReplacement::Value(value) => self.lower_macro_value(value).map(|literal| {
+                let expr_id = self.alloc_expr(Expr::Literal(literal), None);
+                self.record_expr_source(expr_id, source);
+                expr_id
+            }),
+            MacroReplacement::Ast(ast::MacroDefReplacement::Expr(macro_expr)) => {
+                let expr_id = self.lower_expr(&macro_expr);
+                self.record_expr_source(expr_id, source);
+                Some(expr_id)
+            }
+            MacroReplacement::Ast(_) => None,
+            MacroReplacement::ValueArgs(value, args) => {
+                let name = self
+                    .lower_macro_value(value)
+                    .map(|literal| self.alloc_expr(Expr::Literal(literal), None))
+                    .unwrap_or_else(|| self.alloc_expr(Expr::Missing, None));
+                let target = CallTarget::Local { name };
+                let args = args
+                    .args()
+                    .map(|expr| self.lower_optional_expr(expr.expr()))
+                    .collect();
+                let expr_id = self.alloc_expr(Expr::Call { target, args }, None);
+                self.record_expr_source(expr_id, source);
+                Some(expr_id)
+            }
+            MacroReplacement::AstArgs(ast::MacroDefReplacement::Expr(replacement), args) => {
+                let target = self.lower_call_target(Some(replacement));
+                let args = args
+                    .args()
+                    .map(|expr| self.lower_optional_expr(expr.expr()))
+                    .collect();
+                let expr_id = self.alloc_expr(Expr::Call { target, args }, None);
+                self.record_expr_source(expr_id, source);
+                Some(expr_id)
+            }
+            MacroReplacement::AstArgs(_, _) => None,
+            MacroReplacement::Opaque => {
+                let expr_id = self.alloc_expr(Expr::Missing, None);
+                self.record_expr_source(expr_id, source);
+                Some(expr_id)
+            }
+        }
+    }
+
+    /// The macro call `expr`, given the lowering of its replacement if any
+    fn alloc_expr_macro_call(
+        &mut self,
+        expr: &ast::Expr,
+        call: &ast::MacroCallExpr,
+        expansion: Option<ExprId>,
+    ) -> ExprId {
+        match expansion {
+            Some(expansion) => {
+                let args = call
+                    .args()
+                    .iter()
+                    .flat_map(|args| args.args())
+                    .map(|expr| self.lower_optional_expr(expr.expr()))
+                    .collect();
+                self.alloc_expr(Expr::MacroCall { expansion, args }, Some(expr))
+            }
+            None => {
+                let _ = call
+                    .args()
+                    .iter()
+                    .flat_map(|args| args.args())
+                    .for_each(|expr| {
+                        let _ = self.lower_optional_expr(expr.expr());
+                        let _ = self.lower_optional_expr(expr.guard());
+                    });
+                self.alloc_expr(Expr::Missing, Some(expr))
+            }
+        }
+    }
+
     fn lower_expr_max(&mut self, expr_max: &ast::ExprMax, expr: &ast::Expr) -> ExprId {
         match expr_max {
             ast::ExprMax::AnonymousFun(fun) => {
@@ -1047,6 +1222,10 @@ impl<'a> Ctx<'a> {
                     list,
                     |this| this.alloc_expr(Expr::Missing, None),
                     |this, expr| this.lower_expr(expr),
+                    |this, _expr, source, replacement| {
+                        this.lower_expr_macro_replacement(source, replacement)
+                    },
+                    Self::alloc_expr_macro_call,
                 );
                 self.alloc_expr(Expr::List { exprs, tail }, Some(expr))
             }
@@ -1056,72 +1235,14 @@ impl<'a> Ctx<'a> {
                 let exprs = self.lower_lc_exprs(lc.lc_exprs());
                 self.alloc_expr(Expr::Comprehension { builder, exprs }, Some(expr))
             }
-            ast::ExprMax::MacroCallExpr(call) => self
-                .resolve_macro(call, |this, source, replacement| match replacement {
-                    MacroReplacement::BuiltIn(built_in) => {
-                        this.lower_built_in_macro(built_in).map(|literal| {
-                            let expr_id = this.alloc_expr(Expr::Literal(literal), None);
-                            this.record_expr_source(expr_id, source);
-                            expr_id
-                        })
-                    }
-                    MacroReplacement::Ast(ast::MacroDefReplacement::Expr(macro_expr)) => {
-                        let expr_id = this.lower_expr(&macro_expr);
-                        this.record_expr_source(expr_id, source);
-                        Some(expr_id)
-                    }
-                    MacroReplacement::Ast(_) => None,
-                    MacroReplacement::BuiltInArgs(built_in, args) => {
-                        let name = this
-                            .lower_built_in_macro(built_in)
-                            .map(|literal| this.alloc_expr(Expr::Literal(literal), None))
-                            .unwrap_or_else(|| this.alloc_expr(Expr::Missing, None));
-                        let target = CallTarget::Local { name };
-                        let args = args
-                            .args()
-                            .map(|expr| this.lower_optional_expr(expr.expr()))
-                            .collect();
-                        let expr_id = this.alloc_expr(Expr::Call { target, args }, None);
-                        this.record_expr_source(expr_id, source);
-                        Some(expr_id)
-                    }
-                    MacroReplacement::AstArgs(
-                        ast::MacroDefReplacement::Expr(replacement),
-                        args,
-                    ) => {
-                        let target = this.lower_call_target(Some(replacement));
-                        let args = args
-                            .args()
-                            .map(|expr| this.lower_optional_expr(expr.expr()))
-                            .collect();
-                        let expr_id = this.alloc_expr(Expr::Call { target, args }, None);
-                        this.record_expr_source(expr_id, source);
-                        Some(expr_id)
-                    }
-                    MacroReplacement::AstArgs(_, _) => None,
-                })
-                .flatten()
-                .map(|expansion| {
-                    let args = call
-                        .args()
-                        .iter()
-                        .flat_map(|args| args.args())
-                        .map(|expr| self.lower_optional_expr(expr.expr()))
-                        .collect();
-                    let expr_id = self.alloc_expr(Expr::MacroCall { expansion, args }, Some(expr));
-                    expr_id
-                })
-                .unwrap_or_else(|| {
-                    let _ = call
-                        .args()
-                        .iter()
-                        .flat_map(|args| args.args())
-                        .for_each(|expr| {
-                            let _ = self.lower_optional_expr(expr.expr());
-                            let _ = self.lower_optional_expr(expr.guard());
-                        });
-                    self.alloc_expr(Expr::Missing, Some(expr))
-                }),
+            ast::ExprMax::MacroCallExpr(call) => {
+                let expansion = self
+                    .resolve_macro(call, |this, source, replacement| {
+                        this.lower_expr_macro_replacement(source, replacement)
+                    })
+                    .flatten();
+                self.alloc_expr_macro_call(expr, call, expansion)
+            }
             ast::ExprMax::MacroString(_) => self.alloc_expr(Expr::Missing, Some(expr)),
             ast::ExprMax::ParenExpr(paren_expr) => {
                 if let Some(paren_expr) = paren_expr.expr() {
@@ -1141,15 +1262,27 @@ impl<'a> Ctx<'a> {
                     .collect();
                 let after = receive.after().map(|after| {
                     let timeout = self.lower_optional_expr(after.expr());
+                    let kind = self.receive_timeout(timeout);
                     let exprs = self.lower_clause_body(after.body());
-                    ReceiveAfter { timeout, exprs }
+                    ReceiveAfter {
+                        timeout,
+                        kind,
+                        exprs,
+                    }
                 });
                 self.alloc_expr(Expr::Receive { clauses, after }, Some(expr))
             }
-            ast::ExprMax::String(str) => {
-                let value = lower_str(str).map_or(Expr::Missing, Expr::Literal);
-                self.alloc_expr(value, Some(expr))
-            }
+            ast::ExprMax::String(str) => match lower_binary_sigil(str) {
+                Some(value) => {
+                    let elem = self.alloc_expr(Expr::Literal(Literal::String(value)), None);
+                    let segs = vec![self.utf8_binary_seg(elem)];
+                    self.alloc_expr(Expr::Binary { segs }, Some(expr))
+                }
+                None => {
+                    let value = lower_str(str).map_or(Expr::Missing, Expr::Literal);
+                    self.alloc_expr(value, Some(expr))
+                }
+            },
             ast::ExprMax::TryExpr(try_expr) => {
                 let exprs = try_expr
                     .exprs()
@@ -1259,36 +1392,81 @@ impl<'a> Ctx<'a> {
         }
     }
 
+    /// Lower the elements and tail of a list. Each macro call is expanded
+    /// once: a replacement `H | T` is spliced into the list, any other one
+    /// is lowered with `lower_macro_replacement` and `alloc_macro_call`,
+    /// as `lower` would.
     fn lower_list<Id>(
         &mut self,
         list: &ast::List,
         make_missing: impl Fn(&mut Self) -> Id,
         lower: impl Fn(&mut Self, &ast::Expr) -> Id,
+        lower_macro_replacement: impl Fn(
+            &mut Self,
+            &ast::Expr,
+            ExprSource,
+            MacroReplacement,
+        ) -> Option<Id>,
+        alloc_macro_call: impl Fn(&mut Self, &ast::Expr, &ast::MacroCallExpr, Option<Id>) -> Id,
     ) -> (Vec<Id>, Option<Id>) {
         let mut tail = None;
         let mut ids = vec![];
 
         for expr in list.exprs() {
-            if let ast::Expr::Pipe(pipe) = &expr {
-                let id = pipe
-                    .lhs()
-                    .map(|expr| lower(self, &expr))
-                    .unwrap_or_else(|| make_missing(self));
-                ids.push(id);
-
-                if let Some(tail) = tail {
-                    // TODO: add error
-                    ids.push(tail)
+            let (id, pipe_tail) = match &expr {
+                ast::Expr::Pipe(pipe) => self.lower_pipe(pipe, &make_missing, &lower),
+                // A macro replacement `H | T` is spliced into the list, so
+                // that `[A, ?CONS(H, T)]` stands for `[A, H | T]`
+                ast::Expr::ExprMax(ast::ExprMax::MacroCallExpr(call)) => {
+                    let lowered =
+                        self.resolve_macro(call, |this, source, replacement| match replacement {
+                            MacroReplacement::Ast(ast::MacroDefReplacement::Expr(
+                                ast::Expr::Pipe(pipe),
+                            )) => Ok(this.lower_pipe(&pipe, &make_missing, &lower)),
+                            replacement => {
+                                Err(lower_macro_replacement(this, &expr, source, replacement))
+                            }
+                        });
+                    match lowered {
+                        Some(Ok(pipe)) => pipe,
+                        lowered => {
+                            let expansion = lowered.and_then(|lowered| lowered.err()).flatten();
+                            ids.push(alloc_macro_call(self, &expr, call, expansion));
+                            continue;
+                        }
+                    }
                 }
-                tail = pipe.rhs().map(|expr| lower(self, &expr));
-            } else {
-                ids.push(lower(self, &expr));
+                _ => {
+                    ids.push(lower(self, &expr));
+                    continue;
+                }
+            };
+            ids.push(id);
+
+            if let Some(tail) = tail {
+                // TODO: add error
+                ids.push(tail)
             }
+            tail = pipe_tail;
         }
 
         (ids, tail)
     }
 
+    fn lower_pipe<Id>(
+        &mut self,
+        pipe: &ast::Pipe,
+        make_missing: &impl Fn(&mut Self) -> Id,
+        lower: &impl Fn(&mut Self, &ast::Expr) -> Id,
+    ) -> (Id, Option<Id>) {
+        let id = pipe
+            .lhs()
+            .map(|expr| lower(self, &expr))
+            .unwrap_or_else(|| make_missing(self));
+        let tail = pipe.rhs().map(|expr| lower(self, &expr));
+        (id, tail)
+    }
+
     fn lower_bin_element<Id>(
         &mut self,
         element: &ast::BinElement,
@@ -1341,10 +1519,12 @@ impl<'a> Ctx<'a> {
                                 .flat_map(|clause| this.lower_cr_clause(clause))
                                 .collect(),
                             // no built-in macro makes sense in this place
-                            MacroReplacement::Ast(_) | MacroReplacement::BuiltIn(_) => vec![],
+                            MacroReplacement::Ast(_) | MacroReplacement::Value(_) => vec![],
                             // args make no sense here
-                            MacroReplacement::AstArgs(_, _)
-                            | MacroReplacement::BuiltInArgs(_, _) => vec![],
+                            MacroReplacement::AstArgs(_, _) | MacroReplacement::ValueArgs(_, _) => {
+                                vec![]
+                            }
+                            MacroReplacement::Opaque => vec![],
                         }
                     })
                     .into_iter()
@@ -1354,6 +1534,9 @@ impl<'a> Ctx<'a> {
         }
     }
 
+    /// A guard sequence is lowered to its `;`-separated alternatives, each
+    /// of them being the `,`-separated tests that must all succeed: `when
+    /// A, B; C` is lowered to `[[A, B], [C]]`.
     fn lower_guards(&mut self, guards: Option<ast::Guard>) -> Vec<Vec<ExprId>> {
         guards
             .iter()
@@ -1559,8 +1742,8 @@ impl<'a> Ctx<'a> {
             },
             Some(ast::Expr::ExprMax(ast::ExprMax::MacroCallExpr(call))) => self
                 .resolve_macro(call, |this, source, replacement| match replacement {
-                    MacroReplacement::BuiltIn(built_in) => {
-                        this.lower_built_in_macro(built_in).map(|literal| {
+                    MacroReplacement::Value(value) => {
+                        this.lower_macro_value(value).map(|literal| {
                             let name = this.alloc_type_expr(TypeExpr::Literal(literal), None);
                             this.record_type_source(name, source);
                             CallTarget::Local { name }
@@ -1571,7 +1754,12 @@ impl<'a> Ctx<'a> {
                     }
                     MacroReplacement::Ast(_) => None,
                     // This would mean double parens in the call - invalid
-                    MacroReplacement::BuiltInArgs(_, _) | MacroReplacement::AstArgs(_, _) => None,
+                    MacroReplacement::ValueArgs(_, _) | MacroReplacement::AstArgs(_, _) => None,
+                    MacroReplacement::Opaque => {
+                        let name = this.alloc_type_expr(TypeExpr::Missing, None);
+                        this.record_type_source(name, source);
+                        Some(CallTarget::Local { name })
+                    }
                 })
                 .flatten()
                 .unwrap_or_else(|| {
@@ -1603,7 +1791,25 @@ impl<'a> Ctx<'a> {
                 let atom = self.db.atom(atom.as_name());
                 self.alloc_type_expr(TypeExpr::Literal(Literal::Atom(atom)), Some(expr))
             }
-            ast::ExprMax::Binary(_bin) => self.alloc_type_expr(TypeExpr::Missing, Some(expr)),
+            ast::ExprMax::Binary(bin) => {
+                let mut base_size = None;
+                let mut unit = None;
+                for element in bin.elements() {
+                    let size = element
+                        .size()
+                        .and_then(|size| size.size())
+                        .map(ast::Expr::from);
+                    match size {
+                        // `_:_*N`
+                        Some(ast::Expr::BinaryOpExpr(op)) if is_unit_size(&op) => {
+                            unit = Some(self.lower_optional_type_expr(op.rhs()));
+                        }
+                        Some(size) => base_size = Some(self.lower_type_expr(&size)),
+                        None => {}
+                    }
+                }
+                self.alloc_type_expr(TypeExpr::Binary { base_size, unit }, Some(expr))
+            }
             ast::ExprMax::BinaryComprehension(_bc) => {
                 self.alloc_type_expr(TypeExpr::Missing, Some(expr))
             }
@@ -1663,8 +1869,8 @@ impl<'a> Ctx<'a> {
             }
             ast::ExprMax::MacroCallExpr(call) => self
                 .resolve_macro(call, |this, source, replacement| match replacement {
-                    MacroReplacement::BuiltIn(built_in) => {
-                        this.lower_built_in_macro(built_in).map(|literal| {
+                    MacroReplacement::Value(value) => {
+                        this.lower_macro_value(value).map(|literal| {
                             let type_id = this.alloc_type_expr(TypeExpr::Literal(literal), None);
                             this.record_type_source(type_id, source);
                             type_id
@@ -1676,9 +1882,9 @@ impl<'a> Ctx<'a> {
                         Some(type_id)
                     }
                     MacroReplacement::Ast(_) => None,
-                    MacroReplacement::BuiltInArgs(built_in, args) => {
+                    MacroReplacement::ValueArgs(value, args) => {
                         let name = this
-                            .lower_built_in_macro(built_in)
+                            .lower_macro_value(value)
                             .map(|literal| this.alloc_type_expr(TypeExpr::Literal(literal), None))
                             .unwrap_or_else(|| this.alloc_type_expr(TypeExpr::Missing, None));
                         let target = CallTarget::Local { name };
@@ -1704,6 +1910,11 @@ impl<'a> Ctx<'a> {
                         Some(type_id)
                     }
                     MacroReplacement::AstArgs(_, _) => None,
+                    MacroReplacement::Opaque => {
+                        let type_id = this.alloc_type_expr(TypeExpr::Missing, None);
+                        this.record_type_source(type_id, source);
+                        Some(type_id)
+                    }
                 })
                 .flatten()
                 .map(|expansion| {
@@ -1909,6 +2120,63 @@ impl<'a> Ctx<'a> {
         }
     }
 
+    /// Lower the replacement of a macro call, as given by `resolve_macro`
+    fn lower_term_macro_replacement(
+        &mut self,
+        source: ExprSource,
+        replacement: MacroReplacement,
+    ) -> Option<TermId> {
+        match replacement {
+            MacroReplacement::Value(value) => self.lower_macro_value(value).map(|literal| {
+                let term_id = self.alloc_term(Term::Literal(literal), None);
+                self.record_term_source(term_id, source);
+                term_id
+            }),
+            MacroReplacement::Ast(ast::MacroDefReplacement::Expr(macro_expr)) => {
+                let term_id = self.lower_term(&macro_expr);
+                self.record_term_source(term_id, source);
+                Some(term_id)
+            }
+            MacroReplacement::Opaque => {
+                let term_id = self.alloc_term(Term::Missing, None);
+                self.record_term_source(term_id, source);
+                Some(term_id)
+            }
+            _ => None,
+        }
+    }
+
+    /// The macro call `expr`, given the lowering of its replacement if any
+    fn alloc_term_macro_call(
+        &mut self,
+        expr: &ast::Expr,
+        call: &ast::MacroCallExpr,
+        expansion: Option<TermId>,
+    ) -> TermId {
+        match expansion {
+            Some(expansion) => {
+                let args = call
+                    .args()
+                    .iter()
+                    .flat_map(|args| args.args())
+                    .map(|expr| self.lower_optional_expr(expr.expr()))
+                    .collect();
+                self.alloc_term(Term::MacroCall { expansion, args }, Some(expr))
+            }
+            None => {
+                let _ = call
+                    .args()
+                    .iter()
+                    .flat_map(|args| args.args())
+                    .for_each(|expr| {
+                        let _ = self.lower_optional_term(expr.expr());
+                        let _ = self.lower_optional_term(expr.guard());
+                    });
+                self.alloc_term(Term::Missing, Some(expr))
+            }
+        }
+    }
+
     fn lower_term_max(&mut self, expr_max: &ast::ExprMax, expr: &ast::Expr) -> TermId {
         match expr_max {
             ast::ExprMax::AnonymousFun(_fun) => self.alloc_term(Term::Missing, Some(expr)),
@@ -2027,48 +2295,22 @@ impl<'a> Ctx<'a> {
                     list,
                     |this| this.alloc_term(Term::Missing, None),
                     |this, expr| this.lower_term(expr),
+                    |this, _expr, source, replacement| {
+                        this.lower_term_macro_replacement(source, replacement)
+                    },
+                    Self::alloc_term_macro_call,
                 );
                 self.alloc_term(Term::List { exprs, tail }, Some(expr))
             }
             ast::ExprMax::ListComprehension(_lc) => self.alloc_term(Term::Missing, Some(expr)),
-            ast::ExprMax::MacroCallExpr(call) => self
-                .resolve_macro(call, |this, source, replacement| match replacement {
-                    MacroReplacement::BuiltIn(built_in) => {
-                        this.lower_built_in_macro(built_in).map(|literal| {
-                            let term_id = this.alloc_term(Term::Literal(literal), None);
-                            this.record_term_source(term_id, source);
-                            term_id
-                        })
-                    }
-                    MacroReplacement::Ast(ast::MacroDefReplacement::Expr(macro_expr)) => {
-                        let term_id = this.lower_term(&macro_expr);
-                        this.record_term_source(term_id, source);
-                        Some(term_id)
-                    }
-                    _ => None,
-                })
-                .flatten()
-                .map(|expansion| {
-                    let args = call
-                        .args()
-                        .iter()
-                        .flat_map(|args| args.args())
-                        .map(|expr| self.lower_optional_expr(expr.expr()))
-                        .collect();
-                    let expr_id = self.alloc_term(Term::MacroCall { expansion, args }, Some(expr));
-                    expr_id
-                })
-                .unwrap_or_else(|| {
-                    let _ = call
-                        .args()
-                        .iter()
-                        .flat_map(|args| args.args())
-                        .for_each(|expr| {
-                            let _ = self.lower_optional_term(expr.expr());
-                            let _ = self.lower_optional_term(expr.guard());
-                        });
-                    self.alloc_term(Term::Missing, Some(expr))
-                }),
+            ast::ExprMax::MacroCallExpr(call) => {
+                let expansion = self
+                    .resolve_macro(call, |this, source, replacement| {
+                        this.lower_term_macro_replacement(source, replacement)
+                    })
+                    .flatten();
+                self.alloc_term_macro_call(expr, call, expansion)
+            }
             ast::ExprMax::MacroString(_) => self.alloc_term(Term::Missing, Some(expr)),
             ast::ExprMax::ParenExpr(paren_expr) => {
                 if let Some(expr) = paren_expr.expr() {
@@ -2079,7 +2321,10 @@ impl<'a> Ctx<'a> {
             }
             ast::ExprMax::ReceiveExpr(_receive) => self.alloc_term(Term::Missing, Some(expr)),
             ast::ExprMax::String(str) => {
-                let value = lower_str(str).map_or(Term::Missing, Term::Literal);
+                let value = match lower_binary_sigil(str) {
+                    Some(value) => Term::Binary(value.into_bytes()),
+                    None => lower_str(str).map_or(Term::Missing, Term::Literal),
+                };
                 self.alloc_term(value, Some(expr))
             }
             ast::ExprMax::TryExpr(_try_expr) => self.alloc_term(Term::Missing, Some(expr)),
@@ -2105,37 +2350,26 @@ impl<'a> Ctx<'a> {
         }
     }
 
-    fn lower_built_in_macro(&mut self, built_in: BuiltInMacro) -> Option<Literal> {
-        match built_in {
-            // This is a bit of a hack, but allows us not to depend on the file system
-            // It somewhat replicates the behaviour of -deterministic option
-            BuiltInMacro::FILE => {
-                let form_list = self.db.file_form_list(self.original_file_id);
-                form_list
-                    .module_attribute()
-                    .map(|attr| Literal::String(format!("{}.erl", attr.name)))
-            }
-            BuiltInMacro::FUNCTION_NAME => self.function_info.map(|(name, _)| Literal::Atom(name)),
-            BuiltInMacro::FUNCTION_ARITY => self
-                .function_info
-                .map(|(_, arity)| Literal::Integer(arity as i128)),
-            // Dummy value, we don't want to depend on the exact position
-            BuiltInMacro::LINE => Some(Literal::Integer(0)),
-            BuiltInMacro::MODULE => {
-                let form_list = self.db.file_form_list(self.original_file_id);
-                form_list
-                    .module_attribute()
-                    .map(|attr| Literal::Atom(self.db.atom(attr.name.clone())))
-            }
-            BuiltInMacro::MODULE_STRING => {
-                let form_list = self.db.file_form_list(self.original_file_id);
-                form_list
-                    .module_attribute()
-                    .map(|attr| Literal::String(attr.name.to_string()))
-            }
-            BuiltInMacro::MACHINE => Some(Literal::Atom(self.db.atom(known::ELP))),
-            // Dummy value, must be an integer
-            BuiltInMacro::OTP_RELEASE => Some(Literal::Integer(2000)),
+    fn lower_macro_value(&mut self, value: MacroValue) -> Option<Literal> {
+        match value {
+            MacroValue::BuiltIn(built_in) => macro_exp::built_in_macro_value(
+                self.db,
+                self.original_file_id,
+                built_in,
+                self.function_info,
+            ),
+            MacroValue::Predefined(name) => {
+                macro_exp::predefined_macro_value(self.db, self.original_file_id, name)
+            }
+        }
+    }
+
+    fn utf8_binary_seg<T>(&self, elem: T) -> BinarySeg<T> {
+        BinarySeg {
+            elem,
+            size: None,
+            tys: vec![self.db.atom(known::utf8)],
+            unit: None,
         }
     }
 
@@ -2170,9 +2404,19 @@ impl<'a> Ctx<'a> {
         let source = InFileAstPtr::new(self.curr_file_id(), AstPtr::new(call).cast().unwrap());
 
         match self.db.resolve_macro(self.original_file_id, name.clone()) {
+            Some(res) if !self.should_expand(res) => {
+                self.record_macro_resolution(call, res);
+                Some(cb(self, source, MacroReplacement::Opaque))
+            }
             Some(res @ ResolvedMacro::BuiltIn(built_in)) => {
                 self.record_macro_resolution(call, res);
-                Some(cb(self, source, MacroReplacement::BuiltIn(built_in)))
+                let value = MacroValue::BuiltIn(built_in);
+                Some(cb(self, source, MacroReplacement::Value(value)))
+            }
+            Some(res @ ResolvedMacro::Predefined(name)) => {
+                self.record_macro_resolution(call, res);
+                let value = MacroValue::Predefined(name);
+                Some(cb(self, source, MacroReplacement::Value(value)))
             }
             Some(res @ ResolvedMacro::User(def_idx)) => {
                 self.record_macro_resolution(call, res);
@@ -2185,11 +2429,19 @@ impl<'a> Ctx<'a> {
                 let args = call.args()?;
                 let res = self.db.resolve_macro(self.original_file_id, name.clone())?;
                 self.record_macro_resolution(call, res);
+                if !self.should_expand(res) {
+                    return Some(cb(self, source, MacroReplacement::Opaque));
+                }
                 match res {
                     ResolvedMacro::BuiltIn(built_in) => Some(cb(
                         self,
                         source,
-                        MacroReplacement::BuiltInArgs(built_in, args),
+                        MacroReplacement::ValueArgs(MacroValue::BuiltIn(built_in), args),
+                    )),
+                    ResolvedMacro::Predefined(name) => Some(cb(
+                        self,
+                        source,
+                        MacroReplacement::ValueArgs(MacroValue::Predefined(name), args),
                     )),
                     ResolvedMacro::User(def_idx) => {
                         self.enter_macro(name, def_idx, None, |this, replacement| {
@@ -2201,6 +2453,16 @@ impl<'a> Ctx<'a> {
         }
     }
 
+    fn should_expand(&self, resolved: ResolvedMacro) -> bool {
+        match (self.macro_expansion, resolved) {
+            (MacroExpansion::All, _) => true,
+            (MacroExpansion::BuiltInOnly, ResolvedMacro::BuiltIn(_)) => true,
+            (MacroExpansion::BuiltInOnly, ResolvedMacro::Predefined(_)) => false,
+            (MacroExpansion::BuiltInOnly, ResolvedMacro::User(_)) => false,
+            (MacroExpansion::None, _) => false,
+        }
+    }
+
     fn enter_macro<R>(
         &mut self,
         name: MacroName,
@@ -2271,6 +2533,17 @@ impl<'a> Ctx<'a> {
         }
     }
 
+    fn receive_timeout(&self, timeout: ExprId) -> ReceiveTimeout {
+        match &self.body.exprs[timeout] {
+            Expr::Literal(Literal::Atom(atom)) if *atom == self.db.atom(known::infinity) => {
+                ReceiveTimeout::Infinity
+            }
+            Expr::Literal(Literal::Integer(value)) => ReceiveTimeout::Integer(*value),
+            Expr::MacroCall { expansion, .. } => self.receive_timeout(*expansion),
+            _ => ReceiveTimeout::Dynamic,
+        }
+    }
+
     fn alloc_expr(&mut self, expr: Expr, source: Option<&ast::Expr>) -> ExprId {
         let expr_id = self.body.exprs.alloc(expr);
         if let Some(source) = source {
@@ -2342,6 +2615,19 @@ impl<'a> Ctx<'a> {
     }
 }
 
+/// The literal `expr` is, without resolving any macros.
+pub(crate) fn lower_literal(db: &dyn MinDefDatabase, expr: &ast::Expr) -> Option<Literal> {
+    match expr {
+        ast::Expr::ExprMax(ExprMax::Atom(atom)) => Some(Literal::Atom(db.atom(atom.as_name()))),
+        ast::Expr::ExprMax(ExprMax::Char(char)) => lower_char(char),
+        ast::Expr::ExprMax(ExprMax::Concatables(concat)) => lower_concat(concat),
+        ast::Expr::ExprMax(ExprMax::Float(float)) => lower_float(float),
+        ast::Expr::ExprMax(ExprMax::Integer(int)) => lower_int(int),
+        ast::Expr::ExprMax(ExprMax::String(str)) => lower_str(str),
+        _ => None,
+    }
+}
+
 fn lower_char(char: &ast::Char) -> Option<Literal> {
     unescape::unescape_string(&char.text())
         .and_then(|str| str.chars().next())
@@ -2368,9 +2654,20 @@ fn lower_int(int: &ast::Integer) -> Option<Literal> {
 }
 
 fn lower_str(str: &ast::String) -> Option<Literal> {
-    Some(Literal::String(
-        unescape::unescape_string(&str.text())?.to_string(),
-    ))
+    match unescape::unescape_sigil_string(&str.text())? {
+        // Binaries are not literals, see `lower_binary_sigil`
+        (Some(sigil), _) if sigil.is_binary() => None,
+        (_, value) => Some(Literal::String(value.to_string())),
+    }
+}
+
+/// The content of a `~b"..."`, `~B"..."` or `~"..."` sigil string, which
+/// denotes a utf8 binary
+fn lower_binary_sigil(str: &ast::String) -> Option<String> {
+    match unescape::unescape_sigil_string(&str.text())? {
+        (Some(sigil), value) if sigil.is_binary() => Some(value.to_string()),
+        _ => None,
+    }
 }
 
 fn lower_concat(concat: &ast::Concatables) -> Option<Literal> {
@@ -2381,10 +2678,27 @@ fn lower_concat(concat: &ast::Concatables) -> Option<Literal> {
         match concatable {
             ast::Concatable::MacroCallExpr(_) => return None,
             ast::Concatable::MacroString(_) => return None,
-            ast::Concatable::String(str) => buf.push_str(&unescape::unescape_string(&str.text())?),
+            ast::Concatable::String(str) => match unescape::unescape_sigil_string(&str.text())? {
+                (None, value) => buf.push_str(&value),
+                // Sigil strings can't be concatenated
+                (Some(_), _) => return None,
+            },
             ast::Concatable::Var(_) => return None,
         }
     }
 
     Some(Literal::String(buf))
 }
+
+/// The `_*N` size of a binary type segment, giving its unit
+fn is_unit_size(size: &ast::BinaryOpExpr) -> bool {
+    let is_mul = matches!(
+        size.op(),
+        Some((ast::BinaryOp::ArithOp(ast::ArithOp::Mul), _))
+    );
+    let is_wildcard = match size.lhs() {
+        Some(ast::Expr::ExprMax(ast::ExprMax::Var(var))) => var.as_name() == Name::ANONYMOUS,
+        _ => false,
+    };
+    is_mul && is_wildcard
+}
@@ -321,7 +321,7 @@ impl<'a> Printer<'a> {
                 sep = ", ";
                 self.print_type(&self.body[*ty])?;
             }
-            self.indent_level += 1;
+            self.indent_level -= 1;
         }
         Ok(())
     }
@@ -466,6 +466,11 @@ impl<'a> Printer<'a> {
                 write!(self, "/")?;
                 self.print_expr(&self.body[*arity])
             }
+            Expr::RemoteRef { module, name } => {
+                self.print_expr(&self.body[*module])?;
+                write!(self, ":")?;
+                self.print_expr(&self.body[*name])
+            }
             Expr::If { clauses } => {
                 self.print_seq(clauses, None, "if", "end", ";", |this, clause| {
                     this.print_guards(&clause.guards, false)?;
@@ -648,6 +653,21 @@ impl<'a> Printer<'a> {
                 self.print_type(&self.body[*ty])?;
                 write!(self, ")")
             }
+            TypeExpr::Binary { base_size, unit } => {
+                write!(self, "<<")?;
+                if let Some(base_size) = base_size {
+                    write!(self, "_:")?;
+                    self.print_type(&self.body[*base_size])?;
+                }
+                if let Some(unit) = unit {
+                    if base_size.is_some() {
+                        write!(self, ", ")?;
+                    }
+                    write!(self, "_:_*")?;
+                    self.print_type(&self.body[*unit])?;
+                }
+                write!(self, ">>")
+            }
             TypeExpr::BinaryOp { lhs, rhs, op } => {
                 write!(self, "(")?;
                 self.print_type(&self.body[*lhs])?;
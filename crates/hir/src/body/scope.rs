@@ -66,6 +66,10 @@ pub struct ExprScopes {
     scopes: Arena<ScopeData>,
     scope_by_expr: FxHashMap<ExprId, ScopeId>,
     scope_by_pat: FxHashMap<PatId, ScopeId>,
+    /// Variables bound in a comprehension generator or a fun head that
+    /// shadow a variable of the enclosing scope, with the definitions
+    /// they shadow.
+    shadowing: FxHashMap<PatId, Vec<PatId>>,
     // Interned value of the anonymous variable ('_').
     anonymous_var: Var,
 }
@@ -87,9 +91,19 @@ impl IndexMut<ScopeId> for ExprScopes {
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct ScopeData {
     parent: Option<ScopeId>,
+    kind: ScopeKind,
     entries: ScopeEntries,
 }
 
+/// Comprehensions and funs introduce a scope of their own: variables bound
+/// inside them are not visible in the enclosing clause once they end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    Clause,
+    Comprehension,
+    Fun,
+}
+
 type ScopeEntryMap = FxHashMap<Var, Vec<PatId>>;
 #[derive(Debug, PartialEq, Eq, Default, Clone)]
 pub struct ScopeEntries {
@@ -203,11 +217,18 @@ impl ExprScopes {
             scopes: Arena::default(),
             scope_by_expr: FxHashMap::default(),
             scope_by_pat: FxHashMap::default(),
+            shadowing: FxHashMap::default(),
             anonymous_var,
         };
         let mut root = scopes.root_scope();
         let mut vt = VarTable::default();
-        scopes.add_params_bindings(&body.body, &mut root, &clause.pats, &mut vt);
+        scopes.add_params_bindings(
+            &body.body,
+            &mut root,
+            &clause.pats,
+            &mut vt,
+            AddBinding::IfUnused,
+        );
         for exprs in &clause.guards {
             for expr_id in exprs {
                 compute_expr_scopes(*expr_id, &body.body, &mut scopes, &mut root, &mut vt);
@@ -235,16 +256,42 @@ impl ExprScopes {
         self.scope_by_pat.get(&pat_id).copied()
     }
 
+    /// Whether `scope` is inside a comprehension or a fun, so that the
+    /// variables bound in it do not escape to the enclosing clause.
+    pub fn is_local(&self, scope: ScopeId) -> bool {
+        self.scope_chain(Some(scope))
+            .any(|scope| self.scopes[scope].kind != ScopeKind::Clause)
+    }
+
+    /// The definitions shadowed by `pat_id`, if it is a variable bound in
+    /// a comprehension generator or a fun head, and a variable of the same
+    /// name is already bound in an enclosing scope.
+    pub fn shadowed_by(&self, pat_id: PatId) -> Option<&Vec<PatId>> {
+        self.shadowing.get(&pat_id)
+    }
+
+    /// All variables in the clause that shadow a variable of an enclosing
+    /// scope, see `shadowed_by`.
+    pub fn shadowing(&self) -> impl Iterator<Item = (PatId, &Vec<PatId>)> + '_ {
+        self.shadowing.iter().map(|(pat_id, pats)| (*pat_id, pats))
+    }
+
     fn root_scope(&mut self) -> ScopeId {
         self.scopes.alloc(ScopeData {
             parent: None,
+            kind: ScopeKind::Clause,
             entries: ScopeEntries::default(),
         })
     }
 
     fn new_scope(&mut self, parent: ScopeId) -> ScopeId {
+        self.new_scope_of_kind(parent, ScopeKind::Clause)
+    }
+
+    fn new_scope_of_kind(&mut self, parent: ScopeId, kind: ScopeKind) -> ScopeId {
         self.scopes.alloc(ScopeData {
             parent: Some(parent),
+            kind,
             entries: ScopeEntries::default(),
         })
     }
@@ -280,6 +327,14 @@ impl ExprScopes {
                         }
                     }
                     if add_bindings != AddBinding::IfUnused {
+                        // Always a fresh binding, shadowing any outer one
+                        let shadowed = self
+                            .scope_chain(self.scopes[*scope].parent)
+                            .find_map(|scope| self.scopes[scope].entries.lookup(var))
+                            .cloned();
+                        if let Some(shadowed) = shadowed {
+                            self.shadowing.insert(pat, shadowed);
+                        }
                         self.scopes[*scope].entries.insert(*var, vec![pat]);
                     }
                 }
@@ -341,10 +396,11 @@ impl ExprScopes {
         scope: &mut ScopeId,
         params: &[PatId],
         vt: &mut VarTable,
+        add_bindings: AddBinding,
     ) {
         params
             .iter()
-            .for_each(|pat| self.add_bindings(body, scope, *pat, vt, AddBinding::IfUnused));
+            .for_each(|pat| self.add_bindings(body, scope, *pat, vt, add_bindings));
     }
 }
 
@@ -454,7 +510,7 @@ fn compute_expr_scopes(
         }
         crate::Expr::Comprehension { builder, exprs } => {
             let mut sub_vt = vt.clone();
-            let scope = &mut scopes.new_scope(*scope);
+            let scope = &mut scopes.new_scope_of_kind(*scope, ScopeKind::Comprehension);
             for expr in exprs {
                 match expr {
                     crate::ComprehensionExpr::BinGenerator { pat, expr } => {
@@ -593,14 +649,28 @@ fn compute_expr_scopes(
             };
             compute_expr_scopes(*arity, body, scopes, scope, vt);
         }
+        crate::Expr::RemoteRef { module, name } => {
+            compute_expr_scopes(*module, body, scopes, scope, vt);
+            compute_expr_scopes(*name, body, scopes, scope, vt);
+        }
         crate::Expr::Closure { clauses, name } => {
+            // The name of a named fun, like its parameters, is only bound
+            // inside the fun, and shadows any variable of the same name
+            let mut fun_scope = scopes.new_scope_of_kind(*scope, ScopeKind::Fun);
+            let mut fun_vt = vt.clone();
             if let Some(name) = name {
-                scopes.add_bindings(body, scope, *name, vt, AddBinding::Always);
+                scopes.add_bindings(body, &mut fun_scope, *name, &mut fun_vt, AddBinding::Always);
             }
             for clause in clauses.iter() {
-                let mut sub_vt = vt.clone();
-                let mut scope = scopes.new_scope(*scope);
-                scopes.add_params_bindings(body, &mut scope, &clause.pats, &mut sub_vt);
+                let mut sub_vt = fun_vt.clone();
+                let mut scope = scopes.new_scope(fun_scope);
+                scopes.add_params_bindings(
+                    body,
+                    &mut scope,
+                    &clause.pats,
+                    &mut sub_vt,
+                    AddBinding::Always,
+                );
 
                 for guards in &clause.guards {
                     for guard in guards {
@@ -798,4 +868,113 @@ mod tests {
             &["X"],
         );
     }
+
+    #[track_caller]
+    fn check_shadowing(fixture: &str, expected: &[&str]) {
+        let (db, file_id) = TestDB::with_single_file(fixture);
+        let function_id = InFile::new(file_id, find_function(&db, file_id));
+        let body = db.function_body(function_id);
+        let scopes = db.function_scopes(function_id);
+        let mut actual = body
+            .clauses
+            .iter()
+            .flat_map(|(clause_id, _clause)| {
+                let clause_scopes = scopes.get(clause_id).unwrap();
+                clause_scopes
+                    .shadowing()
+                    .map(|(pat_id, _shadowed)| match &body.body[pat_id] {
+                        crate::Pat::Var(var) => db.lookup_var(*var).as_str().to_string(),
+                        pat => panic!("expected a variable, got {:?}", pat),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        actual.sort();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_comprehension_var_does_not_escape() {
+        do_check(
+            r"
+            f() ->
+              [X || X <- [1, 2]],
+              ~.
+            ",
+            &[],
+        );
+    }
+
+    #[test]
+    fn test_comprehension_generator_scope() {
+        do_check(
+            r"
+            f(L) ->
+              [~ || X <- L].
+            ",
+            &["X", "L"],
+        );
+    }
+
+    #[test]
+    fn test_fun_param_scope() {
+        do_check(
+            r"
+            f(X) ->
+              F = fun(X) -> ~ end.
+            ",
+            &["X", "X"],
+        );
+    }
+
+    #[test]
+    fn test_named_fun_name_does_not_escape() {
+        do_check(
+            r"
+            f() ->
+              F = fun Loop() -> Loop() end,
+              ~.
+            ",
+            &["F"],
+        );
+    }
+
+    #[test]
+    fn test_no_shadowing_after_comprehension() {
+        check_shadowing(
+            r"
+            f(L) ->
+              [X || X <- L],
+              X = 1,
+              F = fun(Y) -> Y end,
+              {X, F}.
+            ",
+            &[],
+        );
+    }
+
+    #[test]
+    fn test_comprehension_generator_shadowing() {
+        check_shadowing(
+            r"
+            f(X, L) ->
+              [X || X <- L].
+            ",
+            &["X"],
+        );
+    }
+
+    #[test]
+    fn test_fun_param_shadowing() {
+        check_shadowing(
+            r"
+            f(X) ->
+              Y = 1,
+              F = fun(X, Z) -> {Y, Z} end,
+              G = fun Y() -> ok end,
+              {F, G}.
+            ",
+            &["X", "Y"],
+        );
+    }
 }
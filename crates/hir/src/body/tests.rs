@@ -11,11 +11,18 @@ use elp_base_db::fixture::WithFixture;
 use expect_test::expect;
 use expect_test::Expect;
 
+use super::pretty;
 use crate::db::MinDefDatabase;
 use crate::test_db::TestDB;
 use crate::AnyAttribute;
+use crate::Expr;
 use crate::FormIdx;
+use crate::FunctionBody;
 use crate::InFile;
+use crate::MacroExpansion;
+use crate::MatchDiscard;
+use crate::ReceiveTimeout;
+use crate::Semantic;
 use crate::SpecOrCallback;
 
 #[track_caller]
@@ -67,6 +74,41 @@ fn check(ra_fixture: &str, expect: Expect) {
     expect.assert_eq(pretty.trim_start());
 }
 
+#[track_caller]
+fn check_macro_expansion(ra_fixture: &str, macro_expansion: MacroExpansion, expect: Expect) {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let form_list = db.file_form_list(file_id);
+    let mut out = String::new();
+    for (function_id, function) in form_list.functions() {
+        let (body, source_map) = FunctionBody::lower_with_macro_expansion(
+            &db,
+            InFile::new(file_id, function_id),
+            macro_expansion,
+        );
+        out.push_str(&body.print(&db, function));
+        for (_idx, expr) in body.body.exprs.iter() {
+            if let Expr::MacroCall { expansion, args } = expr {
+                let print = |expr_id| {
+                    pretty::print_expr(&db, &body.body, expr_id)
+                        .trim_end()
+                        .to_string()
+                };
+                let args = args.iter().map(|arg| print(*arg)).collect::<Vec<_>>();
+                out.push_str(&format!(
+                    "macro call: {} ({})\n",
+                    print(*expansion),
+                    args.join(", ")
+                ));
+            }
+        }
+        out.push_str(&format!(
+            "resolved macros: {}\n",
+            source_map.macro_map.len()
+        ));
+    }
+    expect.assert_eq(&out);
+}
+
 #[test]
 fn simple() {
     check(
@@ -132,6 +174,37 @@ foo("") -> "abc\s\x61".
     );
 }
 
+#[test]
+fn sigil_string() {
+    check(
+        r#"
+foo() -> {"\n", ~s"\n", ~S"\n"}.
+bar() -> {~b"\n", ~B"\n", ~"\n"}.
+"#,
+        expect![[r#"
+            foo() ->
+                {
+                    "\n",
+                    "\n",
+                    "\\n"
+                }.
+
+            bar() ->
+                {
+                    <<
+                        "\n"/utf8
+                    >>,
+                    <<
+                        "\\n"/utf8
+                    >>,
+                    <<
+                        "\\n"/utf8
+                    >>
+                }.
+        "#]],
+    );
+}
+
 #[test]
 fn concat() {
     check(
@@ -215,6 +288,115 @@ foo([a | b]) -> [1, 2 | 3].
     );
 }
 
+#[test]
+fn pipe_outside_list() {
+    check(
+        r#"
+-define(CONS(H, T), H | T).
+foo(X, Y) -> ?CONS(X, Y).
+"#,
+        expect![[r#"
+            foo(X, Y) ->
+                [
+                    X
+                    | Y
+                ].
+        "#]],
+    );
+}
+
+#[test]
+fn pipe_macro_in_list() {
+    check(
+        r#"
+-define(CONS(H, T), H | T).
+foo(A, X, T) -> [A, ?CONS(X, T)].
+bar([A, ?CONS(X, T)]) -> X.
+"#,
+        expect![[r#"
+            foo(A, X, T) ->
+                [
+                    A,
+                    X
+                    | T
+                ].
+
+            bar([
+                A,
+                X
+                | T
+            ]) ->
+                X.
+        "#]],
+    );
+}
+
+#[test]
+fn macro_in_list() {
+    check(
+        r#"
+-define(ONE, 1).
+-define(PAIR(A, B), {A, B}).
+foo(X) -> [?ONE, ?PAIR(X, 2)].
+bar([?ONE | T]) -> T.
+"#,
+        expect![[r#"
+            foo(X) ->
+                [
+                    1,
+                    {
+                        X,
+                        2
+                    }
+                ].
+
+            bar([
+                1
+                | T
+            ]) ->
+                T.
+        "#]],
+    );
+}
+
+#[test]
+fn pipe_macro_in_list_tail_resolves() {
+    let (db, file_id) = TestDB::with_single_file(
+        r#"
+-define(CONS(H, T), H | T).
+foo(A, X, T) -> [A, ?CONS(X, T)].
+"#,
+    );
+    let form_list = db.file_form_list(file_id);
+    let (function_id, _) = form_list.functions().next().unwrap();
+    let function_id = InFile::new(file_id, function_id);
+    let body = db.function_body(function_id);
+    let (clause_id, _) = body.clauses.iter().next().unwrap();
+    let resolver = Semantic::new(&db)
+        .clause_resolver(function_id, clause_id)
+        .unwrap();
+    let tail = body
+        .body
+        .exprs
+        .iter()
+        .find_map(|(_idx, expr)| match expr {
+            Expr::List {
+                exprs,
+                tail: Some(tail),
+            } => {
+                assert_eq!(exprs.len(), 2);
+                Some(*tail)
+            }
+            _ => None,
+        })
+        .unwrap();
+    let var = match &body.body[tail] {
+        Expr::Var(var) => *var,
+        expr => panic!("expected a variable, got {:?}", expr),
+    };
+    assert!(resolver.value.resolve_expr_id(&var, tail).is_some());
+}
+
 #[test]
 fn r#match() {
     check(
@@ -329,6 +511,27 @@ foo2(#record{field}) -> #record{field = }.
     );
 }
 
+#[test]
+fn record_missing_name() {
+    check(
+        r#"
+foo1() -> #Type{field = ok}.
+foo2() -> #?UNDEFINED{field = 1 + 2}.
+"#,
+        expect![[r##"
+            foo1() ->
+                #[missing name]{
+                    field = 'ok'
+                }.
+
+            foo2() ->
+                #[missing name]{
+                    field = (1 + 2)
+                }.
+        "##]],
+    );
+}
+
 #[test]
 fn record_update() {
     check(
@@ -464,6 +667,103 @@ foo() ->
     );
 }
 
+#[test]
+fn receive_timeout_kinds() {
+    let (db, file_id) = TestDB::with_single_file(
+        r#"
+-define(TIMEOUT, 5000).
+foo(T) ->
+    receive after infinity -> ok end,
+    receive after 0 -> ok end,
+    receive after ?TIMEOUT -> ok end,
+    receive after T -> ok end.
+"#,
+    );
+    let form_list = db.file_form_list(file_id);
+    let (function_id, _) = form_list.functions().next().unwrap();
+    let body = db.function_body(InFile::new(file_id, function_id));
+    let kinds: Vec<ReceiveTimeout> = body
+        .body
+        .exprs
+        .iter()
+        .filter_map(|(_idx, expr)| match expr {
+            Expr::Receive {
+                after: Some(after), ..
+            } => Some(after.kind),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ReceiveTimeout::Infinity,
+            ReceiveTimeout::Integer(0),
+            ReceiveTimeout::Integer(5000),
+            ReceiveTimeout::Dynamic,
+        ]
+    );
+}
+
+#[test]
+fn match_discard() {
+    let (db, file_id) = TestDB::with_single_file(
+        r#"
+-define(IGNORE, _).
+foo() ->
+    _ = bar(),
+    _Ignored = bar(),
+    ?IGNORE = bar(),
+    Used = bar(),
+    Used.
+"#,
+    );
+    let form_list = db.file_form_list(file_id);
+    let (function_id, _) = form_list.functions().next().unwrap();
+    let body = db.function_body(InFile::new(file_id, function_id));
+    let discards: Vec<String> = body
+        .body
+        .exprs
+        .iter()
+        .filter(|(_idx, expr)| matches!(expr, Expr::Match { .. }))
+        .map(|(idx, _expr)| match body.body.match_discard(&db, idx) {
+            Some(MatchDiscard::Wildcard) => "wildcard".to_string(),
+            Some(MatchDiscard::Named(var)) => format!("named {}", db.lookup_var(var)),
+            None => "none".to_string(),
+        })
+        .collect();
+    assert_eq!(
+        discards,
+        vec!["wildcard", "named _Ignored", "wildcard", "none"]
+    );
+}
+
+#[test]
+fn guard_sequences() {
+    let (db, file_id) = TestDB::with_single_file(
+        r#"
+foo(A, B, C) when A, B; C -> ok.
+"#,
+    );
+    let form_list = db.file_form_list(file_id);
+    let (function_id, _) = form_list.functions().next().unwrap();
+    let body = db.function_body(InFile::new(file_id, function_id));
+    let (_, clause) = body.clauses.iter().next().unwrap();
+    let guards: Vec<Vec<String>> = clause
+        .guards
+        .iter()
+        .map(|guard| {
+            guard
+                .iter()
+                .map(|expr_id| match &body.body[*expr_id] {
+                    Expr::Var(var) => db.lookup_var(*var).to_string(),
+                    expr => format!("{expr:?}"),
+                })
+                .collect()
+        })
+        .collect();
+    assert_eq!(guards, vec![vec!["A", "B"], vec!["C"]]);
+}
+
 #[test]
 fn call() {
     check(
@@ -760,7 +1060,28 @@ foo(a:b) -> a:b.
 "#,
         expect![[r#"
             foo([missing]) ->
-                [missing].
+                'a':'b'.
+        "#]],
+    );
+}
+
+#[test]
+fn remote_ref() {
+    check(
+        r#"
+foo(Mod) ->
+    F = mod:fun,
+    G = Mod:fun,
+    {F, G}.
+"#,
+        expect![[r#"
+            foo(Mod) ->
+                F = 'mod':'fun',
+                G = Mod:'fun',
+                {
+                    F,
+                    G
+                }.
         "#]],
     );
 }
@@ -1044,6 +1365,27 @@ fn range_type() {
     );
 }
 
+#[test]
+fn binary_type() {
+    check(
+        r#"
+-type foo() :: <<_:16>>.
+-type bar() :: <<_:_*8>>.
+-type baz() :: <<>>.
+-type qux() :: <<_:8, _:_*4>>.
+"#,
+        expect![[r#"
+            -type foo() :: <<_:16>>.
+
+            -type bar() :: <<_:_*8>>.
+
+            -type baz() :: <<>>.
+
+            -type qux() :: <<_:8, _:_*4>>.
+        "#]],
+    );
+}
+
 #[test]
 fn map_type() {
     check(
@@ -1252,6 +1594,38 @@ fn guarded_spec() {
     );
 }
 
+#[test]
+fn guarded_spec_shared_var() {
+    check(
+        r#"
+-spec foo(A, A) -> B
+    when A :: [B], B :: atom().
+"#,
+        expect![[r#"
+            -spec foo
+                (A, A) -> B
+                    when A :: [B], B :: 'atom'().
+        "#]],
+    );
+}
+
+#[test]
+fn guarded_spec_multiple_clauses() {
+    check(
+        r#"
+-spec foo(A) -> A when A :: atom();
+         (B) -> B when B :: integer().
+"#,
+        expect![[r#"
+            -spec foo
+                (A) -> A
+                    when A :: 'atom'();
+                (B) -> B
+                    when B :: 'integer'().
+        "#]],
+    );
+}
+
 #[test]
 fn record_definition() {
     check(
@@ -1285,6 +1659,22 @@ fn record_definition() {
     );
 }
 
+#[test]
+fn record_definition_macro_field_name() {
+    check(
+        r#"
+-define(NAME, first).
+-record(foo, {?NAME = 1, second = two}).
+"#,
+        expect![[r#"
+            -record(foo, {
+                first = 1,
+                second = 'two'
+            }).
+        "#]],
+    );
+}
+
 #[test]
 fn simple_term() {
     check(
@@ -1704,6 +2094,23 @@ foo() ->
     );
 }
 
+#[test]
+fn expand_macro_guard() {
+    check(
+        r#"
+-define(is_even(X), X rem 2 =:= 0).
+
+foo(N) when ?is_even(N) -> even.
+"#,
+        expect![[r#"
+            foo(N) when
+                ((N rem 2) =:= 0)
+            ->
+                'even'.
+        "#]],
+    );
+}
+
 #[test]
 fn expand_macro_name() {
     check(
@@ -1804,6 +2211,41 @@ foo(?FUNCTION_ARITY) -> ?FUNCTION_ARITY.
     );
 }
 
+#[test]
+fn expand_built_in_function_info_in_guard() {
+    check(
+        r#"
+foo(X) when X =:= ?FUNCTION_NAME; is_integer(?FUNCTION_ARITY) -> ok.
+"#,
+        expect![[r#"
+            foo(X) when
+                (X =:= 'foo');
+                'is_integer'(1)
+            ->
+                'ok'.
+        "#]],
+    );
+}
+
+#[test]
+fn expand_built_in_function_info_without_function() {
+    check(
+        r#"
+-record(rec, {name = ?FUNCTION_NAME, arity = ?FUNCTION_ARITY}).
+-spec foo(?FUNCTION_NAME) -> ok.
+"#,
+        expect![[r#"
+            -record(rec, {
+                name = [missing],
+                arity = [missing]
+            }).
+
+            -spec foo
+                ([missing]) -> 'ok'.
+        "#]],
+    );
+}
+
 #[test]
 fn expand_built_in_line() {
     check(
@@ -2050,3 +2492,63 @@ end."#,
         "#]],
     );
 }
+
+#[test]
+fn macro_expansion_all() {
+    check_macro_expansion(
+        r#"
+-module(main).
+-define(EXPR(X), 1 + X).
+foo(?MODULE) -> ?EXPR(2), ?MODULE.
+"#,
+        MacroExpansion::All,
+        expect![[r#"
+            foo('main') ->
+                (1 + 2),
+                'main'.
+            macro call: (1 + 2) (2)
+            macro call: 'main' ()
+            resolved macros: 3
+        "#]],
+    );
+}
+
+#[test]
+fn macro_expansion_built_in_only() {
+    check_macro_expansion(
+        r#"
+-module(main).
+-define(EXPR(X), 1 + X).
+foo(?MODULE) -> ?EXPR(2), ?MODULE.
+"#,
+        MacroExpansion::BuiltInOnly,
+        expect![[r#"
+            foo('main') ->
+                [missing],
+                'main'.
+            macro call: [missing] (2)
+            macro call: 'main' ()
+            resolved macros: 3
+        "#]],
+    );
+}
+
+#[test]
+fn macro_expansion_none() {
+    check_macro_expansion(
+        r#"
+-module(main).
+-define(EXPR(X), 1 + X).
+foo(?MODULE) -> ?EXPR(2), ?MODULE.
+"#,
+        MacroExpansion::None,
+        expect![[r#"
+            foo([missing]) ->
+                [missing],
+                [missing].
+            macro call: [missing] (2)
+            macro call: [missing] ()
+            resolved macros: 3
+        "#]],
+    );
+}
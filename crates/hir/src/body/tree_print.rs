@@ -456,6 +456,10 @@ impl<'a> Printer<'a> {
                                     this.print_expr(&this.body[after.timeout])
                                 });
 
+                                this.print_labelled("kind", true, &mut |this| {
+                                    write!(this, "{:?}", after.kind).ok();
+                                });
+
                                 this.print_labelled("exprs", false, &mut |this| {
                                     after.exprs.iter().for_each(|expr_id| {
                                         this.print_expr(&this.body[*expr_id]);
@@ -514,6 +518,16 @@ impl<'a> Printer<'a> {
                     });
                 });
             }
+            Expr::RemoteRef { module, name } => {
+                self.print_herald("Expr::RemoteRef", &mut |this| {
+                    this.print_labelled("module", true, &mut |this| {
+                        this.print_expr(&this.body[*module]);
+                    });
+                    this.print_labelled("name", true, &mut |this| {
+                        this.print_expr(&this.body[*name]);
+                    });
+                });
+            }
             Expr::Closure { clauses, name } => {
                 self.print_herald("Expr::Closure", &mut |this| {
                     this.print_labelled("clauses", false, &mut |this| {
@@ -749,6 +763,20 @@ impl<'a> Printer<'a> {
                     this.print_labelled("ty", true, &mut |this| this.print_type(&this.body[*ty]));
                 });
             }
+            TypeExpr::Binary { base_size, unit } => {
+                self.print_herald("TypeExpr::Binary", &mut |this| {
+                    if let Some(base_size) = base_size {
+                        this.print_labelled("base_size", true, &mut |this| {
+                            this.print_type(&this.body[*base_size])
+                        });
+                    }
+                    if let Some(unit) = unit {
+                        this.print_labelled("unit", true, &mut |this| {
+                            this.print_type(&this.body[*unit])
+                        });
+                    }
+                });
+            }
             TypeExpr::BinaryOp { lhs, rhs, op } => {
                 self.print_herald("TypeExpr::BinaryOp", &mut |this| {
                     this.print_labelled("lhs", true, &mut |this| this.print_type(&this.body[*lhs]));
@@ -1919,6 +1947,8 @@ mod tests {
                                 ReceiveAfter {
                                     timeout
                                         Expr::Var(Timeout)
+                                    kind
+                                        Dynamic
                                     exprs
                                         Literal(Atom('timeout')),
                                 }},
@@ -2174,7 +2204,7 @@ mod tests {
     }
 
     #[test]
-    fn expr_via_fun_missing() {
+    fn expr_via_fun_remote_ref() {
         check(
             r#"
              foo(a:b) -> a:b.
@@ -2185,7 +2215,12 @@ mod tests {
                         Pat::Missing,
                     guards
                     exprs
-                        Expr::Missing,
+                        Expr::RemoteRef {
+                            module
+                                Literal(Atom('a'))
+                            name
+                                Literal(Atom('b'))
+                        },
                 }.
             "#]],
         );
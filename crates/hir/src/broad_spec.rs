@@ -0,0 +1,203 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Functions whose spec says they return `term()` or `any()`, although
+//! every clause obviously returns an atom or a tuple of atoms. This is
+//! advisory and deliberately simple: no subtyping is attempted, precise
+//! checks are left to eqWAlizer.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+
+use crate::db::MinDefDatabase;
+use crate::known;
+use crate::Body;
+use crate::CallTarget;
+use crate::Expr;
+use crate::ExprId;
+use crate::InFile;
+use crate::Literal;
+use crate::NameArity;
+use crate::TypeExpr;
+use crate::TypeExprId;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BroadSpec {
+    pub function: NameArity,
+    /// The values returned by the function, as a type such as
+    /// `ok | {error, closed}`
+    pub result: String,
+}
+
+/// The functions defined in `file_id` with a spec in the same file whose
+/// result could be tightened, sorted by name. Only specs with a single
+/// signature are considered.
+pub(crate) fn broad_specs_query(db: &dyn MinDefDatabase, file_id: FileId) -> Arc<Vec<BroadSpec>> {
+    let form_list = db.file_form_list(file_id);
+    let mut res: Vec<BroadSpec> = form_list
+        .functions()
+        .filter_map(|(function_id, function)| {
+            let (spec_id, _) = form_list
+                .specs()
+                .find(|(_, spec)| spec.name == function.name)?;
+            let spec_body = db.spec_body(InFile::new(file_id, spec_id));
+            let sig = match spec_body.sigs.as_slice() {
+                [sig] => sig,
+                _ => return None,
+            };
+            if !is_any(db, &spec_body.body, sig.result) {
+                return None;
+            }
+            let function_body = db.function_body(InFile::new(file_id, function_id));
+            let mut values = Vec::new();
+            for (_, clause) in function_body.clauses.iter() {
+                let expr_id = clause.exprs.last()?;
+                returned_values(db, &function_body.body, *expr_id, &mut values)?;
+            }
+            if values.is_empty() {
+                return None;
+            }
+            Some(BroadSpec {
+                function: function.name.clone(),
+                result: values.join(" | "),
+            })
+        })
+        .collect();
+    res.sort_by(|a, b| a.function.cmp(&b.function));
+    Arc::new(res)
+}
+
+/// `term()` or `any()`, possibly qualified with `erlang`
+fn is_any(db: &dyn MinDefDatabase, body: &Body, type_expr_id: TypeExprId) -> bool {
+    let name_of = |type_expr_id: &TypeExprId| match &body[*type_expr_id] {
+        TypeExpr::Literal(Literal::Atom(atom)) => Some(db.lookup_atom(*atom)),
+        _ => None,
+    };
+    match &body[type_expr_id] {
+        TypeExpr::Call { target, args } if args.is_empty() => {
+            let name = match target {
+                CallTarget::Local { name } => name_of(name),
+                CallTarget::Remote { module, name } => {
+                    if name_of(module) != Some(known::erlang) {
+                        return false;
+                    }
+                    name_of(name)
+                }
+            };
+            matches!(name, Some(name) if name.as_str() == "term" || name.as_str() == "any")
+        }
+        TypeExpr::MacroCall { expansion, .. } => is_any(db, body, *expansion),
+        _ => false,
+    }
+}
+
+/// Adds the distinct values `expr_id` obviously evaluates to, going through
+/// the branches of `case` and `if`. Returns `None` if any value is not an
+/// atom or a tuple of such values.
+fn returned_values(
+    db: &dyn MinDefDatabase,
+    body: &Body,
+    expr_id: ExprId,
+    acc: &mut Vec<String>,
+) -> Option<()> {
+    match &body[expr_id] {
+        Expr::Block { exprs } => returned_values(db, body, *exprs.last()?, acc),
+        Expr::MacroCall { expansion, .. } => returned_values(db, body, *expansion, acc),
+        Expr::Case { clauses, .. } => {
+            for clause in clauses {
+                returned_values(db, body, *clause.exprs.last()?, acc)?;
+            }
+            Some(())
+        }
+        Expr::If { clauses } => {
+            for clause in clauses {
+                returned_values(db, body, *clause.exprs.last()?, acc)?;
+            }
+            Some(())
+        }
+        _ => {
+            let value = value_type(db, body, expr_id)?;
+            if !acc.contains(&value) {
+                acc.push(value);
+            }
+            Some(())
+        }
+    }
+}
+
+fn value_type(db: &dyn MinDefDatabase, body: &Body, expr_id: ExprId) -> Option<String> {
+    match &body[expr_id] {
+        Expr::Literal(Literal::Atom(atom)) => Some(db.lookup_atom(*atom).to_quoted_string()),
+        Expr::Tuple { exprs } => {
+            let elements = exprs
+                .iter()
+                .map(|expr_id| value_type(db, body, *expr_id))
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("{{{}}}", elements.join(", ")))
+        }
+        Expr::MacroCall { expansion, .. } => value_type(db, body, *expansion),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id) = TestDB::with_single_file(fixture);
+        let broad_specs = db.broad_specs(file_id);
+        let actual = broad_specs
+            .iter()
+            .map(|broad_spec| format!("{}: {}\n", broad_spec.function, broad_spec.result))
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn broad_specs() {
+        check(
+            r#"
+-module(main).
+-define(OK, ok).
+-spec open(atom()) -> term().
+open(a) -> ?OK;
+open(b) -> {error, closed};
+open(_) -> ok.
+-spec check(integer()) -> erlang:any().
+check(X) ->
+    case X of
+        1 -> one;
+        _ -> begin log(X), 'other value' end
+    end.
+-spec narrow() -> ok.
+narrow() -> ok.
+-spec value(term()) -> term().
+value(X) -> X.
+-spec mixed(term()) -> term().
+mixed(a) -> ok;
+mixed(X) -> {ok, X}.
+-spec overloaded(a) -> term(); (b) -> term().
+overloaded(_) -> ok.
+unspecced() -> ok.
+log(_) -> ok.
+"#,
+            expect![[r#"
+                check/1: one | 'other value'
+                open/1: ok | {error, closed}
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,275 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The resolved target of every call in a function, so that features
+//! looking at calls do not each resolve them again.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+use elp_base_db::ModuleName;
+use elp_syntax::ast;
+use fxhash::FxHashMap;
+
+use crate::db::MinDefDatabase;
+use crate::sema::to_def::literal_atom;
+use crate::sema::to_def::resolve_call_target;
+use crate::sema::to_def::resolve_module_name;
+use crate::Body;
+use crate::CallTarget;
+use crate::Expr;
+use crate::ExprId;
+use crate::FoldCtx;
+use crate::FunctionDef;
+use crate::FunctionId;
+use crate::InFile;
+use crate::Name;
+use crate::NameArity;
+use crate::Semantic;
+use crate::Strategy;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedCall {
+    /// A function of the calling module, or of a header it includes. This
+    /// includes remote calls through `?MODULE`.
+    Local(FunctionDef),
+    /// A function imported with an `-import` attribute. The definition is
+    /// only known if the module is part of the project.
+    Imported {
+        module: ModuleName,
+        function: NameArity,
+        def: Option<FunctionDef>,
+    },
+    /// A remote call with literal module and function names. The file and
+    /// definition are only known if the module is part of the project.
+    Remote {
+        module: ModuleName,
+        function: NameArity,
+        file_id: Option<FileId>,
+        def: Option<FunctionDef>,
+    },
+    /// A local call to a function auto-imported from the `erlang` module
+    Bif(NameArity),
+    /// A local call to an undefined function, or a call whose module or
+    /// function is not a literal atom
+    Unresolved,
+}
+
+impl ResolvedCall {
+    /// The definition of the called function, if it is part of the project
+    pub fn def(&self) -> Option<&FunctionDef> {
+        match self {
+            ResolvedCall::Local(def) => Some(def),
+            ResolvedCall::Imported { def, .. } | ResolvedCall::Remote { def, .. } => def.as_ref(),
+            ResolvedCall::Bif(_) | ResolvedCall::Unresolved => None,
+        }
+    }
+}
+
+/// The target of each `Expr::Call` in the clauses of `function_id`,
+/// including the calls coming from macro expansions. The called function
+/// is found by `resolve_call_target`, as for a single call.
+pub(crate) fn call_targets_query(
+    db: &dyn MinDefDatabase,
+    function_id: InFile<FunctionId>,
+) -> Arc<FxHashMap<ExprId, ResolvedCall>> {
+    let function_body = db.function_body(function_id);
+    let body = &function_body.body;
+    let ctx = Ctx {
+        sema: Semantic { db },
+        file_id: function_id.file_id,
+        module_name: db
+            .file_form_list(function_id.file_id)
+            .module_attribute()
+            .map(|attr| attr.name.clone()),
+    };
+    let mut res = FxHashMap::default();
+    for (_clause_id, clause) in function_body.clauses.iter() {
+        for expr_id in &clause.exprs {
+            res = FoldCtx::fold_expr(
+                body,
+                Strategy::TopDown,
+                *expr_id,
+                res,
+                &mut |mut acc, fold_ctx| {
+                    if let Expr::Call { target, args } = &fold_ctx.expr {
+                        acc.insert(
+                            fold_ctx.expr_id,
+                            ctx.resolve(body, target, args.len() as u32),
+                        );
+                    }
+                    acc
+                },
+                &mut |acc, _| acc,
+            );
+        }
+    }
+    Arc::new(res)
+}
+
+struct Ctx<'a> {
+    sema: Semantic<'a>,
+    file_id: FileId,
+    module_name: Option<Name>,
+}
+
+impl Ctx<'_> {
+    fn resolve(&self, body: &Body, target: &CallTarget<ExprId>, arity: u32) -> ResolvedCall {
+        let def = resolve_call_target(&self.sema, target, arity, self.file_id, body);
+        let resolved = match target {
+            CallTarget::Local { name } => self
+                .atom_name(body, *name)
+                .map(|name| self.resolve_local(NameArity::new(name, arity), def)),
+            CallTarget::Remote { module, name } => {
+                match (self.atom_name(body, *module), self.atom_name(body, *name)) {
+                    (Some(module), Some(name)) => {
+                        Some(self.resolve_remote(module, NameArity::new(name, arity), def))
+                    }
+                    _ => None,
+                }
+            }
+        };
+        resolved.unwrap_or(ResolvedCall::Unresolved)
+    }
+
+    fn resolve_local(&self, function: NameArity, def: Option<FunctionDef>) -> ResolvedCall {
+        let def_map = self.sema.db.def_map(self.file_id);
+        if def_map.get_function(&function).is_none() {
+            if let Some(module) = def_map.get_imports().get(&function) {
+                return ResolvedCall::Imported {
+                    module: ModuleName::new(module.as_str()),
+                    function,
+                    def,
+                };
+            }
+        }
+        match def {
+            Some(def) => ResolvedCall::Local(def),
+            None if ast::is_erlang_fun(function.name().as_str(), function.arity() as usize) => {
+                ResolvedCall::Bif(function)
+            }
+            None => ResolvedCall::Unresolved,
+        }
+    }
+
+    fn resolve_remote(
+        &self,
+        module: Name,
+        function: NameArity,
+        def: Option<FunctionDef>,
+    ) -> ResolvedCall {
+        match def {
+            Some(def) if self.module_name.as_ref() == Some(&module) => ResolvedCall::Local(def),
+            def => ResolvedCall::Remote {
+                file_id: resolve_module_name(&self.sema, self.file_id, &module)
+                    .map(|module| module.file.file_id),
+                module: ModuleName::new(module.as_str()),
+                function,
+                def,
+            },
+        }
+    }
+
+    fn atom_name(&self, body: &Body, expr_id: ExprId) -> Option<Name> {
+        Some(self.sema.db.lookup_atom(literal_atom(body, expr_id)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use super::ResolvedCall;
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+    use crate::InFile;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, files) = TestDB::with_many_files(fixture);
+        let file_id = files[0];
+        let form_list = db.file_form_list(file_id);
+        let mut actual = String::new();
+        for (function_id, function) in form_list.functions() {
+            let targets = db.call_targets(InFile::new(file_id, function_id));
+            let mut calls: Vec<_> = targets.iter().collect();
+            calls.sort_by_key(|(expr_id, _)| u32::from(expr_id.into_raw()));
+            for (_, target) in calls {
+                let target = match target {
+                    ResolvedCall::Local(def) => format!("local {}", def.function.name),
+                    ResolvedCall::Imported {
+                        module,
+                        function,
+                        def,
+                    } => format!("imported {}:{} found: {}", module, function, def.is_some()),
+                    ResolvedCall::Remote {
+                        module,
+                        function,
+                        file_id,
+                        def,
+                    } => format!(
+                        "remote {}:{} found: {} defined: {}",
+                        module,
+                        function,
+                        file_id.is_some(),
+                        def.is_some()
+                    ),
+                    ResolvedCall::Bif(function) => format!("bif {}", function),
+                    ResolvedCall::Unresolved => "unresolved".to_string(),
+                };
+                actual.push_str(&format!("{}: {}\n", function.name, target));
+            }
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn call_targets() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-import(other, [imported/0]).
+-import(missing, [gone/1]).
+-define(CALL(X), local(X)).
+local(X) -> X.
+foo(M) ->
+    local(1),
+    ?MODULE:local(2),
+    ?CALL(3),
+    imported(),
+    gone(4),
+    other:run(),
+    other:imported(),
+    nowhere:run(),
+    length([]),
+    undefined_fun(),
+    M:run().
+//- /src/other.erl
+-module(other).
+-export([imported/0]).
+imported() -> ok.
+"#,
+            expect![[r#"
+                foo/1: local local/1
+                foo/1: local local/1
+                foo/1: local local/1
+                foo/1: imported other:imported/0 found: true
+                foo/1: imported missing:gone/1 found: false
+                foo/1: remote other:run/0 found: true defined: false
+                foo/1: remote other:imported/0 found: true defined: true
+                foo/1: remote nowhere:run/0 found: false defined: false
+                foo/1: bif length/1
+                foo/1: unresolved
+                foo/1: unresolved
+            "#]],
+        );
+    }
+}
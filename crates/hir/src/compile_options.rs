@@ -0,0 +1,154 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The options of a module which change how the compiler reports its
+//! warnings, given by its `-compile` attributes or by the `erl_opts` of
+//! its application.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+use fxhash::FxHashSet;
+
+use crate::db::MinDefDatabase;
+use crate::Body;
+use crate::InFile;
+use crate::Literal;
+use crate::Term;
+use crate::TermId;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompileOptions {
+    pub warnings_as_errors: bool,
+    /// The warning categories disabled by `nowarn_` options, without the
+    /// prefix, e.g. `unused_vars` for `nowarn_unused_vars`. Options which
+    /// only disable a warning for some definitions, such as
+    /// `{nowarn_unused_function, [foo/1]}`, are not included.
+    nowarn: FxHashSet<String>,
+}
+
+impl CompileOptions {
+    pub fn is_nowarn(&self, category: &str) -> bool {
+        self.nowarn.contains(category)
+    }
+}
+
+/// Only the `-compile` attributes of the file itself are considered, not
+/// the ones of included headers.
+pub(crate) fn compile_options_query(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+) -> Arc<CompileOptions> {
+    let mut res = CompileOptions::default();
+    if let Some(app_data) = db.app_data(db.file_source_root(file_id)) {
+        // Only atom options matter here, e.g. `warnings_as_errors`
+        for term in &app_data.compile_options {
+            if let eetf::Term::Atom(atom) = term {
+                add_option(&atom.name, &mut res);
+            }
+        }
+    }
+    let form_list = db.file_form_list(file_id);
+    for (idx, _) in form_list.compile_attributes() {
+        let attribute = db.compile_body(InFile::new(file_id, idx));
+        collect(db, &attribute.body, attribute.value, &mut res);
+    }
+    Arc::new(res)
+}
+
+fn collect(db: &dyn MinDefDatabase, body: &Body, term_id: TermId, res: &mut CompileOptions) {
+    match &body[term_id] {
+        Term::Literal(Literal::Atom(atom)) => add_option(db.lookup_atom(*atom).as_str(), res),
+        Term::List { exprs, .. } => {
+            for expr in exprs {
+                collect(db, body, *expr, res);
+            }
+        }
+        Term::MacroCall { expansion, .. } => collect(db, body, *expansion, res),
+        _ => {}
+    }
+}
+
+fn add_option(name: &str, res: &mut CompileOptions) {
+    match name {
+        "warnings_as_errors" => res.warnings_as_errors = true,
+        name => {
+            if let Some(category) = name.strip_prefix("nowarn_") {
+                res.nowarn.insert(category.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use elp_base_db::fixture::WithFixture;
+    use elp_base_db::SourceDatabase;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+
+    #[test]
+    fn compile_options() {
+        let (db, file_id) = TestDB::with_single_file(
+            r#"
+-module(main).
+-define(OPTS, [nowarn_unused_vars]).
+-compile(warnings_as_errors).
+-compile([nowarn_export_all, ?OPTS, {nowarn_unused_function, [foo/0]}]).
+foo() -> ok.
+"#,
+        );
+        let options = db.compile_options(file_id);
+        assert!(options.warnings_as_errors);
+        assert!(options.is_nowarn("export_all"));
+        assert!(options.is_nowarn("unused_vars"));
+        assert!(!options.is_nowarn("unused_function"));
+    }
+
+    #[test]
+    fn no_compile_options() {
+        let (db, file_id) = TestDB::with_single_file(
+            r#"
+-module(main).
+-compile(export_all).
+"#,
+        );
+        let options = db.compile_options(file_id);
+        assert!(!options.warnings_as_errors);
+        assert!(!options.is_nowarn("export_all"));
+    }
+
+    #[test]
+    fn app_compile_options() {
+        let (mut db, file_id) = TestDB::with_single_file(
+            r#"
+-module(main).
+-compile(nowarn_unused_vars).
+"#,
+        );
+        let source_root_id = db.file_source_root(file_id);
+        let mut app_data = (*db.app_data(source_root_id).unwrap()).clone();
+        app_data.compile_options = vec![
+            eetf::Atom::from("warnings_as_errors").into(),
+            eetf::Tuple::from(vec![
+                eetf::Atom::from("d").into(),
+                eetf::Atom::from("nowarn_export_all").into(),
+            ])
+            .into(),
+        ];
+        db.set_app_data(source_root_id, Some(Arc::new(app_data)));
+        let options = db.compile_options(file_id);
+        assert!(options.warnings_as_errors);
+        assert!(options.is_nowarn("unused_vars"));
+        assert!(!options.is_nowarn("export_all"));
+    }
+}
@@ -11,6 +11,8 @@ use std::sync::Arc;
 
 use elp_base_db::salsa;
 use elp_base_db::FileId;
+use elp_base_db::ModuleName;
+use elp_base_db::ProjectId;
 use elp_base_db::SourceDatabase;
 use elp_base_db::Upcast;
 use elp_syntax::ast;
@@ -18,13 +20,45 @@ use fxhash::FxHashMap;
 
 use crate::body::scope::FunctionScopes;
 use crate::body::DefineBody;
+use crate::broad_spec;
+use crate::broad_spec::BroadSpec;
+use crate::call_targets;
+use crate::call_targets::ResolvedCall;
+use crate::compile_options;
+use crate::compile_options::CompileOptions;
+use crate::dialyzer;
+use crate::dialyzer::DialyzerAttributes;
+use crate::doc_comments;
+use crate::doc_comments::CommentBlock;
+use crate::dynamic_call;
+use crate::dynamic_call::DynamicCall;
 use crate::edoc;
 use crate::edoc::EdocHeader;
+use crate::entry_points;
+use crate::entry_points::EntryPoint;
+use crate::file_metrics;
+use crate::file_metrics::FileMetrics;
+use crate::guard_constraints;
+use crate::guard_constraints::GuardConstraint;
+use crate::import;
+use crate::import::ImportedFunction;
 use crate::include;
 pub use crate::intern::MinInternDatabase;
 pub use crate::intern::MinInternDatabaseStorage;
 use crate::macro_exp;
+use crate::macro_exp::BuiltInMacro;
 use crate::macro_exp::MacroResolution;
+use crate::module_atoms;
+use crate::module_atoms::ModuleAtoms;
+use crate::module_dependency;
+use crate::no_return;
+use crate::otp_role;
+use crate::otp_role::OtpBehaviourRole;
+use crate::reachability;
+use crate::reachability::Reachable;
+use crate::var_bindings;
+use crate::var_bindings::FileVarBindings;
+use crate::visible_records;
 use crate::AttributeBody;
 use crate::AttributeId;
 use crate::BodySourceMap;
@@ -32,13 +66,17 @@ use crate::CallbackId;
 use crate::CompileOptionId;
 use crate::DefMap;
 use crate::DefineId;
+use crate::ExprId;
 use crate::FormList;
 use crate::FunctionBody;
 use crate::FunctionId;
 use crate::InFile;
 use crate::InFileAstPtr;
 use crate::IncludeAttributeId;
+use crate::Literal;
 use crate::MacroName;
+use crate::Name;
+use crate::NameArity;
 use crate::RecordBody;
 use crate::RecordId;
 use crate::ResolvedMacro;
@@ -46,6 +84,7 @@ use crate::SpecBody;
 use crate::SpecId;
 use crate::TypeAliasId;
 use crate::TypeBody;
+use crate::VisibleRecord;
 
 #[salsa::query_group(MinDefDatabaseStorage)]
 pub trait MinDefDatabase:
@@ -110,26 +149,161 @@ pub trait MinDefDatabase:
     fn compile_body(&self, attribute_id: InFile<CompileOptionId>) -> Arc<AttributeBody>;
     fn define_body(&self, define_id: InFile<DefineId>) -> Option<Arc<DefineBody>>;
 
+    /// Type constraints on each argument of a function, derived from the
+    /// type tests in its clause guards.
+    #[salsa::invoke(guard_constraints::function_arg_constraints_query)]
+    fn function_arg_constraints(
+        &self,
+        function_id: InFile<FunctionId>,
+    ) -> Arc<Vec<Option<GuardConstraint>>>;
+
     #[salsa::invoke(FunctionScopes::function_scopes_query)]
     fn function_scopes(&self, fun: InFile<FunctionId>) -> Arc<FunctionScopes>;
 
     #[salsa::invoke(include::resolve)]
     fn resolve_include(&self, include_id: InFile<IncludeAttributeId>) -> Option<FileId>;
 
+    /// The headers directly included by `file_id`, skipping the ones that
+    /// cannot be resolved.
+    #[salsa::invoke(include::resolved_includes_query)]
+    fn resolved_includes(&self, file_id: FileId) -> Arc<Vec<FileId>>;
+
+    /// All header files transitively included by `file_id`, in the order
+    /// the preprocessor would first encounter them, without duplicates.
+    /// Includes that cannot be resolved are skipped silently, as they are
+    /// by `resolved_includes`, and the other includes are still followed.
+    #[salsa::invoke(include::include_closure_query)]
+    fn include_closure(&self, file_id: FileId) -> Arc<Vec<FileId>>;
+
+    /// The functions brought into scope by the `-import` attributes of
+    /// `file_id`, resolved to their definitions where possible.
+    #[salsa::invoke(import::imported_functions_query)]
+    fn imported_functions(&self, file_id: FileId) -> Arc<Vec<ImportedFunction>>;
+
+    /// The calls in `function_id` whose target is not syntactically fixed,
+    /// such as `apply/3` or `Mod:Fun(Args)` with a variable module.
+    #[salsa::invoke(dynamic_call::dynamic_calls_query)]
+    fn dynamic_calls(&self, function_id: InFile<FunctionId>) -> Arc<Vec<DynamicCall>>;
+
+    /// The resolved target of each call in `function_id`, keyed by the
+    /// `Expr::Call`.
+    #[salsa::invoke(call_targets::call_targets_query)]
+    fn call_targets(&self, function_id: InFile<FunctionId>)
+        -> Arc<FxHashMap<ExprId, ResolvedCall>>;
+
+    /// The modules called via `apply/3` with a literal module in `file_id`.
+    #[salsa::invoke(dynamic_call::dynamically_called_modules_query)]
+    fn dynamically_called_modules(&self, file_id: FileId) -> Arc<Vec<Name>>;
+
+    /// The functions of `file_id` whose every clause ends by raising an
+    /// exception, sorted.
+    #[salsa::invoke(no_return::no_return_functions_query)]
+    fn no_return_functions(&self, file_id: FileId) -> Arc<Vec<NameArity>>;
+
+    /// The functions transitively reachable from `function` in `file_id`,
+    /// following at most `max_depth` calls when given.
+    #[salsa::invoke(reachability::reachable_functions_query)]
+    fn reachable_functions(
+        &self,
+        file_id: FileId,
+        function: NameArity,
+        max_depth: Option<u32>,
+    ) -> Arc<Reachable>;
+
+    /// The functions of `file_id` whose spec returns `term()` or `any()`
+    /// although every clause returns an atom or a tuple of atoms.
+    #[salsa::invoke(broad_spec::broad_specs_query)]
+    fn broad_specs(&self, file_id: FileId) -> Arc<Vec<BroadSpec>>;
+
+    /// The `-compile` options of `file_id` changing how warnings are
+    /// reported, such as `warnings_as_errors`.
+    #[salsa::invoke(compile_options::compile_options_query)]
+    fn compile_options(&self, file_id: FileId) -> Arc<CompileOptions>;
+
+    /// The options of the `-dialyzer` attributes of `file_id`, such as
+    /// `{nowarn_function, [foo/1]}`.
+    #[salsa::invoke(dialyzer::dialyzer_attributes_query)]
+    fn dialyzer_attributes(&self, file_id: FileId) -> Arc<DialyzerAttributes>;
+
+    /// Line and definition counts of `file_id`, without lowering bodies.
+    #[salsa::invoke(file_metrics::file_metrics_query)]
+    fn file_metrics(&self, file_id: FileId) -> FileMetrics;
+
+    /// The atoms of `file_id`, split between the ones in type contexts and
+    /// the ones used as values. An atom can be in both.
+    #[salsa::invoke(module_atoms::module_atoms_query)]
+    fn module_atoms(&self, file_id: FileId) -> Arc<ModuleAtoms>;
+
+    /// The modules `file_id` depends on through behaviours, imports and
+    /// calls with a literal module, sorted and without duplicates.
+    #[salsa::invoke(module_dependency::module_dependencies_query)]
+    fn module_dependencies(&self, file_id: FileId) -> Arc<Vec<Name>>;
+
+    /// The role of `file_id` given by its `-behaviour` attributes, such as
+    /// `gen_server` or `supervisor`.
+    #[salsa::invoke(otp_role::otp_role_query)]
+    fn otp_role(&self, file_id: FileId) -> Option<OtpBehaviourRole>;
+
+    /// The entry points expected of `file_id` given its role, such as
+    /// `main/1` for an escript, and whether it defines them.
+    #[salsa::invoke(entry_points::entry_points_query)]
+    fn entry_points(&self, file_id: FileId) -> Arc<Vec<EntryPoint>>;
+
+    /// Every variable occurrence in `file_id` with the patterns binding it,
+    /// each function being scoped independently.
+    #[salsa::invoke(var_bindings::file_var_bindings_query)]
+    fn file_var_bindings(&self, file_id: FileId) -> Arc<FileVarBindings>;
+
+    /// The modules of `project_id` in dependency order, as strongly
+    /// connected components so that cycles are kept together.
+    #[salsa::invoke(module_dependency::module_dependency_order_query)]
+    fn module_dependency_order(&self, project_id: ProjectId) -> Arc<Vec<Vec<ModuleName>>>;
+
     #[salsa::invoke(macro_exp::resolve_query)]
     fn resolve_macro(&self, file_id: FileId, name: MacroName) -> Option<ResolvedMacro>;
 
+    /// The value a built-in macro such as `?MODULE` expands to in `file_id`.
+    /// `?FUNCTION_NAME` and `?FUNCTION_ARITY` only have a value inside a
+    /// function.
+    #[salsa::invoke(macro_exp::builtin_macro_value_query)]
+    fn builtin_macro_value(
+        &self,
+        file_id: FileId,
+        built_in: BuiltInMacro,
+        function_id: Option<FunctionId>,
+    ) -> Option<Literal>;
+
+    /// Macros defined before `file_id` is preprocessed, by the build system
+    /// or by `{d, Name}` and `{d, Name, Value}` options of `-compile`
+    /// attributes. The value is `None` if it is not a literal.
+    #[salsa::invoke(macro_exp::predefined_macros_query)]
+    fn predefined_macros(&self, file_id: FileId) -> Arc<FxHashMap<Name, Option<Literal>>>;
+
     #[salsa::invoke(edoc::file_edoc_comments_query)]
     fn file_edoc_comments(
         &self,
         file_id: FileId,
     ) -> Option<FxHashMap<InFileAstPtr<ast::Form>, EdocHeader>>;
 
+    /// The comments surrounding each form of `file_id`, for forms which
+    /// have any
+    #[salsa::invoke(doc_comments::form_doc_comments_query)]
+    fn form_doc_comments(
+        &self,
+        file_id: FileId,
+    ) -> Arc<FxHashMap<InFileAstPtr<ast::Form>, CommentBlock>>;
+
     // Helper query to run the recursive resolution algorithm
     #[salsa::cycle(macro_exp::recover_cycle)]
     #[salsa::invoke(macro_exp::local_resolve_query)]
     fn local_resolve_macro(&self, file_id: FileId, name: MacroName) -> MacroResolution;
 
+    /// The records visible in `file_id`, in the order the preprocessor
+    /// defines them, including redefinitions. `-ifdef` and `-ifndef`
+    /// conditions are evaluated, records under other conditions are ignored.
+    #[salsa::invoke(visible_records::visible_records_query)]
+    fn visible_records(&self, file_id: FileId) -> Arc<Vec<VisibleRecord>>;
+
     #[salsa::cycle(DefMap::recover_cycle)]
     #[salsa::invoke(DefMap::def_map_query)]
     fn def_map(&self, file_id: FileId) -> Arc<DefMap>;
@@ -38,7 +38,6 @@ use crate::DefineDef;
 use crate::File;
 use crate::FormIdx;
 use crate::FunctionDef;
-use crate::InFile;
 use crate::MacroName;
 use crate::Name;
 use crate::NameArity;
@@ -52,6 +51,9 @@ pub struct DefMap {
     _c: Count<Self>,
     included: FxHashSet<FileId>,
     functions: FxHashMap<NameArity, FunctionDef>,
+    /// All the definitions of the functions defined by more than one
+    /// form, in source order, while `functions` only keeps the last one
+    function_redefinitions: FxHashMap<NameArity, Vec<FunctionDef>>,
     specs: FxHashMap<NameArity, SpecDef>,
     exported_functions: FxHashSet<NameArity>,
     deprecated: Deprecated,
@@ -109,16 +111,21 @@ impl DefMap {
             match form {
                 FormIdx::Function(idx) => {
                     let function = form_list[idx].clone();
-                    def_map.functions.insert(
-                        function.name.clone(),
-                        FunctionDef {
-                            file,
-                            exported: false,
-                            deprecated: false,
-                            function,
-                            function_id: idx,
-                        },
-                    );
+                    let name = function.name.clone();
+                    let def = FunctionDef {
+                        file,
+                        exported: false,
+                        deprecated: false,
+                        function,
+                        function_id: idx,
+                    };
+                    if let Some(previous) = def_map.functions.insert(name.clone(), def.clone()) {
+                        def_map
+                            .function_redefinitions
+                            .entry(name)
+                            .or_insert_with(|| vec![previous])
+                            .push(def);
+                    }
                 }
                 FormIdx::Export(idx) => {
                     for export_id in form_list[idx].entries.clone() {
@@ -264,14 +271,15 @@ impl DefMap {
     }
 
     pub(crate) fn def_map_query(db: &dyn MinDefDatabase, file_id: FileId) -> Arc<DefMap> {
+        // Only depends on queries that are stable across edits within
+        // function bodies, so that such edits do not recompute the def map
         let local = db.local_def_map(file_id);
-        let form_list = db.file_form_list(file_id);
 
         let mut remote = Self::default();
 
-        form_list
-            .includes()
-            .filter_map(|(idx, _)| db.resolve_include(InFile::new(file_id, idx)))
+        db.resolved_includes(file_id)
+            .iter()
+            .copied()
             // guard against naive cycles of headers including themselves
             .filter(|&included_file_id| included_file_id != file_id)
             .map(|included_file_id| (included_file_id, db.def_map(included_file_id)))
@@ -306,6 +314,13 @@ impl DefMap {
         self.functions.get(name)
     }
 
+    /// The functions defined by more than one form, with all their
+    /// definitions in source order. Their `exported` and `deprecated`
+    /// flags are not set.
+    pub fn get_function_redefinitions(&self) -> &FxHashMap<NameArity, Vec<FunctionDef>> {
+        &self.function_redefinitions
+    }
+
     pub fn is_deprecated(&self, name: &NameArity) -> bool {
         self.deprecated.is_deprecated(name)
     }
@@ -337,6 +352,32 @@ impl DefMap {
         &self.functions
     }
 
+    /// The exported functions without a spec, ordered by file and then by
+    /// position in the file.
+    pub fn unspecced_exports(&self) -> Vec<&FunctionDef> {
+        let mut defs: Vec<_> = self
+            .exported_functions
+            .iter()
+            .filter(|name| !self.specs.contains_key(name))
+            .filter_map(|name| self.functions.get(name))
+            .collect();
+        defs.sort_by_key(|def| (def.file.file_id, u32::from(def.function_id.into_raw())));
+        defs
+    }
+
+    /// All arities of the function `name` visible in the module, including the
+    /// ones coming from included files, ordered by arity.
+    pub fn get_function_arities(&self, name: &Name) -> Vec<&FunctionDef> {
+        let mut defs: Vec<_> = self
+            .functions
+            .iter()
+            .filter(|(na, _)| na.name() == name)
+            .map(|(_, def)| def)
+            .collect();
+        defs.sort_by_key(|def| def.function.name.arity());
+        defs
+    }
+
     pub fn get_specs(&self) -> &FxHashMap<NameArity, SpecDef> {
         &self.specs
     }
@@ -423,12 +464,25 @@ impl DefMap {
 
     fn merge(&mut self, other: &Self) {
         self.included.extend(other.included.iter().cloned());
-        self.functions.extend(
-            other
-                .functions
-                .iter()
-                .map(|(name, def)| (name.clone(), def.clone())),
-        );
+        for (name, def) in &other.functions {
+            let other_defs = match other.function_redefinitions.get(name) {
+                Some(defs) => defs.clone(),
+                None => vec![def.clone()],
+            };
+            match self.functions.insert(name.clone(), def.clone()) {
+                // The same header may be included more than once
+                Some(previous) if previous != *def => self
+                    .function_redefinitions
+                    .entry(name.clone())
+                    .or_insert_with(|| vec![previous])
+                    .extend(other_defs),
+                _ => {
+                    if other_defs.len() > 1 {
+                        self.function_redefinitions.insert(name.clone(), other_defs);
+                    }
+                }
+            }
+        }
         self.specs.extend(
             other
                 .specs
@@ -504,6 +558,7 @@ impl DefMap {
             _c: _,
             included,
             functions,
+            function_redefinitions,
             specs,
             deprecated,
             exported_functions,
@@ -520,6 +575,7 @@ impl DefMap {
 
         included.shrink_to_fit();
         functions.shrink_to_fit();
+        function_redefinitions.shrink_to_fit();
         specs.shrink_to_fit();
         exported_functions.shrink_to_fit();
         imported_functions.shrink_to_fit();
@@ -536,6 +592,7 @@ impl DefMap {
 #[cfg(test)]
 mod tests {
     use elp_base_db::fixture::WithFixture;
+    use elp_base_db::SourceDatabaseExt;
     use expect_test::expect;
     use expect_test::Expect;
 
@@ -703,6 +760,24 @@ bar() -> ok.
         )
     }
 
+    #[test]
+    fn exported_types_several_attributes() {
+        check_functions(
+            r#"
+-export_type([foo/1]).
+-type foo(A) :: ok.
+-type bar() :: ok.
+-opaque baz() :: ok.
+-export_type([baz/0, missing/0]).
+"#,
+            expect![[r#"
+                -type bar/0 exported: false
+                -opaque baz/0 exported: true
+                -type foo/1 exported: true
+            "#]],
+        )
+    }
+
     #[test]
     fn exported_types_from_header() {
         check_functions(
@@ -757,4 +832,104 @@ bar() -> ok.
             "#]],
         )
     }
+
+    #[test]
+    fn function_redefinitions() {
+        let (db, file_id) = TestDB::with_single_file(
+            r#"
+-module(main).
+foo(1) -> one;
+foo(2) -> two.
+bar() -> ok.
+foo(_) -> other.
+baz() -> ok.
+"#,
+        );
+        let def_map = db.def_map(file_id);
+        let redefinitions = def_map.get_function_redefinitions();
+        assert_eq!(redefinitions.len(), 1);
+        let defs = &redefinitions[&NameArity::new(Name::from_erlang_service("foo"), 1)];
+        assert_eq!(defs.len(), 2);
+        assert!(defs[0].function_id != defs[1].function_id);
+        assert_eq!(
+            def_map
+                .get_function(&NameArity::new(Name::from_erlang_service("foo"), 1))
+                .map(|def| def.function_id),
+            Some(defs[1].function_id)
+        );
+    }
+
+    #[test]
+    fn function_redefinitions_in_header() {
+        let (db, files) = TestDB::with_many_files(
+            r#"
+//- /src/main.erl
+-module(main).
+-include("main.hrl").
+-include("main.hrl").
+foo(_) -> other.
+bar() -> ok.
+//- /src/main.hrl
+foo(1) -> one.
+bar() -> ok.
+"#,
+        );
+        let def_map = db.def_map(files[0]);
+        let redefinitions = def_map.get_function_redefinitions();
+        let files_of = |name: &str, arity| {
+            redefinitions[&NameArity::new(Name::from_erlang_service(name), arity)]
+                .iter()
+                .map(|def| def.file.file_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(redefinitions.len(), 2);
+        assert_eq!(files_of("foo", 1), vec![files[1], files[0]]);
+        assert_eq!(files_of("bar", 0), vec![files[1], files[0]]);
+    }
+
+    #[test]
+    fn not_recomputed_on_body_edit() {
+        let (mut db, files) = TestDB::with_many_files(
+            r#"
+//- /src/main.erl
+-module(main).
+-include("main.hrl").
+-export([foo/0]).
+foo() -> 1.
+//- /src/main.hrl
+bar() -> ok.
+"#,
+        );
+        let file_id = files[0];
+        let executed_def_maps = |db: &TestDB| {
+            db.log_executed(|| {
+                db.def_map(file_id);
+            })
+            .into_iter()
+            .filter(|query| query.starts_with("def_map("))
+            .collect::<Vec<_>>()
+        };
+        executed_def_maps(&db);
+
+        db.set_file_text(
+            file_id,
+            Arc::new(
+                "-module(main).\n-include(\"main.hrl\").\n-export([foo/0]).\nfoo() -> 1 + 2.\n"
+                    .to_string(),
+            ),
+        );
+        assert_eq!(executed_def_maps(&db), Vec::<String>::new());
+
+        db.set_file_text(
+            file_id,
+            Arc::new(
+                "-module(main).\n-include(\"main.hrl\").\n-export([foo/0]).\nfoo() -> 1 + 2.\nbaz() -> ok.\n"
+                    .to_string(),
+            ),
+        );
+        assert_eq!(
+            executed_def_maps(&db),
+            vec!["def_map(FileId(0))".to_string()]
+        );
+    }
 }
@@ -0,0 +1,286 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The options of the `-dialyzer` attributes of a module, such as
+//! `-dialyzer({nowarn_function, [foo/1]})`, so that dialyzer suppressions
+//! can be honoured.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+
+use crate::db::MinDefDatabase;
+use crate::known;
+use crate::Body;
+use crate::InFile;
+use crate::Literal;
+use crate::Name;
+use crate::NameArity;
+use crate::Term;
+use crate::TermId;
+
+/// The options documented by dialyzer. Others are kept, but never
+/// suppress anything.
+const KNOWN_OPTIONS: &[&str] = &[
+    "nowarn_function",
+    "no_behaviours",
+    "no_contracts",
+    "no_extra_return",
+    "no_fail_call",
+    "no_fun_app",
+    "no_improper_lists",
+    "no_match",
+    "no_missing_calls",
+    "no_missing_return",
+    "no_opaque",
+    "no_return",
+    "no_undefined_callbacks",
+    "no_underspecs",
+    "no_unknown",
+    "no_unused",
+    "error_handling",
+    "extra_return",
+    "missing_return",
+    "overspecs",
+    "specdiffs",
+    "underspecs",
+    "unknown",
+    "unmatched_returns",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialyzerOption {
+    /// The name of the option, e.g. `nowarn_function` or `no_return`
+    pub name: Name,
+    /// The functions the option is restricted to, empty if it applies to
+    /// the whole module
+    pub functions: Vec<NameArity>,
+}
+
+impl DialyzerOption {
+    pub fn is_known(&self) -> bool {
+        KNOWN_OPTIONS.contains(&self.name.as_str())
+    }
+
+    fn applies_to(&self, function: &NameArity) -> bool {
+        self.functions.is_empty() || self.functions.contains(function)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DialyzerAttributes {
+    /// In source order
+    options: Vec<DialyzerOption>,
+}
+
+impl DialyzerAttributes {
+    pub fn options(&self) -> &[DialyzerOption] {
+        &self.options
+    }
+
+    /// Whether the dialyzer warnings named `option`, such as `no_return`,
+    /// are suppressed for `function`, either by that option or by
+    /// `nowarn_function`. Options enabling extra warnings, such as
+    /// `unmatched_returns`, never suppress anything.
+    pub fn is_suppressed(&self, option: &str, function: &NameArity) -> bool {
+        self.options.iter().any(|opt| {
+            opt.is_known()
+                && ((opt.name.as_str().starts_with("no_") && opt.name.as_str() == option)
+                    || (opt.name == known::nowarn_function && !opt.functions.is_empty()))
+                && opt.applies_to(function)
+        })
+    }
+}
+
+/// Only the `-dialyzer` attributes of the file itself are considered, not
+/// the ones of included headers.
+pub(crate) fn dialyzer_attributes_query(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+) -> Arc<DialyzerAttributes> {
+    let form_list = db.file_form_list(file_id);
+    let mut res = DialyzerAttributes::default();
+    for (idx, attribute) in form_list.attributes() {
+        if attribute.name != known::dialyzer {
+            continue;
+        }
+        let attribute = db.attribute_body(InFile::new(file_id, idx));
+        collect(db, &attribute.body, attribute.value, &mut res);
+    }
+    Arc::new(res)
+}
+
+/// The attribute takes an option, a `{Options, Functions}` tuple, or a
+/// list of these, where `Options` and `Functions` can each be a single
+/// element or a list.
+fn collect(db: &dyn MinDefDatabase, body: &Body, term_id: TermId, res: &mut DialyzerAttributes) {
+    match &body[term_id] {
+        Term::Literal(Literal::Atom(atom)) => res.options.push(DialyzerOption {
+            name: db.lookup_atom(*atom),
+            functions: Vec::new(),
+        }),
+        Term::List { exprs, .. } => {
+            for expr in exprs {
+                collect(db, body, *expr, res);
+            }
+        }
+        Term::Tuple { exprs } => {
+            if let [options, functions] = exprs[..] {
+                let mut fas = Vec::new();
+                collect_functions(db, body, functions, &mut fas);
+                let mut names = Vec::new();
+                collect_names(db, body, options, &mut names);
+                res.options
+                    .extend(names.into_iter().map(|name| DialyzerOption {
+                        name,
+                        functions: fas.clone(),
+                    }));
+            }
+        }
+        Term::MacroCall { expansion, .. } => collect(db, body, *expansion, res),
+        _ => {}
+    }
+}
+
+fn collect_names(db: &dyn MinDefDatabase, body: &Body, term_id: TermId, res: &mut Vec<Name>) {
+    match &body[term_id] {
+        Term::Literal(Literal::Atom(atom)) => res.push(db.lookup_atom(*atom)),
+        Term::List { exprs, .. } => {
+            for expr in exprs {
+                collect_names(db, body, *expr, res);
+            }
+        }
+        Term::MacroCall { expansion, .. } => collect_names(db, body, *expansion, res),
+        _ => {}
+    }
+}
+
+fn collect_functions(
+    db: &dyn MinDefDatabase,
+    body: &Body,
+    term_id: TermId,
+    res: &mut Vec<NameArity>,
+) {
+    match &body[term_id] {
+        Term::List { exprs, .. } => {
+            for expr in exprs {
+                collect_functions(db, body, *expr, res);
+            }
+        }
+        Term::MacroCall { expansion, .. } => collect_functions(db, body, *expansion, res),
+        _ => res.extend(function_ref(db, body, term_id)),
+    }
+}
+
+/// `foo/1` and `{foo, 1}` are both lowered to a tuple
+pub(crate) fn function_ref(
+    db: &dyn MinDefDatabase,
+    body: &Body,
+    term_id: TermId,
+) -> Option<NameArity> {
+    match &body[term_id] {
+        Term::Tuple { exprs } => match exprs[..] {
+            [name, arity] => match (&body[name], &body[arity]) {
+                (Term::Literal(Literal::Atom(name)), Term::Literal(Literal::Integer(arity))) => {
+                    Some(NameArity::new(
+                        db.lookup_atom(*name),
+                        u32::try_from(*arity).ok()?,
+                    ))
+                }
+                _ => None,
+            },
+            _ => None,
+        },
+        Term::MacroCall { expansion, .. } => function_ref(db, body, *expansion),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+    use crate::Name;
+    use crate::NameArity;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id) = TestDB::with_single_file(fixture);
+        let attributes = db.dialyzer_attributes(file_id);
+        let actual = attributes
+            .options()
+            .iter()
+            .map(|option| {
+                let functions = option
+                    .functions
+                    .iter()
+                    .map(|function| function.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "{} [{}] known: {}\n",
+                    option.name,
+                    functions,
+                    option.is_known()
+                )
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn dialyzer_options() {
+        check(
+            r#"
+-module(main).
+-define(FUNS, [bar/2]).
+-dialyzer(no_return).
+-dialyzer({nowarn_function, [foo/1, {bar, 2}]}).
+-dialyzer([{[no_match, no_unused], ?FUNS}, not_an_option]).
+-dialyzer({no_fail_call, foo/1}).
+foo(_) -> ok.
+bar(_, _) -> ok.
+"#,
+            expect![[r#"
+                no_return [] known: true
+                nowarn_function [foo/1, bar/2] known: true
+                no_match [bar/2] known: true
+                no_unused [bar/2] known: true
+                not_an_option [] known: false
+                no_fail_call [foo/1] known: true
+            "#]],
+        );
+    }
+
+    #[test]
+    fn is_suppressed() {
+        let (db, file_id) = TestDB::with_single_file(
+            r#"
+-module(main).
+-dialyzer({nowarn_function, foo/1}).
+-dialyzer({no_match, bar/0}).
+-dialyzer(unmatched_returns).
+-dialyzer({[underspecs, no_return], qux/0}).
+-dialyzer({not_an_option, baz/0}).
+"#,
+        );
+        let attributes = db.dialyzer_attributes(file_id);
+        let fa = |name: &str, arity| NameArity::new(Name::from_erlang_service(name), arity);
+        assert!(attributes.is_suppressed("no_return", &fa("foo", 1)));
+        assert!(attributes.is_suppressed("no_match", &fa("bar", 0)));
+        assert!(!attributes.is_suppressed("no_return", &fa("bar", 0)));
+        assert!(!attributes.is_suppressed("unmatched_returns", &fa("baz", 0)));
+        assert!(!attributes.is_suppressed("underspecs", &fa("qux", 0)));
+        assert!(attributes.is_suppressed("no_return", &fa("qux", 0)));
+        assert!(!attributes.is_suppressed("not_an_option", &fa("baz", 0)));
+    }
+}
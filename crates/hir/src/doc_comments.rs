@@ -0,0 +1,228 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Attach comments to the forms they document.
+//!
+//! Every form is associated with the run of whole-line comments directly
+//! preceding it, and with a comment following it on its last line. Unlike
+//! edoc extraction, no tags are interpreted, and all forms are considered.
+//! Whether a run separated from its form by blank lines documents it is a
+//! matter of convention, so the number of blank lines is recorded for the
+//! doc extractor to decide.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::AstPtr;
+use elp_syntax::Direction;
+use elp_syntax::NodeOrToken;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use elp_syntax::TextRange;
+use fxhash::FxHashMap;
+
+use crate::db::MinDefDatabase;
+use crate::InFileAstPtr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentBlock {
+    /// The run of comments, each alone on its line, preceding the form. The
+    /// run ends at the first blank line, so it can be empty.
+    pub leading: Vec<DocComment>,
+    /// The number of blank lines between `leading` and the form
+    pub gap: usize,
+    /// A comment on the same line as the end of the form, as in
+    /// `-define(TIMEOUT, 100). % milliseconds`
+    pub trailing: Option<DocComment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocComment {
+    pub range: TextRange,
+    pub text: String,
+}
+
+impl DocComment {
+    fn new(comment: &SyntaxNode) -> DocComment {
+        DocComment {
+            range: comment.text_range(),
+            text: comment.text().to_string(),
+        }
+    }
+}
+
+pub(crate) fn form_doc_comments_query(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+) -> Arc<FxHashMap<InFileAstPtr<ast::Form>, CommentBlock>> {
+    let source = db.parse(file_id).tree();
+    let mut res = FxHashMap::default();
+    for form in source.forms() {
+        let (leading, gap) = leading_comments(form.syntax());
+        let trailing = trailing_comment(form.syntax());
+        if !leading.is_empty() || trailing.is_some() {
+            res.insert(
+                InFileAstPtr::new(file_id, AstPtr::new(&form)),
+                CommentBlock {
+                    leading,
+                    gap,
+                    trailing,
+                },
+            );
+        }
+    }
+    Arc::new(res)
+}
+
+/// The run of whole-line comments before `form`, in source order, and the
+/// number of blank lines between the run and the form.
+fn leading_comments(form: &SyntaxNode) -> (Vec<DocComment>, usize) {
+    let mut comments = Vec::new();
+    // Blank lines seen since the last comment, or since the form
+    let mut blank_lines = 0;
+    let mut gap = 0;
+    for node_or_token in form.siblings_with_tokens(Direction::Prev).skip(1) {
+        match node_or_token {
+            NodeOrToken::Token(token) if token.kind() == SyntaxKind::WHITESPACE => {
+                blank_lines = token.text().matches('\n').count().saturating_sub(1);
+            }
+            NodeOrToken::Node(node) if node.kind() == SyntaxKind::COMMENT => {
+                // A comment at the end of a line of code belongs to that code
+                if !starts_line(&node) {
+                    break;
+                }
+                if comments.is_empty() {
+                    gap = blank_lines;
+                } else if blank_lines > 0 {
+                    break;
+                }
+                blank_lines = 0;
+                comments.push(DocComment::new(&node));
+            }
+            _ => break,
+        }
+    }
+    comments.reverse();
+    if comments.is_empty() {
+        gap = 0;
+    }
+    (comments, gap)
+}
+
+/// The comment following `form` on the line it ends on.
+fn trailing_comment(form: &SyntaxNode) -> Option<DocComment> {
+    for node_or_token in form.siblings_with_tokens(Direction::Next).skip(1) {
+        match node_or_token {
+            NodeOrToken::Token(token)
+                if token.kind() == SyntaxKind::WHITESPACE && !token.text().contains('\n') => {}
+            NodeOrToken::Node(node) if node.kind() == SyntaxKind::COMMENT => {
+                return Some(DocComment::new(&node));
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// Whether nothing but whitespace precedes `comment` on its line.
+fn starts_line(comment: &SyntaxNode) -> bool {
+    match comment.prev_sibling_or_token() {
+        None => true,
+        Some(NodeOrToken::Token(token)) if token.kind() == SyntaxKind::WHITESPACE => {
+            token.text().contains('\n') || token.prev_sibling_or_token().is_none()
+        }
+        Some(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use elp_base_db::SourceDatabase;
+    use elp_syntax::AstNode;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+    use crate::InFile;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id) = TestDB::with_single_file(fixture);
+        let source = db.parse(file_id).tree();
+        let comments = db.form_doc_comments(file_id);
+        let mut blocks: Vec<_> = comments.iter().collect();
+        blocks.sort_by_key(|(ptr, _)| ptr.range().start());
+        let mut actual = String::new();
+        for (ptr, block) in blocks {
+            let form = ptr.to_node(&InFile::new(file_id, source.clone())).unwrap();
+            let text = form.syntax().text().to_string();
+            actual.push_str(&format!("{}\n", text.lines().next().unwrap_or_default()));
+            for comment in &block.leading {
+                actual.push_str(&format!(
+                    "  leading {:?}: {}\n",
+                    comment.range, comment.text
+                ));
+            }
+            if !block.leading.is_empty() {
+                actual.push_str(&format!("  gap {}\n", block.gap));
+            }
+            if let Some(comment) = &block.trailing {
+                actual.push_str(&format!(
+                    "  trailing {:?}: {}\n",
+                    comment.range, comment.text
+                ));
+            }
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn leading_and_trailing_comments() {
+        check(
+            r#"
+%% Module doc
+-module(main).
+-export([foo/0]). % exports
+
+%% About foo
+%%   more
+
+foo() -> ok. % trailing
+%% About bar
+bar() -> ok.
+
+%% First run
+
+%% Second run
+baz() -> ok.
+"#,
+            expect![[r#"
+                -module(main).
+                  leading 0..13: %% Module doc
+                  gap 0
+                -export([foo/0]).
+                  trailing 47..56: % exports
+                foo() -> ok.
+                  leading 58..70: %% About foo
+                  leading 71..80: %%   more
+                  gap 1
+                  trailing 95..105: % trailing
+                bar() -> ok.
+                  leading 106..118: %% About bar
+                  gap 0
+                baz() -> ok.
+                  leading 147..160: %% Second run
+                  gap 0
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,230 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Calls whose target is not syntactically fixed: `apply/3`,
+//! `erlang:apply/3`, and remote calls with a variable module or function.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+
+use crate::db::MinDefDatabase;
+use crate::known;
+use crate::Atom;
+use crate::Body;
+use crate::CallTarget;
+use crate::Expr;
+use crate::ExprId;
+use crate::FoldCtx;
+use crate::FormIdx;
+use crate::FunctionId;
+use crate::InFile;
+use crate::Literal;
+use crate::Name;
+use crate::Strategy;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynamicCall {
+    /// The `Expr::Call`
+    pub expr_id: ExprId,
+    pub target: DynamicCallTarget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DynamicCallTarget {
+    /// `apply(Mod, Fun, Args)` with literal `Mod` and `Fun` atoms. The
+    /// arity is only known if `Args` is a literal proper list.
+    Apply {
+        module: Atom,
+        name: Atom,
+        arity: Option<u32>,
+    },
+    /// The callee can only be known at runtime, as in `apply(M, F, A)` or
+    /// `Mod:Fun(Args)` with variables
+    Dynamic,
+}
+
+/// All dynamic calls in the clauses of `function_id`, in source order.
+pub(crate) fn dynamic_calls_query(
+    db: &dyn MinDefDatabase,
+    function_id: InFile<FunctionId>,
+) -> Arc<Vec<DynamicCall>> {
+    let function_body = db.function_body(function_id);
+    let body = &function_body.body;
+    let mut calls = Vec::new();
+    for (_clause_id, clause) in function_body.clauses.iter() {
+        for expr_id in &clause.exprs {
+            calls = FoldCtx::fold_expr(
+                body,
+                Strategy::TopDown,
+                *expr_id,
+                calls,
+                &mut |mut acc, ctx| {
+                    if let Expr::Call { target, args } = &ctx.expr {
+                        if let Some(target) = dynamic_call_target(db, body, target, args) {
+                            acc.push(DynamicCall {
+                                expr_id: ctx.expr_id,
+                                target,
+                            });
+                        }
+                    }
+                    acc
+                },
+                &mut |acc, _ctx| acc,
+            );
+        }
+    }
+    Arc::new(calls)
+}
+
+/// The modules called through `apply/3` with a literal module, in
+/// `file_id`, sorted and without duplicates.
+pub(crate) fn dynamically_called_modules_query(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+) -> Arc<Vec<Name>> {
+    let form_list = db.file_form_list(file_id);
+    let mut modules = Vec::new();
+    for form in form_list.forms() {
+        if let FormIdx::Function(function_id) = form {
+            let calls = db.dynamic_calls(InFile::new(file_id, *function_id));
+            for call in calls.iter() {
+                if let DynamicCallTarget::Apply { module, .. } = call.target {
+                    modules.push(db.lookup_atom(module));
+                }
+            }
+        }
+    }
+    modules.sort();
+    modules.dedup();
+    Arc::new(modules)
+}
+
+fn dynamic_call_target(
+    db: &dyn MinDefDatabase,
+    body: &Body,
+    target: &CallTarget<ExprId>,
+    args: &[ExprId],
+) -> Option<DynamicCallTarget> {
+    let is_atom = |expr_id: ExprId, name: &Name| match body[expr_id].as_atom() {
+        Some(atom) => db.lookup_atom(atom) == *name,
+        None => false,
+    };
+    let is_apply = match target {
+        CallTarget::Local { name } => is_atom(*name, &known::apply),
+        CallTarget::Remote { .. } if !target.is_static(body) => {
+            return Some(DynamicCallTarget::Dynamic);
+        }
+        CallTarget::Remote { module, name } => {
+            is_atom(*module, &known::erlang) && is_atom(*name, &known::apply)
+        }
+    };
+    match args {
+        [module, name, call_args] if is_apply => {
+            match (body[*module].as_atom(), body[*name].as_atom()) {
+                (Some(module), Some(name)) => {
+                    let arity = match &body[*call_args] {
+                        Expr::List { exprs, tail: None } => Some(exprs.len() as u32),
+                        Expr::Literal(Literal::String(string)) if string.is_empty() => Some(0),
+                        _ => None,
+                    };
+                    Some(DynamicCallTarget::Apply {
+                        module,
+                        name,
+                        arity,
+                    })
+                }
+                _ => Some(DynamicCallTarget::Dynamic),
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use super::DynamicCallTarget;
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+    use crate::FormIdx;
+    use crate::InFile;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id) = TestDB::with_single_file(fixture);
+        let form_list = db.file_form_list(file_id);
+        let mut actual = String::new();
+        for form in form_list.forms() {
+            if let FormIdx::Function(function_id) = form {
+                let calls = db.dynamic_calls(InFile::new(file_id, *function_id));
+                for call in calls.iter() {
+                    let target = match call.target {
+                        DynamicCallTarget::Apply {
+                            module,
+                            name,
+                            arity: Some(arity),
+                        } => format!(
+                            "{}:{}/{}",
+                            db.lookup_atom(module),
+                            db.lookup_atom(name),
+                            arity
+                        ),
+                        DynamicCallTarget::Apply {
+                            module,
+                            name,
+                            arity: None,
+                        } => format!("{}:{}/_", db.lookup_atom(module), db.lookup_atom(name)),
+                        DynamicCallTarget::Dynamic => "dynamic".to_string(),
+                    };
+                    actual.push_str(&format!("{}: {}\n", form_list[*function_id].name, target));
+                }
+            }
+        }
+        let modules = db.dynamically_called_modules(file_id);
+        actual.push_str(&format!(
+            "modules: {}\n",
+            modules
+                .iter()
+                .map(|module| module.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn apply_calls() {
+        check(
+            r#"
+-module(main).
+foo(Args) ->
+    apply(one, run, [1, 2]),
+    erlang:apply(two, run, Args),
+    apply(one, stop, []).
+bar(M, F) ->
+    apply(M, F, []),
+    M:F(),
+    lists:map(F, []),
+    M:run(1).
+"#,
+            expect![[r#"
+                foo/1: one:run/2
+                foo/1: two:run/_
+                foo/1: one:stop/0
+                bar/2: dynamic
+                bar/2: dynamic
+                bar/2: dynamic
+                modules: one, two
+            "#]],
+        );
+    }
+}
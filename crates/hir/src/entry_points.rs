@@ -0,0 +1,162 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The conventional entry points of a module, given by its role: `main/1`
+//! for an escript, `start/2` and `stop/1` for an `application`, and
+//! `init/1` for the other OTP behaviours.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+
+use crate::db::MinDefDatabase;
+use crate::Name;
+use crate::NameArity;
+use crate::OtpBehaviour;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    /// `main/1` of an escript
+    Escript,
+    /// `start/2` and `stop/1` of an `application`
+    Application,
+    /// `init/1` of a supervisor or of a `gen_*` behaviour
+    Init,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryPoint {
+    pub kind: EntryKind,
+    pub name: NameArity,
+    /// Whether the module defines the function
+    pub defined: bool,
+}
+
+/// The entry points expected for `file_id`, defined or not, escript first
+/// and then in the order of the `-behaviour` attributes. An entry point
+/// shared by several behaviours is only listed once.
+pub(crate) fn entry_points_query(db: &dyn MinDefDatabase, file_id: FileId) -> Arc<Vec<EntryPoint>> {
+    let mut expected: Vec<(EntryKind, &str, u32)> = Vec::new();
+    if is_escript(db, file_id) {
+        expected.push((EntryKind::Escript, "main", 1));
+    }
+    if let Some(role) = db.otp_role(file_id) {
+        for behaviour in role.behaviours() {
+            match behaviour {
+                OtpBehaviour::Application => {
+                    expected.push((EntryKind::Application, "start", 2));
+                    expected.push((EntryKind::Application, "stop", 1));
+                }
+                OtpBehaviour::GenEvent
+                | OtpBehaviour::GenFsm
+                | OtpBehaviour::GenServer
+                | OtpBehaviour::GenStatem
+                | OtpBehaviour::Supervisor
+                | OtpBehaviour::SupervisorBridge => expected.push((EntryKind::Init, "init", 1)),
+                OtpBehaviour::Other(_) => {}
+            }
+        }
+    }
+
+    let def_map = db.def_map(file_id);
+    let mut res: Vec<EntryPoint> = Vec::new();
+    for (kind, name, arity) in expected {
+        let name = NameArity::new(Name::from_erlang_service(name), arity);
+        if res.iter().any(|entry| entry.name == name) {
+            continue;
+        }
+        res.push(EntryPoint {
+            kind,
+            defined: def_map.get_function(&name).is_some(),
+            name,
+        });
+    }
+    Arc::new(res)
+}
+
+fn is_escript(db: &dyn MinDefDatabase, file_id: FileId) -> bool {
+    let source_root = db.source_root(db.file_source_root(file_id));
+    let ext = source_root
+        .path_for_file(&file_id)
+        .and_then(|path| path.name_and_extension())
+        .and_then(|(_name, ext)| ext);
+    ext == Some("escript")
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, files) = TestDB::with_many_files(fixture);
+        let actual = db
+            .entry_points(files[0])
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{:?} {} defined: {}\n",
+                    entry.kind, entry.name, entry.defined
+                )
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn several_roles() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-behaviour(application).
+-behaviour(supervisor).
+-behaviour(gen_server).
+start(_Type, _Args) -> ok.
+init(_) -> ok.
+"#,
+            expect![[r#"
+                Application start/2 defined: true
+                Application stop/1 defined: false
+                Init init/1 defined: true
+            "#]],
+        );
+    }
+
+    #[test]
+    fn escript() {
+        check(
+            r#"
+//- /src/tool.escript
+main(_) -> ok.
+"#,
+            expect![[r#"
+                Escript main/1 defined: true
+            "#]],
+        );
+    }
+
+    #[test]
+    fn no_role() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-behaviour(my_behaviour).
+main(_) -> ok.
+init(_) -> ok.
+"#,
+            expect![""],
+        );
+    }
+}
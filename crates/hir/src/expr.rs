@@ -162,6 +162,12 @@ pub enum Expr {
         target: CallTarget<ExprId>,
         arity: ExprId,
     },
+    /// `module:name` on its own rather than as the target of a call,
+    /// such as in a macro replacement
+    RemoteRef {
+        module: ExprId,
+        name: ExprId,
+    },
     Closure {
         clauses: Vec<Clause>,
         name: Option<PatId>,
@@ -213,6 +219,7 @@ pub type ClauseId = Idx<Clause>;
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Clause {
     pub pats: Vec<PatId>,
+    /// Disjunction (`;`) of conjunctions (`,`) of guard tests
     pub guards: Vec<Vec<ExprId>>,
     pub exprs: Vec<ExprId>,
 }
@@ -249,9 +256,32 @@ pub struct RecordFieldBody {
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ReceiveAfter {
     pub timeout: ExprId,
+    pub kind: ReceiveTimeout,
     pub exprs: Vec<ExprId>,
 }
 
+/// What is known statically about the timeout of a `receive ... after`,
+/// looking through macros.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ReceiveTimeout {
+    /// `after infinity`, the receive may block forever
+    Infinity,
+    /// A literal integer. `after 0` polls the mailbox without blocking.
+    Integer(i128),
+    /// Only known at runtime
+    Dynamic,
+}
+
+/// The `_ = Expr` idiom, matching a value only to discard it, usually to
+/// silence warnings about an unused result.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MatchDiscard {
+    /// `_ = Expr`
+    Wildcard,
+    /// `_Name = Expr`, naming the discarded value for readability
+    Named(Var),
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum CallTarget<Id> {
     Local { name: Id },
@@ -259,6 +289,18 @@ pub enum CallTarget<Id> {
 }
 
 impl CallTarget<ExprId> {
+    /// Whether the name, and the module of a remote target, are literal
+    /// atoms. Otherwise the target can only be known at runtime, as in
+    /// `M:F(Args)` or `fun M:F/A` with variable parts.
+    pub fn is_static(&self, body: &Body) -> bool {
+        match self {
+            CallTarget::Local { name } => body[*name].as_atom().is_some(),
+            CallTarget::Remote { module, name } => {
+                body[*module].as_atom().is_some() && body[*name].as_atom().is_some()
+            }
+        }
+    }
+
     pub fn resolve_call(
         &self,
         arity: u32,
@@ -472,6 +514,11 @@ pub enum TypeExpr {
         var: Var,
         ty: TypeExprId,
     },
+    /// `<<_:M, _:_*N>>`, where either segment can be omitted
+    Binary {
+        base_size: Option<TypeExprId>,
+        unit: Option<TypeExprId>,
+    },
     BinaryOp {
         lhs: TypeExprId,
         rhs: TypeExprId,
@@ -0,0 +1,115 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Size metrics of a file, computed from its text and form list only, so
+//! that they are cheap to compute for a whole project.
+
+use elp_base_db::FileId;
+
+use crate::db::MinDefDatabase;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMetrics {
+    /// The number of lines, as shown by an editor, so a trailing newline
+    /// starts a last empty line
+    pub lines: usize,
+    pub functions: usize,
+    pub exported_functions: usize,
+    pub types: usize,
+    pub records: usize,
+    pub macros: usize,
+    /// Whether the file is marked `@generated`, so that it can be excluded
+    pub generated: bool,
+}
+
+/// Only the definitions of the file itself are counted, not the ones of
+/// included headers.
+pub(crate) fn file_metrics_query(db: &dyn MinDefDatabase, file_id: FileId) -> FileMetrics {
+    let text = db.file_text(file_id);
+    let def_map = db.local_def_map(file_id);
+    FileMetrics {
+        lines: text.matches('\n').count() + 1,
+        functions: def_map.get_functions().len(),
+        exported_functions: def_map
+            .get_functions()
+            .values()
+            .filter(|def| def.exported)
+            .count(),
+        types: def_map.get_types().len(),
+        records: def_map.get_records().len(),
+        macros: def_map.get_macros().len(),
+        generated: db.is_generated(file_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id) = TestDB::with_single_file(fixture);
+        let metrics = db.file_metrics(file_id);
+        expect.assert_debug_eq(&metrics);
+    }
+
+    #[test]
+    fn metrics() {
+        check(
+            r#"
+-module(main).
+-export([foo/0, bar/1]).
+-type t() :: ok.
+-record(r, {a}).
+-define(M, 1).
+-define(N(X), X).
+foo() -> ok.
+bar(_) -> ok.
+baz() -> ok.
+"#,
+            expect![[r#"
+                FileMetrics {
+                    lines: 11,
+                    functions: 3,
+                    exported_functions: 2,
+                    types: 1,
+                    records: 1,
+                    macros: 2,
+                    generated: false,
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn generated() {
+        check(
+            r#"
+%% @generated
+-module(main).
+-compile(export_all).
+foo() -> ok."#,
+            expect![[r#"
+                FileMetrics {
+                    lines: 4,
+                    functions: 1,
+                    exported_functions: 1,
+                    types: 0,
+                    records: 0,
+                    macros: 0,
+                    generated: true,
+                }
+            "#]],
+        );
+    }
+}
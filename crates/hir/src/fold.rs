@@ -345,6 +345,10 @@ impl<'a, T> FoldCtx<'a, T> {
                 };
                 self.do_fold_expr(*arity, r)
             }
+            crate::Expr::RemoteRef { module, name } => {
+                let r = self.do_fold_expr(*module, acc);
+                self.do_fold_expr(*name, r)
+            }
             crate::Expr::Closure { clauses, name: _ } => clauses.iter().fold(
                 acc,
                 |acc,
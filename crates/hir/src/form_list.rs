@@ -114,6 +114,14 @@ impl FormList {
         self.data.exports.iter()
     }
 
+    pub fn imports(&self) -> impl Iterator<Item = (ImportId, &Import)> {
+        self.data.imports.iter()
+    }
+
+    pub fn type_exports(&self) -> impl Iterator<Item = (TypeExportId, &TypeExport)> {
+        self.data.type_exports.iter()
+    }
+
     pub fn specs(&self) -> impl Iterator<Item = (SpecId, &Spec)> {
         self.data.specs.iter()
     }
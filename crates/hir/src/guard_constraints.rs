@@ -0,0 +1,296 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Type constraints on function arguments, derived from the type tests
+//! in clause guards. In
+//!
+//! ```erlang
+//! foo(X, Y) when is_integer(X) -> X;
+//! foo(X, Y) when is_float(X); is_atom(X) -> Y.
+//! ```
+//!
+//! the first argument of `foo/2` is constrained to
+//! `atom() | float() | integer()`, and the second one is not constrained.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::db::MinDefDatabase;
+use crate::Body;
+use crate::CallTarget;
+use crate::Clause;
+use crate::Expr;
+use crate::ExprId;
+use crate::FunctionId;
+use crate::InFile;
+use crate::Pat;
+use crate::PatId;
+use crate::Var;
+
+/// A type checked by one of the `is_*` guard BIFs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GuardType {
+    Atom,
+    Binary,
+    Bitstring,
+    Boolean,
+    Float,
+    Function,
+    Integer,
+    List,
+    Map,
+    Number,
+    Pid,
+    Port,
+    Record,
+    Reference,
+    Tuple,
+}
+
+impl GuardType {
+    fn from_type_test(name: &str, arity: usize) -> Option<GuardType> {
+        let ty = match (name, arity) {
+            ("is_atom", 1) => GuardType::Atom,
+            ("is_binary", 1) => GuardType::Binary,
+            ("is_bitstring", 1) => GuardType::Bitstring,
+            ("is_boolean", 1) => GuardType::Boolean,
+            ("is_float", 1) => GuardType::Float,
+            ("is_function", 1 | 2) => GuardType::Function,
+            ("is_integer", 1) => GuardType::Integer,
+            ("is_list", 1) => GuardType::List,
+            ("is_map", 1) => GuardType::Map,
+            ("is_number", 1) => GuardType::Number,
+            ("is_pid", 1) => GuardType::Pid,
+            ("is_port", 1) => GuardType::Port,
+            ("is_record", 2 | 3) => GuardType::Record,
+            ("is_reference", 1) => GuardType::Reference,
+            ("is_tuple", 1) => GuardType::Tuple,
+            _ => return None,
+        };
+        Some(ty)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GuardType::Atom => "atom()",
+            GuardType::Binary => "binary()",
+            GuardType::Bitstring => "bitstring()",
+            GuardType::Boolean => "boolean()",
+            GuardType::Float => "float()",
+            GuardType::Function => "function()",
+            GuardType::Integer => "integer()",
+            GuardType::List => "list()",
+            GuardType::Map => "map()",
+            GuardType::Number => "number()",
+            GuardType::Pid => "pid()",
+            GuardType::Port => "port()",
+            GuardType::Record => "record()",
+            GuardType::Reference => "reference()",
+            GuardType::Tuple => "tuple()",
+        }
+    }
+}
+
+/// The union of the types an argument is constrained to
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GuardConstraint {
+    types: BTreeSet<GuardType>,
+}
+
+impl GuardConstraint {
+    pub fn types(&self) -> impl Iterator<Item = GuardType> + '_ {
+        self.types.iter().copied()
+    }
+
+    fn union(mut self, other: GuardConstraint) -> GuardConstraint {
+        self.types.extend(other.types);
+        self
+    }
+}
+
+impl fmt::Display for GuardConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let types: Vec<_> = self.types().map(|ty| ty.as_str()).collect();
+        write!(f, "{}", types.join(" | "))
+    }
+}
+
+pub(crate) fn function_arg_constraints_query(
+    db: &dyn MinDefDatabase,
+    function_id: InFile<FunctionId>,
+) -> Arc<Vec<Option<GuardConstraint>>> {
+    let form_list = db.file_form_list(function_id.file_id);
+    let arity = form_list[function_id.value].name.arity() as usize;
+    let function_body = db.function_body(function_id);
+    let constraints = (0..arity)
+        .map(|idx| {
+            function_body
+                .clauses
+                .iter()
+                .map(|(_, clause)| clause_constraint(db, &function_body.body, clause, idx))
+                .reduce(|acc, constraint| Some(acc?.union(constraint?)))
+                .flatten()
+        })
+        .collect();
+    Arc::new(constraints)
+}
+
+/// A clause constrains an argument if it is a variable, and each of the
+/// guard sequences tests its type. Only the first type test on the
+/// variable in a guard sequence is taken into account.
+fn clause_constraint(
+    db: &dyn MinDefDatabase,
+    body: &Body,
+    clause: &Clause,
+    idx: usize,
+) -> Option<GuardConstraint> {
+    let var = pat_var(body, *clause.pats.get(idx)?)?;
+    if clause.guards.is_empty() {
+        return None;
+    }
+    let mut constraint = GuardConstraint::default();
+    for guard in &clause.guards {
+        let ty = guard
+            .iter()
+            .find_map(|expr_id| type_test(db, body, *expr_id, var))?;
+        constraint.types.insert(ty);
+    }
+    Some(constraint)
+}
+
+fn pat_var(body: &Body, pat_id: PatId) -> Option<Var> {
+    match &body[pat_id] {
+        Pat::Var(var) => Some(*var),
+        Pat::Match { lhs, rhs } => pat_var(body, *lhs).or_else(|| pat_var(body, *rhs)),
+        _ => None,
+    }
+}
+
+fn type_test(db: &dyn MinDefDatabase, body: &Body, expr_id: ExprId, var: Var) -> Option<GuardType> {
+    match &body[expr_id] {
+        Expr::Call { target, args } => {
+            let name = match target {
+                CallTarget::Local { name } => name,
+                CallTarget::Remote { module, name } => {
+                    if db.lookup_atom(body[*module].as_atom()?).as_str() != "erlang" {
+                        return None;
+                    }
+                    name
+                }
+            };
+            match &body[*args.first()?] {
+                Expr::Var(arg) if *arg == var => {
+                    let name = db.lookup_atom(body[*name].as_atom()?);
+                    GuardType::from_type_test(name.as_str(), args.len())
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+    use crate::InFile;
+
+    #[track_caller]
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id) = TestDB::with_single_file(fixture);
+        let def_map = db.def_map(file_id);
+        let mut functions: Vec<_> = def_map.get_functions().values().collect();
+        functions.sort_by_key(|def| def.function.name.to_string());
+        let actual = functions
+            .into_iter()
+            .map(|def| {
+                let constraints = db
+                    .function_arg_constraints(InFile::new(file_id, def.function_id))
+                    .iter()
+                    .map(|constraint| match constraint {
+                        Some(constraint) => constraint.to_string(),
+                        None => "_".to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{}: {}\n", def.function.name, constraints)
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn single_clause() {
+        check(
+            r#"
+-module(main).
+foo(X, Y) when is_integer(X) -> {X, Y}.
+bar(X) when erlang:is_list(X), length(X) > 2 -> X.
+baz(X) when length(X) > 2 -> X.
+"#,
+            expect![[r#"
+                bar/1: list()
+                baz/1: _
+                foo/2: integer(), _
+            "#]],
+        );
+    }
+
+    #[test]
+    fn union_across_clauses_and_guards() {
+        check(
+            r#"
+-module(main).
+foo(X, Y) when is_integer(X) -> {X, Y};
+foo(X = {_}, Y) when is_tuple(X), is_atom(Y) -> {X, Y};
+foo(X, Y) when is_float(X); is_atom(X) -> {X, Y}.
+"#,
+            expect![[r#"
+                foo/2: atom() | float() | integer() | tuple(), _
+            "#]],
+        );
+    }
+
+    #[test]
+    fn conjunction_within_disjunction() {
+        check(
+            r#"
+-module(main).
+foo(X) when X > 0, is_integer(X); is_atom(X) -> X.
+bar(X) when is_integer(X); X > 0, X < 10 -> X.
+"#,
+            expect![[r#"
+                bar/1: _
+                foo/1: atom() | integer()
+            "#]],
+        );
+    }
+
+    #[test]
+    fn unguarded_clause() {
+        check(
+            r#"
+-module(main).
+foo(X) when is_integer(X) -> X;
+foo(X) -> X.
+bar(X) when is_integer(X); X > 2 -> X.
+"#,
+            expect![[r#"
+                bar/1: _
+                foo/1: _
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,118 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+
+use crate::db::MinDefDatabase;
+use crate::FaEntryId;
+use crate::FunctionDef;
+use crate::ImportId;
+use crate::Name;
+use crate::NameArity;
+
+/// A single entry of an `-import` attribute, resolved to the function it
+/// refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedFunction {
+    pub import_id: ImportId,
+    pub entry_id: FaEntryId,
+    /// The module the function is imported from, as written
+    pub module: Name,
+    pub name: NameArity,
+    /// The file of the imported module, if it could be found
+    pub module_file: Option<FileId>,
+    /// The imported function, if the module defines it
+    pub def: Option<FunctionDef>,
+}
+
+/// All entries of all `-import` attributes in `file_id`, in source order.
+pub(crate) fn imported_functions_query(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+) -> Arc<Vec<ImportedFunction>> {
+    let form_list = db.file_form_list(file_id);
+    let module_index = db
+        .app_data(db.file_source_root(file_id))
+        .map(|app_data| db.module_index(app_data.project_id));
+    let mut imports = Vec::new();
+    for (import_id, import) in form_list.imports() {
+        let module_file = module_index
+            .as_ref()
+            .and_then(|index| index.file_for_module(import.from.as_str()));
+        let def_map = module_file.map(|module_file| db.def_map(module_file));
+        for entry_id in import.entries.clone() {
+            let name = form_list[entry_id].name.clone();
+            imports.push(ImportedFunction {
+                import_id,
+                entry_id,
+                module: import.from.clone(),
+                def: def_map
+                    .as_ref()
+                    .and_then(|def_map| def_map.get_function(&name).cloned()),
+                name,
+                module_file,
+            });
+        }
+    }
+    Arc::new(imports)
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, files) = TestDB::with_many_files(fixture);
+        let actual = db
+            .imported_functions(files[0])
+            .iter()
+            .map(|imported| {
+                let resolution = match (&imported.def, imported.module_file) {
+                    (Some(def), _) => format!("{:?}", def.file.file_id),
+                    (None, Some(_)) => "unknown function".to_string(),
+                    (None, None) => "unknown module".to_string(),
+                };
+                format!("{}:{} => {}\n", imported.module, imported.name, resolution)
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn resolves_all_import_attributes() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-import(one, [foo/1, bar/0]).
+-import(two, [baz/2]).
+-import(missing, [foo/1]).
+//- /src/one.erl
+-module(one).
+foo(_) -> ok.
+//- /src/two.erl
+-module(two).
+baz(_, _) -> ok.
+"#,
+            expect![[r#"
+                one:foo/1 => FileId(1)
+                one:bar/0 => unknown function
+                two:baz/2 => FileId(2)
+                missing:foo/1 => unknown module
+            "#]],
+        );
+    }
+}
@@ -12,12 +12,41 @@ use std::sync::Arc;
 use elp_base_db::FileId;
 use elp_base_db::SourceRoot;
 use elp_base_db::SourceRootId;
+use elp_base_db::VfsPath;
+use fxhash::FxHashMap;
+use fxhash::FxHashSet;
 
 use crate::db::MinDefDatabase;
 use crate::InFile;
 use crate::IncludeAttribute;
 use crate::IncludeAttributeId;
 
+/// Where a header offered for an include attribute is found, in the
+/// order the compiler searches these locations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IncludeSource {
+    /// The directory of the including file
+    Relative,
+    /// The include directories of the application
+    IncludePath,
+    /// Another application of the project, for `-include_lib` only
+    ProjectApp,
+    /// An OTP application, for `-include_lib` only
+    OtpApp,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncludeCandidate {
+    /// The path as written in the attribute
+    pub path: String,
+    /// The header the compiler would resolve `path` to
+    pub file_id: FileId,
+    pub source: IncludeSource,
+    /// The locations of the other headers with the same path, which are
+    /// never picked since `source` is searched first
+    pub shadowed: Vec<IncludeSource>,
+}
+
 struct IncludeCtx<'a> {
     db: &'a dyn MinDefDatabase,
     source_root_id: SourceRootId,
@@ -32,6 +61,69 @@ pub(crate) fn resolve(
     IncludeCtx::new(db, include_id.file_id).resolve(include_id.value)
 }
 
+/// The paths searched for the header of an include attribute, in the order
+/// they are tried.
+pub(crate) fn search_paths(
+    db: &dyn MinDefDatabase,
+    include_id: InFile<IncludeAttributeId>,
+) -> Vec<String> {
+    IncludeCtx::new(db, include_id.file_id).search_paths(include_id.value)
+}
+
+/// The headers that can be included from `file_id`, in the order they
+/// are searched, with a single entry per path.
+pub(crate) fn candidates(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+    is_lib: bool,
+) -> Vec<IncludeCandidate> {
+    IncludeCtx::new(db, file_id).candidates(is_lib)
+}
+
+/// The headers resolved from the include attributes of `file_id`, in
+/// source order. Unlike the form list, this is unaffected by edits that
+/// leave the include attributes alone, such as within function bodies.
+pub(crate) fn resolved_includes_query(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+) -> Arc<Vec<FileId>> {
+    let form_list = db.file_form_list(file_id);
+    Arc::new(
+        form_list
+            .includes()
+            // Unresolved includes are already logged by `resolve`
+            .filter_map(|(idx, _)| db.resolve_include(InFile::new(file_id, idx)))
+            .collect(),
+    )
+}
+
+/// Unresolved includes are missing from `resolved_includes`, so they are
+/// skipped here without being reported again.
+pub(crate) fn include_closure_query(db: &dyn MinDefDatabase, file_id: FileId) -> Arc<Vec<FileId>> {
+    let mut seen = FxHashSet::default();
+    seen.insert(file_id);
+    let mut closure = Vec::new();
+    collect_includes(db, file_id, &mut seen, &mut closure);
+    Arc::new(closure)
+}
+
+fn collect_includes(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+    seen: &mut FxHashSet<FileId>,
+    closure: &mut Vec<FileId>,
+) {
+    for &included in db.resolved_includes(file_id).iter() {
+        // Headers may be re-included (typically guarded by `-ifndef`),
+        // only the first occurrence is relevant and this also
+        // guarantees termination on cyclic includes.
+        if seen.insert(included) {
+            closure.push(included);
+            collect_includes(db, included, seen, closure);
+        }
+    }
+}
+
 impl<'a> IncludeCtx<'a> {
     fn new(db: &'a dyn MinDefDatabase, file_id: FileId) -> Self {
         let source_root_id = db.file_source_root(file_id);
@@ -47,8 +139,8 @@ impl<'a> IncludeCtx<'a> {
     fn resolve(&self, id: IncludeAttributeId) -> Option<FileId> {
         let form_list = self.db.file_form_list(self.file_id);
         let (path, file_id) = match &form_list[id] {
-            IncludeAttribute::Include { path, .. } => (path, self.resolve_include(path)),
-            IncludeAttribute::IncludeLib { path, .. } => (path, self.resolve_include_lib(path)),
+            IncludeAttribute::Include { path, .. } => (path, self.resolve_path(path, false)),
+            IncludeAttribute::IncludeLib { path, .. } => (path, self.resolve_path(path, true)),
         };
         if file_id.is_none() {
             let module_str = if let Some(module_attribute) = form_list.module_attribute() {
@@ -61,41 +153,156 @@ impl<'a> IncludeCtx<'a> {
         file_id
     }
 
-    fn resolve_include(&self, path: &str) -> Option<FileId> {
-        self.resolve_relative(path)
-            .or_else(|| self.resolve_local(path))
+    fn search_paths(&self, id: IncludeAttributeId) -> Vec<String> {
+        let form_list = self.db.file_form_list(self.file_id);
+        let (path, is_lib) = match &form_list[id] {
+            IncludeAttribute::Include { path, .. } => (path, false),
+            IncludeAttribute::IncludeLib { path, .. } => (path, true),
+        };
+        self.search_locations(path, is_lib)
+            .into_iter()
+            .map(|(path, _)| path.to_string())
+            .collect()
     }
 
-    fn resolve_include_lib(&self, path: &str) -> Option<FileId> {
-        self.resolve_include(path)
-            .or_else(|| self.resolve_remote(path))
+    /// The locations searched for the header `path`, in the order they
+    /// are tried, each with the source root the header is looked up in.
+    /// Shared by `resolve` and `search_paths`, so that they agree.
+    fn search_locations(&self, path: &str, is_lib: bool) -> Vec<(VfsPath, Arc<SourceRoot>)> {
+        let mut res = Vec::new();
+        // Relative to the directory of the including file
+        if let Some(relative) = self
+            .source_root
+            .path_for_file(&self.file_id)
+            .and_then(|file_path| file_path.parent()?.join(path))
+        {
+            res.push((relative, self.source_root.clone()));
+        }
+        let app_data = match self.db.app_data(self.source_root_id) {
+            Some(app_data) => app_data,
+            None => return res,
+        };
+        // The include path of the application
+        res.extend(
+            app_data
+                .include_path
+                .iter()
+                .map(|include| (VfsPath::from(include.join(path)), self.source_root.clone())),
+        );
+        // For `-include_lib`, the path starts with the name of an
+        // application of the project
+        if is_lib {
+            let project_data = self.db.project_data(app_data.project_id);
+            if let Some((app_name, path)) = path.split_once('/') {
+                if let Some(source_root_id) = project_data.app_roots.get(app_name) {
+                    if let Some(target_app_data) = self.db.app_data(source_root_id) {
+                        res.push((
+                            VfsPath::from(target_app_data.dir.join(path)),
+                            self.db.source_root(source_root_id),
+                        ));
+                    }
+                }
+            }
+        }
+        res
     }
 
-    fn resolve_relative(&self, path: &str) -> Option<FileId> {
-        self.source_root.relative_path(self.file_id, path)
-    }
+    fn candidates(&self, is_lib: bool) -> Vec<IncludeCandidate> {
+        let mut res: Vec<IncludeCandidate> = Vec::new();
+        let mut seen: FxHashMap<String, usize> = FxHashMap::default();
+        let mut add = |path: String, file_id: FileId, source: IncludeSource| {
+            if file_id == self.file_id {
+                return;
+            }
+            match seen.get(&path) {
+                Some(idx) => {
+                    let candidate = &mut res[*idx];
+                    if candidate.file_id != file_id && !candidate.shadowed.contains(&source) {
+                        candidate.shadowed.push(source);
+                    }
+                }
+                None => {
+                    seen.insert(path.clone(), res.len());
+                    res.push(IncludeCandidate {
+                        path,
+                        file_id,
+                        source,
+                        shadowed: Vec::new(),
+                    });
+                }
+            }
+        };
 
-    fn resolve_local(&self, path: &str) -> Option<FileId> {
-        let app_data = self.db.app_data(self.source_root_id)?;
-        app_data.include_path.iter().find_map(|include| {
-            let name = include.join(path);
-            self.source_root.file_for_path(&name.into())
-        })
-    }
+        if let Some(dir) = self
+            .source_root
+            .path_for_file(&self.file_id)
+            .and_then(|file_path| file_path.parent())
+        {
+            for (path, file_id) in headers(&self.source_root, &dir.to_string()) {
+                add(path, file_id, IncludeSource::Relative);
+            }
+        }
+        let app_data = match self.db.app_data(self.source_root_id) {
+            Some(app_data) => app_data,
+            None => return res,
+        };
+        for include in &app_data.include_path {
+            let dir = include.display().to_string();
+            for (path, file_id) in headers(&self.source_root, &dir) {
+                add(path, file_id, IncludeSource::IncludePath);
+            }
+        }
+        if !is_lib {
+            return res;
+        }
 
-    fn resolve_remote(&self, path: &str) -> Option<FileId> {
-        let app_data = self.db.app_data(self.source_root_id)?;
+        // Mirrors `search_locations`: the path starts with the name of the
+        // application, and project applications take precedence over OTP
         let project_data = self.db.project_data(app_data.project_id);
+        let mut add_app = |source_root_id: SourceRootId, source: IncludeSource| {
+            if let Some(target_app_data) = self.db.app_data(source_root_id) {
+                let source_root = self.db.source_root(source_root_id);
+                let dir = target_app_data.dir.display().to_string();
+                for (path, file_id) in headers(&source_root, &dir) {
+                    add(format!("{}/{path}", target_app_data.name), file_id, source);
+                }
+            }
+        };
+        for source_root_id in &project_data.source_roots {
+            add_app(*source_root_id, IncludeSource::ProjectApp);
+        }
+        for otp_app in self.db.otp_apps(app_data.project_id).iter() {
+            if let Some(source_root_id) = project_data.app_roots.get(&otp_app.name) {
+                add_app(source_root_id, IncludeSource::OtpApp);
+            }
+        }
+        res
+    }
 
-        let (app_name, path) = path.split_once('/')?;
-        let source_root_id = project_data.app_roots.get(app_name)?;
-        let source_root = self.db.source_root(source_root_id);
-        let target_app_data = self.db.app_data(source_root_id)?;
-        let path = target_app_data.dir.join(path);
-        source_root.file_for_path(&path.into())
+    fn resolve_path(&self, path: &str, is_lib: bool) -> Option<FileId> {
+        self.search_locations(path, is_lib)
+            .into_iter()
+            .find_map(|(path, source_root)| source_root.file_for_path(&path))
     }
 }
 
+/// The headers below `dir`, with their path relative to it, sorted by path
+fn headers(source_root: &SourceRoot, dir: &str) -> Vec<(String, FileId)> {
+    let prefix = format!("{}/", dir.trim_end_matches('/'));
+    let mut res: Vec<(String, FileId)> = source_root
+        .iter()
+        .filter_map(|file_id| {
+            let path = source_root.path_for_file(&file_id)?.to_string();
+            let relative = path.strip_prefix(&prefix)?;
+            relative
+                .ends_with(".hrl")
+                .then(|| (relative.to_string(), file_id))
+        })
+        .collect();
+    res.sort();
+    res
+}
+
 #[cfg(test)]
 mod tests {
     use elp_base_db::fixture::WithFixture;
@@ -137,6 +344,24 @@ mod tests {
         expect.assert_eq(&resolved);
     }
 
+    fn check_closure(ra_fixture: &str, expect: Expect) {
+        let (db, files) = TestDB::with_many_files(ra_fixture);
+        let file_id = files[0];
+        let mut closure = db
+            .include_closure(file_id)
+            .iter()
+            .map(|included| {
+                db.source_root(db.file_source_root(*included))
+                    .path_for_file(included)
+                    .unwrap()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        closure.push('\n');
+        expect.assert_eq(&closure);
+    }
+
     #[test]
     fn relative() {
         check(
@@ -182,4 +407,144 @@ mod tests {
             "#]],
         )
     }
+
+    #[test]
+    fn search_paths_unresolved() {
+        let (db, files) = TestDB::with_many_files(
+            r#"
+//- /src/module.erl include_path:/include
+-include("missing.hrl").
+//- /include/header.hrl
+"#,
+        );
+        let file_id = files[0];
+        let form_list = db.file_form_list(file_id);
+        let (idx, _) = form_list.includes().next().unwrap();
+        assert_eq!(db.resolve_include(InFile::new(file_id, idx)), None);
+        let paths = search_paths(&db, InFile::new(file_id, idx));
+        assert_eq!(paths.first().map(|p| p.as_str()), Some("/src/missing.hrl"));
+        assert!(
+            paths.iter().any(|p| p == "/include/missing.hrl"),
+            "{:?}",
+            paths
+        );
+    }
+
+    #[test]
+    fn candidates_in_search_order() {
+        let (db, files) = TestDB::with_many_files(
+            r#"
+//- /main/src/module.erl app:main include_path:/main/include
+-module(module).
+//- /main/src/local.hrl app:main
+//- /main/include/local.hrl app:main
+//- /another/include/header.hrl app:another
+//- /opt/lib/stdlib-3.17/include/assert.hrl otp_app:/opt/lib/stdlib-3.17
+"#,
+        );
+        let render = |is_lib| {
+            candidates(&db, files[0], is_lib)
+                .iter()
+                .map(|candidate| {
+                    format!(
+                        "{} {:?} shadowed: {:?}\n",
+                        candidate.path, candidate.source, candidate.shadowed
+                    )
+                })
+                .collect::<String>()
+        };
+        expect![[r#"
+            local.hrl Relative shadowed: [IncludePath]
+            main/include/local.hrl IncludePath shadowed: []
+            main/src/local.hrl IncludePath shadowed: []
+        "#]]
+        .assert_eq(&render(false));
+        expect![[r#"
+            local.hrl Relative shadowed: [IncludePath]
+            main/include/local.hrl IncludePath shadowed: []
+            main/src/local.hrl IncludePath shadowed: []
+            another/include/header.hrl ProjectApp shadowed: []
+            stdlib/include/assert.hrl OtpApp shadowed: []
+        "#]]
+        .assert_eq(&render(true));
+    }
+
+    #[test]
+    fn closure_nested() {
+        check_closure(
+            r#"
+//- /src/module.erl
+-include("a.hrl").
+-include("c.hrl").
+//- /src/a.hrl
+-include("b.hrl").
+//- /src/b.hrl
+//- /src/c.hrl
+"#,
+            expect![[r#"
+                /src/a.hrl
+                /src/b.hrl
+                /src/c.hrl
+            "#]],
+        )
+    }
+
+    #[test]
+    fn closure_cyclic_and_duplicate() {
+        check_closure(
+            r#"
+//- /src/module.erl
+-include("a.hrl").
+-include("b.hrl").
+-include("a.hrl").
+//- /src/a.hrl
+-ifndef(A_HRL).
+-define(A_HRL, true).
+-include("b.hrl").
+-endif.
+//- /src/b.hrl
+-include("a.hrl").
+"#,
+            expect![[r#"
+                /src/a.hrl
+                /src/b.hrl
+            "#]],
+        )
+    }
+
+    #[test]
+    fn closure_otp_and_missing() {
+        check_closure(
+            r#"
+//- /src/module.erl
+-include("missing.hrl").
+-include_lib("stdlib/include/assert.hrl").
+-include("local.hrl").
+//- /src/local.hrl
+//- /opt/lib/stdlib-3.17/include/assert.hrl otp_app:/opt/lib/stdlib-3.17
+"#,
+            expect![[r#"
+                /opt/lib/stdlib-3.17/include/assert.hrl
+                /src/local.hrl
+            "#]],
+        )
+    }
+
+    #[test]
+    fn closure_missing_in_header() {
+        check_closure(
+            r#"
+//- /src/module.erl
+-include("a.hrl").
+//- /src/a.hrl
+-include("missing.hrl").
+-include("b.hrl").
+//- /src/b.hrl
+"#,
+            expect![[r#"
+                /src/a.hrl
+                /src/b.hrl
+            "#]],
+        )
+    }
 }
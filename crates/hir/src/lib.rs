@@ -12,21 +12,39 @@ use elp_base_db::SourceDatabase;
 use elp_syntax::ast;
 
 mod body;
+mod broad_spec;
+mod call_targets;
+mod compile_options;
 pub mod db;
 mod def_map;
 mod diagnostics;
+mod dialyzer;
+pub mod doc_comments;
+mod dynamic_call;
 pub mod edoc;
+mod entry_points;
 mod expr;
+mod file_metrics;
 mod fold;
 mod form_list;
+mod guard_constraints;
+mod import;
 mod include;
 mod intern;
 mod macro_exp;
+mod module_atoms;
 mod module_data;
+mod module_dependency;
 mod name;
+mod no_return;
+mod otp_role;
+mod reachability;
 pub mod resolver;
 mod sema;
+#[cfg(test)]
 mod test_db;
+mod var_bindings;
+mod visible_records;
 
 pub use body::AnyAttribute;
 pub use body::AttributeBody;
@@ -36,13 +54,23 @@ pub use body::DefineBody;
 pub use body::ExprSource;
 pub use body::FunctionBody;
 pub use body::InFileAstPtr;
+pub use body::MacroExpansion;
 pub use body::RecordBody;
 pub use body::SpecBody;
 pub use body::SpecOrCallback;
 pub use body::TypeBody;
+pub use broad_spec::BroadSpec;
+pub use call_targets::ResolvedCall;
+pub use compile_options::CompileOptions;
 pub use def_map::DefMap;
 pub use diagnostics::Diagnostic;
 pub use diagnostics::DiagnosticMessage;
+pub use dialyzer::DialyzerAttributes;
+pub use dialyzer::DialyzerOption;
+pub use dynamic_call::DynamicCall;
+pub use dynamic_call::DynamicCallTarget;
+pub use entry_points::EntryKind;
+pub use entry_points::EntryPoint;
 pub use expr::AnyExprId;
 pub use expr::AnyExprRef;
 pub use expr::BinarySeg;
@@ -50,6 +78,7 @@ pub use expr::CRClause;
 pub use expr::CallTarget;
 pub use expr::CatchClause;
 pub use expr::Clause;
+pub use expr::ClauseId;
 pub use expr::ComprehensionBuilder;
 pub use expr::ComprehensionExpr;
 pub use expr::Expr;
@@ -59,15 +88,19 @@ pub use expr::IfClause;
 pub use expr::ListType;
 pub use expr::Literal;
 pub use expr::MapOp;
+pub use expr::MatchDiscard;
+pub use expr::MaybeExpr;
 pub use expr::Pat;
 pub use expr::PatId;
 pub use expr::ReceiveAfter;
+pub use expr::ReceiveTimeout;
 pub use expr::RecordFieldBody;
 pub use expr::SpecSig;
 pub use expr::Term;
 pub use expr::TermId;
 pub use expr::TypeExpr;
 pub use expr::TypeExprId;
+pub use file_metrics::FileMetrics;
 pub use fold::FoldCtx;
 pub use fold::On;
 pub use fold::Strategy;
@@ -113,9 +146,16 @@ pub use form_list::TypeAlias;
 pub use form_list::TypeAliasId;
 pub use form_list::TypeExport;
 pub use form_list::TypeExportId;
+pub use guard_constraints::GuardConstraint;
+pub use guard_constraints::GuardType;
+pub use import::ImportedFunction;
+pub use include::IncludeCandidate;
+pub use include::IncludeSource;
 pub use intern::Atom;
 pub use intern::Var;
+pub use macro_exp::BuiltInMacro;
 pub use macro_exp::ResolvedMacro;
+pub use module_atoms::ModuleAtoms;
 pub use module_data::CallbackDef;
 pub use module_data::DefineDef;
 pub use module_data::File;
@@ -133,12 +173,18 @@ pub use name::known;
 pub use name::MacroName;
 pub use name::Name;
 pub use name::NameArity;
+pub use otp_role::OtpBehaviour;
+pub use otp_role::OtpBehaviourRole;
+pub use reachability::Reachable;
 pub use sema::CallDef;
 pub use sema::DefinitionOrReference;
 pub use sema::FaDef;
 pub use sema::InFunctionBody;
 pub use sema::ScopeAnalysis;
 pub use sema::Semantic;
+pub use var_bindings::FileVarBindings;
+pub use var_bindings::VarOccurrence;
+pub use visible_records::VisibleRecord;
 
 /// `InFile<T>` stores a value of `T` inside a particular file.
 ///
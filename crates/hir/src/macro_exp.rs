@@ -7,17 +7,25 @@
  * of this source tree.
  */
 
+use std::sync::Arc;
+
 use elp_base_db::FileId;
 use elp_syntax::ast;
+use fxhash::FxHashMap;
 
+use crate::body::lower_literal;
 use crate::db::MinDefDatabase;
 use crate::form_list::FormListData;
 use crate::known;
 use crate::name::AsName;
+use crate::Atom;
 use crate::Define;
 use crate::DefineId;
+use crate::FunctionId;
 use crate::InFile;
+use crate::Literal;
 use crate::MacroName;
+use crate::Name;
 use crate::PPDirective;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -52,6 +60,9 @@ impl BuiltInMacro {
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum ResolvedMacro {
     BuiltIn(BuiltInMacro),
+    /// Defined by the build system or a `-compile({d, ...})` option, see
+    /// `MinDefDatabase::predefined_macros`
+    Predefined(Atom),
     User(InFile<DefineId>),
 }
 
@@ -59,6 +70,7 @@ impl ResolvedMacro {
     pub fn name(&self, db: &dyn MinDefDatabase) -> MacroName {
         match self {
             ResolvedMacro::BuiltIn(built_in) => built_in.name(),
+            ResolvedMacro::Predefined(name) => MacroName::new(db.lookup_atom(*name), None),
             ResolvedMacro::User(def) => {
                 let form_list = db.file_form_list(def.file_id);
                 form_list[def.value].name.clone()
@@ -83,10 +95,16 @@ pub(crate) fn resolve_query(
         return value.map(ResolvedMacro::BuiltIn);
     }
 
-    match db.local_resolve_macro(file_id, name) {
+    match db.local_resolve_macro(file_id, name.clone()) {
         MacroResolution::Resolved(resolved) => Some(ResolvedMacro::User(resolved)),
         MacroResolution::Undef => None,
-        MacroResolution::Unresolved => None,
+        MacroResolution::Unresolved => {
+            if name.arity().is_none() && db.predefined_macros(file_id).contains_key(name.name()) {
+                Some(ResolvedMacro::Predefined(db.atom(name.name().clone())))
+            } else {
+                None
+            }
+        }
     }
 }
 
@@ -164,6 +182,102 @@ pub(crate) fn recover_cycle(
     MacroResolution::Unresolved
 }
 
+/// Macros defined before `file_id` is preprocessed, rather than by a
+/// `-define` attribute: by the build system, or by a `{d, Name}` or
+/// `{d, Name, Value}` option of a `-compile` attribute in the file.
+/// A macro maps to `None` if its value is not a literal.
+pub(crate) fn predefined_macros_query(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+) -> Arc<FxHashMap<Name, Option<Literal>>> {
+    let mut macros = FxHashMap::default();
+    if let Some(app_data) = db.app_data(db.file_source_root(file_id)) {
+        // These are `Name`, `{Name, Value}` or `{Name, Value, redefine}`
+        for term in &app_data.macros {
+            let (name, value) = match term {
+                eetf::Term::Atom(name) => (name, None),
+                eetf::Term::Tuple(tuple) => match tuple.elements.as_slice() {
+                    [eetf::Term::Atom(name), value] | [eetf::Term::Atom(name), value, _] => {
+                        (name, Some(value))
+                    }
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            let value = match value {
+                None => Some(Literal::Atom(db.atom(known::r#true))),
+                Some(value) => eetf_literal(db, value),
+            };
+            macros.insert(Name::from_erlang_service(&name.name), value);
+        }
+    }
+
+    // Read from the syntax rather than `compile_body`, as lowering the
+    // attribute would resolve macros, and so end up back here
+    let form_list = db.file_form_list(file_id);
+    let source_file = db.parse(file_id).tree();
+    for (_idx, compile) in form_list.compile_attributes() {
+        let options = match compile.form_id.get(&source_file).options() {
+            Some(ast::Expr::ExprMax(ast::ExprMax::List(list))) => list.exprs().collect(),
+            Some(option) => vec![option],
+            None => continue,
+        };
+        for option in options {
+            let tuple = match option {
+                ast::Expr::ExprMax(ast::ExprMax::Tuple(tuple)) => tuple,
+                _ => continue,
+            };
+            let (tag, name, value) = match tuple.expr().collect::<Vec<_>>().as_slice() {
+                [tag, name] => (lower_literal(db, tag), lower_literal(db, name), None),
+                [tag, name, value] => (
+                    lower_literal(db, tag),
+                    lower_literal(db, name),
+                    Some(lower_literal(db, value)),
+                ),
+                _ => continue,
+            };
+            let name = match (tag, name) {
+                (Some(Literal::Atom(tag)), Some(Literal::Atom(name)))
+                    if db.lookup_atom(tag) == known::d =>
+                {
+                    db.lookup_atom(name)
+                }
+                _ => continue,
+            };
+            let value = value.unwrap_or_else(|| Some(Literal::Atom(db.atom(known::r#true))));
+            macros.insert(name, value);
+        }
+    }
+    Arc::new(macros)
+}
+
+fn eetf_literal(db: &dyn MinDefDatabase, term: &eetf::Term) -> Option<Literal> {
+    match term {
+        eetf::Term::Atom(atom) => Some(Literal::Atom(
+            db.atom(Name::from_erlang_service(&atom.name)),
+        )),
+        eetf::Term::FixInteger(int) => Some(Literal::Integer(int.value.into())),
+        eetf::Term::BigInteger(int) => int.value.to_string().parse().ok().map(Literal::Integer),
+        eetf::Term::Float(float) => Some(Literal::Float(float.value.to_bits())),
+        eetf::Term::ByteList(list) => String::from_utf8(list.bytes.clone())
+            .ok()
+            .map(Literal::String),
+        _ => None,
+    }
+}
+
+/// The value of the predefined macro `name` in `file_id`, if it is a literal.
+pub(crate) fn predefined_macro_value(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+    name: Atom,
+) -> Option<Literal> {
+    db.predefined_macros(file_id)
+        .get(&db.lookup_atom(name))
+        .cloned()
+        .flatten()
+}
+
 pub struct MacroExpCtx<'a> {
     _db: &'a dyn MinDefDatabase,
     form_list: &'a FormListData,
@@ -264,6 +378,61 @@ pub fn macro_name(macro_call: &ast::MacroCallExpr) -> Option<MacroName> {
     Some(MacroName::new(name, arity))
 }
 
+/// The value a built-in macro expands to in `file_id`, given the name and
+/// arity of the enclosing function, if any.
+pub(crate) fn built_in_macro_value(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+    built_in: BuiltInMacro,
+    function_info: Option<(Atom, u32)>,
+) -> Option<Literal> {
+    match built_in {
+        // This is a bit of a hack, but allows us not to depend on the file system
+        // It somewhat replicates the behaviour of -deterministic option
+        BuiltInMacro::FILE => {
+            let form_list = db.file_form_list(file_id);
+            form_list
+                .module_attribute()
+                .map(|attr| Literal::String(format!("{}.erl", attr.name)))
+        }
+        BuiltInMacro::FUNCTION_NAME => function_info.map(|(name, _)| Literal::Atom(name)),
+        BuiltInMacro::FUNCTION_ARITY => {
+            function_info.map(|(_, arity)| Literal::Integer(arity as i128))
+        }
+        // Dummy value, we don't want to depend on the exact position
+        BuiltInMacro::LINE => Some(Literal::Integer(0)),
+        BuiltInMacro::MODULE => {
+            let form_list = db.file_form_list(file_id);
+            form_list
+                .module_attribute()
+                .map(|attr| Literal::Atom(db.atom(attr.name.clone())))
+        }
+        BuiltInMacro::MODULE_STRING => {
+            let form_list = db.file_form_list(file_id);
+            form_list
+                .module_attribute()
+                .map(|attr| Literal::String(attr.name.to_string()))
+        }
+        BuiltInMacro::MACHINE => Some(Literal::Atom(db.atom(known::ELP))),
+        // Dummy value, must be an integer
+        BuiltInMacro::OTP_RELEASE => Some(Literal::Integer(2000)),
+    }
+}
+
+pub(crate) fn builtin_macro_value_query(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+    built_in: BuiltInMacro,
+    function_id: Option<FunctionId>,
+) -> Option<Literal> {
+    let function_info = function_id.map(|function_id| {
+        let form_list = db.file_form_list(file_id);
+        let name = &form_list[function_id].name;
+        (db.atom(name.name().clone()), name.arity())
+    });
+    built_in_macro_value(db, file_id, built_in, function_info)
+}
+
 #[cfg(test)]
 mod tests {
     use elp_base_db::fixture::ChangeFixture;
@@ -275,6 +444,7 @@ mod tests {
     use elp_syntax::AstNode;
 
     use super::*;
+    use crate::intern::MinInternDatabase;
     use crate::test_db::TestDB;
     use crate::DefineDef;
     use crate::File;
@@ -313,11 +483,11 @@ mod tests {
         let (expected_range, _) = annos[0];
 
         let resolved = match resolved.expect("failed to resolve macro") {
-            ResolvedMacro::BuiltIn(built_in) => panic!(
+            ResolvedMacro::User(def) => def,
+            other => panic!(
                 "expected to resolve to a custom macro, got {:?} instead",
-                built_in
+                other
             ),
-            ResolvedMacro::User(def) => def,
         };
         let def = DefineDef {
             file: File {
@@ -478,6 +648,147 @@ foo() -> ?~MACRO.
 -module(main).
 -include("include.hrl").
 foo() -> ?~FOO.
+"#,
+        );
+        assert_eq!(resolved, None);
+    }
+
+    #[track_caller]
+    fn check_value(fixture: &str, expected: &str) {
+        let (db, fixture) = TestDB::with_fixture(fixture);
+        let position = fixture.position();
+        let parsed = db.parse(position.file_id);
+        let macro_call =
+            algo::find_node_at_offset::<ast::MacroCallExpr>(&parsed.syntax_node(), position.offset)
+                .expect("macro call marked with ~ not found");
+        let function_id = macro_call
+            .syntax()
+            .ancestors()
+            .find_map(ast::Form::cast)
+            .and_then(|form| db.file_form_list(position.file_id).find_form(&form))
+            .and_then(|form| match form {
+                crate::FormIdx::Function(function_id) => Some(function_id),
+                _ => None,
+            });
+        let value = match db.resolve_macro(position.file_id, macro_name(&macro_call).unwrap()) {
+            Some(ResolvedMacro::BuiltIn(built_in)) => {
+                db.builtin_macro_value(position.file_id, built_in, function_id)
+            }
+            Some(ResolvedMacro::Predefined(name)) => {
+                predefined_macro_value(&db, position.file_id, name)
+            }
+            other => panic!("expected a built-in or predefined macro, got {:?}", other),
+        };
+        let actual = match value {
+            Some(Literal::Atom(atom)) => format!("'{}'", db.lookup_atom(atom)),
+            Some(Literal::Integer(int)) => int.to_string(),
+            Some(Literal::String(string)) => format!("{:?}", string),
+            Some(other) => format!("{:?}", other),
+            None => "None".to_string(),
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_module_value() {
+        check_value(
+            r#"
+-module(main).
+bar() -> ?~MODULE.
+"#,
+            "'main'",
+        );
+    }
+
+    #[test]
+    fn test_file_value() {
+        check_value(
+            r#"
+-module(main).
+bar() -> ?~FILE.
+"#,
+            "\"main.erl\"",
+        );
+    }
+
+    #[test]
+    fn test_function_arity_value() {
+        check_value(
+            r#"
+-module(main).
+bar(A, B) -> ?~FUNCTION_ARITY.
+"#,
+            "2",
+        );
+    }
+
+    #[test]
+    fn test_function_name_outside_function() {
+        check_value(
+            r#"
+-module(main).
+-define(NAME, ?~FUNCTION_NAME).
+"#,
+            "None",
+        );
+    }
+
+    #[test]
+    fn test_compile_define_value() {
+        check_value(
+            r#"
+-module(main).
+-compile([export_all, {d, 'LIMIT', 42}]).
+bar() -> ?~LIMIT.
+"#,
+            "42",
+        );
+    }
+
+    #[test]
+    fn test_compile_define_without_value() {
+        check_value(
+            r#"
+-module(main).
+-compile({d, 'DEBUG'}).
+bar() -> ?~DEBUG.
+"#,
+            "'true'",
+        );
+    }
+
+    #[test]
+    fn test_build_system_define() {
+        check_value(
+            r#"
+//- /src/main.erl macros:TEST
+-module(main).
+bar() -> ?~TEST.
+"#,
+            "'true'",
+        );
+    }
+
+    #[test]
+    fn test_define_shadows_compile_define() {
+        check_user(
+            r#"
+-module(main).
+-compile({d, 'LIMIT', 42}).
+   -define(LIMIT, 10).
+%% ^^^^^^^^^^^^^^^^^^^
+bar() -> ?~LIMIT.
+"#,
+        );
+    }
+
+    #[test]
+    fn test_compile_define_with_args() {
+        let (resolved, _db, _fixture) = resolve_macro(
+            r#"
+-module(main).
+-compile({d, 'LIMIT', 42}).
+bar() -> ?~LIMIT(1).
 "#,
         );
         assert_eq!(resolved, None);
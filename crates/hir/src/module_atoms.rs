@@ -0,0 +1,171 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The atoms used by a module, split between type contexts and values.
+//!
+//! Lowering already keeps type expressions, from type definitions, specs,
+//! callbacks and record field types, in their own `TypeExpr` arena, so an
+//! atom is in a type context if it is a `TypeExpr::Literal`, and is a value
+//! if it is an `Expr::Literal` or `Pat::Literal`. The names of called
+//! functions, types and modules are neither.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+use fxhash::FxHashSet;
+
+use crate::db::MinDefDatabase;
+use crate::Atom;
+use crate::Body;
+use crate::CallTarget;
+use crate::Expr;
+use crate::FormIdx;
+use crate::InFile;
+use crate::Literal;
+use crate::Pat;
+use crate::TypeExpr;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ModuleAtoms {
+    /// Atoms appearing in type contexts
+    pub types: FxHashSet<Atom>,
+    /// Atoms used as runtime values, in expressions and patterns
+    pub values: FxHashSet<Atom>,
+}
+
+impl ModuleAtoms {
+    /// An atom such as `undefined` can be both a type and a value
+    pub fn is_type(&self, atom: Atom) -> bool {
+        self.types.contains(&atom)
+    }
+
+    pub fn is_value(&self, atom: Atom) -> bool {
+        self.values.contains(&atom)
+    }
+
+    fn add_body(&mut self, body: &Body) {
+        let mut call_targets = FxHashSet::default();
+        for (_, expr) in body.exprs.iter() {
+            match expr {
+                Expr::Call { target, .. } | Expr::CaptureFun { target, .. } => match target {
+                    CallTarget::Local { name } => {
+                        call_targets.insert(*name);
+                    }
+                    CallTarget::Remote { module, name } => {
+                        call_targets.insert(*module);
+                        call_targets.insert(*name);
+                    }
+                },
+                _ => {}
+            }
+        }
+        for (expr_id, expr) in body.exprs.iter() {
+            if let Expr::Literal(Literal::Atom(atom)) = expr {
+                if !call_targets.contains(&expr_id) {
+                    self.values.insert(*atom);
+                }
+            }
+        }
+        for (_, pat) in body.pats.iter() {
+            if let Pat::Literal(Literal::Atom(atom)) = pat {
+                self.values.insert(*atom);
+            }
+        }
+
+        let mut type_targets = FxHashSet::default();
+        for (_, type_expr) in body.type_exprs.iter() {
+            if let TypeExpr::Call { target, .. } = type_expr {
+                match target {
+                    CallTarget::Local { name } => {
+                        type_targets.insert(*name);
+                    }
+                    CallTarget::Remote { module, name } => {
+                        type_targets.insert(*module);
+                        type_targets.insert(*name);
+                    }
+                }
+            }
+        }
+        for (type_expr_id, type_expr) in body.type_exprs.iter() {
+            if let TypeExpr::Literal(Literal::Atom(atom)) = type_expr {
+                if !type_targets.contains(&type_expr_id) {
+                    self.types.insert(*atom);
+                }
+            }
+        }
+    }
+}
+
+/// The atoms of the functions, records, types, specs and callbacks defined
+/// in `file_id`. Attributes and macro definitions are not included.
+pub(crate) fn module_atoms_query(db: &dyn MinDefDatabase, file_id: FileId) -> Arc<ModuleAtoms> {
+    let form_list = db.file_form_list(file_id);
+    let mut res = ModuleAtoms::default();
+    for form in form_list.forms() {
+        match *form {
+            FormIdx::Function(id) => res.add_body(&db.function_body(InFile::new(file_id, id)).body),
+            FormIdx::Record(id) => res.add_body(&db.record_body(InFile::new(file_id, id)).body),
+            FormIdx::TypeAlias(id) => res.add_body(&db.type_body(InFile::new(file_id, id)).body),
+            FormIdx::Spec(id) => res.add_body(&db.spec_body(InFile::new(file_id, id)).body),
+            FormIdx::Callback(id) => res.add_body(&db.callback_body(InFile::new(file_id, id)).body),
+            _ => {}
+        }
+    }
+    Arc::new(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use expect_test::Expect;
+    use fxhash::FxHashSet;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+    use crate::Atom;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id) = TestDB::with_single_file(fixture);
+        let atoms = db.module_atoms(file_id);
+        let names = |atoms: &FxHashSet<Atom>| {
+            let mut names: Vec<String> = atoms
+                .iter()
+                .map(|atom| db.lookup_atom(*atom).to_string())
+                .collect();
+            names.sort();
+            names.join(", ")
+        };
+        let actual = format!(
+            "types: {}\nvalues: {}\n",
+            names(&atoms.types),
+            names(&atoms.values)
+        );
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn types_and_values() {
+        check(
+            r#"
+-module(main).
+-record(state, {mode = idle :: idle | running, owner :: pid() | undefined}).
+-type result() :: ok | {error, other:reason()}.
+-spec run(atom()) -> result() | undefined.
+run(start) -> lists:foreach(fun log/1, [started]), ok;
+run(_) -> undefined.
+log(_) -> ok.
+"#,
+            expect![[r#"
+                types: error, idle, ok, running, undefined
+                values: idle, ok, start, started, undefined
+            "#]],
+        );
+    }
+}
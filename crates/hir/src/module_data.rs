@@ -20,9 +20,11 @@ use elp_syntax::SyntaxNode;
 use crate::db::MinDefDatabase;
 use crate::db::MinInternDatabase;
 use crate::edoc::EdocHeader;
+use crate::guard_constraints::GuardConstraint;
 use crate::Callback;
 use crate::DefMap;
 use crate::Define;
+use crate::EntryKind;
 use crate::Function;
 use crate::FunctionId;
 use crate::InFile;
@@ -31,6 +33,7 @@ use crate::InFunctionBody;
 use crate::ModuleAttribute;
 use crate::Name;
 use crate::NameArity;
+use crate::OtpBehaviourRole;
 use crate::Record;
 use crate::RecordField;
 use crate::Spec;
@@ -106,6 +109,32 @@ impl Module {
     pub fn is_in_otp(&self, db: &dyn MinDefDatabase) -> bool {
         is_in_otp(self.file.file_id, db)
     }
+
+    /// The OTP behaviour the module implements, with the others it
+    /// declares when there are several
+    pub fn otp_role(&self, db: &dyn MinDefDatabase) -> Option<OtpBehaviourRole> {
+        db.otp_role(self.file.file_id)
+    }
+
+    /// The conventional entry points the module defines given its role,
+    /// such as `main/1` for an escript or `init/1` for a supervisor
+    pub fn entry_points(&self, db: &dyn MinDefDatabase) -> Vec<(EntryKind, NameArity)> {
+        db.entry_points(self.file.file_id)
+            .iter()
+            .filter(|entry| entry.defined)
+            .map(|entry| (entry.kind, entry.name.clone()))
+            .collect()
+    }
+
+    /// The entry points expected given the module's role but not defined,
+    /// such as `start/2` of an `application`
+    pub fn missing_entry_points(&self, db: &dyn MinDefDatabase) -> Vec<(EntryKind, NameArity)> {
+        db.entry_points(self.file.file_id)
+            .iter()
+            .filter(|entry| !entry.defined)
+            .map(|entry| (entry.kind, entry.name.clone()))
+            .collect()
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -141,6 +170,42 @@ impl FunctionDef {
         is_in_otp(self.file.file_id, db)
     }
 
+    /// The spec of the function, which may be declared in an included header
+    pub fn spec(&self, db: &dyn MinDefDatabase) -> Option<SpecDef> {
+        db.def_map(self.file.file_id)
+            .get_spec(&self.function.name)
+            .cloned()
+    }
+
+    /// The signature of the function for display, such as
+    /// `foo(integer(), atom()) -> ok` from its spec, or `foo/2` when it has
+    /// none.
+    pub fn signature_string(&self, db: &dyn MinDefDatabase) -> String {
+        self.spec_signature_string(db)
+            .unwrap_or_else(|| self.function.name.to_string())
+    }
+
+    /// The signature of the function given by its spec, if any. The
+    /// clauses of an overloaded spec are separated by `; `.
+    pub fn spec_signature_string(&self, db: &dyn MinDefDatabase) -> Option<String> {
+        let sigs: Vec<String> = self
+            .spec(db)?
+            .source(db.upcast())
+            .sigs()
+            .map(|sig| {
+                let text = sig.syntax().text().to_string();
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                format!("{}{}", self.function.name.name().to_quoted_string(), text)
+            })
+            .collect();
+        (!sigs.is_empty()).then(|| sigs.join("; "))
+    }
+
+    /// Type constraints on each argument, derived from the clause guards
+    pub fn arg_constraints(&self, db: &dyn MinDefDatabase) -> Arc<Vec<Option<GuardConstraint>>> {
+        db.function_arg_constraints(InFile::new(self.file.file_id, self.function_id))
+    }
+
     pub fn edoc_comments(&self, db: &dyn MinDefDatabase) -> Option<EdocHeader> {
         let form = InFileAstPtr::new(
             self.file.file_id,
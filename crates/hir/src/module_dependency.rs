@@ -0,0 +1,315 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The dependencies between the modules of a project, and the order in
+//! which they can be processed so that each module comes after the ones it
+//! depends on.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+use elp_base_db::ModuleName;
+use elp_base_db::ProjectId;
+use fxhash::FxHashMap;
+
+use crate::db::MinDefDatabase;
+use crate::CallTarget;
+use crate::Expr;
+use crate::FoldCtx;
+use crate::FormIdx;
+use crate::InFile;
+use crate::Name;
+use crate::Strategy;
+
+/// The modules `file_id` depends on: its behaviours, the modules it
+/// imports from, and the modules it calls with a literal module name,
+/// directly, through `fun M:F/A` or through `apply/3`.
+pub(crate) fn module_dependencies_query(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+) -> Arc<Vec<Name>> {
+    let form_list = db.file_form_list(file_id);
+    let mut modules: Vec<Name> = Vec::new();
+    modules.extend(
+        form_list
+            .behaviour_attributes()
+            .map(|(_, behaviour)| behaviour.name.clone()),
+    );
+    modules.extend(form_list.imports().map(|(_, import)| import.from.clone()));
+    modules.extend(db.dynamically_called_modules(file_id).iter().cloned());
+    for form in form_list.forms() {
+        if let FormIdx::Function(function_id) = form {
+            let function_body = db.function_body(InFile::new(file_id, *function_id));
+            let body = &function_body.body;
+            for (_clause_id, clause) in function_body.clauses.iter() {
+                for expr_id in &clause.exprs {
+                    modules = FoldCtx::fold_expr(
+                        body,
+                        Strategy::TopDown,
+                        *expr_id,
+                        modules,
+                        &mut |mut acc, ctx| {
+                            let target = match &ctx.expr {
+                                Expr::Call { target, .. } => Some(target),
+                                Expr::CaptureFun { target, .. } => Some(target),
+                                _ => None,
+                            };
+                            if let Some(CallTarget::Remote { module, .. }) = target {
+                                if let Some(module) = body[*module].as_atom() {
+                                    acc.push(db.lookup_atom(module));
+                                }
+                            }
+                            acc
+                        },
+                        &mut |acc, _ctx| acc,
+                    );
+                }
+            }
+        }
+    }
+    if let Some(attr) = form_list.module_attribute() {
+        modules.retain(|module| *module != attr.name);
+    }
+    modules.sort();
+    modules.dedup();
+    Arc::new(modules)
+}
+
+/// The modules of `project_id`, grouped into the strongly connected
+/// components of their dependency graph, with every component after the
+/// ones it depends on. Components without dependencies come first, and
+/// components with the same depth are sorted by name, as are the modules
+/// within a component, so the order only depends on the graph. Only
+/// dependencies on modules of the project itself are considered.
+pub(crate) fn module_dependency_order_query(
+    db: &dyn MinDefDatabase,
+    project_id: ProjectId,
+) -> Arc<Vec<Vec<ModuleName>>> {
+    let module_index = db.module_index(project_id);
+    let mut modules: Vec<(ModuleName, FileId)> = module_index
+        .iter_own()
+        .map(|(name, _source, file_id)| (name.clone(), file_id))
+        .collect();
+    modules.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let index: FxHashMap<&str, usize> = modules
+        .iter()
+        .enumerate()
+        .map(|(idx, (name, _))| (name.as_str(), idx))
+        .collect();
+    let graph: Vec<Vec<usize>> = modules
+        .iter()
+        .map(|(_, file_id)| {
+            db.module_dependencies(*file_id)
+                .iter()
+                .filter_map(|dep| index.get(dep.as_str()).copied())
+                .collect()
+        })
+        .collect();
+
+    let components = strongly_connected_components(&graph);
+    let mut component_of = vec![0; graph.len()];
+    for (component_idx, component) in components.iter().enumerate() {
+        for node in component {
+            component_of[*node] = component_idx;
+        }
+    }
+    // Number of components each component depends on, and the reverse edges
+    let mut pending = vec![0; components.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); components.len()];
+    for (component_idx, component) in components.iter().enumerate() {
+        let mut deps: Vec<usize> = component
+            .iter()
+            .flat_map(|node| graph[*node].iter())
+            .map(|dep| component_of[*dep])
+            .filter(|dep| *dep != component_idx)
+            .collect();
+        deps.sort();
+        deps.dedup();
+        pending[component_idx] = deps.len();
+        for dep in deps {
+            dependents[dep].push(component_idx);
+        }
+    }
+
+    // Components are sorted by their first node, and nodes by name
+    let mut order = Vec::with_capacity(components.len());
+    let mut layer: Vec<usize> = (0..components.len())
+        .filter(|idx| pending[*idx] == 0)
+        .collect();
+    while !layer.is_empty() {
+        layer.sort_by_key(|idx| components[*idx][0]);
+        let mut next = Vec::new();
+        for component_idx in &layer {
+            for dependent in &dependents[*component_idx] {
+                pending[*dependent] -= 1;
+                if pending[*dependent] == 0 {
+                    next.push(*dependent);
+                }
+            }
+            order.push(
+                components[*component_idx]
+                    .iter()
+                    .map(|node| modules[*node].0.clone())
+                    .collect(),
+            );
+        }
+        layer = next;
+    }
+    Arc::new(order)
+}
+
+/// Tarjan's algorithm, without recursion so deep dependency chains can't
+/// overflow the stack. Each component is sorted.
+fn strongly_connected_components(graph: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+    let mut index = vec![UNVISITED; graph.len()];
+    let mut low_link = vec![0; graph.len()];
+    let mut on_stack = vec![false; graph.len()];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+
+    for root in 0..graph.len() {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        // (node, position of the next successor to visit)
+        let mut work = vec![(root, 0)];
+        while let Some((node, succ_pos)) = work.pop() {
+            if succ_pos == 0 {
+                index[node] = next_index;
+                low_link[node] = next_index;
+                next_index += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            } else {
+                let succ = graph[node][succ_pos - 1];
+                low_link[node] = low_link[node].min(low_link[succ]);
+            }
+            let mut recurse = None;
+            for (pos, succ) in graph[node].iter().enumerate().skip(succ_pos) {
+                if index[*succ] == UNVISITED {
+                    recurse = Some((pos + 1, *succ));
+                    break;
+                } else if on_stack[*succ] {
+                    low_link[node] = low_link[node].min(index[*succ]);
+                }
+            }
+            match recurse {
+                Some((pos, succ)) => {
+                    work.push((node, pos));
+                    work.push((succ, 0));
+                }
+                None => {
+                    if low_link[node] == index[node] {
+                        let mut component = Vec::new();
+                        while let Some(member) = stack.pop() {
+                            on_stack[member] = false;
+                            component.push(member);
+                            if member == node {
+                                break;
+                            }
+                        }
+                        component.sort();
+                        components.push(component);
+                    }
+                }
+            }
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use elp_base_db::SourceDatabase;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, files) = TestDB::with_many_files(fixture);
+        let project_id = db
+            .app_data(db.file_source_root(files[0]))
+            .unwrap()
+            .project_id;
+        let mut actual = String::new();
+        for file_id in &files {
+            let deps = db.module_dependencies(*file_id);
+            let module = db
+                .module_index(project_id)
+                .module_for_file(*file_id)
+                .cloned();
+            actual.push_str(&format!(
+                "{} -> [{}]\n",
+                module.unwrap().as_str(),
+                deps.iter()
+                    .map(|dep| dep.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        let order = db.module_dependency_order(project_id);
+        for component in order.iter() {
+            actual.push_str(&format!(
+                "{}\n",
+                component
+                    .iter()
+                    .map(|module| module.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn dependency_order() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-behaviour(server).
+-import(util, [id/1]).
+start() -> worker:run(), fun lists:map/2, apply(util, id, [1]).
+//- /src/worker.erl
+-module(worker).
+run() -> util:id(?MODULE:run()).
+//- /src/util.erl
+-module(util).
+id(X) -> X.
+//- /src/server.erl
+-module(server).
+-callback init() -> ok.
+//- /src/ping.erl
+-module(ping).
+ping() -> pong:pong().
+//- /src/pong.erl
+-module(pong).
+pong() -> ping:ping().
+"#,
+            expect![[r#"
+                main -> [lists, server, util, worker]
+                worker -> [util]
+                util -> []
+                server -> []
+                ping -> [pong]
+                pong -> [ping]
+                ping, pong
+                server
+                util
+                worker
+                main
+            "#]],
+        );
+    }
+}
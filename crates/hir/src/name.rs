@@ -221,6 +221,12 @@ pub mod known {
         apply,
         export_all,
         parse_transform,
+        utf8,
+        d,
+        infinity,
+        error,
+        exit,
+        throw,
         // Common Test framework
         all,
         group,
@@ -232,5 +238,12 @@ pub mod known {
         nowarn_missing_spec,
         warn_missing_spec_all,
         nowarn_missing_spec_all,
+        // dialyzer
+        dialyzer,
+        nowarn_function,
     );
+
+    // Keywords can't go through `known_names!`, as `stringify!` keeps the `r#`
+    #[allow(bad_style)]
+    pub const r#true: super::Name = super::Name::new_inline("true");
 }
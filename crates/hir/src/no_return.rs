@@ -0,0 +1,195 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Functions which never return normally, since every clause ends by
+//! raising an exception. This is a best-effort analysis: only calls to
+//! `error`, `exit/1` and `throw/1`, and tail calls to other such functions
+//! of the same module, are considered raising.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+use fxhash::FxHashSet;
+
+use crate::db::MinDefDatabase;
+use crate::known;
+use crate::Body;
+use crate::CallTarget;
+use crate::Expr;
+use crate::ExprId;
+use crate::FunctionBody;
+use crate::InFile;
+use crate::Name;
+use crate::NameArity;
+
+/// The functions defined in `file_id` which never return, sorted.
+pub(crate) fn no_return_functions_query(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+) -> Arc<Vec<NameArity>> {
+    let form_list = db.file_form_list(file_id);
+    let bodies: Vec<(NameArity, Arc<FunctionBody>)> = form_list
+        .functions()
+        .map(|(function_id, function)| {
+            let body = db.function_body(InFile::new(file_id, function_id));
+            (function.name.clone(), body)
+        })
+        .collect();
+
+    // Tail calls to local functions need the result for the callee, so
+    // iterate until no more functions are found
+    let mut no_return: FxHashSet<NameArity> = FxHashSet::default();
+    loop {
+        let ctx = Ctx {
+            db,
+            no_return: &no_return,
+        };
+        let found: Vec<NameArity> = bodies
+            .iter()
+            .filter(|(name, body)| !no_return.contains(name) && ctx.function_never_returns(body))
+            .map(|(name, _)| name.clone())
+            .collect();
+        if found.is_empty() {
+            break;
+        }
+        no_return.extend(found);
+    }
+
+    let mut res: Vec<NameArity> = no_return.into_iter().collect();
+    res.sort();
+    Arc::new(res)
+}
+
+struct Ctx<'a> {
+    db: &'a dyn MinDefDatabase,
+    no_return: &'a FxHashSet<NameArity>,
+}
+
+impl Ctx<'_> {
+    fn function_never_returns(&self, function_body: &FunctionBody) -> bool {
+        let body = &function_body.body;
+        !function_body.clauses.is_empty()
+            && function_body
+                .clauses
+                .iter()
+                .all(|(_, clause)| self.exprs_never_return(body, &clause.exprs))
+    }
+
+    fn exprs_never_return(&self, body: &Body, exprs: &[ExprId]) -> bool {
+        match exprs.last() {
+            Some(expr_id) => self.never_returns(body, *expr_id),
+            None => false,
+        }
+    }
+
+    fn never_returns(&self, body: &Body, expr_id: ExprId) -> bool {
+        match &body[expr_id] {
+            Expr::Call { target, args } => self.is_raising_call(body, target, args.len()),
+            Expr::Block { exprs } => self.exprs_never_return(body, exprs),
+            Expr::MacroCall { expansion, .. } => self.never_returns(body, *expansion),
+            Expr::Case { clauses, .. } => {
+                !clauses.is_empty()
+                    && clauses
+                        .iter()
+                        .all(|clause| self.exprs_never_return(body, &clause.exprs))
+            }
+            Expr::If { clauses } => {
+                !clauses.is_empty()
+                    && clauses
+                        .iter()
+                        .all(|clause| self.exprs_never_return(body, &clause.exprs))
+            }
+            _ => false,
+        }
+    }
+
+    fn is_raising_call(&self, body: &Body, target: &CallTarget<ExprId>, arity: usize) -> bool {
+        let name_of = |expr_id: ExprId| -> Option<Name> {
+            body[expr_id]
+                .as_atom()
+                .map(|atom| self.db.lookup_atom(atom))
+        };
+        match target {
+            CallTarget::Local { name } => match name_of(*name) {
+                Some(name) => {
+                    is_raising_bif(&name, arity)
+                        || self.no_return.contains(&NameArity::new(name, arity as u32))
+                }
+                None => false,
+            },
+            CallTarget::Remote { module, name } => match (name_of(*module), name_of(*name)) {
+                (Some(module), Some(name)) => {
+                    module == known::erlang && is_raising_bif(&name, arity)
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+/// `exit/2` sends an exit signal to another process and returns, so it is
+/// not included.
+fn is_raising_bif(name: &Name, arity: usize) -> bool {
+    (*name == known::error && (1..=3).contains(&arity))
+        || ((*name == known::exit || *name == known::throw) && arity == 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id) = TestDB::with_single_file(fixture);
+        let functions = db.no_return_functions(file_id);
+        let actual = functions
+            .iter()
+            .map(|name| format!("{name}\n"))
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn raising_functions() {
+        check(
+            r#"
+-module(main).
+fail(Reason) -> error(Reason).
+fail(Reason, Args) -> erlang:error(Reason, Args).
+stop() -> exit(normal).
+kill(Pid) -> exit(Pid, kill).
+bail(X) -> throw(X).
+check(ok) -> ok;
+check(Error) -> error(Error).
+both(true) -> throw(yes);
+both(false) -> erlang:exit(no).
+choose(X) ->
+    case X of
+        1 -> fail(one);
+        _ -> begin log(X), bail(X) end
+    end.
+wrapper(X) -> choose(X).
+log(_) -> ok.
+"#,
+            expect![[r#"
+                bail/1
+                both/1
+                choose/1
+                fail/1
+                fail/2
+                stop/0
+                wrapper/1
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,161 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The role of a module in an OTP application, as given by its
+//! `-behaviour` attributes, used to group modules by what they implement.
+
+use std::fmt;
+
+use elp_base_db::FileId;
+use elp_base_db::ModuleName;
+
+use crate::db::MinDefDatabase;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OtpBehaviour {
+    Application,
+    GenEvent,
+    GenFsm,
+    GenServer,
+    GenStatem,
+    Supervisor,
+    SupervisorBridge,
+    /// A behaviour which is not part of OTP
+    Other(ModuleName),
+}
+
+impl OtpBehaviour {
+    fn from_name(name: &str) -> OtpBehaviour {
+        match name {
+            "application" => OtpBehaviour::Application,
+            "gen_event" => OtpBehaviour::GenEvent,
+            "gen_fsm" => OtpBehaviour::GenFsm,
+            "gen_server" => OtpBehaviour::GenServer,
+            "gen_statem" => OtpBehaviour::GenStatem,
+            "supervisor" => OtpBehaviour::Supervisor,
+            "supervisor_bridge" => OtpBehaviour::SupervisorBridge,
+            name => OtpBehaviour::Other(ModuleName::new(name)),
+        }
+    }
+
+    pub fn module_name(&self) -> &str {
+        match self {
+            OtpBehaviour::Application => "application",
+            OtpBehaviour::GenEvent => "gen_event",
+            OtpBehaviour::GenFsm => "gen_fsm",
+            OtpBehaviour::GenServer => "gen_server",
+            OtpBehaviour::GenStatem => "gen_statem",
+            OtpBehaviour::Supervisor => "supervisor",
+            OtpBehaviour::SupervisorBridge => "supervisor_bridge",
+            OtpBehaviour::Other(name) => name.as_str(),
+        }
+    }
+}
+
+impl fmt::Display for OtpBehaviour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.module_name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtpBehaviourRole {
+    /// The first behaviour of the module
+    pub primary: OtpBehaviour,
+    /// The other behaviours of the module, in source order
+    pub others: Vec<OtpBehaviour>,
+}
+
+impl OtpBehaviourRole {
+    pub fn behaviours(&self) -> impl Iterator<Item = &OtpBehaviour> {
+        std::iter::once(&self.primary).chain(self.others.iter())
+    }
+}
+
+/// Behaviours declared more than once are only listed the first time.
+pub(crate) fn otp_role_query(db: &dyn MinDefDatabase, file_id: FileId) -> Option<OtpBehaviourRole> {
+    let form_list = db.file_form_list(file_id);
+    let mut behaviours: Vec<OtpBehaviour> = Vec::new();
+    for (_, behaviour) in form_list.behaviour_attributes() {
+        let behaviour = OtpBehaviour::from_name(behaviour.name.as_str());
+        if !behaviours.contains(&behaviour) {
+            behaviours.push(behaviour);
+        }
+    }
+    let mut behaviours = behaviours.into_iter();
+    Some(OtpBehaviourRole {
+        primary: behaviours.next()?,
+        others: behaviours.collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id) = TestDB::with_single_file(fixture);
+        let actual = match db.otp_role(file_id) {
+            Some(role) => role
+                .behaviours()
+                .map(|behaviour| format!("{:?}\n", behaviour))
+                .collect::<String>(),
+            None => "no role\n".to_string(),
+        };
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn single_behaviour() {
+        check(
+            r#"
+-module(main).
+-behaviour(gen_server).
+"#,
+            expect![[r#"
+                GenServer
+            "#]],
+        );
+    }
+
+    #[test]
+    fn several_behaviours() {
+        check(
+            r#"
+-module(main).
+-behavior(supervisor).
+-behaviour(my_behaviour).
+-behaviour(application).
+-behaviour(supervisor).
+"#,
+            expect![[r#"
+                Supervisor
+                Other(ModuleName("my_behaviour"))
+                Application
+            "#]],
+        );
+    }
+
+    #[test]
+    fn no_behaviour() {
+        check(
+            r#"
+-module(main).
+"#,
+            expect![[r#"
+                no role
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,258 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The functions transitively called by an entry point, across the
+//! modules of the project, for impact analysis.
+
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+use elp_base_db::ModuleName;
+use fxhash::FxHashSet;
+
+use crate::db::MinDefDatabase;
+use crate::sema::to_def::resolve_call_target;
+use crate::sema::to_def::resolve_module_name;
+use crate::CallTarget;
+use crate::DynamicCallTarget;
+use crate::Expr;
+use crate::FoldCtx;
+use crate::FunctionDef;
+use crate::InFile;
+use crate::Literal;
+use crate::NameArity;
+use crate::ResolvedCall;
+use crate::Semantic;
+use crate::Strategy;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reachable {
+    /// The functions reachable from the entry point, including itself.
+    /// Functions of modules outside the project are included, but their
+    /// own calls are unknown.
+    pub functions: BTreeSet<(ModuleName, NameArity)>,
+    /// The reachable functions with a call whose target is only known at
+    /// runtime, such as `apply(M, F, A)`, beyond which reachability is
+    /// unknown
+    pub unknown: BTreeSet<(ModuleName, NameArity)>,
+}
+
+/// The functions reachable from `function` in `file_id` through calls and
+/// `fun` captures, following at most `max_depth` calls when given. The
+/// functions at `max_depth` are included, but not their callees.
+pub(crate) fn reachable_functions_query(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+    function: NameArity,
+    max_depth: Option<u32>,
+) -> Arc<Reachable> {
+    let sema = Semantic { db };
+    let mut res = Reachable::default();
+    let entry = match db.def_map(file_id).get_function(&function) {
+        Some(def) => def.clone(),
+        None => return Arc::new(res),
+    };
+    let mut seen = FxHashSet::default();
+    seen.insert((entry.file.file_id, entry.function.name.clone()));
+    let mut queue = VecDeque::new();
+    queue.push_back((entry, 0));
+    while let Some((def, depth)) = queue.pop_front() {
+        let file_id = def.file.file_id;
+        let module = match sema.module_name(file_id) {
+            Some(module) => module,
+            None => continue,
+        };
+        let key = (module, def.function.name.clone());
+        res.functions.insert(key.clone());
+        if max_depth.map_or(false, |max_depth| depth >= max_depth) {
+            continue;
+        }
+        let callees = callees(&sema, &def);
+        if callees.dynamic {
+            res.unknown.insert(key);
+        }
+        for callee in callees.resolved {
+            if seen.insert((callee.file.file_id, callee.function.name.clone())) {
+                queue.push_back((callee, depth + 1));
+            }
+        }
+        res.functions.extend(callees.external);
+    }
+    Arc::new(res)
+}
+
+#[derive(Default)]
+struct Callees {
+    /// Calls to functions defined in the project
+    resolved: Vec<FunctionDef>,
+    /// Remote calls with a literal target outside the project
+    external: Vec<(ModuleName, NameArity)>,
+    /// Whether some call can only be resolved at runtime
+    dynamic: bool,
+}
+
+fn callees(sema: &Semantic, def: &FunctionDef) -> Callees {
+    let db = sema.db;
+    let file_id = def.file.file_id;
+    let function_id = InFile::new(file_id, def.function_id);
+    let function_body = db.function_body(function_id);
+    let body = &function_body.body;
+    let mut res = Callees::default();
+    for call in db.dynamic_calls(function_id).iter() {
+        match call.target {
+            DynamicCallTarget::Apply {
+                module,
+                name,
+                arity: Some(arity),
+            } => {
+                let module = db.lookup_atom(module);
+                let name = NameArity::new(db.lookup_atom(name), arity);
+                match resolve_module_name(sema, file_id, &module) {
+                    Some(module) => res
+                        .resolved
+                        .extend(db.def_map(module.file.file_id).get_function(&name).cloned()),
+                    None => res.external.push((ModuleName::new(&module), name)),
+                }
+            }
+            DynamicCallTarget::Apply { arity: None, .. } | DynamicCallTarget::Dynamic => {
+                res.dynamic = true
+            }
+        }
+    }
+    for call in db.call_targets(function_id).values() {
+        match call {
+            ResolvedCall::Remote {
+                module,
+                function,
+                file_id: None,
+                ..
+            } => res.external.push((module.clone(), function.clone())),
+            call => res.resolved.extend(call.def().cloned()),
+        }
+    }
+    for (_clause_id, clause) in function_body.clauses.iter() {
+        for expr_id in &clause.exprs {
+            res = FoldCtx::fold_expr(
+                body,
+                Strategy::TopDown,
+                *expr_id,
+                res,
+                &mut |mut acc, ctx| {
+                    // Calls were handled above
+                    let (target, arity) = match &ctx.expr {
+                        Expr::CaptureFun { target, arity } => match &body[*arity] {
+                            Expr::Literal(Literal::Integer(arity)) => {
+                                (target, (*arity).try_into().ok())
+                            }
+                            _ => (target, None),
+                        },
+                        _ => return acc,
+                    };
+                    // Dynamic calls were handled above
+                    let arity = match arity {
+                        Some(arity) if target.is_static(body) => arity,
+                        _ => return acc,
+                    };
+                    if let Some(def) = resolve_call_target(sema, target, arity, file_id, body) {
+                        acc.resolved.push(def);
+                    } else if let CallTarget::Remote { module, name } = target {
+                        let module = body[*module].as_atom().map(|atom| db.lookup_atom(atom));
+                        let name = body[*name].as_atom().map(|atom| db.lookup_atom(atom));
+                        if let (Some(module), Some(name)) = (module, name) {
+                            if resolve_module_name(sema, file_id, &module).is_none() {
+                                acc.external
+                                    .push((ModuleName::new(&module), NameArity::new(name, arity)));
+                            }
+                        }
+                    }
+                    acc
+                },
+                &mut |acc, _ctx| acc,
+            );
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+    use crate::Name;
+    use crate::NameArity;
+
+    fn check(fixture: &str, max_depth: Option<u32>, expect: Expect) {
+        let (db, files) = TestDB::with_many_files(fixture);
+        let entry = NameArity::new(Name::from_erlang_service("main"), 0);
+        let reachable = db.reachable_functions(files[0], entry, max_depth);
+        let mut actual = String::new();
+        for function in reachable.functions.iter() {
+            let (module, name) = function;
+            actual.push_str(&format!("{}:{}", module.as_str(), name));
+            if reachable.unknown.contains(function) {
+                actual.push_str(" (unknown)");
+            }
+            actual.push('\n');
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn reachable_across_modules() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+main() -> foo(1), lists:map(fun other:bar/1, []).
+foo(0) -> ok;
+foo(N) -> foo(N - 1), apply(other, baz, []).
+unused() -> ok.
+//- /src/other.erl
+-module(other).
+-export([bar/1, baz/0]).
+bar(X) -> main:main(), X.
+baz() -> ok.
+"#,
+            None,
+            expect![[r#"
+                lists:map/2
+                main:foo/1
+                main:main/0
+                other:bar/1
+                other:baz/0
+            "#]],
+        );
+    }
+
+    #[test]
+    fn dynamic_calls_and_depth() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+main() -> foo(other).
+foo(M) -> M:run(), bar().
+bar() -> baz().
+baz() -> ok.
+"#,
+            Some(2),
+            expect![[r#"
+                main:bar/0
+                main:foo/1 (unknown)
+                main:main/0
+            "#]],
+        );
+    }
+}
@@ -22,6 +22,7 @@ use elp_syntax::SyntaxNode;
 use elp_syntax::TextRange;
 use fxhash::FxHashMap;
 use fxhash::FxHashSet;
+use itertools::Itertools;
 use la_arena::RawIdx;
 
 use self::find::FindForm;
@@ -32,6 +33,7 @@ use self::to_def::ToDef;
 use crate::body::scope::ScopeId;
 use crate::body::UnexpandedIndex;
 use crate::db::MinDefDatabase;
+use crate::dialyzer;
 use crate::edoc::EdocHeader;
 use crate::expr::ClauseId;
 use crate::fold::ExprCallBack;
@@ -41,12 +43,16 @@ use crate::fold::FoldCtx;
 use crate::fold::PatCallBack;
 use crate::fold::PatCallBackCtx;
 use crate::fold::Strategy;
+use crate::include;
 pub use crate::intern::MinInternDatabase;
 pub use crate::intern::MinInternDatabaseStorage;
+use crate::known;
+use crate::macro_exp;
 use crate::resolver::Resolution;
 use crate::resolver::Resolver;
 use crate::Body;
 use crate::BodySourceMap;
+use crate::BuiltInMacro;
 use crate::CRClause;
 use crate::Clause;
 use crate::DefMap;
@@ -55,9 +61,12 @@ use crate::ExprId;
 use crate::File;
 use crate::FormIdx;
 use crate::FunctionBody;
+use crate::FunctionDef;
 use crate::FunctionId;
+use crate::ImportedFunction;
 use crate::InFile;
 use crate::InFileAstPtr;
+use crate::IncludeCandidate;
 use crate::Literal;
 use crate::MacroName;
 use crate::Module;
@@ -65,6 +74,8 @@ use crate::Name;
 use crate::PPDirective;
 use crate::Pat;
 use crate::PatId;
+use crate::ResolvedCall;
+use crate::ResolvedMacro;
 use crate::SpecId;
 use crate::Term;
 use crate::TermId;
@@ -300,6 +311,106 @@ impl<'db> Semantic<'db> {
         Some(ClauseId::from_raw(RawIdx::from(idx as u32)))
     }
 
+    /// The built-in macro called at `call`, such as `?MODULE`, together with
+    /// the value it expands to there.
+    pub fn built_in_macro_value(
+        &self,
+        call: InFile<&ast::MacroCallExpr>,
+    ) -> Option<(BuiltInMacro, Literal)> {
+        let name = macro_exp::macro_name(call.value)?;
+        let built_in = match self.db.resolve_macro(call.file_id, name)? {
+            ResolvedMacro::BuiltIn(built_in) => built_in,
+            ResolvedMacro::Predefined(_) | ResolvedMacro::User(_) => return None,
+        };
+        let function_id = self.find_enclosing_function(call.file_id, call.value.syntax());
+        let value = self
+            .db
+            .builtin_macro_value(call.file_id, built_in, function_id)?;
+        Some((built_in, value))
+    }
+
+    /// The function referred to by `fa`, written `foo/1` or `{foo, 1}` in
+    /// a `-dialyzer` attribute.
+    pub fn resolve_dialyzer_function(&self, fa: InFile<&ast::Expr>) -> Option<FunctionDef> {
+        let form = fa.value.syntax().ancestors().find_map(ast::Form::cast)?;
+        let form_list = self.db.file_form_list(fa.file_id);
+        let attribute_id = match form_list.find_form(&form)? {
+            FormIdx::Attribute(attribute_id) => attribute_id,
+            _ => return None,
+        };
+        if form_list[attribute_id].name != known::dialyzer {
+            return None;
+        }
+        let (attribute, source_map) = self
+            .db
+            .attribute_body_with_source(InFile::new(fa.file_id, attribute_id));
+        let term_id = source_map.term_id(fa)?;
+        let function = dialyzer::function_ref(self.db, &attribute.body, term_id)?;
+        self.db.def_map(fa.file_id).get_function(&function).cloned()
+    }
+
+    /// The functions referred to by `remote`, written `mod:fun` as a value
+    /// rather than as the target of a call. Without an arity, this is
+    /// every arity of `fun` defined in `mod`.
+    pub fn resolve_remote_ref(&self, remote: InFile<&ast::Remote>) -> Option<Vec<FunctionDef>> {
+        let (body, body_map) = self.find_body(remote.file_id, remote.value.syntax())?;
+        let expr = remote.map(|remote| ast::Expr::from(remote.clone()));
+        let (module, name) = match &body[body_map.expr_id(expr.as_ref())?] {
+            Expr::RemoteRef { module, name } => (*module, *name),
+            _ => return None,
+        };
+        let module = to_def::resolve_module_expr(self, &body, remote.file_id, module)?;
+        let name = self.db.lookup_atom(to_def::literal_atom(&body, name)?);
+        let functions: Vec<_> = self
+            .db
+            .def_map(module.file.file_id)
+            .get_functions()
+            .iter()
+            .filter(|(name_arity, _)| name_arity.name() == &name)
+            .sorted_by_key(|(name_arity, _)| name_arity.arity())
+            .map(|(_, def)| def.clone())
+            .collect();
+        if functions.is_empty() {
+            None
+        } else {
+            Some(functions)
+        }
+    }
+
+    /// The paths searched for the header of the `-include` or
+    /// `-include_lib` attribute `form`, in the order they are tried.
+    pub fn include_search_paths(&self, form: InFile<&ast::Form>) -> Vec<String> {
+        match find::find_any_include(self, form.file_id, form.value) {
+            Some(idx) => include::search_paths(self.db, form.with_value(idx)),
+            None => Vec::new(),
+        }
+    }
+
+    /// The headers that can be included from `file_id`, in the order the
+    /// compiler searches for them. For `-include_lib`, the applications of
+    /// the project and of OTP are searched as well.
+    pub fn include_candidates(&self, file_id: FileId, is_lib: bool) -> Vec<IncludeCandidate> {
+        include::candidates(self.db, file_id, is_lib)
+    }
+
+    /// The entry of an `-import` attribute that `fa` is, if any.
+    pub fn imported_function(&self, fa: InFile<&ast::Fa>) -> Option<ImportedFunction> {
+        let attr = ast::ImportAttribute::cast(fa.value.syntax().parent()?)?;
+        let form_list = self.db.file_form_list(fa.file_id);
+        let import_id = match form_list.find_form(&ast::Form::ImportAttribute(attr.clone()))? {
+            FormIdx::Import(idx) => idx,
+            _ => return None,
+        };
+        let idx = attr.funs().position(|entry| &entry == fa.value)? as u32;
+        self.db
+            .imported_functions(fa.file_id)
+            .iter()
+            .find(|imported| {
+                imported.import_id == import_id && form_list[imported.entry_id].idx == idx
+            })
+            .cloned()
+    }
+
     pub fn find_enclosing_spec(&self, file_id: FileId, syntax: &SyntaxNode) -> Option<SpecId> {
         let form = syntax.ancestors().find_map(ast::Form::cast)?;
         let form_list = self.db.file_form_list(file_id);
@@ -824,6 +935,16 @@ impl<T> InFunctionBody<T> {
         body_map
     }
 
+    /// The resolved target of each `Expr::Call` of the function
+    pub fn call_targets(&self, db: &dyn MinDefDatabase) -> Arc<FxHashMap<ExprId, ResolvedCall>> {
+        db.call_targets(self.function_id)
+    }
+
+    /// The resolved target of the `Expr::Call` `expr_id`
+    pub fn resolved_call(&self, db: &dyn MinDefDatabase, expr_id: ExprId) -> Option<ResolvedCall> {
+        self.call_targets(db).get(&expr_id).cloned()
+    }
+
     pub fn expr_id(&self, expr: &Expr) -> Option<ExprId> {
         self.body.body.expr_id(expr)
     }
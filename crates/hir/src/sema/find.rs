@@ -285,7 +285,7 @@ impl FindForm for ast::PpIncludeLib {
     }
 }
 
-fn find_any_include(
+pub(super) fn find_any_include(
     sema: &Semantic<'_>,
     file_id: FileId,
     ast_form: &ast::Form,
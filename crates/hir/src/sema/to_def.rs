@@ -16,7 +16,9 @@ use crate::known;
 use crate::macro_exp;
 use crate::macro_exp::MacroExpCtx;
 use crate::resolver::Resolver;
+use crate::AnyExprId;
 use crate::AnyExprRef;
+use crate::Atom;
 use crate::Body;
 use crate::CallTarget;
 use crate::CallbackDef;
@@ -133,10 +135,24 @@ impl ToDef for ast::Call {
         let file_id = ast.file_id;
         let expr = ast.map(|call| ast::Expr::from(call.clone()));
         let any_expr_id = body_map.any_id(expr.as_ref())?;
-        let def = match body.get_any(any_expr_id) {
+        let def = match body.get_any(any_expr_id.clone()) {
             AnyExprRef::Expr(Expr::Call { target, args }) => {
                 let arity = args.len().try_into().ok()?;
-                resolve_call_target(sema, target, arity, file_id, &body).map(CallDef::Function)
+                // The calls of a function are resolved once for all of them
+                let function_id = sema.find_enclosing_function(file_id, ast.value.syntax());
+                let resolved = match (any_expr_id, function_id) {
+                    (AnyExprId::Expr(expr_id), Some(function_id)) => sema
+                        .db
+                        .call_targets(InFile::new(file_id, function_id))
+                        .get(&expr_id)
+                        .cloned(),
+                    _ => None,
+                };
+                match resolved {
+                    Some(resolved) => resolved.def().cloned(),
+                    None => resolve_call_target(sema, target, arity, file_id, &body),
+                }
+                .map(CallDef::Function)
             }
             AnyExprRef::TypeExpr(TypeExpr::Call { target, args }) => {
                 let arity = args.len().try_into().ok()?;
@@ -278,7 +294,7 @@ impl ToDef for ast::MacroCallExpr {
         let name = macro_exp::macro_name(ast.value)?;
         let resolved = match sema.db.resolve_macro(ast.file_id, name.clone()) {
             Some(ResolvedMacro::User(resolved)) => resolved,
-            Some(ResolvedMacro::BuiltIn(_)) => return None,
+            Some(ResolvedMacro::BuiltIn(_) | ResolvedMacro::Predefined(_)) => return None,
             None => {
                 let name = name.with_arity(None);
                 match sema.db.resolve_macro(ast.file_id, name) {
@@ -355,19 +371,8 @@ impl ToDef for ast::Fa {
                         .cloned()
                         .map(FaDef::Function)
                 },
-                ast::ImportAttribute(attr) => {
-                    let import_attr = sema.find_form(ast.with_value(&attr))?;
-                    let idx = attr.funs().position(|fa| &fa == ast.value)? as u32;
-                    let entry = import_attr
-                        .entries
-                        .clone()
-                        .find(|&entry| form_list[entry].idx == idx)?;
-                    let imported_module = ToDef::to_def(sema, InFile::new(ast.file_id, &attr))?;
-                    sema.db
-                        .def_map(imported_module.file.file_id)
-                        .get_function(&form_list[entry].name)
-                        .cloned()
-                        .map(FaDef::Function)
+                ast::ImportAttribute(_) => {
+                    sema.imported_function(ast)?.def.map(FaDef::Function)
                 },
                 ast::ExportTypeAttribute(attr) => {
                     let export_attr = sema.find_form(ast.with_value(&attr))?;
@@ -497,10 +502,18 @@ pub(crate) fn resolve_module_expr(
     file_id: FileId,
     expr_id: ExprId,
 ) -> Option<Module> {
-    let name = sema.db.lookup_atom(body[expr_id].as_atom()?);
+    let name = sema.db.lookup_atom(literal_atom(body, expr_id)?);
     resolve_module_name(sema, file_id, &name)
 }
 
+/// The atom of a literal, looking through macro calls such as `?MODULE`
+pub(crate) fn literal_atom(body: &Body, expr_id: ExprId) -> Option<Atom> {
+    match &body[expr_id] {
+        Expr::MacroCall { expansion, .. } => literal_atom(body, *expansion),
+        expr => expr.as_atom(),
+    }
+}
+
 pub fn resolve_module_name(sema: &Semantic<'_>, file_id: FileId, name: &str) -> Option<Module> {
     let source_root_id = sema.db.file_source_root(file_id);
     let project_id = sema.db.app_data(source_root_id)?.project_id;
@@ -531,7 +544,7 @@ pub(crate) fn resolve_call_target(
         ),
     };
 
-    let name = sema.db.lookup_atom(body[fun_expr].as_atom()?);
+    let name = sema.db.lookup_atom(literal_atom(body, fun_expr)?);
     let name_arity = NameArity::new(name, arity);
     if let Some(def) = sema.db.def_map(file_id).get_function(&name_arity).cloned() {
         Some(def)
@@ -561,7 +574,7 @@ fn resolve_capture(sema: &Semantic<'_>, fun: InFile<ast::Expr>) -> Option<Functi
     let (body, body_map) = sema.find_body(fun.file_id, fun.value.syntax())?;
     let expr_id = body_map.expr_id(fun.as_ref())?;
     let (target, arity) = match &body[expr_id] {
-        Expr::CaptureFun { target, arity } => (target, arity),
+        Expr::CaptureFun { target, arity } if target.is_static(&body) => (target, arity),
         _ => return None,
     };
     let arity = match body[*arity] {
@@ -12,6 +12,7 @@
 use std::fmt;
 use std::panic;
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use elp_base_db::salsa;
 use elp_base_db::FileId;
@@ -31,6 +32,7 @@ use crate::db::MinInternDatabase;
 #[derive(Default)]
 pub(crate) struct TestDB {
     storage: salsa::Storage<TestDB>,
+    events: Mutex<Option<Vec<salsa::Event>>>,
 }
 
 impl Upcast<dyn SourceDatabase> for TestDB {
@@ -45,7 +47,32 @@ impl Upcast<dyn MinInternDatabase> for TestDB {
     }
 }
 
-impl salsa::Database for TestDB {}
+impl salsa::Database for TestDB {
+    fn salsa_event(&self, event: salsa::Event) {
+        let mut events = self.events.lock().unwrap();
+        if let Some(events) = &mut *events {
+            events.push(event);
+        }
+    }
+}
+
+impl TestDB {
+    /// The queries executed, rather than reused, while running `f`
+    pub(crate) fn log_executed(&self, f: impl FnOnce()) -> Vec<String> {
+        *self.events.lock().unwrap() = Some(Vec::new());
+        f();
+        let events = self.events.lock().unwrap().take().unwrap();
+        events
+            .into_iter()
+            .filter_map(|event| match event.kind {
+                salsa::EventKind::WillExecute { database_key } => {
+                    Some(format!("{:?}", database_key.debug(self)))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
 
 impl fmt::Debug for TestDB {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -60,3 +87,42 @@ impl FileLoader for TestDB {
         FileLoaderDelegate(self).file_text(file_id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use elp_base_db::fixture::WithFixture;
+    use elp_base_db::SourceDatabase;
+    use elp_base_db::SourceDatabaseExt;
+
+    use super::TestDB;
+
+    #[test]
+    fn test_helper_kept_on_unrelated_change() {
+        let (mut db, files) = TestDB::with_many_files(
+            r#"
+//- /erl/my_app/src/main.erl
+-module(main).
+//- /erl/my_app/test/main_SUITE.erl extra:test
+-module(main_SUITE).
+"#,
+        );
+        let suite = files[1];
+        assert_eq!(db.is_test_suite_or_test_helper(suite), Some(true));
+
+        db.set_file_text(
+            files[0],
+            Arc::new("-module(main).\nfoo() -> ok.\n".to_string()),
+        );
+        let executed = db.log_executed(|| {
+            assert_eq!(db.is_test_suite_or_test_helper(suite), Some(true));
+        });
+        assert!(
+            !executed
+                .iter()
+                .any(|query| query.contains("is_test_suite_or_test_helper")),
+            "{executed:?}"
+        );
+    }
+}
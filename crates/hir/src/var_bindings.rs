@@ -0,0 +1,337 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Every occurrence of a variable in a file, together with the patterns
+//! binding it, so that all the occurrences of a variable can be found
+//! without resolving each of them separately. Each function is scoped
+//! independently.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+
+use crate::db::MinDefDatabase;
+use crate::fold::ExprCallBackCtx;
+use crate::fold::PatCallBackCtx;
+use crate::resolver::Resolver;
+use crate::AnyExprId;
+use crate::BodySourceMap;
+use crate::Expr;
+use crate::ExprSource;
+use crate::FoldCtx;
+use crate::FunctionId;
+use crate::InFile;
+use crate::Pat;
+use crate::PatId;
+use crate::Strategy;
+use crate::Var;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VarOccurrence {
+    /// `None` for the occurrences in a record definition
+    pub function_id: Option<FunctionId>,
+    pub var: Var,
+    pub id: AnyExprId,
+    pub range: TextRange,
+    /// The ranges of the patterns binding the variable, more than one when
+    /// it is bound in several branches. Empty if the variable is unbound,
+    /// which is always the case outside of functions.
+    pub bindings: Vec<TextRange>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FileVarBindings {
+    /// Sorted by range
+    occurrences: Vec<VarOccurrence>,
+}
+
+impl FileVarBindings {
+    pub fn occurrences(&self) -> &[VarOccurrence] {
+        &self.occurrences
+    }
+
+    pub fn at_offset(&self, offset: TextSize) -> Option<&VarOccurrence> {
+        self.occurrences
+            .iter()
+            .find(|occurrence| occurrence.range.contains_inclusive(offset))
+    }
+
+    /// The occurrences of the same variable as `occurrence`, including
+    /// itself, in source order. A variable bound in several branches of a
+    /// `case` gathers the occurrences of all those branches.
+    pub fn related(&self, occurrence: &VarOccurrence) -> Vec<&VarOccurrence> {
+        if occurrence.function_id.is_none() || occurrence.bindings.is_empty() {
+            return self
+                .occurrences
+                .iter()
+                .filter(|other| *other == occurrence)
+                .collect();
+        }
+        let in_function: Vec<&VarOccurrence> = self
+            .occurrences
+            .iter()
+            .filter(|other| other.function_id == occurrence.function_id)
+            .collect();
+        let mut bindings = occurrence.bindings.clone();
+        loop {
+            let before = bindings.len();
+            for other in &in_function {
+                if other
+                    .bindings
+                    .iter()
+                    .any(|binding| bindings.contains(binding))
+                {
+                    for binding in &other.bindings {
+                        if !bindings.contains(binding) {
+                            bindings.push(*binding);
+                        }
+                    }
+                }
+            }
+            if bindings.len() == before {
+                break;
+            }
+        }
+        in_function
+            .into_iter()
+            .filter(|other| {
+                other
+                    .bindings
+                    .iter()
+                    .any(|binding| bindings.contains(binding))
+            })
+            .collect()
+    }
+}
+
+/// Only occurrences written in `file_id` itself are included, not the
+/// ones coming from the body of a macro, so that a variable in a `-define`
+/// is not attributed to each function using the macro.
+pub(crate) fn file_var_bindings_query(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+) -> Arc<FileVarBindings> {
+    let form_list = db.file_form_list(file_id);
+    let source_file = db.parse(file_id).tree();
+    let mut occurrences = Vec::new();
+
+    for (function_id, function) in form_list.functions() {
+        let form_range = function.form_id.get(&source_file).syntax().text_range();
+        let range_of = |source: Option<ExprSource>| {
+            let source = source?;
+            let range = source.range();
+            (source.file_id() == file_id
+                && source.value().syntax_node_ptr().kind() == SyntaxKind::VAR
+                && form_range.contains_range(range))
+            .then_some(range)
+        };
+        let in_file_id = InFile::new(file_id, function_id);
+        let (function_body, source_map) = db.function_body_with_source(in_file_id);
+        let scopes = db.function_scopes(in_file_id);
+        let body = &function_body.body;
+        for (clause_id, clause) in function_body.clauses.iter() {
+            let resolver = match scopes.get(clause_id) {
+                Some(clause_scopes) => Resolver::new(clause_scopes),
+                None => continue,
+            };
+            let mut vars = Vec::new();
+            for pat_id in &clause.pats {
+                vars = FoldCtx::fold_pat(
+                    body,
+                    Strategy::TopDown,
+                    *pat_id,
+                    vars,
+                    &mut collect_expr_var,
+                    &mut collect_pat_var,
+                );
+            }
+            for expr_id in clause.guards.iter().flatten().chain(clause.exprs.iter()) {
+                vars = FoldCtx::fold_expr(
+                    body,
+                    Strategy::TopDown,
+                    *expr_id,
+                    vars,
+                    &mut collect_expr_var,
+                    &mut collect_pat_var,
+                );
+            }
+            for (var, id) in vars {
+                let (range, pat_ids) = match &id {
+                    AnyExprId::Expr(expr_id) => (
+                        range_of(source_map.expr(*expr_id)),
+                        resolver.resolve_expr_id(&var, *expr_id),
+                    ),
+                    AnyExprId::Pat(pat_id) => (
+                        range_of(source_map.pat(*pat_id)),
+                        resolver.resolve_pat_id(&var, *pat_id),
+                    ),
+                    _ => continue,
+                };
+                if let Some(range) = range {
+                    let mut bindings = binding_ranges(&source_map, pat_ids, &range_of);
+                    bindings.sort_by_key(|range| range.start());
+                    occurrences.push(VarOccurrence {
+                        function_id: Some(function_id),
+                        var,
+                        id,
+                        range,
+                        bindings,
+                    });
+                }
+            }
+        }
+    }
+
+    // Variables in record field defaults can't be bound
+    for (record_id, record) in form_list.records() {
+        let form_range = record.form_id.get(&source_file).syntax().text_range();
+        let (record_body, source_map) = db.record_body_with_source(InFile::new(file_id, record_id));
+        for field in &record_body.fields {
+            if let Some(expr_id) = field.expr {
+                let vars = FoldCtx::fold_expr(
+                    &record_body.body,
+                    Strategy::TopDown,
+                    expr_id,
+                    Vec::new(),
+                    &mut collect_expr_var,
+                    &mut collect_pat_var,
+                );
+                for (var, id) in vars {
+                    let source = match &id {
+                        AnyExprId::Expr(expr_id) => source_map.expr(*expr_id),
+                        _ => continue,
+                    };
+                    let range = source.filter(|source| source.file_id() == file_id);
+                    if let Some(range) = range.map(|source| source.range()) {
+                        if form_range.contains_range(range) {
+                            occurrences.push(VarOccurrence {
+                                function_id: None,
+                                var,
+                                id,
+                                range,
+                                bindings: Vec::new(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    occurrences.sort_by_key(|occurrence| occurrence.range.start());
+    Arc::new(FileVarBindings { occurrences })
+}
+
+fn collect_expr_var(mut acc: Vec<(Var, AnyExprId)>, ctx: ExprCallBackCtx) -> Vec<(Var, AnyExprId)> {
+    if let Expr::Var(var) = ctx.expr {
+        acc.push((var, AnyExprId::Expr(ctx.expr_id)));
+    }
+    acc
+}
+
+fn collect_pat_var(mut acc: Vec<(Var, AnyExprId)>, ctx: PatCallBackCtx) -> Vec<(Var, AnyExprId)> {
+    if let Pat::Var(var) = ctx.pat {
+        acc.push((var, AnyExprId::Pat(ctx.pat_id)));
+    }
+    acc
+}
+
+fn binding_ranges(
+    source_map: &BodySourceMap,
+    pat_ids: Option<&Vec<PatId>>,
+    range_of: &dyn Fn(Option<ExprSource>) -> Option<TextRange>,
+) -> Vec<TextRange> {
+    pat_ids
+        .into_iter()
+        .flatten()
+        .filter_map(|pat_id| range_of(source_map.pat(*pat_id)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use elp_base_db::SourceDatabaseExt;
+    use elp_syntax::TextRange;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id) = TestDB::with_single_file(fixture);
+        let text = db.file_text(file_id);
+        let bindings = db.file_var_bindings(file_id);
+        let occurrences = bindings.occurrences();
+        // Occurrences are referred to by their index, to not depend on
+        // offsets in the fixture
+        let index_of = |range: &TextRange| {
+            occurrences
+                .iter()
+                .position(|occurrence| occurrence.range == *range)
+                .map_or("?".to_string(), |idx| idx.to_string())
+        };
+        let actual = occurrences
+            .iter()
+            .enumerate()
+            .map(|(idx, occurrence)| {
+                let bound_at = occurrence
+                    .bindings
+                    .iter()
+                    .map(index_of)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let related = bindings
+                    .related(occurrence)
+                    .iter()
+                    .map(|related| index_of(&related.range))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "{idx}: {} bound at [{bound_at}], related [{related}]\n",
+                    &text[occurrence.range],
+                )
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn var_bindings() {
+        check(
+            r#"
+-module(main).
+-define(M(X), X + Y).
+-record(rec, {f = Z}).
+foo(A) ->
+    case A of
+        1 -> B = 1;
+        _ -> B = 2
+    end,
+    ?M(B).
+bar(A) -> A.
+"#,
+            expect![[r#"
+                0: Z bound at [], related [0]
+                1: A bound at [1], related [1, 2]
+                2: A bound at [1], related [1, 2]
+                3: B bound at [3], related [3, 4, 5]
+                4: B bound at [4], related [3, 4, 5]
+                5: B bound at [3, 4], related [3, 4, 5]
+                6: A bound at [6], related [6, 7]
+                7: A bound at [6], related [6, 7]
+            "#]],
+        );
+    }
+}
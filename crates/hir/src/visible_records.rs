@@ -0,0 +1,232 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! All the record definitions the preprocessor sees for a file, including
+//! the ones in its headers, in order and without merging definitions with
+//! the same name. Unlike the `DefMap`, this evaluates `-ifdef` and
+//! `-ifndef` conditions, so a header protected against double inclusion
+//! only contributes its records once.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+use fxhash::FxHashSet;
+
+use crate::db::MinDefDatabase;
+use crate::File;
+use crate::FormIdx;
+use crate::InFile;
+use crate::IncludeAttributeId;
+use crate::Name;
+use crate::PPCondition;
+use crate::PPDirective;
+use crate::RecordDef;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisibleRecord {
+    pub def: RecordDef,
+    /// The `-include` of the original file through which the definition is
+    /// reached, if it is in a header
+    pub via: Option<IncludeAttributeId>,
+}
+
+pub(crate) fn visible_records_query(
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+) -> Arc<Vec<VisibleRecord>> {
+    let mut ctx = Ctx {
+        db,
+        defined: db.predefined_macros(file_id).keys().cloned().collect(),
+        files: Vec::new(),
+        records: Vec::new(),
+    };
+    ctx.process_file(file_id, None);
+    Arc::new(ctx.records)
+}
+
+/// The state of the branch of a conditional the preprocessor is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Branch {
+    Active,
+    Inactive,
+    /// `-if` and `-elif` conditions are not evaluated
+    Unknown,
+}
+
+struct Ctx<'a> {
+    db: &'a dyn MinDefDatabase,
+    defined: FxHashSet<Name>,
+    /// The files being processed, to guard against include cycles
+    files: Vec<FileId>,
+    records: Vec<VisibleRecord>,
+}
+
+impl Ctx<'_> {
+    fn process_file(&mut self, file_id: FileId, via: Option<IncludeAttributeId>) {
+        if self.files.contains(&file_id) {
+            return;
+        }
+        self.files.push(file_id);
+        let form_list = self.db.file_form_list(file_id);
+        // For each open conditional, its branch and whether a previous
+        // branch was taken
+        let mut conditions: Vec<(Branch, bool)> = Vec::new();
+        for form in form_list.forms() {
+            let state = current_branch(&conditions);
+            match form {
+                FormIdx::PPCondition(idx) => match &form_list[*idx] {
+                    PPCondition::Ifdef { name, .. } => {
+                        let branch = branch(self.defined.contains(name));
+                        conditions.push((branch, branch == Branch::Active));
+                    }
+                    PPCondition::Ifndef { name, .. } => {
+                        let branch = branch(!self.defined.contains(name));
+                        conditions.push((branch, branch == Branch::Active));
+                    }
+                    PPCondition::If { .. } => conditions.push((Branch::Unknown, false)),
+                    PPCondition::Elif { .. } => {
+                        if let Some((branch, taken)) = conditions.last_mut() {
+                            *taken |= *branch == Branch::Active;
+                            *branch = if *taken {
+                                Branch::Inactive
+                            } else {
+                                Branch::Unknown
+                            };
+                        }
+                    }
+                    PPCondition::Else { .. } => {
+                        if let Some((branch, taken)) = conditions.last_mut() {
+                            *taken |= *branch == Branch::Active;
+                            *branch = match branch {
+                                Branch::Unknown => Branch::Unknown,
+                                _ if *taken => Branch::Inactive,
+                                _ => Branch::Active,
+                            };
+                        }
+                    }
+                    PPCondition::Endif { .. } => {
+                        conditions.pop();
+                    }
+                },
+                // Definitions in unknown branches are ignored, to avoid
+                // reporting alternatives as redefinitions
+                _ if state != Branch::Active => {}
+                FormIdx::PPDirective(idx) => match &form_list[*idx] {
+                    PPDirective::Define(define) => {
+                        self.defined.insert(form_list[*define].name.name().clone());
+                    }
+                    PPDirective::Undef { name, .. } => {
+                        self.defined.remove(name);
+                    }
+                    PPDirective::Include(include) => {
+                        if let Some(included) =
+                            self.db.resolve_include(InFile::new(file_id, *include))
+                        {
+                            self.process_file(included, via.or(Some(*include)));
+                        }
+                    }
+                },
+                FormIdx::Record(idx) => self.records.push(VisibleRecord {
+                    def: RecordDef {
+                        file: File { file_id },
+                        record: form_list[*idx].clone(),
+                    },
+                    via,
+                }),
+                _ => {}
+            }
+        }
+        self.files.pop();
+    }
+}
+
+/// The branch of the innermost conditional, taking the enclosing ones into
+/// account
+fn current_branch(conditions: &[(Branch, bool)]) -> Branch {
+    let branches = conditions.iter().map(|(branch, _)| *branch);
+    let mut state = Branch::Active;
+    for branch in branches {
+        match branch {
+            Branch::Inactive => return Branch::Inactive,
+            Branch::Unknown => state = Branch::Unknown,
+            Branch::Active => {}
+        }
+    }
+    state
+}
+
+fn branch(active: bool) -> Branch {
+    if active {
+        Branch::Active
+    } else {
+        Branch::Inactive
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::db::MinDefDatabase;
+    use crate::test_db::TestDB;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, files) = TestDB::with_many_files(fixture);
+        let records = db.visible_records(files[0]);
+        let mut actual = String::new();
+        for record in records.iter() {
+            actual.push_str(&format!(
+                "{} in {:?}, via include: {}\n",
+                record.def.record.name,
+                record.def.file.file_id,
+                record.via.is_some()
+            ));
+        }
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn redefinitions_and_include_guards() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-include("guarded.hrl").
+-include("guarded.hrl").
+-include("unguarded.hrl").
+-include("unguarded.hrl").
+-ifdef(DEBUG).
+-record(test, {}).
+-else.
+-record(test, {a}).
+-endif.
+-if(?OTP_RELEASE >= 26).
+-record(new, {}).
+-endif.
+-record(guarded, {}).
+//- /src/guarded.hrl
+-ifndef(GUARDED_HRL).
+-define(GUARDED_HRL, true).
+-record(guarded, {}).
+-endif.
+//- /src/unguarded.hrl
+-record(unguarded, {}).
+"#,
+            expect![[r#"
+                guarded in FileId(1), via include: true
+                unguarded in FileId(2), via include: true
+                unguarded in FileId(2), via include: true
+                test in FileId(0), via include: false
+                guarded in FileId(0), via include: false
+            "#]],
+        );
+    }
+}
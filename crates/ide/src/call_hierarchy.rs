@@ -7,6 +7,7 @@
  * of this source tree.
  */
 
+use elp_ide_db::elp_base_db::FileId;
 use elp_ide_db::elp_base_db::FilePosition;
 use elp_ide_db::FxIndexMap;
 use elp_ide_db::RootDatabase;
@@ -15,8 +16,10 @@ use elp_syntax::ast::{self};
 use elp_syntax::AstNode;
 use elp_syntax::SmolStr;
 use elp_syntax::TextRange;
+use hir::DynamicCallTarget;
 use hir::Expr;
 use hir::InFile;
+use hir::NameArity;
 use hir::Semantic;
 
 use crate::handlers::goto_definition;
@@ -83,6 +86,7 @@ pub(crate) fn outgoing_calls(db: &RootDatabase, position: FilePosition) -> Optio
         let function_id_idx = sema.find_enclosing_function(file_id, function.syntax())?;
         let function_id = InFile::new(file_id, function_id_idx);
         let function_body = sema.to_function_body(function_id);
+        let dynamic_calls = sema.db.dynamic_calls(function_id);
         sema.fold_function(
             function_id,
             (),
@@ -91,11 +95,20 @@ pub(crate) fn outgoing_calls(db: &RootDatabase, position: FilePosition) -> Optio
                     Expr::Call { target, args } => {
                         let arity = args.len() as u32;
                         let body = &function_body.body();
-                        if let Some(call_def) = target.resolve_call(arity, &sema, file_id, body) {
-                            let mut nav = call_def.to_nav(db);
-                            if let Some(label) = target.label(arity, &sema, &body) {
-                                nav.name = label
+                        let nav = match target.resolve_call(arity, &sema, file_id, body) {
+                            Some(call_def) => {
+                                let mut nav = call_def.to_nav(db);
+                                if let Some(label) = target.label(arity, &sema, &body) {
+                                    nav.name = label
+                                }
+                                Some(nav)
                             }
+                            None => dynamic_calls
+                                .iter()
+                                .find(|call| call.expr_id == ctx.expr_id)
+                                .and_then(|call| apply_target_nav(&sema, file_id, &call.target)),
+                        };
+                        if let Some(nav) = nav {
                             if let Some(expr) = &function_body.get_body_map(db).expr(ctx.expr_id) {
                                 if let Some(node) = expr.to_node(&source_file) {
                                     if let Some(call) = algo::find_node_at_offset::<ast::Call>(
@@ -104,7 +117,7 @@ pub(crate) fn outgoing_calls(db: &RootDatabase, position: FilePosition) -> Optio
                                     ) {
                                         if let Some(expr) = call.expr() {
                                             let range = expr.syntax().text_range();
-                                            calls.add(nav.clone(), range);
+                                            calls.add(nav, range);
                                         }
                                     }
                                 }
@@ -121,6 +134,52 @@ pub(crate) fn outgoing_calls(db: &RootDatabase, position: FilePosition) -> Optio
     Some(calls.into_items())
 }
 
+/// The signature of the function defined at the given position, used as the
+/// detail of call hierarchy items.
+pub(crate) fn function_signature(db: &RootDatabase, position: FilePosition) -> Option<String> {
+    let sema = Semantic::new(db);
+    let file_id = position.file_id;
+    let source_file = sema.parse(file_id);
+    let function =
+        algo::find_node_at_offset::<ast::FunDecl>(source_file.value.syntax(), position.offset)?;
+    let function_id = sema.find_enclosing_function(file_id, function.syntax())?;
+    let name = &sema.db.file_form_list(file_id)[function_id].name;
+    let def = sema.def_map(file_id).get_function(name)?.clone();
+    Some(def.signature_string(db))
+}
+
+/// The function called by `apply(Mod, Fun, Args)` with literal `Mod`, `Fun`
+/// and a literal list of `Args`, labelled as a remote call.
+fn apply_target_nav(
+    sema: &Semantic,
+    file_id: FileId,
+    target: &DynamicCallTarget,
+) -> Option<NavigationTarget> {
+    let (module, name, arity) = match target {
+        DynamicCallTarget::Apply {
+            module,
+            name,
+            arity: Some(arity),
+        } => (
+            sema.db.lookup_atom(*module),
+            sema.db.lookup_atom(*name),
+            *arity,
+        ),
+        _ => return None,
+    };
+    let module_file = sema
+        .resolve_module_name(file_id, module.as_str())?
+        .file
+        .file_id;
+    let call_def = sema
+        .def_map(module_file)
+        .get_function(&NameArity::new(name.clone(), arity))?
+        .clone();
+    let mut nav = call_def.to_nav(sema.db);
+    nav.name = SmolStr::new(format!("{module}:{name}/{arity}"));
+    Some(nav)
+}
+
 #[derive(Default)]
 struct CallLocations {
     funcs: FxIndexMap<NavigationTarget, Vec<TextRange>>,
@@ -142,8 +201,31 @@ impl CallLocations {
 #[cfg(test)]
 mod tests {
 
+    use crate::fixture;
     use crate::tests::check_call_hierarchy;
 
+    #[test]
+    fn test_function_signature() {
+        let (analysis, position) = fixture::position(
+            r#"
+-module(main).
+-spec foo(integer()) -> ok.
+f~oo(_) -> ok.
+"#,
+        );
+        let signature = analysis.function_signature(position).unwrap();
+        assert_eq!(signature.as_deref(), Some("foo(integer()) -> ok"));
+
+        let (analysis, position) = fixture::position(
+            r#"
+-module(main).
+b~ar(_, _) -> ok.
+"#,
+        );
+        let signature = analysis.function_signature(position).unwrap();
+        assert_eq!(signature.as_deref(), Some("bar/2"));
+    }
+
     #[test]
     fn test_call_hierarchy_on_ref() {
         check_call_hierarchy(
@@ -392,4 +474,54 @@ mod tests {
     "#,
         );
     }
+
+    #[test]
+    fn test_call_hierarchy_outgoing_apply() {
+        check_call_hierarchy(
+            r#"
+ //- /src/a.erl
+    -module(a).
+    cal~ler(F) ->
+ %% ^^^^^^
+      apply(b, callee, []),
+      erlang:apply(b, callee, [1]),
+      apply(b, F, []).
+ //- /src/b.erl
+    -module(b).
+    -export([callee/0, callee/1]).
+    callee() -> ok.
+    callee(_) -> ok.
+    "#,
+            r#"
+ //- /src/a.erl
+    -module(a).
+    cal~ler(F) ->
+      apply(b, callee, []),
+      erlang:apply(b, callee, [1]),
+      apply(b, F, []).
+ //- /src/b.erl
+    -module(b).
+    -export([callee/0, callee/1]).
+    callee() -> ok.
+    callee(_) -> ok.
+    "#,
+            r#"
+ //- /src/a.erl
+    -module(a).
+    ca~ller(F) ->
+      apply(b, callee, []),
+   %% ^^^^^ from_range: b:callee/0
+      erlang:apply(b, callee, [1]),
+   %% ^^^^^^^^^^^^ from_range: b:callee/1
+      apply(b, F, []).
+ //- /src/b.erl
+    -module(b).
+    -export([callee/0, callee/1]).
+    callee() -> ok.
+ %% ^^^^^^ to: b:callee/0
+    callee(_) -> ok.
+ %% ^^^^^^ to: b:callee/1
+    "#,
+        );
+    }
 }
@@ -51,7 +51,7 @@ use crate::navigation_target::ToNav;
 use crate::runnables::RunnableKind;
 use crate::Runnable;
 
-const SUFFIX: &str = "_SUITE";
+pub(crate) const SUFFIX: &str = "_SUITE";
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum GroupName {
@@ -170,6 +170,33 @@ pub fn runnables(sema: &Semantic, file_id: FileId) -> Result<Vec<Runnable>, ()>
     Ok(res)
 }
 
+// Names of all the testcases mentioned in the all/0 and groups/0 functions,
+// whether or not a corresponding function exists
+pub fn declared_test_names(sema: &Semantic, file_id: FileId) -> Result<FxHashSet<Name>, ()> {
+    let mut res = FxHashSet::default();
+    let groups = groups(sema, file_id)?;
+    let all = all(sema, file_id)?;
+    collect_test_names(&mut res, all.iter());
+    for group in groups.values() {
+        collect_test_names(&mut res, group.content.iter());
+    }
+    Ok(res)
+}
+
+fn collect_test_names<'a>(res: &mut FxHashSet<Name>, test_defs: impl Iterator<Item = &'a TestDef>) {
+    for test_def in test_defs {
+        match test_def {
+            TestDef::TestName(testcase_name) => {
+                res.insert(testcase_name.clone());
+            }
+            TestDef::GroupName(_) => (),
+            TestDef::GroupDef(_, group_test_defs) => {
+                collect_test_names(res, group_test_defs.iter())
+            }
+        }
+    }
+}
+
 fn runnables_for_test_defs(
     res: &mut Vec<Runnable>,
     sema: &Semantic,
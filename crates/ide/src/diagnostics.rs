@@ -40,6 +40,7 @@ use elp_syntax::TextSize;
 use fxhash::FxHashMap;
 use fxhash::FxHashSet;
 use hir::db::MinDefDatabase;
+use hir::CompileOptions;
 use hir::InFile;
 use hir::Semantic;
 use lazy_static::lazy_static;
@@ -54,16 +55,36 @@ use crate::RootDatabase;
 use crate::SourceDatabase;
 
 mod application_env;
+mod bare_catch;
+mod broad_spec_result;
+mod conflicting_specs;
 mod effect_free_statement;
+mod elp_attribute;
+mod function_redefinition;
 mod head_mismatch;
+mod map_generator_op;
+mod maybe_else_not_exhaustive;
 // @fb-only: mod meta_only;
 mod missing_compile_warn_missing_spec;
+mod missing_export;
 mod misspelled_attribute;
 mod module_mismatch;
 mod mutable_variable;
+mod no_return_spec;
+mod non_guard_call;
+mod record_redefinition;
 mod redundant_assignment;
 mod replace_call;
+mod strict_comparison;
+mod swallowed_exception;
+mod too_many_arguments;
 mod trivial_match;
+mod undefined_exported_type;
+mod unexported_type;
+mod unmatchable_binary_pattern;
+mod unreachable_clause;
+mod unresolved_import;
+mod unused_export;
 mod unused_function_args;
 mod unused_include;
 mod unused_macro;
@@ -234,6 +255,26 @@ pub enum DiagnosticCode {
     ApplicationGetEnv,
     MissingCompileWarnMissingSpec,
     MisspelledAttribute,
+    UnreachableClause,
+    MaybeElseNotExhaustive,
+    ConflictingSpecs,
+    StrictComparison,
+    MapGeneratorOp,
+    UnresolvedImport,
+    MissingExport,
+    UnmatchableBinaryPattern,
+    RecordRedefinition,
+    UnexportedType,
+    NoReturnSpec,
+    InvalidElpAttribute,
+    UnusedExport,
+    SwallowedException,
+    NonGuardCall,
+    BroadSpecResult,
+    TooManyArguments,
+    FunctionRedefinition,
+    BareCatch,
+    UndefinedExportedType,
 
     // Wrapper for erlang service diagnostic codes
     ErlangService(String),
@@ -270,6 +311,26 @@ impl DiagnosticCode {
             DiagnosticCode::ApplicationGetEnv => "W0011".to_string(),   // application_get_env
             DiagnosticCode::MissingCompileWarnMissingSpec => "W0012".to_string(),
             DiagnosticCode::MisspelledAttribute => "W0013".to_string(), // misspelled-attribute
+            DiagnosticCode::UnreachableClause => "W0014".to_string(),   // unreachable-clause
+            DiagnosticCode::MaybeElseNotExhaustive => "W0015".to_string(), // maybe-else-not-exhaustive
+            DiagnosticCode::ConflictingSpecs => "W0016".to_string(),       // conflicting-specs
+            DiagnosticCode::StrictComparison => "W0017".to_string(),       // strict-comparison
+            DiagnosticCode::MapGeneratorOp => "W0018".to_string(),         // map-generator-op
+            DiagnosticCode::UnresolvedImport => "W0019".to_string(),       // unresolved-import
+            DiagnosticCode::MissingExport => "W0020".to_string(),          // missing-export
+            DiagnosticCode::UnmatchableBinaryPattern => "W0021".to_string(), // unmatchable-binary-pattern
+            DiagnosticCode::RecordRedefinition => "W0022".to_string(),       // record-redefinition
+            DiagnosticCode::UnexportedType => "W0023".to_string(),           // unexported-type
+            DiagnosticCode::NoReturnSpec => "W0024".to_string(),             // no-return-spec
+            DiagnosticCode::InvalidElpAttribute => "W0025".to_string(), // invalid-elp-attribute
+            DiagnosticCode::UnusedExport => "W0026".to_string(),        // unused-export
+            DiagnosticCode::SwallowedException => "W0027".to_string(),  // swallowed-exception
+            DiagnosticCode::NonGuardCall => "W0028".to_string(),        // non-guard-call
+            DiagnosticCode::BroadSpecResult => "W0029".to_string(),     // broad-spec-result
+            DiagnosticCode::TooManyArguments => "W0030".to_string(),    // too-many-arguments
+            DiagnosticCode::FunctionRedefinition => "W0031".to_string(), // function-redefinition
+            DiagnosticCode::BareCatch => "W0032".to_string(),           // bare-catch
+            DiagnosticCode::UndefinedExportedType => "W0033".to_string(), // undefined-exported-type
             DiagnosticCode::ErlangService(c) => c.to_string(),
             DiagnosticCode::AdHoc(c) => format!("ad-hoc: {c}").to_string(),
             // @fb-only: DiagnosticCode::MetaOnly(c) => c.as_code(),
@@ -300,6 +361,26 @@ impl DiagnosticCode {
             }
             DiagnosticCode::ApplicationGetEnv => "application_get_env".to_string(),
             DiagnosticCode::MisspelledAttribute => "misspelled_attribute".to_string(),
+            DiagnosticCode::UnreachableClause => "unreachable_clause".to_string(),
+            DiagnosticCode::MaybeElseNotExhaustive => "maybe_else_not_exhaustive".to_string(),
+            DiagnosticCode::ConflictingSpecs => "conflicting_specs".to_string(),
+            DiagnosticCode::StrictComparison => "strict_comparison".to_string(),
+            DiagnosticCode::MapGeneratorOp => "map_generator_op".to_string(),
+            DiagnosticCode::UnresolvedImport => "unresolved_import".to_string(),
+            DiagnosticCode::MissingExport => "missing_export".to_string(),
+            DiagnosticCode::UnmatchableBinaryPattern => "unmatchable_binary_pattern".to_string(),
+            DiagnosticCode::RecordRedefinition => "record_redefinition".to_string(),
+            DiagnosticCode::UnexportedType => "unexported_type".to_string(),
+            DiagnosticCode::NoReturnSpec => "no_return_spec".to_string(),
+            DiagnosticCode::InvalidElpAttribute => "invalid_elp_attribute".to_string(),
+            DiagnosticCode::UnusedExport => "unused_export".to_string(),
+            DiagnosticCode::SwallowedException => "swallowed_exception".to_string(),
+            DiagnosticCode::NonGuardCall => "non_guard_call".to_string(),
+            DiagnosticCode::BroadSpecResult => "broad_spec_result".to_string(),
+            DiagnosticCode::TooManyArguments => "too_many_arguments".to_string(),
+            DiagnosticCode::FunctionRedefinition => "function_redefinition".to_string(),
+            DiagnosticCode::BareCatch => "bare_catch".to_string(),
+            DiagnosticCode::UndefinedExportedType => "undefined_exported_type".to_string(),
             DiagnosticCode::ErlangService(c) => c.to_string(),
             DiagnosticCode::AdHoc(c) => format!("ad-hoc: {c}").to_string(),
             // @fb-only: DiagnosticCode::MetaOnly(c) => c.as_label(),
@@ -385,6 +466,29 @@ impl<F> AdhocSemanticDiagnostics for F where
 #[derive(Default, Clone)]
 pub struct DiagnosticsConfig<'a> {
     pub disable_experimental: bool,
+    /// Report `==` and `/=` on values which do not look like numbers
+    pub enable_strict_comparison: bool,
+    /// Report functions defined after the last `-export` which are not
+    /// exported, in modules where at least this ratio of the functions is
+    pub missing_export_threshold: Option<f64>,
+    /// Report exported functions which are not called anywhere in the
+    /// project. This searches the whole project for each export.
+    pub enable_unused_export: bool,
+    /// Report `try ... catch` clauses catching any exception, which
+    /// neither use nor re-raise it
+    pub enable_swallowed_exception: bool,
+    /// Report `catch Expr`, suggesting `try ... catch ... end` instead
+    pub enable_bare_catch: bool,
+    /// Report `-export_type` entries naming a type which is not defined
+    pub enable_undefined_exported_type: bool,
+    /// Report `-import` attributes naming an unknown module, or a function
+    /// which the module does not define or export
+    pub enable_unresolved_import: bool,
+    /// Report the Erlang compiler warnings of modules compiled with
+    /// `warnings_as_errors` as errors, as the compiler does
+    pub enable_warnings_as_errors: bool,
+    /// Report functions taking more than this number of arguments
+    pub max_function_arguments: Option<usize>,
     disabled: FxHashSet<DiagnosticCode>,
     pub adhoc_semantic_diagnostics: Vec<&'a dyn AdhocSemanticDiagnostics>,
 }
@@ -397,6 +501,15 @@ impl<'a> DiagnosticsConfig<'a> {
     ) -> DiagnosticsConfig<'a> {
         DiagnosticsConfig {
             disable_experimental,
+            enable_strict_comparison: false,
+            missing_export_threshold: None,
+            enable_unused_export: false,
+            enable_swallowed_exception: false,
+            enable_bare_catch: false,
+            enable_undefined_exported_type: false,
+            enable_unresolved_import: false,
+            enable_warnings_as_errors: false,
+            max_function_arguments: None,
             disabled,
             adhoc_semantic_diagnostics,
         }
@@ -426,15 +539,20 @@ pub fn diagnostics(
     let report_diagnostics = EXTENSIONS.iter().any(|it| Some(it.as_str()) == ext);
 
     let mut res = Vec::new();
+    let mut module_disabled = FxHashSet::default();
 
     if report_diagnostics {
         let is_erl_module = matches!(path.name_and_extension(), Some((_, Some("erl"))));
         let sema = Semantic::new(db);
+        let mut directives = elp_attribute::module_directives(&sema, file_id);
+        res.append(&mut directives.errors);
+        module_disabled = directives.disabled;
 
         if is_erl_module {
             no_module_definition_diagnostic(&mut res, &parse);
             if include_generated || !db.is_generated(file_id) {
                 unused_include::unused_includes(&sema, db, &mut res, file_id);
+                module_mismatch::module_mismatch(&mut res, &sema, file_id);
             }
             let is_test_suite = match path.name_and_extension() {
                 Some((name, _)) => name.ends_with("_SUITE"),
@@ -452,6 +570,30 @@ pub fn diagnostics(
             .iter()
             .for_each(|f| f(&mut res, &sema, file_id, ext));
         semantic_diagnostics(&mut res, &sema, file_id, ext, config.disable_experimental);
+        if config.enable_strict_comparison {
+            strict_comparison::strict_comparison(&mut res, &sema, file_id);
+        }
+        if let Some(threshold) = config.missing_export_threshold {
+            missing_export::missing_export(&mut res, &sema, file_id, threshold);
+        }
+        if config.enable_unused_export {
+            unused_export::unused_export(&mut res, &sema, file_id);
+        }
+        if config.enable_swallowed_exception {
+            swallowed_exception::swallowed_exception(&mut res, &sema, file_id);
+        }
+        if config.enable_bare_catch {
+            bare_catch::bare_catch(&mut res, &sema, file_id);
+        }
+        if config.enable_undefined_exported_type {
+            undefined_exported_type::undefined_exported_type(&mut res, &sema, file_id, ext);
+        }
+        if config.enable_unresolved_import {
+            unresolved_import::unresolved_import(&mut res, &sema, file_id);
+        }
+        if let Some(max_arguments) = config.max_function_arguments {
+            too_many_arguments::too_many_arguments(&mut res, &sema, file_id, max_arguments);
+        }
         syntax_diagnostics(db, &parse, &mut res, file_id);
 
         res.extend(parse.errors().iter().take(128).map(|err| {
@@ -465,6 +607,7 @@ pub fn diagnostics(
     let line_index = db.file_line_index(file_id);
     res.retain(|d| {
         !config.disabled.contains(&d.code)
+            && !module_disabled.contains(&d.code)
             && !(config.disable_experimental && d.experimental)
             && !d.should_be_ignored(&line_index, &parse.syntax_node())
     });
@@ -484,12 +627,22 @@ pub fn semantic_diagnostics(
         unused_function_args::unused_function_args(res, sema, file_id);
         redundant_assignment::redundant_assignment(res, sema, file_id);
         trivial_match::trivial_match(res, sema, file_id);
+        maybe_else_not_exhaustive::maybe_else_not_exhaustive(res, sema, file_id);
+        no_return_spec::no_return_spec(res, sema, file_id);
+        broad_spec_result::broad_spec_result(res, sema, file_id);
+        non_guard_call::non_guard_call(res, sema, file_id);
+        unmatchable_binary_pattern::unmatchable_binary_pattern(res, sema, file_id);
+        unreachable_clause::unreachable_clause(res, sema, file_id);
+        function_redefinition::function_redefinition(res, sema, file_id);
     }
     unused_macro::unused_macro(res, sema, file_id, ext);
     unused_record_field::unused_record_field(res, sema, file_id, ext);
     mutable_variable::mutable_variable_bug(res, sema, file_id);
     effect_free_statement::effect_free_statement(res, sema, file_id);
     application_env::application_env(res, sema, file_id);
+    record_redefinition::record_redefinition(res, sema, file_id);
+    unexported_type::unexported_type(res, sema, file_id);
+    conflicting_specs::conflicting_specs(res, sema, file_id);
     // @fb-only: meta_only::diagnostics(res, sema, file_id);
     missing_compile_warn_missing_spec::missing_compile_warn_missing_spec(res, sema, file_id);
 }
@@ -503,7 +656,7 @@ pub fn syntax_diagnostics(
     misspelled_attribute::misspelled_attribute(res, db, file_id);
     for node in parse.tree().syntax().descendants() {
         head_mismatch::head_mismatch(res, file_id, &node);
-        module_mismatch::module_mismatch(res, db, file_id, &node);
+        map_generator_op::map_generator_op(res, file_id, &node);
     }
 }
 
@@ -691,6 +844,7 @@ fn make_missing_diagnostic(range: TextRange, item: &'static str, code: String) -
 
 pub fn erlang_service_diagnostics(
     db: &RootDatabase,
+    config: &DiagnosticsConfig,
     file_id: FileId,
 ) -> Vec<(FileId, Vec<Diagnostic>)> {
     // Use the same format as eqwalizer, so we can re-use the salsa cache entry
@@ -718,6 +872,7 @@ pub fn erlang_service_diagnostics(
             warning_info.insert(val);
         });
 
+    let compile_options = db.compile_options(file_id);
     let diags: Vec<(FileId, Diagnostic)> = error_info
         .into_iter()
         .map(|(file_id, start, end, code, msg)| {
@@ -740,6 +895,7 @@ pub fn erlang_service_diagnostics(
                     // Temporary for T148094436
                     let _pctx =
                         stdx::panic_context::enter(format!("\nerlang_service_diagnostics:2"));
+                    let severity = warning_severity(config, &compile_options, &code);
                     (
                         file_id,
                         Diagnostic::new(
@@ -747,16 +903,19 @@ pub fn erlang_service_diagnostics(
                             msg,
                             TextRange::new(start, end),
                         )
-                        .severity(Severity::Warning),
+                        .severity(severity),
                     )
                 }),
         )
         .collect();
 
-    // Remove diagnostics already reported by ELP
+    // Remove diagnostics already reported by ELP, or disabled for the
+    // module by an `-elp` attribute
+    let module_disabled = elp_attribute::module_directives(&Semantic::new(db), file_id).disabled;
     let diags: Vec<(FileId, Diagnostic)> = diags
         .into_iter()
         .filter(|(_, d)| is_implemented_in_elp(&d.message))
+        .filter(|(diag_file_id, d)| *diag_file_id != file_id || !module_disabled.contains(&d.code))
         .collect();
     if diags.len() == 0 {
         // If there are no diagnostics reported, return an empty list
@@ -778,6 +937,57 @@ pub fn erlang_service_diagnostics(
     }
 }
 
+/// The severity of an Erlang compiler warning: an error if the module is
+/// compiled with `warnings_as_errors`, unless the warning is disabled
+fn warning_severity(
+    config: &DiagnosticsConfig,
+    compile_options: &CompileOptions,
+    code: &str,
+) -> Severity {
+    let escalate = config.enable_warnings_as_errors
+        && compile_options.warnings_as_errors
+        && !warning_category(code).map_or(false, |category| compile_options.is_nowarn(category));
+    if escalate {
+        Severity::Error
+    } else {
+        Severity::Warning
+    }
+}
+
+/// The Erlang compiler warnings which can be disabled for a whole module,
+/// with the `nowarn_` option category of each
+const WARNING_CATEGORIES: &[(&str, &str)] = &[
+    ("L1224", "export_all"),
+    ("L1226", "unused_import"),
+    ("L1230", "unused_function"),
+    ("L1235", "deprecated_function"),
+    ("L1236", "deprecated_function"),
+    ("L1237", "deprecated_type"),
+    ("L1238", "deprecated_type"),
+    ("L1260", "unused_record"),
+    ("L1264", "export_vars"),
+    ("L1267", "shadow_vars"),
+    ("L1268", "unused_vars"),
+    ("L1296", "unused_type"),
+];
+
+/// The `nowarn_` option category of an Erlang compiler warning
+fn warning_category(code: &str) -> Option<&'static str> {
+    WARNING_CATEGORIES
+        .iter()
+        .find(|(warning, _)| *warning == code)
+        .map(|(_, category)| *category)
+}
+
+/// The codes of the Erlang compiler warnings in a `nowarn_` option category
+pub(crate) fn category_warnings(category: &str) -> Vec<DiagnosticCode> {
+    WARNING_CATEGORIES
+        .iter()
+        .filter(|(_, warning_category)| *warning_category == category)
+        .map(|(code, _)| DiagnosticCode::ErlangService(code.to_string()))
+        .collect()
+}
+
 pub fn edoc_diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<(FileId, Vec<Diagnostic>)> {
     // We use a BTreeSet of a tuple because neither ParseError nor
     // Diagnostic nor TextRange has an Ord instance
@@ -1039,6 +1249,7 @@ fn location_range(location: Location, line_index: &LineIndex) -> TextRange {
 // cargo test --package elp_ide --lib
 #[cfg(test)]
 mod tests {
+    use elp_ide_db::elp_base_db::fixture::WithFixture;
     use elp_syntax::ast;
     use expect_test::expect;
 
@@ -1226,6 +1437,15 @@ baz(1)->4.
     fn filter_experimental() {
         let mut config = DiagnosticsConfig {
             disable_experimental: false,
+            enable_strict_comparison: false,
+            missing_export_threshold: None,
+            enable_unused_export: false,
+            enable_swallowed_exception: false,
+            enable_bare_catch: false,
+            enable_undefined_exported_type: false,
+            enable_unresolved_import: false,
+            enable_warnings_as_errors: false,
+            max_function_arguments: None,
             disabled: FxHashSet::default(),
             adhoc_semantic_diagnostics: vec![&|acc, sema, file_id, _ext| {
                 replace_call::replace_call_site(
@@ -1346,4 +1566,103 @@ baz(1)->4.
         "#]]
         .assert_debug_eq(&codes);
     }
+
+    #[test]
+    fn warnings_as_errors() {
+        let (db, file_id) = RootDatabase::with_single_file(
+            r#"
+-module(main).
+-compile([warnings_as_errors, nowarn_unused_vars]).
+"#,
+        );
+        let compile_options = db.compile_options(file_id);
+        let mut config = DiagnosticsConfig::new(false, FxHashSet::default(), vec![]);
+        let severity = |config: &DiagnosticsConfig, code: &str| {
+            format!("{:?}", warning_severity(config, &compile_options, code))
+        };
+        // Opt-in
+        assert_eq!(severity(&config, "L1230"), "Warning");
+
+        config.enable_warnings_as_errors = true;
+        assert_eq!(severity(&config, "L1230"), "Error");
+        // Disabled by `nowarn_unused_vars`
+        assert_eq!(severity(&config, "L1268"), "Warning");
+        // Not in any `nowarn_` category
+        assert_eq!(severity(&config, "L1500"), "Error");
+    }
+
+    #[test]
+    fn warnings_without_warnings_as_errors() {
+        let (db, file_id) = RootDatabase::with_single_file(
+            r#"
+-module(main).
+-compile(nowarn_unused_vars).
+"#,
+        );
+        let compile_options = db.compile_options(file_id);
+        let mut config = DiagnosticsConfig::new(false, FxHashSet::default(), vec![]);
+        config.enable_warnings_as_errors = true;
+        assert!(matches!(
+            warning_severity(&config, &compile_options, "L1230"),
+            Severity::Warning
+        ));
+    }
+
+    #[test]
+    fn warning_categories() {
+        let categories = ["L1224", "L1230", "L1235", "L1236", "L1268", "L1500"]
+            .iter()
+            .map(|code| (*code, warning_category(code)))
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                (
+                    "L1224",
+                    Some(
+                        "export_all",
+                    ),
+                ),
+                (
+                    "L1230",
+                    Some(
+                        "unused_function",
+                    ),
+                ),
+                (
+                    "L1235",
+                    Some(
+                        "deprecated_function",
+                    ),
+                ),
+                (
+                    "L1236",
+                    Some(
+                        "deprecated_function",
+                    ),
+                ),
+                (
+                    "L1268",
+                    Some(
+                        "unused_vars",
+                    ),
+                ),
+                (
+                    "L1500",
+                    None,
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&categories);
+        expect![[r#"
+            [
+                ErlangService(
+                    "L1235",
+                ),
+                ErlangService(
+                    "L1236",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&category_warnings("deprecated_function"));
+    }
 }
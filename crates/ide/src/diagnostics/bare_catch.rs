@@ -0,0 +1,264 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint/fix: bare_catch
+//!
+//! Return a diagnostic for a `catch Expr` expression, which turns every
+//! exception into a value without telling its class, and offer to replace
+//! it by the equivalent `try ... catch ... end`, so that the value of the
+//! expression is unchanged.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::FileLoader;
+use elp_ide_db::source_change::SourceChange;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use fxhash::FxHashSet;
+use hir::Expr;
+use hir::FunctionDef;
+use hir::Semantic;
+use text_edit::TextEdit;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+use crate::fix;
+
+pub(crate) fn bare_catch(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    sema.def_map(file_id)
+        .get_functions()
+        .iter()
+        .for_each(|(_arity, def)| {
+            if def.file.file_id == file_id {
+                process_catch_exprs(diags, sema, def)
+            }
+        });
+}
+
+fn process_catch_exprs(diags: &mut Vec<Diagnostic>, sema: &Semantic, def: &FunctionDef) {
+    let def_fb = def.in_function_body(sema.db, def);
+    let body_map = def_fb.get_body_map(sema.db);
+    let source_file = sema.parse(def.file.file_id);
+    let text = sema.db.file_text(def.file.file_id);
+
+    def_fb.fold_function(
+        (),
+        &mut |_acc, _, ctx| {
+            if ctx.in_macro.is_some() {
+                return;
+            }
+            if let Expr::Catch { .. } = ctx.expr {
+                let catch = match body_map
+                    .expr(ctx.expr_id)
+                    .and_then(|ptr| ptr.to_node(&source_file))
+                {
+                    Some(ast::Expr::CatchExpr(catch)) => catch,
+                    _ => return,
+                };
+                // Not valid in a guard anyway, the compiler reports it
+                if catch
+                    .syntax()
+                    .ancestors()
+                    .any(|n| ast::Guard::can_cast(n.kind()))
+                {
+                    return;
+                }
+                let range = catch.syntax().text_range();
+                let diag = Diagnostic::new(
+                    DiagnosticCode::BareCatch,
+                    "Prefer `try ... catch ... end`, which tells exceptions from values",
+                    range,
+                )
+                .severity(Severity::WeakWarning);
+                let fixes = replacement(&text, &catch).map(|replacement| {
+                    let mut edit_builder = TextEdit::builder();
+                    edit_builder.replace(range, replacement);
+                    vec![fix(
+                        "replace_catch_with_try",
+                        "Replace with `try ... catch ... end`",
+                        SourceChange::from_text_edit(def.file.file_id, edit_builder.finish()),
+                        range,
+                    )]
+                });
+                diags.push(diag.with_fixes(fixes));
+            }
+        },
+        &mut |_acc, _, _| (),
+    );
+}
+
+/// The `try` expression with the same value as `catch`: what is thrown,
+/// or `{'EXIT', ...}` for errors and exits. It is aligned on the column of
+/// `catch`, and its variables are fresh in the function clause.
+fn replacement(text: &str, catch: &ast::CatchExpr) -> Option<String> {
+    let expr = catch.expr()?;
+    let taken: FxHashSet<String> = catch
+        .syntax()
+        .ancestors()
+        .find_map(ast::FunctionClause::cast)
+        .map(|clause| {
+            clause
+                .syntax()
+                .descendants()
+                .filter_map(ast::Var::cast)
+                .map(|var| var.text().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    let thrown = fresh_var(&taken, "Thrown");
+    let reason = fresh_var(&taken, "Reason");
+    let stack = fresh_var(&taken, "Stack");
+
+    let start: usize = catch.syntax().text_range().start().into();
+    let line_start = text[..start].rfind('\n').map_or(0, |idx| idx + 1);
+    let indent = " ".repeat(text[line_start..start].chars().count());
+    Some(format!(
+        "try {expr}\n\
+         {indent}catch\n\
+         {indent}    throw:{thrown} -> {thrown};\n\
+         {indent}    error:{reason}:{stack} -> {{'EXIT', {{{reason}, {stack}}}}};\n\
+         {indent}    exit:{reason} -> {{'EXIT', {reason}}}\n\
+         {indent}end",
+        expr = expr.syntax().text(),
+    ))
+}
+
+/// `name`, or `name` with the smallest numeric suffix not in `taken`
+fn fresh_var(taken: &FxHashSet<String>, name: &str) -> String {
+    if !taken.contains(name) {
+        return name.to_string();
+    }
+    (0..)
+        .map(|idx| format!("{name}{idx}"))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+    use crate::DiagnosticCode;
+    use crate::DiagnosticsConfig;
+
+    fn config() -> DiagnosticsConfig<'static> {
+        let mut config =
+            DiagnosticsConfig::default().disable(DiagnosticCode::MissingCompileWarnMissingSpec);
+        config.enable_bare_catch = true;
+        config
+    }
+
+    #[test]
+    fn catch_exprs() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([foo/0]).
+
+foo() ->
+    Res = catch bar(),
+    %%    ^^^^^^^^^^^ 💡 weak: Prefer `try ... catch ... end`, which tells exceptions from values
+    case catch bar() of
+    %%   ^^^^^^^^^^^ 💡 weak: Prefer `try ... catch ... end`, which tells exceptions from values
+        {'EXIT', Reason} -> Reason;
+        _ -> Res
+    end.
+
+bar() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        check_diagnostics(
+            r#"
+-module(main).
+-export([foo/0]).
+
+foo() ->
+    catch bar().
+
+bar() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_keeps_value() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-export([foo/0]).
+
+foo() ->
+    Res = ~catch bar(),
+    Res.
+
+bar() -> ok.
+"#,
+            r#"
+-module(main).
+-export([foo/0]).
+
+foo() ->
+    Res = try bar()
+          catch
+              throw:Thrown -> Thrown;
+              error:Reason:Stack -> {'EXIT', {Reason, Stack}};
+              exit:Reason -> {'EXIT', Reason}
+          end,
+    Res.
+
+bar() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_with_fresh_variables() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-export([foo/1]).
+
+foo(Stack) ->
+    case ~catch bar(Stack) of
+        {'EXIT', Reason} -> Reason;
+        Res -> Res
+    end.
+
+bar(_) -> ok.
+"#,
+            r#"
+-module(main).
+-export([foo/1]).
+
+foo(Stack) ->
+    case try bar(Stack)
+         catch
+             throw:Thrown -> Thrown;
+             error:Reason0:Stack0 -> {'EXIT', {Reason0, Stack0}};
+             exit:Reason0 -> {'EXIT', Reason0}
+         end of
+        {'EXIT', Reason} -> Reason;
+        Res -> Res
+    end.
+
+bar(_) -> ok.
+"#,
+        );
+    }
+}
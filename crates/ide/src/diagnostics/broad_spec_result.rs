@@ -0,0 +1,107 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: broad_spec_result
+//!
+//! Return a diagnostic for a function whose spec says it returns `term()`
+//! or `any()`, although every clause obviously returns an atom or a tuple
+//! of atoms, with a fix tightening the spec to the values returned.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_syntax::AstNode;
+use hir::Semantic;
+use text_edit::TextEdit;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+use crate::fix;
+
+pub(crate) fn broad_spec_result(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let broad_specs = sema.db.broad_specs(file_id);
+    if broad_specs.is_empty() {
+        return;
+    }
+    let def_map = sema.def_map(file_id);
+    let source_file = sema.parse(file_id);
+    for broad_spec in broad_specs.iter() {
+        let spec = match def_map.get_spec(&broad_spec.function) {
+            Some(spec) if spec.file.file_id == file_id => spec,
+            _ => continue,
+        };
+        let source = spec.spec.form_id.get(&source_file.value);
+        let range = match source.sigs().next().and_then(|sig| sig.ty()) {
+            Some(ty) => ty.syntax().text_range(),
+            None => continue,
+        };
+        let mut edit_builder = TextEdit::builder();
+        edit_builder.replace(range, broad_spec.result.clone());
+        diags.push(
+            Diagnostic::new(
+                DiagnosticCode::BroadSpecResult,
+                format!(
+                    "Function {} always returns `{}`, its spec could be narrowed",
+                    broad_spec.function, broad_spec.result
+                ),
+                range,
+            )
+            .severity(Severity::WeakWarning)
+            // Marking as EXPERIMENTAL since a broad spec may be a deliberate API contract
+            .experimental()
+            .with_fixes(Some(vec![fix(
+                "narrow_spec_result",
+                &format!("Change the spec to return `{}`", broad_spec.result),
+                SourceChange::from_text_edit(file_id, edit_builder.finish()),
+                range,
+            )])),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics;
+    use crate::tests::check_fix;
+
+    #[test]
+    fn broad_spec_result() {
+        check_diagnostics(
+            r#"
+-module(main).
+-spec open(atom()) -> term().
+%%                    ^^^^^^ 💡 weak: Function open/1 always returns `ok | {error, closed}`, its spec could be narrowed
+open(a) -> ok;
+open(_) -> {error, closed}.
+-spec value(term()) -> any().
+value(X) -> X.
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_spec() {
+        check_fix(
+            r#"
+-module(main).
+-spec open(atom()) -> te~rm().
+open(a) -> ok;
+open(_) -> {error, closed}.
+"#,
+            r#"
+-module(main).
+-spec open(atom()) -> ok | {error, closed}.
+open(a) -> ok;
+open(_) -> {error, closed}.
+"#,
+        );
+    }
+}
@@ -0,0 +1,130 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: conflicting_specs
+//!
+//! Return a diagnostic if a function defined in a module has a spec in more
+//! than one of the files it is compiled from, at least one of them being an
+//! included header. Specs in headers for functions the module doesn't define
+//! are fine.
+//!
+
+use std::iter;
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use fxhash::FxHashMap;
+use hir::File;
+use hir::NameArity;
+use hir::Semantic;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+pub(crate) fn conflicting_specs(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let includes = sema.db.include_closure(file_id);
+    if includes.is_empty() {
+        return;
+    }
+    let mut spec_files: FxHashMap<NameArity, Vec<FileId>> = FxHashMap::default();
+    for spec_file_id in iter::once(file_id).chain(includes.iter().copied()) {
+        for name in sema.db.local_def_map(spec_file_id).get_specs().keys() {
+            spec_files
+                .entry(name.clone())
+                .or_default()
+                .push(spec_file_id);
+        }
+    }
+
+    let def_map = sema.def_map(file_id);
+    let mut conflicts: Vec<_> = spec_files
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1 && files.iter().any(|&id| id != file_id))
+        .collect();
+    conflicts.sort_by_key(|(name, _)| name.to_string());
+    for (name, files) in conflicts {
+        let def = match def_map.get_function(&name) {
+            Some(def) if def.file.file_id == file_id => def,
+            _ => continue,
+        };
+        let range = match def
+            .source(sema.db.upcast())
+            .clauses()
+            .find_map(|clause| match clause {
+                ast::FunctionOrMacroClause::FunctionClause(clause) => {
+                    clause.name().map(|name| name.syntax().text_range())
+                }
+                ast::FunctionOrMacroClause::MacroCallExpr(_) => None,
+            }) {
+            Some(range) => range,
+            None => continue,
+        };
+        let files = files
+            .into_iter()
+            .map(|file_id| File { file_id }.name(sema.db.upcast()).to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        diags.push(
+            Diagnostic::new(
+                DiagnosticCode::ConflictingSpecs,
+                format!("Function {name} has specs in more than one file: {files}"),
+                range,
+            )
+            .severity(Severity::Warning),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn specs_in_two_headers() {
+        check_diagnostics(
+            r#"
+//- /src/main.erl
+-module(main).
+-include("a.hrl").
+-include("b.hrl").
+  foo(X) -> X.
+%%^^^ warning: Function foo/1 has specs in more than one file: a.hrl, b.hrl
+bar(X) -> X.
+//- /src/a.hrl
+-include("c.hrl").
+-spec foo(integer()) -> integer().
+-spec bar(integer()) -> integer().
+//- /src/b.hrl
+-spec foo(atom()) -> atom().
+//- /src/c.hrl
+-spec baz(integer()) -> integer().
+-spec baz(atom()) -> atom().
+"#,
+        );
+    }
+
+    #[test]
+    fn specs_in_module_and_header() {
+        check_diagnostics(
+            r#"
+//- /src/main.erl
+-module(main).
+-include("a.hrl").
+-spec foo(integer()) -> integer().
+  foo(X) -> X.
+%%^^^ warning: Function foo/1 has specs in more than one file: main.erl, a.hrl
+//- /src/a.hrl
+-spec foo(atom()) -> atom().
+"#,
+        );
+    }
+}
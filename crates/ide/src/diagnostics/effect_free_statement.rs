@@ -74,6 +74,7 @@ fn has_no_effect(def_fb: &InFunctionBody<&FunctionDef>, expr_id: &ExprId) -> boo
         Expr::Binary { .. } => true,
         Expr::Var(_) => true,
         Expr::CaptureFun { .. } => true,
+        Expr::RemoteRef { .. } => true,
         Expr::Closure { .. } => true,
 
         Expr::Tuple { exprs } => exprs
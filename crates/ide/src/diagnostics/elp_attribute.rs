@@ -0,0 +1,243 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Per-module tooling directives, given as `-elp(Directive)` or
+//! `-elp([Directive, ...])` attributes. The only directive supported for
+//! now is `{diagnostics, disable, [Code, ...]}`, where each code is the
+//! code or label of a diagnostic, or the `nowarn_` category of a compiler
+//! warning, as an atom or a string, as in
+//! `-elp({diagnostics, disable, ['W0010', unused_macro, unused_function]})`.
+//!
+//! The same directive is accepted in an `-eqwalizer(...)` attribute.
+//! Other `-eqwalizer` pragmas, such as `{nowarn_function, F/A}`, belong to
+//! eqWAlizer and are left alone.
+//!
+//! This is a structured alternative to `% elp:ignore` comments, applying
+//! to the whole module. It is independent of `-compile(nowarn_...)`
+//! options, which the compiler applies to its own warnings, so both can
+//! be used together. Malformed directives are reported.
+//!
+
+use std::str::FromStr;
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use fxhash::FxHashSet;
+use hir::Body;
+use hir::InFile;
+use hir::Literal;
+use hir::Name;
+use hir::Semantic;
+use hir::Term;
+use hir::TermId;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::category_warnings;
+use crate::diagnostics::DiagnosticCode;
+
+const ELP_ATTRIBUTE: &str = "elp";
+const EQWALIZER_ATTRIBUTE: &str = "eqwalizer";
+
+#[derive(Debug, Default)]
+pub(crate) struct ModuleDirectives {
+    /// The diagnostics disabled for the whole module
+    pub(crate) disabled: FxHashSet<DiagnosticCode>,
+    /// A diagnostic for each malformed directive
+    pub(crate) errors: Vec<Diagnostic>,
+}
+
+pub(crate) fn module_directives(sema: &Semantic, file_id: FileId) -> ModuleDirectives {
+    let mut res = ModuleDirectives::default();
+    let form_list = sema.db.file_form_list(file_id);
+    for (idx, attr) in form_list.attributes() {
+        let is_eqwalizer = attr.name == EQWALIZER_ATTRIBUTE;
+        if attr.name != ELP_ATTRIBUTE && !is_eqwalizer {
+            continue;
+        }
+        let attr_body = sema.db.attribute_body(InFile::new(file_id, idx));
+        let range = attr.form_id.get_ast(sema.db, file_id).syntax().text_range();
+        let mut ctx = Ctx {
+            sema,
+            body: &attr_body.body,
+            range,
+            is_eqwalizer,
+            res: &mut res,
+        };
+        match &attr_body.body[attr_body.value] {
+            Term::List { exprs, tail: None } => {
+                exprs.iter().for_each(|directive| ctx.directive(*directive))
+            }
+            _ => ctx.directive(attr_body.value),
+        }
+    }
+    res
+}
+
+struct Ctx<'a> {
+    sema: &'a Semantic<'a>,
+    body: &'a Body,
+    range: TextRange,
+    /// Only our own directive is looked at in `-eqwalizer` attributes
+    is_eqwalizer: bool,
+    res: &'a mut ModuleDirectives,
+}
+
+impl Ctx<'_> {
+    fn directive(&mut self, term_id: TermId) {
+        let (tool, action, codes) = match &self.body[term_id] {
+            Term::Tuple { exprs } if exprs.len() == 3 => (exprs[0], exprs[1], exprs[2]),
+            _ if self.is_eqwalizer => return,
+            _ => {
+                return self.error(
+                    "Malformed -elp attribute, expected {diagnostics, disable, [Code, ...]}"
+                        .to_string(),
+                )
+            }
+        };
+        match (self.atom(tool), self.atom(action)) {
+            (Some(tool), Some(action)) if tool == "diagnostics" && action == "disable" => {}
+            _ if self.is_eqwalizer => return,
+            (Some(tool), Some(action)) => {
+                return self.error(format!("Unknown -elp directive {{{tool}, {action}, ...}}"));
+            }
+            _ => {
+                return self.error(
+                    "Malformed -elp attribute, expected {diagnostics, disable, [Code, ...]}"
+                        .to_string(),
+                )
+            }
+        }
+        let codes = match &self.body[codes] {
+            Term::List { exprs, tail: None } => exprs.clone(),
+            _ => vec![codes],
+        };
+        for code in codes {
+            let name = match &self.body[code] {
+                Term::Literal(Literal::Atom(atom)) => {
+                    self.sema.db.lookup_atom(*atom).as_str().to_string()
+                }
+                Term::Literal(Literal::String(string)) => string.clone(),
+                _ => {
+                    self.error("Malformed -elp attribute, expected a diagnostic code".to_string());
+                    continue;
+                }
+            };
+            match DiagnosticCode::from_str(&name) {
+                Ok(code) => {
+                    self.res.disabled.insert(code);
+                }
+                Err(_) => {
+                    let warnings = category_warnings(&name);
+                    if warnings.is_empty() {
+                        self.error(format!("Unknown diagnostic '{name}' in -elp attribute"));
+                    }
+                    self.res.disabled.extend(warnings);
+                }
+            }
+        }
+    }
+
+    fn atom(&self, term_id: TermId) -> Option<Name> {
+        match &self.body[term_id] {
+            Term::Literal(Literal::Atom(atom)) => Some(self.sema.db.lookup_atom(*atom)),
+            _ => None,
+        }
+    }
+
+    fn error(&mut self, message: String) {
+        self.res.errors.push(
+            Diagnostic::new(DiagnosticCode::InvalidElpAttribute, message, self.range)
+                .severity(Severity::Warning),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use elp_ide_db::elp_base_db::fixture::WithFixture;
+    use elp_ide_db::RootDatabase;
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn disable_diagnostics() {
+        check_diagnostics(
+            r#"
+-module(main).
+-elp({diagnostics, disable, [unused_macro]}).
+-elp([{diagnostics, disable, ["W0007"]}]).
+-define(UNUSED, 1).
+foo() ->
+    42 = 42,
+    ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn malformed_directives() {
+        check_diagnostics(
+            r#"
+    -module(main).
+    -elp(disable).
+%%  ^^^^^^^^^^^^^^ warning: Malformed -elp attribute, expected {diagnostics, disable, [Code, ...]}
+    -elp({diagnostics, enable, [unused_macro]}).
+%%  ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ warning: Unknown -elp directive {diagnostics, enable, ...}
+    -elp({diagnostics, disable, [unused_thing, {x}]}).
+%%  ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ warning: Unknown diagnostic 'unused_thing' in -elp attribute
+%%  ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ warning: Malformed -elp attribute, expected a diagnostic code
+    -compile(nowarn_unused_function).
+"#,
+        );
+    }
+
+    #[test]
+    fn compiler_warning_names() {
+        let (db, file_id) = RootDatabase::with_single_file(
+            r#"
+-module(main).
+-elp({diagnostics, disable, [unused_function, "unused_vars"]}).
+"#,
+        );
+        let directives = module_directives(&Semantic::new(&db), file_id);
+        assert!(directives.errors.is_empty());
+        expect![[r#"
+            [
+                "L1230",
+                "L1268",
+            ]
+        "#]]
+        .assert_debug_eq(&sorted_codes(&directives.disabled));
+    }
+
+    #[test]
+    fn eqwalizer_attribute() {
+        check_diagnostics(
+            r#"
+-module(main).
+-eqwalizer({nowarn_function, foo/0}).
+-eqwalizer(ignore).
+-eqwalizer({diagnostics, disable, [unused_macro]}).
+-define(UNUSED, 1).
+foo() -> ok.
+"#,
+        );
+    }
+
+    fn sorted_codes(codes: &FxHashSet<DiagnosticCode>) -> Vec<String> {
+        let mut codes: Vec<String> = codes.iter().map(|code| code.as_code()).collect();
+        codes.sort();
+        codes
+    }
+}
@@ -0,0 +1,137 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: function_redefinition
+//!
+//! Return a diagnostic if a function is defined by more than one form of
+//! the module or of its included headers, which is an error, as opposed to
+//! a single definition with several clauses. Each redefinition is related
+//! to the first definition, when that one is in the same file.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use hir::FunctionDef;
+use hir::Semantic;
+
+use super::Diagnostic;
+use super::RelatedInformation;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+pub(crate) fn function_redefinition(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let def_map = sema.def_map(file_id);
+    let name_range = |def: &FunctionDef| {
+        let source = def.source(sema.db.upcast());
+        source.clauses().find_map(|clause| match clause {
+            ast::FunctionOrMacroClause::FunctionClause(clause) => {
+                clause.name().map(|name| name.syntax().text_range())
+            }
+            ast::FunctionOrMacroClause::MacroCallExpr(_) => None,
+        })
+    };
+
+    // The first definition may be in an included header, in which case
+    // there is no related location in this file
+    let mut redefinitions: Vec<(TextRange, Option<TextRange>, String)> = Vec::new();
+    for (name, defs) in def_map.get_function_redefinitions() {
+        let first = defs
+            .first()
+            .filter(|def| def.file.file_id == file_id)
+            .and_then(name_range);
+        redefinitions.extend(
+            defs.iter()
+                .skip(1)
+                .filter(|def| def.file.file_id == file_id)
+                .filter_map(name_range)
+                .map(|range| (range, first, name.to_string())),
+        );
+    }
+    redefinitions.sort_by_key(|(range, _, _)| range.start());
+
+    for (range, first, name) in redefinitions {
+        diags.push(
+            Diagnostic::new(
+                DiagnosticCode::FunctionRedefinition,
+                format!("Function {name} is already defined"),
+                range,
+            )
+            .severity(Severity::Error)
+            // Marking as EXPERIMENTAL since it duplicates the compiler error
+            .experimental()
+            .with_related(first.map(|first| {
+                vec![RelatedInformation {
+                    range: first,
+                    message: "Previous definition".to_string(),
+                }]
+            })),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics_with_config;
+    use crate::DiagnosticCode;
+    use crate::DiagnosticsConfig;
+
+    fn config() -> DiagnosticsConfig<'static> {
+        DiagnosticsConfig::default().disable(DiagnosticCode::MissingCompileWarnMissingSpec)
+    }
+
+    #[test]
+    fn redefined_function() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([foo/1, bar/0]).
+foo(1) -> one;
+foo(2) -> two.
+bar() -> ok.
+  foo(_) -> other.
+%%^^^ error: Function foo/1 is already defined
+"#,
+        );
+    }
+
+    #[test]
+    fn clauses_are_not_redefinitions() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([foo/1, foo/2]).
+foo(1) -> one;
+foo(_) -> other.
+foo(A, B) -> {A, B}.
+"#,
+        );
+    }
+
+    #[test]
+    fn redefined_header_function() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /src/main.erl
+-module(main).
+-include("main.hrl").
+-export([foo/1]).
+  foo(_) -> other.
+%%^^^ error: Function foo/1 is already defined
+//- /src/main.hrl
+foo(1) -> one.
+"#,
+        );
+    }
+}
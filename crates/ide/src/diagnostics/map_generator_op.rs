@@ -0,0 +1,104 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: map-generator-op
+//
+// Return a warning if a map generator uses `=>`: like map patterns, the key
+// and value of a generator `K := V <- Map` must be separated by `:=`.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_syntax::ast;
+use elp_syntax::ast::AstNode;
+use elp_syntax::ast::MapOp;
+use elp_syntax::syntax_node::SyntaxNode;
+use text_edit::TextEdit;
+
+use super::DiagnosticCode;
+use crate::fix;
+use crate::Diagnostic;
+
+pub(crate) fn map_generator_op(
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let map_gen = ast::MapGenerator::cast(node.clone())?;
+    let (op, token) = map_gen.lhs()?.op()?;
+    if op != MapOp::Assoc {
+        return None;
+    }
+    let range = token.text_range();
+    let mut edit_builder = TextEdit::builder();
+    edit_builder.replace(range, ":=".to_string());
+    let edit = edit_builder.finish();
+    acc.push(
+        Diagnostic::warning(
+            DiagnosticCode::MapGeneratorOp,
+            range,
+            "Map generators must use `:=`, not `=>`".to_string(),
+        )
+        .with_fixes(Some(vec![fix(
+            "use_exact_map_generator",
+            "Replace `=>` with `:=`",
+            SourceChange::from_text_edit(file_id, edit),
+            range,
+        )])),
+    );
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+    use crate::tests::check_fix;
+
+    #[test]
+    fn map_generator_assoc() {
+        check_diagnostics(
+            r#"
+-module(main).
+
+foo(Map) ->
+    #{K => V + 1 || K => V <- Map}.
+    %%                ^^ 💡 warning: Map generators must use `:=`, not `=>`
+"#,
+        );
+    }
+
+    #[test]
+    fn map_generator_exact() {
+        check_diagnostics(
+            r#"
+-module(main).
+
+foo(Map) ->
+    #{K => V + 1 || K := V <- Map}.
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_map_generator_assoc() {
+        check_fix(
+            r#"
+-module(main).
+
+foo(Map) ->
+    #{K => V || K =~> V <- Map}.
+"#,
+            r#"
+-module(main).
+
+foo(Map) ->
+    #{K => V || K := V <- Map}.
+"#,
+        );
+    }
+}
@@ -0,0 +1,223 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: maybe_else_not_exhaustive
+//!
+//! Return a diagnostic for a conditional match `Pat ?= Expr` in a `maybe`
+//! block when the block has `else` clauses, but none of them can handle the
+//! value the match plausibly fails with. This is best-effort, based on the
+//! leading atom of the pattern: `{ok, _}` and `ok` usually fail with an
+//! `error` result, `true` with `false`, and vice versa.
+//!
+//! A `maybe` without `else` returns the non-matching value, which is legal.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use hir::Expr;
+use hir::FunctionDef;
+use hir::InFunctionBody;
+use hir::Literal;
+use hir::MaybeExpr;
+use hir::Pat;
+use hir::PatId;
+use hir::Semantic;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+pub(crate) fn maybe_else_not_exhaustive(
+    diags: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+) {
+    sema.def_map(file_id)
+        .get_functions()
+        .iter()
+        .for_each(|(_arity, def)| {
+            if def.file.file_id == file_id {
+                check_function(diags, sema, def)
+            }
+        });
+}
+
+/// What a pattern matches, as far as this check is concerned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tag {
+    /// Matches any value, or any tagged tuple
+    Any,
+    /// An atom, or a tuple with this atom as first element
+    Atom(String),
+    Unknown,
+}
+
+fn check_function(diags: &mut Vec<Diagnostic>, sema: &Semantic, def: &FunctionDef) {
+    let def_fb = def.in_function_body(sema.db, def);
+    let body_map = def_fb.get_body_map(sema.db);
+    let source_file = sema.parse(def.file.file_id);
+
+    def_fb.fold_function(
+        (),
+        &mut |(), _, ctx| {
+            let (exprs, else_clauses) = match &ctx.expr {
+                Expr::Maybe {
+                    exprs,
+                    else_clauses,
+                } if !else_clauses.is_empty() => (exprs, else_clauses),
+                _ => return,
+            };
+            let handled: Vec<Tag> = else_clauses
+                .iter()
+                .map(|clause| pat_tag(sema, &def_fb, clause.pat))
+                .collect();
+            if handled.contains(&Tag::Any) {
+                return;
+            }
+            for expr in exprs {
+                if let MaybeExpr::Cond { lhs, rhs } = expr {
+                    let failure = match pat_tag(sema, &def_fb, *lhs) {
+                        Tag::Atom(tag) => plausible_failure(&tag),
+                        _ => None,
+                    };
+                    let failure = match failure {
+                        Some(failure) => failure,
+                        None => continue,
+                    };
+                    if handled.contains(&Tag::Atom(failure.to_string())) {
+                        continue;
+                    }
+                    let range = |ptr: Option<hir::ExprSource>| {
+                        ptr.and_then(|ptr| ptr.to_node(&source_file))
+                            .map(|node| node.syntax().text_range())
+                    };
+                    if let (Some(lhs_range), Some(rhs_range)) =
+                        (range(body_map.pat(*lhs)), range(body_map.expr(*rhs)))
+                    {
+                        diags.push(make_diagnostic(lhs_range.cover(rhs_range), failure));
+                    }
+                }
+            }
+        },
+        &mut |(), _, _| (),
+    );
+}
+
+fn plausible_failure(tag: &str) -> Option<&'static str> {
+    match tag {
+        "ok" => Some("error"),
+        "true" => Some("false"),
+        "false" => Some("true"),
+        _ => None,
+    }
+}
+
+fn pat_tag(sema: &Semantic, def_fb: &InFunctionBody<&FunctionDef>, pat_id: PatId) -> Tag {
+    match &def_fb[pat_id] {
+        Pat::Var(_) => Tag::Any,
+        Pat::Literal(Literal::Atom(atom)) => Tag::Atom(atom.as_string(sema.db.upcast())),
+        Pat::Tuple { pats } => match pats.first() {
+            Some(first) => match pat_tag(sema, def_fb, *first) {
+                Tag::Unknown => Tag::Unknown,
+                tag => tag,
+            },
+            None => Tag::Unknown,
+        },
+        Pat::Match { lhs, rhs } => match pat_tag(sema, def_fb, *lhs) {
+            Tag::Any => pat_tag(sema, def_fb, *rhs),
+            tag => tag,
+        },
+        Pat::MacroCall { expansion, args: _ } => pat_tag(sema, def_fb, *expansion),
+        _ => Tag::Unknown,
+    }
+}
+
+fn make_diagnostic(range: TextRange, failure: &str) -> Diagnostic {
+    Diagnostic::new(
+        DiagnosticCode::MaybeElseNotExhaustive,
+        format!("No `else` clause handles this `?=` failing with `{failure}`"),
+        range,
+    )
+    .severity(Severity::WeakWarning)
+    .experimental()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn else_does_not_handle_failure() {
+        check_diagnostics(
+            r#"
+            -module(main).
+
+            foo() ->
+                maybe
+                    {ok, A} ?= bar(),
+%%                  ^^^^^^^^^^^^^^^^ weak: No `else` clause handles this `?=` failing with `error`
+                    true ?= is_integer(A),
+                    A
+                else
+                    false -> not_an_integer
+                end.
+
+            bar() -> {ok, 1}.
+            "#,
+        );
+    }
+
+    #[test]
+    fn no_else_propagates() {
+        check_diagnostics(
+            r#"
+            -module(main).
+
+            foo() ->
+                maybe
+                    {ok, A} ?= bar(),
+                    A
+                end.
+
+            bar() -> {ok, 1}.
+            "#,
+        );
+    }
+
+    #[test]
+    fn else_handles_failure() {
+        check_diagnostics(
+            r#"
+            -module(main).
+
+            foo() ->
+                maybe
+                    {ok, A} ?= bar(),
+                    ok ?= baz(A),
+                    A
+                else
+                    {error, _} -> error;
+                    error -> error
+                end.
+
+            bar() ->
+                maybe
+                    {ok, A} ?= baz(1),
+                    A
+                else
+                    Other -> Other
+                end.
+
+            baz(_) -> {ok, 1}.
+            "#,
+        );
+    }
+}
@@ -0,0 +1,266 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: missing_export
+//!
+//! Return a diagnostic for a function defined after the last `-export`
+//! attribute of a module which is not exported, if its name does not look
+//! private and the module exports most of its functions (the ratio of
+//! exported functions is at least the configured threshold). This is a
+//! heuristic: test modules are never reported, and neither are modules
+//! with `export_all`, since all their functions are exported.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::helpers::add_export;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use hir::FunctionDef;
+use hir::Semantic;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+use crate::fix;
+
+pub(crate) fn missing_export(
+    diags: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    threshold: f64,
+) {
+    if is_test_module(sema, file_id) {
+        return;
+    }
+    let def_map = sema.def_map(file_id);
+    let functions: Vec<&FunctionDef> = def_map
+        .get_functions()
+        .values()
+        .filter(|def| def.file.file_id == file_id)
+        .collect();
+    if functions.is_empty() {
+        return;
+    }
+    let exported = functions.iter().filter(|def| def.exported).count();
+    if (exported as f64) / (functions.len() as f64) < threshold {
+        return;
+    }
+
+    let source_file = sema.parse(file_id);
+    let last_export = match sema
+        .db
+        .file_form_list(file_id)
+        .exports()
+        .map(|(_, export)| {
+            export
+                .form_id
+                .get(&source_file.value)
+                .syntax()
+                .text_range()
+                .end()
+        })
+        .max()
+    {
+        Some(end) => end,
+        None => return,
+    };
+
+    let mut unexported: Vec<_> = functions
+        .into_iter()
+        .filter(|def| !def.exported && !looks_private(def.function.name.name().as_str()))
+        .filter_map(|def| {
+            let source = def.function.form_id.get(&source_file.value);
+            if source.syntax().text_range().start() < last_export {
+                return None;
+            }
+            let range = source.clauses().find_map(|clause| match clause {
+                ast::FunctionOrMacroClause::FunctionClause(clause) => {
+                    clause.name().map(|name| name.syntax().text_range())
+                }
+                ast::FunctionOrMacroClause::MacroCallExpr(_) => None,
+            })?;
+            Some((range, def))
+        })
+        .collect();
+    unexported.sort_by_key(|(range, _)| range.start());
+
+    for (range, def) in unexported {
+        let name = &def.function.name;
+        let fixes = add_export(sema, def).map(|change| {
+            vec![fix(
+                "export_function",
+                &format!("Export the function `{name}`"),
+                change,
+                range,
+            )]
+        });
+        diags.push(
+            Diagnostic::new(
+                DiagnosticCode::MissingExport,
+                format!("Function {name} is not exported, but most functions in this module are"),
+                range,
+            )
+            .severity(Severity::WeakWarning)
+            .with_fixes(fixes),
+        );
+    }
+}
+
+pub(super) fn is_test_module(sema: &Semantic, file_id: FileId) -> bool {
+    if sema.db.is_test_suite_or_test_helper(file_id) == Some(true) {
+        return true;
+    }
+    match sema.db.file_form_list(file_id).module_attribute() {
+        Some(attr) => {
+            let name = attr.name.as_str();
+            name.ends_with("_SUITE") || name.ends_with("_tests")
+        }
+        None => false,
+    }
+}
+
+/// Naming conventions for helpers which are not meant to be exported.
+fn looks_private(name: &str) -> bool {
+    name.starts_with('_')
+        || name.starts_with("do_")
+        || name.ends_with("_internal")
+        || name.ends_with("_impl")
+        || name.ends_with("_loop")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+    use crate::DiagnosticCode;
+    use crate::DiagnosticsConfig;
+
+    fn config(threshold: f64) -> DiagnosticsConfig<'static> {
+        let mut config =
+            DiagnosticsConfig::default().disable(DiagnosticCode::MissingCompileWarnMissingSpec);
+        config.missing_export_threshold = Some(threshold);
+        config
+    }
+
+    #[test]
+    fn unexported_after_last_export() {
+        check_diagnostics_with_config(
+            config(0.4),
+            r#"
+-module(main).
+-export([foo/0, bar/0]).
+helper() -> ok.
+-export([baz/1]).
+foo() -> ok.
+bar() -> ok.
+baz(X) -> X.
+  qux() -> ok.
+%%^^^ 💡 weak: Function qux/0 is not exported, but most functions in this module are
+do_qux() -> ok.
+qux_internal() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn below_threshold() {
+        check_diagnostics_with_config(
+            config(0.8),
+            r#"
+-module(main).
+-export([foo/0, bar/0]).
+foo() -> ok.
+bar() -> ok.
+baz() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn test_module_exempt() {
+        check_diagnostics_with_config(
+            config(0.5),
+            r#"
+//- /test/main_tests.erl
+-module(main_tests).
+-export([foo/0]).
+foo() -> ok.
+bar() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn export_all_exempt() {
+        check_diagnostics_with_config(
+            config(0.5),
+            r#"
+-module(main).
+-compile(export_all).
+-export([foo/0]).
+foo() -> ok.
+bar() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_adds_to_export_with_same_name() {
+        check_fix_with_config(
+            config(0.5),
+            r#"
+-module(main).
+-export([foo/0]).
+-export([bar/0, bar/1]).
+foo() -> ok.
+bar() -> ok.
+bar(X) -> X.
+b~ar(X, Y) -> {X, Y}.
+"#,
+            r#"
+-module(main).
+-export([foo/0]).
+-export([bar/0, bar/1, bar/2]).
+foo() -> ok.
+bar() -> ok.
+bar(X) -> X.
+bar(X, Y) -> {X, Y}.
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_keeps_multiline_layout() {
+        check_fix_with_config(
+            config(0.5),
+            r#"
+-module(main).
+-export([
+    foo/0,
+    bar/0
+]).
+foo() -> ok.
+bar() -> ok.
+b~az() -> ok.
+"#,
+            r#"
+-module(main).
+-export([
+    foo/0,
+    bar/0,
+    baz/0
+]).
+foo() -> ok.
+bar() -> ok.
+baz() -> ok.
+"#,
+        );
+    }
+}
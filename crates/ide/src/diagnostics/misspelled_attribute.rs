@@ -179,6 +179,15 @@ mod tests {
         );
         let mut config = DiagnosticsConfig {
             disable_experimental: true,
+            enable_strict_comparison: false,
+            missing_export_threshold: None,
+            enable_unused_export: false,
+            enable_swallowed_exception: false,
+            enable_bare_catch: false,
+            enable_undefined_exported_type: false,
+            enable_unresolved_import: false,
+            enable_warnings_as_errors: false,
+            max_function_arguments: None,
             disabled: FxHashSet::default(),
             adhoc_semantic_diagnostics: vec![],
         };
@@ -207,6 +216,15 @@ mod tests {
         );
         let mut config = DiagnosticsConfig {
             disable_experimental: true,
+            enable_strict_comparison: false,
+            missing_export_threshold: None,
+            enable_unused_export: false,
+            enable_swallowed_exception: false,
+            enable_bare_catch: false,
+            enable_undefined_exported_type: false,
+            enable_unresolved_import: false,
+            enable_warnings_as_errors: false,
+            max_function_arguments: None,
             disabled: FxHashSet::default(),
             adhoc_semantic_diagnostics: vec![],
         };
@@ -13,13 +13,14 @@
 
 use elp_ide_assists::Assist;
 use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::ModuleName;
 use elp_ide_db::elp_base_db::SourceDatabase;
 use elp_ide_db::source_change::SourceChange;
-use elp_ide_db::RootDatabase;
-use elp_syntax::ast;
 use elp_syntax::AstNode;
-use elp_syntax::SyntaxNode;
 use elp_syntax::TextRange;
+use hir::File;
+use hir::Module;
+use hir::Semantic;
 use text_edit::TextEdit;
 
 use crate::fix;
@@ -27,15 +28,18 @@ use crate::Diagnostic;
 
 pub(crate) fn module_mismatch(
     acc: &mut Vec<Diagnostic>,
-    db: &RootDatabase,
+    sema: &Semantic,
     file_id: FileId,
-    node: &SyntaxNode,
 ) -> Option<()> {
-    let module_name = ast::ModuleAttribute::cast(node.clone())?.name()?;
-    let root_id = db.file_source_root(file_id);
-    let root = db.source_root(root_id);
-    let path = root.path_for_file(&file_id).unwrap();
-    let filename = path.name_and_extension().unwrap_or_default().0;
+    let module = Module {
+        file: File { file_id },
+    };
+    let attr = module.module_attribute(sema.db)?;
+    let source = module.file.source(sema.db.upcast());
+    let module_name = attr.form_id.get(&source).name()?;
+    let root = sema.db.source_root(sema.db.file_source_root(file_id));
+    let path = root.path_for_file(&file_id)?;
+    let filename = path.name_and_extension()?.0;
     let loc = module_name.syntax().text_range();
     if module_name.text()? != filename {
         let d = Diagnostic::new(
@@ -53,7 +57,7 @@ pub(crate) fn module_mismatch(
 
 fn rename_module_to_match_filename(file_id: FileId, loc: TextRange, filename: &str) -> Assist {
     let mut builder = TextEdit::builder();
-    builder.replace(loc, filename.to_string());
+    builder.replace(loc, ModuleName::new(filename).to_quoted_string());
     let edit = builder.finish();
     fix(
         "rename_module_to_match_filename",
@@ -89,6 +93,35 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_module_mismatch_quoted() {
+        check_fix(
+            r#"
+//- /src/my-mod.erl
+-module(b~ar).
+"#,
+            r#"
+-module('my-mod').
+"#,
+        );
+        check_diagnostics(
+            r#"
+//- /src/my-mod.erl
+-module('my-mod').
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_module_mismatch_header() {
+        check_diagnostics(
+            r#"
+//- /include/foo.hrl
+-module(bar).
+            "#,
+        );
+    }
+
     #[test]
     fn test_module_mismatch_correct() {
         check_diagnostics(
@@ -0,0 +1,190 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: no_return_spec
+//!
+//! Return a diagnostic for a function which never returns, as every clause
+//! ends by raising an exception, but whose spec does not say so with
+//! `no_return()`. Functions without a spec are reported too, with a fix
+//! adding one. Specs in headers are left alone.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use hir::FunctionDef;
+use hir::Semantic;
+use text_edit::TextEdit;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+use crate::fix;
+
+pub(crate) fn no_return_spec(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let no_return = sema.db.no_return_functions(file_id);
+    if no_return.is_empty() {
+        return;
+    }
+    let def_map = sema.def_map(file_id);
+    let source_file = sema.parse(file_id);
+    for name in no_return.iter() {
+        let def = match def_map.get_function(name) {
+            Some(def) if def.file.file_id == file_id => def,
+            _ => continue,
+        };
+        let source = def.function.form_id.get(&source_file.value);
+        let range = match source.clauses().find_map(|clause| match clause {
+            ast::FunctionOrMacroClause::FunctionClause(clause) => {
+                clause.name().map(|name| name.syntax().text_range())
+            }
+            ast::FunctionOrMacroClause::MacroCallExpr(_) => None,
+        }) {
+            Some(range) => range,
+            None => continue,
+        };
+
+        let mut edit_builder = TextEdit::builder();
+        let label = match def_map.get_spec(name) {
+            Some(spec) if spec.file.file_id == file_id => {
+                let spec = spec.spec.form_id.get(&source_file.value);
+                let results: Vec<ast::Expr> = spec
+                    .sigs()
+                    .filter_map(|sig| sig.ty())
+                    .filter(|ty| !is_no_return(ty))
+                    .collect();
+                if results.is_empty() {
+                    continue;
+                }
+                for result in results {
+                    edit_builder.replace(result.syntax().text_range(), "no_return()".to_string());
+                }
+                "Change the spec to return `no_return()`"
+            }
+            Some(_) => continue,
+            None => {
+                edit_builder.insert(source.syntax().text_range().start(), spec_text(def));
+                "Add a spec returning `no_return()`"
+            }
+        };
+        diags.push(make_diagnostic(
+            file_id,
+            def,
+            range,
+            label,
+            edit_builder.finish(),
+        ));
+    }
+}
+
+fn make_diagnostic(
+    file_id: FileId,
+    def: &FunctionDef,
+    range: TextRange,
+    label: &str,
+    edit: TextEdit,
+) -> Diagnostic {
+    Diagnostic::new(
+        DiagnosticCode::NoReturnSpec,
+        format!(
+            "Function {} never returns, its spec could use `no_return()`",
+            def.function.name
+        ),
+        range,
+    )
+    .severity(Severity::WeakWarning)
+    // Marking as EXPERIMENTAL since stubs raising `not_implemented` and the like are reported too
+    .experimental()
+    .with_fixes(Some(vec![fix(
+        "use_no_return_spec",
+        label,
+        SourceChange::from_text_edit(file_id, edit),
+        range,
+    )]))
+}
+
+/// Whether a spec result already says the function does not return.
+/// `none()` is equivalent to `no_return()`.
+fn is_no_return(ty: &ast::Expr) -> bool {
+    let text: String = ty.syntax().text().to_string().split_whitespace().collect();
+    text == "no_return()" || text == "none()"
+}
+
+fn spec_text(def: &FunctionDef) -> String {
+    let name = &def.function.name;
+    let args = vec!["term()"; name.arity() as usize].join(", ");
+    format!(
+        "-spec {}({}) -> no_return().\n",
+        name.name().to_quoted_string(),
+        args
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics;
+    use crate::tests::check_fix;
+
+    #[test]
+    fn no_return_functions() {
+        check_diagnostics(
+            r#"
+-module(main).
+-spec fail(term()) -> ok.
+  fail(Reason) -> error(Reason).
+%%^^^^ 💡 weak: Function fail/1 never returns, its spec could use `no_return()`
+-spec stop() -> no_return().
+stop() -> exit(normal).
+-spec halt() -> none().
+halt() -> throw(halt).
+  bail(X) -> fail(X).
+%%^^^^ 💡 weak: Function bail/1 never returns, its spec could use `no_return()`
+-spec check(term()) -> ok.
+check(ok) -> ok;
+check(Error) -> error(Error).
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_spec() {
+        check_fix(
+            r#"
+-module(main).
+-spec fail(atom()) -> ok;
+          (integer()) -> no_return().
+f~ail(Reason) -> error(Reason).
+"#,
+            r#"
+-module(main).
+-spec fail(atom()) -> no_return();
+          (integer()) -> no_return().
+fail(Reason) -> error(Reason).
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_add_spec() {
+        check_fix(
+            r#"
+-module(main).
+f~ail(Reason, Args) -> erlang:error(Reason, Args).
+"#,
+            r#"
+-module(main).
+-spec fail(term(), term()) -> no_return().
+fail(Reason, Args) -> erlang:error(Reason, Args).
+"#,
+        );
+    }
+}
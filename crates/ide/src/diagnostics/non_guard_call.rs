@@ -0,0 +1,191 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: non_guard_call
+//!
+//! Return a diagnostic for a call in a guard to a function which is not a
+//! guard BIF, which the compiler rejects. Guards are checked after macro
+//! expansion, and a call coming from a macro is reported on the macro
+//! call.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast::is_guard_bif;
+use elp_syntax::AstNode;
+use hir::CallTarget;
+use hir::Expr;
+use hir::ExprId;
+use hir::FunctionDef;
+use hir::InFunctionBody;
+use hir::Semantic;
+use hir::Strategy;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+pub(crate) fn non_guard_call(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    sema.def_map(file_id)
+        .get_functions()
+        .iter()
+        .for_each(|(_arity, def)| {
+            if def.file.file_id == file_id {
+                process_guards(diags, sema, def)
+            }
+        });
+}
+
+fn process_guards(diags: &mut Vec<Diagnostic>, sema: &Semantic, def: &FunctionDef) {
+    let def_fb = def.in_function_body(sema.db, def);
+    let body_map = def_fb.get_body_map(sema.db);
+    let source_file = sema.parse(def.file.file_id);
+    for guard in guard_exprs(&def_fb) {
+        def_fb.fold_expr(
+            Strategy::TopDown,
+            guard,
+            (),
+            &mut |_acc, ctx| {
+                if let Expr::Call { target, args } = &ctx.expr {
+                    let arity = args.len() as u32;
+                    if is_guard_call(sema, &def_fb, target, arity) {
+                        return;
+                    }
+                    let label = match target.label(arity, sema, &def_fb.body()) {
+                        Some(label) => label,
+                        None => return,
+                    };
+                    let range = body_map
+                        .expr(ctx.in_macro.unwrap_or(ctx.expr_id))
+                        .and_then(|ptr| ptr.to_node(&source_file))
+                        .map(|expr| expr.syntax().text_range());
+                    if let Some(range) = range {
+                        diags.push(
+                            Diagnostic::new(
+                                DiagnosticCode::NonGuardCall,
+                                format!("Call to {label} is not allowed in a guard"),
+                                range,
+                            )
+                            .severity(Severity::Warning)
+                            // Marking as EXPERIMENTAL since it duplicates the compiler error
+                            .experimental(),
+                        );
+                    }
+                }
+            },
+            &mut |acc, _| acc,
+        );
+    }
+}
+
+/// The guard expressions of the clauses of the function, and of the
+/// `case`, `receive`, `try`, `if`, `maybe` and `fun` expressions in it
+fn guard_exprs(def_fb: &InFunctionBody<&FunctionDef>) -> Vec<ExprId> {
+    fn flatten(guards: &[Vec<ExprId>]) -> impl Iterator<Item = ExprId> + '_ {
+        guards.iter().flatten().copied()
+    }
+    let clauses = def_fb
+        .clauses()
+        .flat_map(|(_, clause)| flatten(&clause.guards))
+        .collect();
+    def_fb.fold_function(
+        clauses,
+        &mut |mut acc, _, ctx| {
+            match &ctx.expr {
+                Expr::If { clauses } => {
+                    acc.extend(clauses.iter().flat_map(|clause| flatten(&clause.guards)))
+                }
+                Expr::Case { clauses, .. } | Expr::Receive { clauses, .. } => {
+                    acc.extend(clauses.iter().flat_map(|clause| flatten(&clause.guards)))
+                }
+                Expr::Try {
+                    of_clauses,
+                    catch_clauses,
+                    ..
+                } => {
+                    acc.extend(of_clauses.iter().flat_map(|clause| flatten(&clause.guards)));
+                    acc.extend(
+                        catch_clauses
+                            .iter()
+                            .flat_map(|clause| flatten(&clause.guards)),
+                    );
+                }
+                Expr::Maybe { else_clauses, .. } => acc.extend(
+                    else_clauses
+                        .iter()
+                        .flat_map(|clause| flatten(&clause.guards)),
+                ),
+                Expr::Closure { clauses, .. } => {
+                    acc.extend(clauses.iter().flat_map(|clause| flatten(&clause.guards)))
+                }
+                _ => {}
+            }
+            acc
+        },
+        &mut |acc, _, _| acc,
+    )
+}
+
+/// Guard BIFs, called locally or as `erlang:F(...)`
+fn is_guard_call(
+    sema: &Semantic,
+    def_fb: &InFunctionBody<&FunctionDef>,
+    target: &CallTarget<ExprId>,
+    arity: u32,
+) -> bool {
+    let name = |expr_id: &ExprId| def_fb.as_atom_name(sema.db, expr_id);
+    let is_bif = |expr_id: &ExprId| {
+        name(expr_id).map_or(false, |name| is_guard_bif(name.as_str(), arity as usize))
+    };
+    match target {
+        CallTarget::Local { name } => is_bif(name),
+        CallTarget::Remote { module, name: fun } => {
+            name(module).map_or(false, |module| module.as_str() == "erlang") && is_bif(fun)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn guard_macros() {
+        check_diagnostics(
+            r#"
+-module(main).
+-define(is_even(X), X rem 2 =:= 0).
+-define(is_small(X), is_small(X)).
+
+foo(N) when ?is_even(N) -> even;
+foo(N) when ?is_small(N) -> small;
+%%          ^^^^^^^^^^^^ warning: Call to is_small/1 is not allowed in a guard
+foo(N) when is_integer(N), abs(N) > erlang:length([N]) -> other.
+
+is_small(N) -> N < 10.
+"#,
+        );
+    }
+
+    #[test]
+    fn nested_guards() {
+        check_diagnostics(
+            r#"
+-module(main).
+
+foo(X) ->
+    case X of
+        Y when lists:member(Y, [a, b]) -> ok;
+    %%         ^^^^^^^^^^^^^^^^^^^^^^^ warning: Call to lists:member/2 is not allowed in a guard
+        _ when is_atom(X) -> ok
+    end.
+"#,
+        );
+    }
+}
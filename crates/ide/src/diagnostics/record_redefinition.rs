@@ -0,0 +1,147 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: record_redefinition
+//!
+//! Return a diagnostic if a record is defined more than once for a module,
+//! in the module itself or in the headers it includes. Identical
+//! redefinitions are reported too, since they are errors as well. When the
+//! redefinition is in a header, the `-include` bringing it in is reported.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use fxhash::FxHashMap;
+use fxhash::FxHashSet;
+use hir::Name;
+use hir::Semantic;
+use hir::VisibleRecord;
+
+use super::Diagnostic;
+use super::RelatedInformation;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+pub(crate) fn record_redefinition(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let records = sema.db.visible_records(file_id);
+    let form_list = sema.db.file_form_list(file_id);
+    let source_file = sema.parse(file_id);
+    let range = |record: &VisibleRecord| match record.via {
+        Some(include) => Some(
+            form_list[include]
+                .form_id()
+                .get(&source_file.value)
+                .syntax()
+                .text_range(),
+        ),
+        None => {
+            let source = record.def.source(sema.db.upcast());
+            let name = source.name()?;
+            Some(name.syntax().text_range())
+        }
+    };
+
+    let mut first: FxHashMap<&Name, &VisibleRecord> = FxHashMap::default();
+    let mut reported: FxHashSet<(TextRange, TextRange)> = FxHashSet::default();
+    for record in records.iter() {
+        let name = &record.def.record.name;
+        match first.get(name) {
+            None => {
+                first.insert(name, record);
+            }
+            Some(previous) => {
+                if let Some((range, previous_range)) = range(record).zip(range(previous)) {
+                    if reported.insert((range, previous_range)) {
+                        diags.push(make_diagnostic(
+                            name,
+                            record.via.is_some(),
+                            range,
+                            previous_range,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn make_diagnostic(
+    name: &Name,
+    in_header: bool,
+    range: TextRange,
+    previous_range: TextRange,
+) -> Diagnostic {
+    let message = if in_header {
+        format!("Record {name} is redefined by this include")
+    } else {
+        format!("Record {name} is already defined")
+    };
+    Diagnostic::new(DiagnosticCode::RecordRedefinition, message, range)
+        .severity(Severity::Error)
+        .with_related(Some(vec![RelatedInformation {
+            range: previous_range,
+            message: "Previous definition".to_string(),
+        }]))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics_with_config;
+    use crate::DiagnosticCode;
+    use crate::DiagnosticsConfig;
+
+    fn config() -> DiagnosticsConfig<'static> {
+        DiagnosticsConfig::default()
+            .disable(DiagnosticCode::MissingCompileWarnMissingSpec)
+            .disable(DiagnosticCode::UnusedInclude)
+    }
+
+    #[test]
+    fn redefined_in_header() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /src/main.erl
+    -module(main).
+    -record(rec, {}).
+    -include("some.hrl").
+%%  ^^^^^^^^^^^^^^^^^^^^^ error: Record rec is redefined by this include
+    -record(rec, {}).
+%%          ^^^ error: Record rec is already defined
+//- /src/some.hrl
+-record(rec, {}).
+"#,
+        );
+    }
+
+    #[test]
+    fn include_guard() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /src/main.erl
+-module(main).
+-include("guarded.hrl").
+-include("guarded.hrl").
+-ifdef(DEBUG).
+-record(rec, {}).
+-else.
+-record(rec, {}).
+-endif.
+//- /src/guarded.hrl
+-ifndef(GUARDED_HRL).
+-define(GUARDED_HRL, true).
+-record(guarded, {}).
+-endif.
+"#,
+        );
+    }
+}
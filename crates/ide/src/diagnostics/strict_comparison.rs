@@ -0,0 +1,220 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint/fix: strict_comparison
+//!
+//! Return a diagnostic if `==` or `/=` is used to compare values which
+//! do not look like numbers, and offer to replace the operator with `=:=`
+//! or `=/=`. This is a best-effort check, comparisons involving only
+//! variables or calls are not reported.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_syntax::ast;
+use elp_syntax::ast::BinaryOp;
+use elp_syntax::ast::CompOp;
+use elp_syntax::SyntaxToken;
+use hir::Expr;
+use hir::ExprId;
+use hir::FunctionDef;
+use hir::InFunctionBody;
+use hir::Literal;
+use hir::Semantic;
+use text_edit::TextEdit;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+use crate::fix;
+
+pub(crate) fn strict_comparison(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    sema.def_map(file_id)
+        .get_functions()
+        .iter()
+        .for_each(|(_arity, def)| {
+            if def.file.file_id == file_id {
+                process_comparisons(diags, sema, def)
+            }
+        });
+}
+
+fn process_comparisons(diags: &mut Vec<Diagnostic>, sema: &Semantic, def: &FunctionDef) {
+    let def_fb = def.in_function_body(sema.db, def);
+    let body_map = def_fb.get_body_map(sema.db);
+    let source_file = sema.parse(def.file.file_id);
+
+    def_fb.fold_function(
+        (),
+        &mut |_acc, _, ctx| {
+            if let Expr::BinaryOp {
+                lhs,
+                rhs,
+                op:
+                    BinaryOp::CompOp(CompOp::Eq {
+                        strict: false,
+                        negated,
+                    }),
+            } = ctx.expr
+            {
+                let operands = [kind(&def_fb, lhs), kind(&def_fb, rhs)];
+                if operands.contains(&Kind::Number) || !operands.contains(&Kind::Term) {
+                    return;
+                }
+                let op_token = body_map
+                    .expr(ctx.expr_id)
+                    .and_then(|ptr| ptr.to_node(&source_file))
+                    .and_then(|expr| match expr {
+                        ast::Expr::BinaryOpExpr(binary_op) => binary_op.op(),
+                        _ => None,
+                    });
+                if let Some((_, token)) = op_token {
+                    diags.push(make_diagnostic(def.file.file_id, token, negated));
+                }
+            }
+        },
+        &mut |_acc, _, _| (),
+    );
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Kind {
+    Number,
+    Term,
+    Unknown,
+}
+
+fn kind(def_fb: &InFunctionBody<&FunctionDef>, expr_id: ExprId) -> Kind {
+    match &def_fb[expr_id] {
+        Expr::Literal(Literal::Integer(_) | Literal::Float(_) | Literal::Char(_)) => Kind::Number,
+        Expr::Literal(Literal::Atom(_) | Literal::String(_)) => Kind::Term,
+        Expr::UnaryOp { .. } => Kind::Number,
+        Expr::BinaryOp {
+            op: BinaryOp::ArithOp(_),
+            ..
+        } => Kind::Number,
+        Expr::Tuple { .. }
+        | Expr::List { .. }
+        | Expr::Binary { .. }
+        | Expr::Map { .. }
+        | Expr::Record { .. } => Kind::Term,
+        Expr::MacroCall { expansion, .. } => kind(def_fb, *expansion),
+        _ => Kind::Unknown,
+    }
+}
+
+fn make_diagnostic(file_id: FileId, token: SyntaxToken, negated: bool) -> Diagnostic {
+    let range = token.text_range();
+    let (from, to) = if negated {
+        ("/=", "=/=")
+    } else {
+        ("==", "=:=")
+    };
+    let mut edit_builder = TextEdit::builder();
+    edit_builder.replace(range, to.to_string());
+    let edit = edit_builder.finish();
+
+    Diagnostic::new(
+        DiagnosticCode::StrictComparison,
+        format!("`{from}` coerces numbers, use `{to}` to compare terms exactly"),
+        range,
+    )
+    .severity(Severity::WeakWarning)
+    .with_fixes(Some(vec![fix(
+        "use_strict_comparison",
+        &format!("Replace `{from}` with `{to}`"),
+        SourceChange::from_text_edit(file_id, edit),
+        range,
+    )]))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use elp_ide_db::elp_base_db::fixture::WithFixture;
+    use elp_ide_db::RootDatabase;
+
+    use crate::diagnostics;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+    use crate::DiagnosticCode;
+    use crate::DiagnosticsConfig;
+
+    fn config() -> DiagnosticsConfig<'static> {
+        let mut config =
+            DiagnosticsConfig::default().disable(DiagnosticCode::MissingCompileWarnMissingSpec);
+        config.enable_strict_comparison = true;
+        config
+    }
+
+    #[test]
+    fn atom_comparisons() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+
+foo(X, Y) ->
+    A = X == ok,
+    %%    ^^ 💡 weak: `==` coerces numbers, use `=:=` to compare terms exactly
+    B = {X} /= Y,
+    %%      ^^ 💡 weak: `/=` coerces numbers, use `=/=` to compare terms exactly
+    {A, B}.
+"#,
+        );
+    }
+
+    #[test]
+    fn number_comparisons_not_flagged() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+
+foo(X, Y) when X == 1 ->
+    A = X + 1 /= 2.0,
+    B = X == Y,
+    C = [] == 0,
+    {A, B, C}.
+"#,
+        );
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let (db, file_id) = RootDatabase::with_single_file(
+            r#"
+-module(main).
+
+foo(X) -> X == ok.
+"#,
+        );
+        let config =
+            DiagnosticsConfig::default().disable(DiagnosticCode::MissingCompileWarnMissingSpec);
+        let diags = diagnostics::diagnostics(&db, &config, file_id, true);
+        assert!(diags.is_empty(), "unexpected diagnostics: {:?}", diags);
+    }
+
+    #[test]
+    fn fix_strict_comparison() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+
+foo(X) -> X /~= undefined.
+"#,
+            r#"
+-module(main).
+
+foo(X) -> X =/= undefined.
+"#,
+        );
+    }
+}
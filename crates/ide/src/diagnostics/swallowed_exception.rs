@@ -0,0 +1,243 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: swallowed_exception
+//!
+//! Return a diagnostic for a `try ... catch` clause which catches any
+//! exception, such as `_:_` or `_`, and whose body neither uses the
+//! caught class, reason or stacktrace, for instance to log them, nor
+//! raises an exception itself.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use fxhash::FxHashSet;
+use hir::CallTarget;
+use hir::CatchClause;
+use hir::Expr;
+use hir::ExprId;
+use hir::FunctionDef;
+use hir::InFunctionBody;
+use hir::Name;
+use hir::Pat;
+use hir::PatId;
+use hir::Semantic;
+use hir::Strategy;
+use hir::Var;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+pub(crate) fn swallowed_exception(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    sema.def_map(file_id)
+        .get_functions()
+        .iter()
+        .for_each(|(_arity, def)| {
+            if def.file.file_id == file_id {
+                process_try_exprs(diags, sema, def)
+            }
+        });
+}
+
+fn process_try_exprs(diags: &mut Vec<Diagnostic>, sema: &Semantic, def: &FunctionDef) {
+    let def_fb = def.in_function_body(sema.db, def);
+    let body_map = def_fb.get_body_map(sema.db);
+    let source_file = sema.parse(def.file.file_id);
+
+    def_fb.fold_function(
+        (),
+        &mut |_acc, _, ctx| {
+            if ctx.in_macro.is_some() {
+                return;
+            }
+            if let Expr::Try { catch_clauses, .. } = ctx.expr {
+                for clause in catch_clauses.iter().filter(|c| swallows(sema, &def_fb, c)) {
+                    let range = body_map
+                        .pat(clause.reason)
+                        .and_then(|ptr| ptr.to_node(&source_file))
+                        .and_then(|reason| {
+                            reason
+                                .syntax()
+                                .ancestors()
+                                .find_map(ast::CatchClause::cast)
+                        })
+                        .map(|clause| {
+                            let end = match (clause.stack(), clause.pat()) {
+                                (Some(stack), _) => stack.syntax().text_range().end(),
+                                (None, Some(pat)) => pat.syntax().text_range().end(),
+                                (None, None) => clause.syntax().text_range().end(),
+                            };
+                            TextRange::new(clause.syntax().text_range().start(), end)
+                        });
+                    if let Some(range) = range {
+                        diags.push(
+                            Diagnostic::new(
+                                DiagnosticCode::SwallowedException,
+                                "Exception is caught and discarded, without being logged or re-raised",
+                                range,
+                            )
+                            .severity(Severity::WeakWarning),
+                        );
+                    }
+                }
+            }
+        },
+        &mut |_acc, _, _| (),
+    );
+}
+
+/// A clause catching anything, whose body doesn't use what it caught
+fn swallows(sema: &Semantic, def_fb: &InFunctionBody<&FunctionDef>, clause: &CatchClause) -> bool {
+    if !clause.guards.is_empty() {
+        return false;
+    }
+    let mut vars = FxHashSet::default();
+    for pat_id in clause
+        .class
+        .iter()
+        .chain([&clause.reason])
+        .chain(&clause.stack)
+    {
+        match catch_all_var(sema, def_fb, *pat_id) {
+            Some(Some(var)) => {
+                vars.insert(var);
+            }
+            Some(None) => {}
+            None => return false,
+        }
+    }
+    !clause.exprs.iter().any(|expr_id| {
+        def_fb.fold_expr(
+            Strategy::TopDown,
+            *expr_id,
+            false,
+            &mut |acc, ctx| {
+                acc || match ctx.expr {
+                    Expr::Var(var) => vars.contains(&var),
+                    Expr::Call { target, .. } => is_raise(sema, def_fb, &target),
+                    _ => false,
+                }
+            },
+            &mut |acc, _| acc,
+        )
+    })
+}
+
+/// `Some(None)` for `_`, `Some(Some(var))` for any other variable, and
+/// `None` for a pattern which doesn't match everything
+fn catch_all_var(
+    sema: &Semantic,
+    def_fb: &InFunctionBody<&FunctionDef>,
+    pat_id: PatId,
+) -> Option<Option<Var>> {
+    match &def_fb[pat_id] {
+        Pat::Var(var) if sema.db.lookup_var(*var) == Name::ANONYMOUS => Some(None),
+        Pat::Var(var) => Some(Some(*var)),
+        _ => None,
+    }
+}
+
+/// Calls raising an exception: `erlang:raise/3`, `error`, `exit` and
+/// `throw`
+fn is_raise(
+    sema: &Semantic,
+    def_fb: &InFunctionBody<&FunctionDef>,
+    target: &CallTarget<ExprId>,
+) -> bool {
+    let name = |expr_id: &ExprId| def_fb.as_atom_name(sema.db, expr_id);
+    let is_raise_name = |expr_id: &ExprId| {
+        name(expr_id).map_or(false, |name| {
+            matches!(name.as_str(), "raise" | "error" | "exit" | "throw")
+        })
+    };
+    match target {
+        CallTarget::Local { name } => is_raise_name(name),
+        CallTarget::Remote { module, name: fun } => {
+            name(module).map_or(false, |module| module.as_str() == "erlang") && is_raise_name(fun)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics_with_config;
+    use crate::DiagnosticCode;
+    use crate::DiagnosticsConfig;
+
+    fn config() -> DiagnosticsConfig<'static> {
+        let mut config =
+            DiagnosticsConfig::default().disable(DiagnosticCode::MissingCompileWarnMissingSpec);
+        config.enable_swallowed_exception = true;
+        config
+    }
+
+    #[test]
+    fn catch_all_clauses() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+
+foo() ->
+    try bar()
+    catch _:_ -> ok
+    %%    ^^^ weak: Exception is caught and discarded, without being logged or re-raised
+    end,
+    try bar()
+    catch _ -> ok
+    %%    ^ weak: Exception is caught and discarded, without being logged or re-raised
+    end,
+    try bar()
+    catch _:_Reason:_Stack -> undefined
+    %%    ^^^^^^^^^^^^^^^^ weak: Exception is caught and discarded, without being logged or re-raised
+    end.
+
+bar() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn handled_exceptions_not_flagged() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+
+foo() ->
+    try bar()
+    catch
+        error:badarg -> ok
+    end,
+    try bar()
+    catch
+        _:Reason -> logger:error(#{failed => Reason})
+    end,
+    try bar()
+    catch
+        Class:Error:Stack -> erlang:raise(Class, Error, Stack)
+    end,
+    try bar()
+    catch
+        _:_ -> error(failed)
+    end,
+    try bar()
+    catch
+        _:_ when true -> ok
+    end.
+
+bar() -> ok.
+"#,
+        );
+    }
+}
@@ -0,0 +1,173 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: too_many_arguments
+//!
+//! Return a diagnostic for a function taking more than the configured
+//! number of arguments, suggesting to group them in a record or a map.
+//! Generated files are not checked, and neither are behaviour callbacks
+//! or Common Test callbacks, whose arity is imposed.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use hir::Semantic;
+
+use super::unused_export::behaviour_callbacks;
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+/// Common Test callbacks, with their arity
+const CT_CALLBACKS: &[(&str, u32)] = &[
+    ("all", 0),
+    ("groups", 0),
+    ("suite", 0),
+    ("group", 1),
+    ("init_per_suite", 1),
+    ("end_per_suite", 1),
+    ("init_per_group", 2),
+    ("end_per_group", 2),
+    ("init_per_testcase", 2),
+    ("end_per_testcase", 2),
+];
+
+pub(crate) fn too_many_arguments(
+    diags: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    max_arguments: usize,
+) {
+    if sema.db.is_generated(file_id) {
+        return;
+    }
+    let is_test_suite = match sema.db.file_form_list(file_id).module_attribute() {
+        Some(attr) => attr.name.as_str().ends_with("_SUITE"),
+        None => false,
+    };
+    let callbacks = behaviour_callbacks(sema, file_id);
+    let def_map = sema.def_map(file_id);
+    let mut too_long: Vec<_> = def_map
+        .get_functions()
+        .iter()
+        .filter(|(name, def)| {
+            def.file.file_id == file_id
+                && name.arity() as usize > max_arguments
+                && !callbacks.contains(name)
+                && !(is_test_suite && is_ct_callback(name.name().as_str(), name.arity()))
+        })
+        .filter_map(|(name, def)| {
+            let source = def.source(sema.db.upcast());
+            let range = source.clauses().find_map(|clause| match clause {
+                ast::FunctionOrMacroClause::FunctionClause(clause) => {
+                    clause.name().map(|name| name.syntax().text_range())
+                }
+                ast::FunctionOrMacroClause::MacroCallExpr(_) => None,
+            })?;
+            Some((range, name))
+        })
+        .collect();
+    too_long.sort_by_key(|(range, _)| range.start());
+
+    for (range, name) in too_long {
+        diags.push(
+            Diagnostic::new(
+                DiagnosticCode::TooManyArguments,
+                format!(
+                    "Function {name} takes {} arguments, consider passing a record or a map instead",
+                    name.arity()
+                ),
+                range,
+            )
+            .severity(Severity::WeakWarning),
+        );
+    }
+}
+
+fn is_ct_callback(name: &str, arity: u32) -> bool {
+    CT_CALLBACKS
+        .iter()
+        .any(|(callback, callback_arity)| *callback == name && *callback_arity == arity)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics_with_config;
+    use crate::DiagnosticCode;
+    use crate::DiagnosticsConfig;
+
+    fn config(max_arguments: usize) -> DiagnosticsConfig<'static> {
+        let mut config =
+            DiagnosticsConfig::default().disable(DiagnosticCode::MissingCompileWarnMissingSpec);
+        config.max_function_arguments = Some(max_arguments);
+        config
+    }
+
+    #[test]
+    fn above_threshold() {
+        check_diagnostics_with_config(
+            config(2),
+            r#"
+-module(main).
+-export([foo/3]).
+  foo(A, B, C) -> {A, B, C}.
+%%^^^ weak: Function foo/3 takes 3 arguments, consider passing a record or a map instead
+bar(A, B) -> {A, B}.
+"#,
+        );
+    }
+
+    #[test]
+    fn behaviour_callbacks_not_reported() {
+        check_diagnostics_with_config(
+            config(2),
+            r#"
+//- /src/main.erl
+-module(main).
+-behaviour(my_behaviour).
+-export([handle/3]).
+handle(A, B, C) -> {A, B, C}.
+//- /src/my_behaviour.erl
+-module(my_behaviour).
+-callback handle(term(), term(), term()) -> term().
+"#,
+        );
+    }
+
+    #[test]
+    fn ct_callbacks_not_reported() {
+        check_diagnostics_with_config(
+            config(1),
+            r#"
+//- /test/main_SUITE.erl
+-module(main_SUITE).
+-export([all/0, init_per_testcase/2, end_per_testcase/2, helper/2]).
+all() -> [].
+init_per_testcase(_Case, Config) -> Config.
+end_per_testcase(_Case, Config) -> Config.
+  helper(A, B) -> {A, B}.
+%%^^^^^^ weak: Function helper/2 takes 2 arguments, consider passing a record or a map instead
+"#,
+        );
+    }
+
+    #[test]
+    fn not_in_generated_file() {
+        check_diagnostics_with_config(
+            config(1),
+            r#"
+%% @generated from blah
+-module(main).
+foo(A, B) -> {A, B}.
+"#,
+        );
+    }
+}
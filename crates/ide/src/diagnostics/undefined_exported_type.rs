@@ -0,0 +1,114 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: undefined_exported_type
+//!
+//! Return a diagnostic for an entry of an `-export_type` attribute naming a
+//! type which is neither defined in the module nor in one of its includes.
+//! Only modules are checked, since the types exported from a header may be
+//! defined by the modules including it. Opt-in, as the compiler reports
+//! the same.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::AstNode;
+use hir::Semantic;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+pub(crate) fn undefined_exported_type(
+    diags: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    ext: Option<&str>,
+) {
+    if ext != Some("erl") {
+        return;
+    }
+    let form_list = sema.db.file_form_list(file_id);
+    let def_map = sema.def_map(file_id);
+    let source_file = sema.parse(file_id).value;
+    for (_idx, export) in form_list.type_exports() {
+        let attr = export.form_id.get(&source_file);
+        for entry_id in export.entries.clone() {
+            let entry = &form_list[entry_id];
+            if def_map.get_type(&entry.name).is_some() {
+                continue;
+            }
+            if let Some(fa) = attr.types().nth(entry.idx as usize) {
+                diags.push(
+                    Diagnostic::new(
+                        DiagnosticCode::UndefinedExportedType,
+                        format!("Type {} is exported but not defined", entry.name),
+                        fa.syntax().text_range(),
+                    )
+                    .severity(Severity::Error),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::DiagnosticCode;
+    use crate::DiagnosticsConfig;
+
+    fn config() -> DiagnosticsConfig<'static> {
+        let mut config =
+            DiagnosticsConfig::default().disable(DiagnosticCode::MissingCompileWarnMissingSpec);
+        config.enable_undefined_exported_type = true;
+        config
+    }
+
+    #[test]
+    fn undefined_types() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /src/main.erl
+-module(main).
+-include("main.hrl").
+-export_type([local/0, missing/0]).
+%%                     ^^^^^^^^^ error: Type missing/0 is exported but not defined
+-export_type([included/1, local/1]).
+%%                        ^^^^^^^ error: Type local/1 is exported but not defined
+-type local() :: ok.
+//- /src/main.hrl
+-type included(T) :: T.
+"#,
+        );
+    }
+
+    #[test]
+    fn header_not_checked() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /src/main.hrl
+-export_type([defined_by_includer/0]).
+"#,
+        );
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        check_diagnostics(
+            r#"
+-module(main).
+-export_type([missing/0]).
+"#,
+        );
+    }
+}
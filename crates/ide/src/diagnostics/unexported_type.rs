@@ -0,0 +1,90 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: unexported_type
+//!
+//! Return a diagnostic for a remote type, such as `mod:foo()`, which is
+//! defined in the other module but not exported from it. References to
+//! opaque types are fine, as long as they are exported.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use hir::CallDef;
+use hir::InFile;
+use hir::Semantic;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+pub(crate) fn unexported_type(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let source_file = sema.parse(file_id);
+    let own_module = sema
+        .db
+        .file_form_list(file_id)
+        .module_attribute()
+        .map(|attr| attr.name.clone());
+    for remote in source_file
+        .value
+        .syntax()
+        .descendants()
+        .filter_map(ast::Remote::cast)
+    {
+        let module = match remote.module().and_then(|module| module.module()) {
+            Some(ast::ExprMax::Atom(atom)) => atom.as_name(),
+            _ => continue,
+        };
+        if Some(&module) == own_module.as_ref() {
+            continue;
+        }
+        if let Some(CallDef::Type(def)) = sema.to_def(InFile::new(file_id, &remote)) {
+            if !def.exported {
+                diags.push(
+                    Diagnostic::new(
+                        DiagnosticCode::UnexportedType,
+                        format!("Type {}:{} is not exported", module, def.name()),
+                        remote.syntax().text_range(),
+                    )
+                    .severity(Severity::Warning),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn unexported_remote_type() {
+        check_diagnostics(
+            r#"
+//- /src/main.erl
+    -module(main).
+    -export_type([foo/0]).
+    -type foo() :: other:exported() | other:opaque().
+    -spec bar(other:internal(atom())) -> main:foo().
+%%            ^^^^^^^^^^^^^^ warning: Type other:internal/1 is not exported
+    bar(X) -> other:internal(X).
+//- /src/other.erl
+-module(other).
+-export([internal/1]).
+-export_type([exported/0, opaque/0]).
+-type exported() :: ok.
+-opaque opaque() :: ok.
+-type internal(T) :: T.
+internal(X) -> X.
+"#,
+        );
+    }
+}
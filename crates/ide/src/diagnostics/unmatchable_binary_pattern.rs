@@ -0,0 +1,308 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: unmatchable_binary_pattern
+//!
+//! Return a diagnostic if a binary pattern is matched against a binary
+//! that is too short for it, as in `<<X:8, Y:8>> = <<1:4>>`. This is a
+//! best-effort check: it only applies when the size of every segment on
+//! both sides is statically known, so variable sizes and `/binary` tails
+//! without a size are never reported.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use hir::Atom;
+use hir::BinarySeg;
+use hir::Expr;
+use hir::ExprId;
+use hir::FunctionDef;
+use hir::InFunctionBody;
+use hir::Literal;
+use hir::Pat;
+use hir::PatId;
+use hir::Semantic;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+pub(crate) fn unmatchable_binary_pattern(
+    diags: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+) {
+    sema.def_map(file_id)
+        .get_functions()
+        .iter()
+        .for_each(|(_arity, def)| {
+            if def.file.file_id == file_id {
+                check_function(diags, sema, def)
+            }
+        });
+}
+
+fn check_function(diags: &mut Vec<Diagnostic>, sema: &Semantic, def: &FunctionDef) {
+    let def_fb = def.in_function_body(sema.db, def);
+    let body_map = def_fb.get_body_map(sema.db);
+    let source_file = sema.parse(def.file.file_id);
+
+    def_fb.fold_function(
+        (),
+        &mut |(), _, ctx| {
+            if let Expr::Match { lhs, rhs } = ctx.expr {
+                let pat_bits = match &def_fb[lhs] {
+                    Pat::Binary { segs } => pat_bits(sema, &def_fb, segs),
+                    _ => None,
+                };
+                let expr_bits = match &def_fb[rhs] {
+                    Expr::Binary { segs } => expr_bits(sema, &def_fb, segs),
+                    _ => None,
+                };
+                if let (Some(pat_bits), Some(expr_bits)) = (pat_bits, expr_bits) {
+                    if pat_bits > expr_bits {
+                        if let Some(pat) = body_map
+                            .pat(lhs)
+                            .and_then(|infile_ast_ptr| infile_ast_ptr.to_node(&source_file))
+                        {
+                            let range = pat.syntax().text_range();
+                            diags.push(make_diagnostic(range, pat_bits, expr_bits));
+                        }
+                    }
+                }
+            }
+        },
+        &mut |(), _, _| (),
+    );
+}
+
+fn make_diagnostic(range: TextRange, pat_bits: u64, expr_bits: u64) -> Diagnostic {
+    Diagnostic::new(
+        DiagnosticCode::UnmatchableBinaryPattern,
+        format!(
+            "This pattern can never match: it needs {pat_bits} bits, but the binary only has {expr_bits}"
+        ),
+        range,
+    )
+    .severity(Severity::Warning)
+    // Marking as EXPERIMENTAL since the compiler reports most of these too
+    .experimental()
+}
+
+/// The total size in bits of a binary pattern, if it is statically known.
+fn pat_bits(
+    sema: &Semantic,
+    def_fb: &InFunctionBody<&FunctionDef>,
+    segs: &[BinarySeg<PatId>],
+) -> Option<u64> {
+    segs.iter()
+        .map(|seg| {
+            let value = match &def_fb[seg.elem] {
+                Pat::Literal(literal) => literal_value(literal),
+                _ => Value::Other,
+            };
+            segment_bits(sema, def_fb, seg, value)
+        })
+        .try_fold(0u64, |total, bits| total.checked_add(bits?))
+}
+
+/// The total size in bits of a binary expression, if it is statically known.
+fn expr_bits(
+    sema: &Semantic,
+    def_fb: &InFunctionBody<&FunctionDef>,
+    segs: &[BinarySeg<ExprId>],
+) -> Option<u64> {
+    segs.iter()
+        .map(|seg| {
+            let value = match &def_fb[seg.elem] {
+                Expr::Literal(literal) => literal_value(literal),
+                Expr::Binary { segs } => match expr_bits(sema, def_fb, segs) {
+                    Some(bits) => Value::Binary(bits),
+                    None => Value::Other,
+                },
+                _ => Value::Other,
+            };
+            segment_bits(sema, def_fb, seg, value)
+        })
+        .try_fold(0u64, |total, bits| total.checked_add(bits?))
+}
+
+/// What is known about the value of a segment, as far as its size is
+/// concerned
+enum Value<'a> {
+    /// A string literal, which stands for one segment per character
+    String(&'a str),
+    CodePoint(u32),
+    /// A nested binary of the given size in bits
+    Binary(u64),
+    Other,
+}
+
+fn literal_value(literal: &Literal) -> Value {
+    match literal {
+        Literal::String(string) => Value::String(string),
+        Literal::Char(ch) => Value::CodePoint(*ch as u32),
+        Literal::Integer(int) => match u32::try_from(*int) {
+            Ok(code_point) => Value::CodePoint(code_point),
+            Err(_) => Value::Other,
+        },
+        _ => Value::Other,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SegmentType {
+    Integer,
+    Float,
+    Binary,
+    Bitstring,
+    Utf8,
+    Utf16,
+    Utf32,
+}
+
+fn segment_type(sema: &Semantic, tys: &[Atom]) -> SegmentType {
+    tys.iter()
+        .filter_map(|ty| match sema.db.lookup_atom(*ty).as_str() {
+            "integer" => Some(SegmentType::Integer),
+            "float" => Some(SegmentType::Float),
+            "binary" | "bytes" => Some(SegmentType::Binary),
+            "bitstring" | "bits" => Some(SegmentType::Bitstring),
+            "utf8" => Some(SegmentType::Utf8),
+            "utf16" => Some(SegmentType::Utf16),
+            "utf32" => Some(SegmentType::Utf32),
+            _ => None,
+        })
+        .last()
+        .unwrap_or(SegmentType::Integer)
+}
+
+/// The size in bits of a segment, following the defaults of
+/// https://www.erlang.org/doc/reference_manual/expressions.html#bit-syntax-expressions
+fn segment_bits<T>(
+    sema: &Semantic,
+    def_fb: &InFunctionBody<&FunctionDef>,
+    seg: &BinarySeg<T>,
+    value: Value,
+) -> Option<u64> {
+    let ty = segment_type(sema, &seg.tys);
+    let utf_bits = |code_point: u32| match ty {
+        SegmentType::Utf8 => match code_point {
+            0..=0x7F => 8,
+            0x80..=0x7FF => 16,
+            0x800..=0xFFFF => 24,
+            _ => 32,
+        },
+        SegmentType::Utf16 if code_point > 0xFFFF => 32,
+        SegmentType::Utf16 => 16,
+        _ => 32,
+    };
+    if matches!(
+        ty,
+        SegmentType::Utf8 | SegmentType::Utf16 | SegmentType::Utf32
+    ) {
+        // The size of a utf segment depends on the code point it encodes
+        return match value {
+            Value::String(string) => Some(string.chars().map(|ch| utf_bits(ch as u32)).sum()),
+            Value::CodePoint(code_point) => Some(utf_bits(code_point)),
+            _ => None,
+        };
+    }
+
+    let bits = match seg.size {
+        Some(size) => {
+            let size = match &def_fb[size] {
+                Expr::Literal(Literal::Integer(size)) => u64::try_from(*size).ok()?,
+                _ => return None,
+            };
+            let unit = match (seg.unit, ty) {
+                (Some(unit), _) => u64::try_from(unit).ok()?,
+                (None, SegmentType::Binary) => 8,
+                (None, _) => 1,
+            };
+            size.checked_mul(unit)?
+        }
+        None => match (ty, &value) {
+            (SegmentType::Integer, _) => 8,
+            (SegmentType::Float, _) => 64,
+            (SegmentType::Binary | SegmentType::Bitstring, Value::Binary(bits)) => {
+                return Some(*bits)
+            }
+            // A segment without a size matching the rest of the binary
+            _ => return None,
+        },
+    };
+    match value {
+        Value::String(string) => (string.chars().count() as u64).checked_mul(bits),
+        _ => Some(bits),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn pattern_longer_than_binary() {
+        check_diagnostics(
+            r#"
+-module(main).
+foo() ->
+    <<X:8, Y:8>> = <<1:4>>,
+%%  ^^^^^^^^^^^^ warning: This pattern can never match: it needs 16 bits, but the binary only has 4
+    {X, Y}.
+"#,
+        );
+    }
+
+    #[test]
+    fn strings_and_units() {
+        check_diagnostics(
+            r#"
+-module(main).
+foo() ->
+    <<"abc", X:2/binary>> = <<"abcd">>,
+%%  ^^^^^^^^^^^^^^^^^^^^^ warning: This pattern can never match: it needs 40 bits, but the binary only has 32
+    <<Y:1/unit:16, Z/utf8>> = <<1:16, $a/utf8>>,
+    {X, Y, Z}.
+"#,
+        );
+    }
+
+    #[test]
+    fn unknown_sizes_not_reported() {
+        check_diagnostics(
+            r#"
+-module(main).
+foo(N, Bin) ->
+    <<A:N, B:8>> = <<1:4>>,
+    <<C:8, Rest/binary>> = <<>>,
+    <<D:8, E:8>> = <<Bin/binary>>,
+    <<F:8, G:8>> = <<1>>,
+%%  ^^^^^^^^^^^^ warning: This pattern can never match: it needs 16 bits, but the binary only has 8
+    {A, B, C, Rest, D, E, F, G}.
+"#,
+        );
+    }
+
+    #[test]
+    fn overflowing_sizes_not_reported() {
+        check_diagnostics(
+            r#"
+-module(main).
+foo() ->
+    <<A:18446744073709551615/unit:2>> = <<1>>,
+    <<B:18446744073709551615, C:18446744073709551615>> = <<1>>,
+    {A, B, C}.
+"#,
+        );
+    }
+}
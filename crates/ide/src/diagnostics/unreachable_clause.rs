@@ -0,0 +1,244 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: unreachable_clause
+//!
+//! Return a diagnostic for every function, case, receive or try clause that
+//! follows a clause that always matches, i.e. one whose patterns are all
+//! `_` or fresh variables and which has no guard.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use hir::BodySourceMap;
+use hir::CRClause;
+use hir::DefinitionOrReference;
+use hir::Expr;
+use hir::FunctionDef;
+use hir::InFile;
+use hir::InFunctionBody;
+use hir::Pat;
+use hir::PatId;
+use hir::Semantic;
+
+use super::Diagnostic;
+use super::RelatedInformation;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+pub(crate) fn unreachable_clause(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    sema.def_map(file_id)
+        .get_functions()
+        .iter()
+        .for_each(|(_arity, def)| {
+            if def.file.file_id == file_id {
+                check_function(diags, sema, def)
+            }
+        });
+}
+
+fn check_function(diags: &mut Vec<Diagnostic>, sema: &Semantic, def: &FunctionDef) {
+    let def_fb = def.in_function_body(sema.db, def);
+    let body_map = def_fb.get_body_map(sema.db);
+    let source_file = sema.parse(def.file.file_id);
+    let is_catch_all =
+        |pat_id: &PatId| is_catch_all_pat(sema, &def_fb, &body_map, &source_file, *pat_id);
+
+    // Function clauses may come from macros, only report when the lowered
+    // clauses line up with the syntax.
+    let ast_clauses: Vec<_> = def.source(sema.db.upcast()).clauses().collect();
+    let clauses: Vec<_> = def_fb.clauses().map(|(_, clause)| clause).collect();
+    if ast_clauses.len() == clauses.len() {
+        if let Some(idx) = clauses.iter().position(|clause| {
+            clause.guards.is_empty() && clause.pats.iter().all(|pat| is_catch_all(pat))
+        }) {
+            let catch_all = ast_clauses[idx].syntax().text_range();
+            for clause in &ast_clauses[idx + 1..] {
+                diags.push(make_diagnostic(clause.syntax().text_range(), catch_all));
+            }
+        }
+    }
+
+    def_fb.fold_function(
+        (),
+        &mut |(), _, ctx| {
+            let clauses = match &ctx.expr {
+                Expr::Case { clauses, .. } | Expr::Receive { clauses, .. } => clauses,
+                Expr::Try { of_clauses, .. } => of_clauses,
+                _ => return,
+            };
+            check_cr_clauses(diags, &body_map, &source_file, clauses, &is_catch_all);
+        },
+        &mut |(), _, _| (),
+    );
+}
+
+fn check_cr_clauses(
+    diags: &mut Vec<Diagnostic>,
+    body_map: &BodySourceMap,
+    source_file: &InFile<ast::SourceFile>,
+    clauses: &[CRClause],
+    is_catch_all: &dyn Fn(&PatId) -> bool,
+) {
+    // Report the whole clause, as for function clauses
+    let range = |pat_id: PatId| {
+        body_map
+            .pat(pat_id)
+            .and_then(|ptr| ptr.to_node(source_file))
+            .and_then(|node| node.syntax().ancestors().find_map(ast::CrClause::cast))
+            .map(|clause| clause.syntax().text_range())
+    };
+    if let Some(idx) = clauses
+        .iter()
+        .position(|clause| clause.guards.is_empty() && is_catch_all(&clause.pat))
+    {
+        if let Some(catch_all) = range(clauses[idx].pat) {
+            for clause in &clauses[idx + 1..] {
+                if let Some(clause_range) = range(clause.pat) {
+                    diags.push(make_diagnostic(clause_range, catch_all));
+                }
+            }
+        }
+    }
+}
+
+fn is_catch_all_pat(
+    sema: &Semantic,
+    def_fb: &InFunctionBody<&FunctionDef>,
+    body_map: &BodySourceMap,
+    source_file: &InFile<ast::SourceFile>,
+    pat_id: PatId,
+) -> bool {
+    match &def_fb[pat_id] {
+        Pat::Var(var) => {
+            if var.as_string(sema.db.upcast()) == "_" {
+                return true;
+            }
+            // A variable only matches anything if it is not already bound
+            match body_map
+                .pat(pat_id)
+                .and_then(|ptr| ptr.to_node(source_file))
+            {
+                Some(ast::Expr::ExprMax(ast::ExprMax::Var(ast_var))) => matches!(
+                    sema.to_def(InFile::new(source_file.file_id, &ast_var)),
+                    Some(DefinitionOrReference::Definition(_))
+                ),
+                _ => false,
+            }
+        }
+        Pat::Match { lhs, rhs } => {
+            is_catch_all_pat(sema, def_fb, body_map, source_file, *lhs)
+                && is_catch_all_pat(sema, def_fb, body_map, source_file, *rhs)
+        }
+        Pat::MacroCall { expansion, args: _ } => {
+            is_catch_all_pat(sema, def_fb, body_map, source_file, *expansion)
+        }
+        _ => false,
+    }
+}
+
+fn make_diagnostic(range: TextRange, catch_all: TextRange) -> Diagnostic {
+    Diagnostic::new(
+        DiagnosticCode::UnreachableClause,
+        "This clause is unreachable, a previous clause always matches",
+        range,
+    )
+    .severity(Severity::Warning)
+    // Marking as EXPERIMENTAL since it duplicates the compiler warning
+    .experimental()
+    .with_related(Some(vec![RelatedInformation {
+        range: catch_all,
+        message: "Clause always matching".to_string(),
+    }]))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn unreachable_function_clause() {
+        check_diagnostics(
+            r#"
+            -module(main).
+
+            foo(X) -> X;
+            foo(_) -> ok.
+%%          ^^^^^^^^^^^^ warning: This clause is unreachable, a previous clause always matches
+
+            bar(_, ok) -> ok;
+            bar(X, _) when is_atom(X) -> X;
+            bar(X, _) -> X.
+            "#,
+        );
+    }
+
+    #[test]
+    fn unreachable_case_clause() {
+        check_diagnostics(
+            r#"
+            -module(main).
+
+            foo(X) ->
+                case X of
+                    {ok, Y} -> Y;
+                    Other -> Other;
+                    error -> error
+%%                  ^^^^^^^^^^^^^^ warning: This clause is unreachable, a previous clause always matches
+                end.
+            "#,
+        );
+    }
+
+    #[test]
+    fn unreachable_receive_and_try_clause() {
+        check_diagnostics(
+            r#"
+            -module(main).
+
+            foo() ->
+                receive
+                    _ -> ok;
+                    stop -> stop
+%%                  ^^^^^^^^^^^^ warning: This clause is unreachable, a previous clause always matches
+                end.
+
+            bar() ->
+                try baz() of
+                    Res = _ -> Res;
+                    ok -> ok
+%%                  ^^^^^^^^ warning: This clause is unreachable, a previous clause always matches
+                catch
+                    _ -> error
+                end.
+
+            baz() -> ok.
+            "#,
+        );
+    }
+
+    #[test]
+    fn guarded_or_bound_var_is_not_catch_all() {
+        check_diagnostics(
+            r#"
+            -module(main).
+
+            foo(X, Y) ->
+                case X of
+                    Y -> same;
+                    Z when is_atom(Z) -> Z;
+                    _ -> other
+                end.
+            "#,
+        );
+    }
+}
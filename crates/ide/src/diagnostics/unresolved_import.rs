@@ -0,0 +1,154 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: unresolved_import
+//!
+//! Return a diagnostic if an `-import` attribute names a module that cannot
+//! be found, or a function that the imported module does not define or
+//! does not export. Opt-in, as the compiler reports the same.
+//!
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::AstNode;
+use hir::Semantic;
+
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+pub(crate) fn unresolved_import(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let imports = sema.db.imported_functions(file_id);
+    if imports.is_empty() {
+        return;
+    }
+    let source_file = sema.parse(file_id).value;
+    let form_list = sema.db.file_form_list(file_id);
+    let mut reported_modules = Vec::new();
+    for imported in imports.iter() {
+        let attr = form_list[imported.import_id].form_id.get(&source_file);
+        if imported.module_file.is_none() {
+            if reported_modules.contains(&imported.import_id) {
+                continue;
+            }
+            reported_modules.push(imported.import_id);
+            if let Some(module) = attr.module() {
+                diags.push(
+                    Diagnostic::new(
+                        DiagnosticCode::UnresolvedImport,
+                        format!("Module `{}` not found", imported.module),
+                        module.syntax().text_range(),
+                    )
+                    .severity(Severity::Warning),
+                );
+            }
+        } else {
+            let message = match &imported.def {
+                None => format!(
+                    "Function `{}` is not defined in module `{}`",
+                    imported.name, imported.module
+                ),
+                Some(def) if !def.exported => format!(
+                    "Function `{}` is not exported by module `{}`",
+                    imported.name, imported.module
+                ),
+                Some(_) => continue,
+            };
+            let idx = form_list[imported.entry_id].idx as usize;
+            if let Some(fa) = attr.funs().nth(idx) {
+                diags.push(
+                    Diagnostic::new(
+                        DiagnosticCode::UnresolvedImport,
+                        message,
+                        fa.syntax().text_range(),
+                    )
+                    .severity(Severity::Warning),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::DiagnosticCode;
+    use crate::DiagnosticsConfig;
+
+    fn config() -> DiagnosticsConfig<'static> {
+        let mut config =
+            DiagnosticsConfig::default().disable(DiagnosticCode::MissingCompileWarnMissingSpec);
+        config.enable_unresolved_import = true;
+        config
+    }
+
+    #[test]
+    fn unknown_module() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /src/main.erl
+-module(main).
+-import(missing, [foo/1, bar/0]).
+%%      ^^^^^^^ warning: Module `missing` not found
+//- /src/another.erl
+-module(another).
+"#,
+        );
+    }
+
+    #[test]
+    fn unknown_function() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /src/main.erl
+-module(main).
+-import(another, [foo/1, bar/0]).
+%%                       ^^^^^ warning: Function `bar/0` is not defined in module `another`
+-import(another, [foo/2]).
+%%                ^^^^^ warning: Function `foo/2` is not defined in module `another`
+//- /src/another.erl
+-module(another).
+-export([foo/1]).
+foo(_) -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn unexported_function() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /src/main.erl
+-module(main).
+-import(another, [foo/1, bar/0]).
+%%                       ^^^^^ warning: Function `bar/0` is not exported by module `another`
+//- /src/another.erl
+-module(another).
+-export([foo/1]).
+foo(_) -> ok.
+bar() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        check_diagnostics(
+            r#"
+//- /src/main.erl
+-module(main).
+-import(missing, [foo/1]).
+"#,
+        );
+    }
+}
@@ -0,0 +1,216 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Lint: unused_export
+//!
+//! Return a diagnostic for an exported function which is not called
+//! anywhere in the project, other than recursively by itself. This is
+//! advisory, as calls from outside the project can't be seen, so only
+//! modules of project applications are checked, and not dependencies or
+//! OTP. Behaviour callbacks, `main/1` escript entry points, test modules
+//! and EUnit test functions are not reported, nor functions listed in an
+//! `-ignore_xref` attribute, as `{F, A}` or `{M, F, A}`.
+//!
+
+use elp_ide_db::elp_base_db::AppType;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::SymbolDefinition;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use fxhash::FxHashSet;
+use hir::FunctionDef;
+use hir::InFile;
+use hir::Literal;
+use hir::Name;
+use hir::NameArity;
+use hir::Semantic;
+use hir::Term;
+use hir::TermId;
+
+use super::missing_export::is_test_module;
+use super::Diagnostic;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+const IGNORE_XREF: &str = "ignore_xref";
+
+pub(crate) fn unused_export(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    if sema.db.file_app_type(file_id) != Some(AppType::App) || is_test_module(sema, file_id) {
+        return;
+    }
+    let mut unused: Vec<_> = unused_exports(sema, file_id)
+        .into_iter()
+        .filter_map(|def| {
+            let source = def.source(sema.db.upcast());
+            let range = source.clauses().find_map(|clause| match clause {
+                ast::FunctionOrMacroClause::FunctionClause(clause) => {
+                    clause.name().map(|name| name.syntax().text_range())
+                }
+                ast::FunctionOrMacroClause::MacroCallExpr(_) => None,
+            })?;
+            Some((range, def))
+        })
+        .collect();
+    unused.sort_by_key(|(range, _)| range.start());
+
+    for (range, def) in unused {
+        diags.push(
+            Diagnostic::new(
+                DiagnosticCode::UnusedExport,
+                format!(
+                    "Exported function {} is not called anywhere in the project",
+                    def.function.name
+                ),
+                range,
+            )
+            .severity(Severity::WeakWarning),
+        );
+    }
+}
+
+/// The functions exported by `file_id` without callers in the project
+pub(crate) fn unused_exports(sema: &Semantic, file_id: FileId) -> Vec<FunctionDef> {
+    let def_map = sema.def_map(file_id);
+    let callbacks = behaviour_callbacks(sema, file_id);
+    let ignored = ignored_functions(sema, file_id);
+    def_map
+        .get_functions()
+        .values()
+        .filter(|def| def.file.file_id == file_id && def.exported)
+        .filter(|def| {
+            let name = &def.function.name;
+            !callbacks.contains(name) && !ignored.contains(name) && !is_entry_point(name)
+        })
+        .filter(|def| {
+            let own_range = def.source(sema.db.upcast()).syntax().text_range();
+            !SymbolDefinition::Function((*def).clone())
+                .usages(sema)
+                .direct_only()
+                .all()
+                .file_ranges()
+                .any(|usage| usage.file_id != file_id || !own_range.contains_range(usage.range))
+        })
+        .cloned()
+        .collect()
+}
+
+pub(super) fn behaviour_callbacks(sema: &Semantic, file_id: FileId) -> FxHashSet<NameArity> {
+    sema.db
+        .file_form_list(file_id)
+        .behaviour_attributes()
+        .filter_map(|(_, behaviour)| sema.resolve_module_name(file_id, behaviour.name.as_str()))
+        .flat_map(|module| {
+            sema.def_map(module.file.file_id)
+                .get_callbacks()
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// `main/1` is the entry point of escripts, and EUnit runs the functions
+/// ending in `_test` and `_test_`
+fn is_entry_point(name: &NameArity) -> bool {
+    let function = name.name().as_str();
+    (function == "main" && name.arity() == 1)
+        || (name.arity() == 0 && (function.ends_with("_test") || function.ends_with("_test_")))
+}
+
+fn ignored_functions(sema: &Semantic, file_id: FileId) -> FxHashSet<NameArity> {
+    let module = sema.module_name(file_id);
+    let mut res = FxHashSet::default();
+    for (idx, attr) in sema.db.file_form_list(file_id).attributes() {
+        if attr.name != IGNORE_XREF {
+            continue;
+        }
+        let attr_body = sema.db.attribute_body(InFile::new(file_id, idx));
+        let body = &attr_body.body;
+        let atom = |term_id: TermId| -> Option<Name> {
+            match &body[term_id] {
+                Term::Literal(Literal::Atom(atom)) => Some(sema.db.lookup_atom(*atom)),
+                _ => None,
+            }
+        };
+        let function = |term_id: TermId| -> Option<NameArity> {
+            let exprs = match &body[term_id] {
+                Term::Tuple { exprs } => exprs,
+                _ => return None,
+            };
+            let (name, arity) = match exprs.as_slice() {
+                [name, arity] => (*name, *arity),
+                [m, name, arity] => {
+                    if atom(*m)?.as_str() != module.as_ref()?.as_str() {
+                        return None;
+                    }
+                    (*name, *arity)
+                }
+                _ => return None,
+            };
+            match &body[arity] {
+                Term::Literal(Literal::Integer(arity)) => {
+                    Some(NameArity::new(atom(name)?, (*arity).try_into().ok()?))
+                }
+                _ => None,
+            }
+        };
+        match &body[attr_body.value] {
+            Term::List { exprs, .. } => res.extend(exprs.iter().filter_map(|e| function(*e))),
+            _ => res.extend(function(attr_body.value)),
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::tests::check_diagnostics_with_config;
+    use crate::DiagnosticCode;
+    use crate::DiagnosticsConfig;
+
+    fn config() -> DiagnosticsConfig<'static> {
+        let mut config =
+            DiagnosticsConfig::default().disable(DiagnosticCode::MissingCompileWarnMissingSpec);
+        config.enable_unused_export = true;
+        config
+    }
+
+    #[test]
+    fn exported_without_callers() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /src/main.erl
+-module(main).
+-behaviour(my_behaviour).
+-export([used/0, init/1, main/1, ignored/0, also_ignored/1]).
+-export([unused/0, loop/1]).
+-ignore_xref([{ignored, 0}, {main, also_ignored, 1}]).
+used() -> ok.
+  unused() -> ok.
+%%^^^^^^ weak: Exported function unused/0 is not called anywhere in the project
+init(_) -> ok.
+main(_) -> ok.
+ignored() -> ok.
+also_ignored(_) -> ok.
+  loop(X) -> loop(X).
+%%^^^^ weak: Exported function loop/1 is not called anywhere in the project
+//- /src/other.erl
+-module(other).
+-export([call/0]).
+  call() -> main:used().
+%%^^^^ weak: Exported function call/0 is not called anywhere in the project
+//- /src/my_behaviour.erl
+-module(my_behaviour).
+-callback init(term()) -> ok.
+"#,
+        );
+    }
+}
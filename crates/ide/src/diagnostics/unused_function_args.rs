@@ -20,6 +20,7 @@ use elp_ide_db::elp_base_db::FileId;
 use elp_ide_db::source_change::SourceChange;
 use elp_syntax::ast;
 use hir::Clause;
+use hir::ClauseId;
 use hir::FunctionDef;
 use hir::InFile;
 use hir::InFunctionBody;
@@ -42,50 +43,17 @@ pub(crate) fn unused_function_args(diags: &mut Vec<Diagnostic>, sema: &Semantic,
             if def.file.file_id != file_id {
                 return;
             }
-            let source_file = sema.parse(file_id);
-
-            let mut def_fb = def.in_function_body(sema.db, def);
-            let body_map = def_fb.get_body_map(sema.db);
-
-            for (_clause_id, clause @ Clause { pats, .. }) in def_fb.clone().clauses() {
-                let mut unused_vars_with_wrong_name = HashMap::new();
-
-                for clause_arg_pat_id in pats.iter() {
-                    def_fb.fold_pat(
-                        Strategy::TopDown,
-                        *clause_arg_pat_id,
-                        (),
-                        &mut |(), _| {},
-                        &mut |(), ctx| {
-                            if let Some(var) = ctx.pat.as_var() {
-                                if is_unused_var(
-                                    &sema,
-                                    &def_fb,
-                                    &body_map,
-                                    &source_file,
-                                    &ctx.pat_id,
-                                ) {
-                                    let var_name = var.as_string(sema.db.upcast());
-                                    if !var_name.starts_with("_") {
-                                        unused_vars_with_wrong_name.insert(ctx.pat_id, var_name);
-                                    }
-                                }
-                            }
-                        },
-                    );
-                }
-
-                if !unused_vars_with_wrong_name.is_empty() {
-                    if let Some(replacements) = pick_new_unused_var_names(
-                        &sema,
-                        &def_fb,
-                        &clause,
-                        &unused_vars_with_wrong_name,
-                    ) {
-                        for (pat_id, new_name) in replacements.iter() {
-                            if let Some(range) = def_fb.range_for_pat(sema.db, *pat_id) {
-                                diags.push(make_diagnostic(file_id, range, new_name.clone()));
-                            }
+            let def_fb = def.in_function_body(sema.db, def);
+            for (clause_id, unused_vars_with_wrong_name) in unused_clause_args(sema, def) {
+                if let Some(replacements) = pick_new_unused_var_names(
+                    &sema,
+                    &def_fb,
+                    &def_fb[clause_id],
+                    &unused_vars_with_wrong_name,
+                ) {
+                    for (pat_id, new_name) in replacements.iter() {
+                        if let Some(range) = def_fb.range_for_pat(sema.db, *pat_id) {
+                            diags.push(make_diagnostic(file_id, range, new_name.clone()));
                         }
                     }
                 }
@@ -93,6 +61,46 @@ pub(crate) fn unused_function_args(diags: &mut Vec<Diagnostic>, sema: &Semantic,
         });
 }
 
+/// The variables bound by the arguments of each clause of `def` which are
+/// not used anywhere else in the clause, including its guards, and are not
+/// prefixed with an underscore. Each clause is considered on its own, so an
+/// argument can be reported in one clause but not in another. Only the
+/// clauses with such variables are returned.
+pub(crate) fn unused_clause_args(
+    sema: &Semantic,
+    def: &FunctionDef,
+) -> Vec<(ClauseId, HashMap<PatId, String>)> {
+    let source_file = sema.parse(def.file.file_id);
+    let def_fb = def.in_function_body(sema.db, def);
+    let body_map = def_fb.get_body_map(sema.db);
+    let mut res = Vec::new();
+    for (clause_id, Clause { pats, .. }) in def_fb.clauses() {
+        let mut unused_vars_with_wrong_name = HashMap::new();
+        for clause_arg_pat_id in pats.iter() {
+            def_fb.fold_pat(
+                Strategy::TopDown,
+                *clause_arg_pat_id,
+                (),
+                &mut |(), _| {},
+                &mut |(), ctx| {
+                    if let Some(var) = ctx.pat.as_var() {
+                        if is_unused_var(&sema, &def_fb, &body_map, &source_file, &ctx.pat_id) {
+                            let var_name = var.as_string(sema.db.upcast());
+                            if !var_name.starts_with("_") {
+                                unused_vars_with_wrong_name.insert(ctx.pat_id, var_name);
+                            }
+                        }
+                    }
+                },
+            );
+        }
+        if !unused_vars_with_wrong_name.is_empty() {
+            res.push((clause_id, unused_vars_with_wrong_name));
+        }
+    }
+    res
+}
+
 fn is_unused_var(
     sema: &Semantic,
     def_fb: &InFunctionBody<&FunctionDef>,
@@ -275,6 +283,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn used_in_guard_only() {
+        check_diagnostics(
+            r#"
+               -module(main).
+               foo(X, Y) when is_integer(X), Y > 0 -> ok.
+                "#,
+        );
+    }
+
+    #[test]
+    fn clauses_reported_independently() {
+        check_diagnostics(
+            r#"
+               -module(main).
+               foo(A, B) -> A;
+               %%     ^ 💡 warning: this variable is unused
+               foo(A, B) when B -> ok;
+               %%  ^ 💡 warning: this variable is unused
+               foo(A, B) -> {A, B}.
+            "#,
+        );
+    }
+
     #[test]
     fn more_than_one_clause() {
         check_diagnostics(
@@ -15,12 +15,14 @@ use elp_ide_db::elp_base_db::FileId;
 use elp_ide_db::source_change::SourceChange;
 use elp_ide_db::SearchScope;
 use elp_ide_db::SymbolDefinition;
+use elp_syntax::ast;
 use elp_syntax::ast::AstNode;
 use fxhash::FxHashMap;
 use fxhash::FxHashSet;
 use hir::db::MinDefDatabase;
 use hir::InFile;
 use hir::IncludeAttribute;
+use hir::IncludeAttributeId;
 use hir::Semantic;
 use text_edit::TextEdit;
 
@@ -36,48 +38,120 @@ pub(crate) fn unused_includes(
     file_id: FileId,
 ) {
     let form_list = db.file_form_list(file_id);
-    let mut cache = Default::default();
+    let needed = needed_includes(sema, db, file_id);
     for (include_idx, attr) in form_list.includes() {
-        let in_file = InFile::new(file_id, include_idx);
-        if let Some(include_file_id) = db.resolve_include(in_file) {
-            if is_file_used(sema, db, include_file_id, file_id, &mut cache) {
-                continue;
-            }
+        if needed.contains(&include_idx) {
+            continue;
+        }
+
+        let path = match attr {
+            IncludeAttribute::Include { path, .. } => path,
+            IncludeAttribute::IncludeLib { path, .. } => path,
+        };
+
+        let source_file = db.parse(file_id);
+        let inc_text_rage = attr
+            .form_id()
+            .get(&source_file.tree())
+            .syntax()
+            .text_range();
+
+        let mut edit_builder = TextEdit::builder();
+        edit_builder.delete(inc_text_rage.clone());
+        let edit = edit_builder.finish();
+
+        let diagnostic = Diagnostic::new(
+            DiagnosticCode::UnusedInclude,
+            format!("Unused file: {}", path),
+            inc_text_rage.clone(),
+        )
+        .severity(Severity::Warning)
+        .with_fixes(Some(vec![fix(
+            "remove_unused_include",
+            "Remove unused include",
+            SourceChange::from_text_edit(file_id, edit),
+            inc_text_rage,
+        )]));
+
+        log::debug!("Found unused include {:?}", path);
+
+        diagnostics.push(diagnostic);
+    }
+}
 
-            let path = match attr {
-                IncludeAttribute::Include { path, .. } => path,
-                IncludeAttribute::IncludeLib { path, .. } => path,
-            };
-
-            let source_file = db.parse(file_id);
-            let inc_text_rage = attr
-                .form_id()
-                .get(&source_file.tree())
-                .syntax()
-                .text_range();
-
-            let mut edit_builder = TextEdit::builder();
-            edit_builder.delete(inc_text_rage.clone());
-            let edit = edit_builder.finish();
-
-            let diagnostic = Diagnostic::new(
-                DiagnosticCode::UnusedInclude,
-                format!("Unused file: {}", path),
-                inc_text_rage.clone(),
-            )
-            .severity(Severity::Warning)
-            .with_fixes(Some(vec![fix(
-                "remove_unused_include",
-                "Remove unused include",
-                SourceChange::from_text_edit(file_id, edit),
-                inc_text_rage,
-            )]));
-
-            log::debug!("Found unused include {:?}", path);
-
-            diagnostics.push(diagnostic);
+/// The includes of `file_id` which contribute something it uses,
+/// directly or through the headers they include themselves. Includes
+/// which can't be resolved are kept, as we can't tell.
+pub(crate) fn needed_includes(
+    sema: &Semantic,
+    db: &dyn MinDefDatabase,
+    file_id: FileId,
+) -> Vec<IncludeAttributeId> {
+    let form_list = db.file_form_list(file_id);
+    let conditional_macros = conditional_macros(db, file_id);
+    let mut cache = Default::default();
+    form_list
+        .includes()
+        .filter(
+            |(include_idx, _)| match db.resolve_include(InFile::new(file_id, *include_idx)) {
+                Some(include_file_id) => is_file_used(
+                    sema,
+                    db,
+                    include_file_id,
+                    file_id,
+                    &conditional_macros,
+                    &mut cache,
+                ),
+                None => true,
+            },
+        )
+        .map(|(include_idx, _)| include_idx)
+        .collect()
+}
+
+/// The names of the macros tested by `-ifdef` and `-ifndef`, or used in
+/// forms between `-if`, `-ifdef` or `-ifndef` and `-endif`. Only one
+/// branch is compiled, and we don't know which one, so a header defining
+/// one of these is conservatively considered used.
+fn conditional_macros(db: &dyn MinDefDatabase, file_id: FileId) -> FxHashSet<String> {
+    let source_file = db.parse(file_id).tree();
+    let mut res = FxHashSet::default();
+    let mut depth = 0;
+    for form in source_file.forms() {
+        let name = match &form {
+            ast::Form::PreprocessorDirective(directive) => match directive {
+                ast::PreprocessorDirective::PpIfdef(ifdef) => {
+                    depth += 1;
+                    ifdef.name()
+                }
+                ast::PreprocessorDirective::PpIfndef(ifndef) => {
+                    depth += 1;
+                    ifndef.name()
+                }
+                ast::PreprocessorDirective::PpIf(_) => {
+                    depth += 1;
+                    None
+                }
+                ast::PreprocessorDirective::PpEndif(_) => {
+                    depth -= 1;
+                    None
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        res.extend(name.map(|name| name.syntax().text().to_string()));
+        if depth > 0 {
+            res.extend(
+                form.syntax()
+                    .descendants()
+                    .filter_map(ast::MacroCallExpr::cast)
+                    .filter_map(|call| call.name())
+                    .map(|name| name.syntax().text().to_string()),
+            );
         }
     }
+    res
 }
 
 fn is_file_used(
@@ -85,6 +159,7 @@ fn is_file_used(
     db: &dyn MinDefDatabase,
     include_file_id: FileId,
     target: FileId,
+    conditional_macros: &FxHashSet<String>,
     cache: &mut FxHashMap<FileId, bool>,
 ) -> bool {
     if let Some(used) = cache.get(&include_file_id) {
@@ -169,10 +244,11 @@ fn is_file_used(
         }
 
         for (_, macro_def) in def_map.get_macros() {
-            if SymbolDefinition::Define(macro_def.clone())
-                .usages(&sema)
-                .set_scope(&scope)
-                .at_least_one()
+            if conditional_macros.contains(macro_def.define.name.name().as_str())
+                || SymbolDefinition::Define(macro_def.clone())
+                    .usages(&sema)
+                    .set_scope(&scope)
+                    .at_least_one()
             {
                 cache.insert(file_id, true);
                 return true;
@@ -218,6 +294,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn optimise_includes_used_include_with_conditional_macro() {
+        check_diagnostics(
+            r#"
+//- /include/foo.hrl include_path:/include
+  -define(FOO,3).
+//- /include/bar.hrl include_path:/include
+  -define(BAR,3).
+//- /src/foo.erl
+  -module(foo).
+  -include("foo.hrl").
+  -include("bar.hrl").
+  -ifndef(FOO).
+  -define(FOO,1).
+  -endif.
+  -ifdef(TEST).
+  -define(BAR,1).
+  bar() -> ?BAR.
+  -endif.
+  foo() -> ?FOO.
+        "#,
+        );
+    }
+
     #[test]
     fn optimise_includes_unused_include_with_function() {
         check_diagnostics(
@@ -98,7 +98,7 @@ impl ToDocumentSymbol for FunctionDef {
             range,
             selection_range,
             deprecated: self.deprecated,
-            detail: None,
+            detail: self.spec_signature_string(db),
             children,
         }
     }
@@ -334,6 +334,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_function_spec() {
+        check(
+            r#"~
+   -module(main).
+   -export([ a/2, b/1]).
+   -spec a(integer(),
+           atom()) -> ok.
+   a(_, _) -> ok.
+%% ^ Function | a/2 | a(integer(), atom()) -> ok
+%% ^ Function | a(_, _) | a/2
+   -spec b(integer()) -> integer(); (atom()) -> atom().
+   b(X) -> X.
+%% ^ Function | b/1 | b(integer()) -> integer(); b(atom()) -> atom()
+%% ^ Function | b(X) | b/1
+"#,
+        );
+    }
+
     #[test]
     fn test_header_file() {
         check(
@@ -9,6 +9,7 @@
 
 use elp_ide_db::elp_base_db::FileId;
 use elp_ide_db::RootDatabase;
+use elp_syntax::ast;
 use elp_syntax::AstNode;
 use elp_syntax::TextRange;
 use hir::Semantic;
@@ -17,6 +18,8 @@ use hir::Semantic;
 pub enum FoldKind {
     Function,
     Record,
+    /// Between `%% region` and `%% endregion` marker comments
+    Region,
 }
 
 #[derive(Debug)]
@@ -27,7 +30,8 @@ pub struct Fold {
 
 // Feature: Folding
 //
-// Defines folding regions for functions.
+// Defines folding regions for functions, records, and for the code between
+// `%% region` and `%% endregion` comments.
 pub(crate) fn folding_ranges(db: &RootDatabase, file_id: FileId) -> Vec<Fold> {
     let mut folds = Vec::new();
     let sema = Semantic::new(db);
@@ -46,9 +50,49 @@ pub(crate) fn folding_ranges(db: &RootDatabase, file_id: FileId) -> Vec<Fold> {
             range: def.source(db).syntax().text_range(),
         })
     }
+    // Region markers
+    let source_file = sema.parse(file_id).value;
+    let mut open_regions = Vec::new();
+    for comment in source_file
+        .syntax()
+        .descendants()
+        .filter_map(ast::Comment::cast)
+    {
+        let range = comment.syntax().text_range();
+        match region_marker(&comment.syntax().text().to_string()) {
+            Some(RegionMarker::Start) => open_regions.push(range),
+            Some(RegionMarker::End) => {
+                // An `endregion` without a matching `region` is ignored
+                if let Some(start) = open_regions.pop() {
+                    folds.push(Fold {
+                        kind: FoldKind::Region,
+                        range: start.cover(range),
+                    })
+                }
+            }
+            None => {}
+        }
+    }
     folds
 }
 
+enum RegionMarker {
+    Start,
+    End,
+}
+
+/// Recognise `%% region` and `%% endregion` comments, optionally followed by
+/// a description, e.g. `%% region Generated code`.
+fn region_marker(comment: &str) -> Option<RegionMarker> {
+    let text = comment.trim_start_matches('%').trim();
+    let (word, _description) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    match word {
+        "region" => Some(RegionMarker::Start),
+        "endregion" => Some(RegionMarker::End),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use elp_ide_db::elp_base_db::fixture::extract_tags;
@@ -81,7 +125,7 @@ mod tests {
             );
 
             let kind = match fold.kind {
-                FoldKind::Function | FoldKind::Record => "region",
+                FoldKind::Function | FoldKind::Record | FoldKind::Region => "region",
             };
             assert_eq!(kind, &attr.unwrap());
         }
@@ -123,6 +167,51 @@ mod tests {
 <fold region>two() ->
   ok,
   ok.</fold>
+"#,
+        );
+    }
+
+    #[test]
+    fn test_region_markers() {
+        check(
+            r#"
+-module(my_module).
+
+<fold region>%% region Generated
+<fold region>one() ->
+  ok.</fold>
+%% endregion</fold>
+"#,
+        );
+    }
+
+    #[test]
+    fn test_nested_region_markers() {
+        check(
+            r#"
+-module(my_module).
+
+<fold region>%% region
+<fold region>%% region inner
+-define(A, 1).
+%% endregion</fold>
+-define(B, 2).
+%% endregion</fold>
+"#,
+        );
+    }
+
+    #[test]
+    fn test_unbalanced_region_markers() {
+        check(
+            r#"
+-module(my_module).
+
+%% endregion
+-define(A, 1).
+%% region
+-define(B, 2).
+%% regional news
 "#,
         );
     }
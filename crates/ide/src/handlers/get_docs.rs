@@ -8,24 +8,930 @@
  */
 
 use elp_ide_db::docs::Doc;
+use elp_ide_db::docs::Documentation;
 use elp_ide_db::elp_base_db::FilePosition;
 use elp_ide_db::elp_base_db::FileRange;
+use elp_ide_db::elp_base_db::SourceDatabase;
 use elp_ide_db::find_best_token;
+use elp_ide_db::LineIndexDatabase;
 use elp_ide_db::RootDatabase;
+use elp_ide_db::SymbolClass;
+use elp_ide_db::SymbolDefinition;
+use elp_syntax::algo;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxToken;
+use hir::BuiltInMacro;
+use hir::InFile;
+use hir::Literal;
+use hir::NameArity;
 use hir::Semantic;
+use itertools::Itertools;
 
 pub(crate) fn get_doc_at_position(
     db: &RootDatabase,
     position: FilePosition,
 ) -> Option<(Doc, FileRange)> {
     let sema = Semantic::new(db);
-    let docs = elp_ide_db::docs::Documentation::new(db, &sema);
+    let docs = Documentation::new(db, &sema);
     let token = find_best_token(&sema, position)?;
 
     let range = FileRange {
         file_id: token.file_id,
         range: token.value.text_range(),
     };
+    if let Some(doc) = built_in_macro_doc(db, &sema, &token) {
+        return Some((doc, range));
+    }
+    if let Some(doc) = operator_doc(&token.value) {
+        return Some((doc, range));
+    }
+    if let Some(doc) = error_reason_doc(&token.value) {
+        return Some((doc, range));
+    }
+    if let Some(doc) = include_doc(&sema, &token) {
+        return Some((doc, range));
+    }
+    if let Some(doc) = import_doc(&sema, &docs, &token) {
+        return Some((doc, range));
+    }
     let doc = Doc::from_reference(&docs, &token);
     doc.map(|d| (d, range))
 }
+
+/// The docs shown on hover: those of `get_doc_at_position`, followed by
+/// a summary of the clauses and arities when hovering a function.
+pub(crate) fn get_hover_doc_at_position(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<(Doc, FileRange)> {
+    let doc = get_doc_at_position(db, position);
+    let sema = Semantic::new(db);
+    let token = find_best_token(&sema, position)?;
+    let range = FileRange {
+        file_id: token.file_id,
+        range: token.value.text_range(),
+    };
+    match (doc, function_summary(&sema, &token)) {
+        (Some((doc, range)), Some(summary)) => Some((
+            Doc::new(format!("{}\n\n-----\n\n{}", doc.markdown_text(), summary)),
+            range,
+        )),
+        (Some(doc), None) => Some(doc),
+        (None, summary) => summary.map(|summary| (Doc::new(summary), range)),
+    }
+}
+
+/// A structural overview of the function referred to by `token`, such as
+/// `3 clauses, arities: foo/1, foo/2`, listing the arities only when
+/// the module defines several. A clause coming from a macro call counts
+/// once.
+fn function_summary(sema: &Semantic, token: &InFile<SyntaxToken>) -> Option<String> {
+    let def = match SymbolClass::classify(sema, token.clone())?
+        .into_iter()
+        .exactly_one()
+    {
+        Ok(SymbolDefinition::Function(def)) => def,
+        _ => return None,
+    };
+    let clauses = def.source(sema.db.upcast()).clauses().count();
+    let mut summary = match clauses {
+        1 => "1 clause".to_string(),
+        n => format!("{n} clauses"),
+    };
+    let def_map = sema.def_map(def.file.file_id);
+    let arities: Vec<&NameArity> = def_map
+        .get_functions()
+        .keys()
+        .filter(|name| name.name() == def.function.name.name())
+        .sorted_by_key(|name| name.arity())
+        .collect();
+    if arities.len() > 1 {
+        summary.push_str(&format!(", arities: {}", arities.iter().join(", ")));
+    }
+    Some(summary)
+}
+
+/// Show the value a built-in macro such as `?MODULE` expands to. The
+/// values used when lowering `?LINE`, `?FILE` and `?MACHINE` do not
+/// depend on the environment, so the actual ones are computed here, and
+/// `?OTP_RELEASE` is only described as it depends on the compiler.
+fn built_in_macro_doc(
+    db: &RootDatabase,
+    sema: &Semantic,
+    token: &InFile<SyntaxToken>,
+) -> Option<Doc> {
+    let call = token
+        .value
+        .parent_ancestors()
+        .find_map(ast::MacroCallExpr::cast)?;
+    if !call
+        .name()?
+        .syntax()
+        .text_range()
+        .contains_range(token.value.text_range())
+    {
+        return None;
+    }
+    let (built_in, value) = sema.built_in_macro_value(token.with_value(&call))?;
+    let value = match built_in {
+        BuiltInMacro::LINE => {
+            let line_index = db.file_line_index(token.file_id);
+            let line_col = line_index.line_col(call.syntax().text_range().start());
+            (line_col.line + 1).to_string()
+        }
+        BuiltInMacro::FILE => {
+            let source_root = db.source_root(db.file_source_root(token.file_id));
+            let path = source_root.path_for_file(&token.file_id)?;
+            format!("{:?}", path.to_string())
+        }
+        BuiltInMacro::MACHINE => "'BEAM'".to_string(),
+        BuiltInMacro::OTP_RELEASE => {
+            return Some(Doc::new(
+                "```erlang\n?OTP_RELEASE\n```\n\n\
+                 The major version of the OTP release the module is compiled with"
+                    .to_string(),
+            ));
+        }
+        _ => match value {
+            Literal::String(string) => format!("{:?}", string),
+            Literal::Char(char) => format!("${}", char),
+            Literal::Atom(atom) => sema.db.lookup_atom(atom).to_quoted_string(),
+            Literal::Integer(int) => int.to_string(),
+            Literal::Float(bits) => f64::from_bits(bits).to_string(),
+        },
+    };
+    Some(Doc::new(format!(
+        "```erlang\n?{} = {}\n```",
+        built_in.name().name(),
+        value
+    )))
+}
+
+/// The documentation and detail of a completion item, filled in when the
+/// client resolves the item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionDocs {
+    pub documentation: Option<Doc>,
+    pub detail: Option<String>,
+}
+
+/// The docs for the completion of the definition starting at `position`:
+/// the documentation and spec of a function, or the text of a macro,
+/// record or type definition.
+pub(crate) fn get_completion_docs(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<CompletionDocs> {
+    let sema = Semantic::new(db);
+    let source_file = sema.parse(position.file_id);
+    let form = algo::find_node_at_offset::<ast::Form>(source_file.value.syntax(), position.offset)?;
+    let definition = match &form {
+        ast::Form::FunDecl(fun) => return function_completion_docs(db, &sema, position, fun),
+        ast::Form::PreprocessorDirective(ast::PreprocessorDirective::PpDefine(_))
+        | ast::Form::RecordDecl(_)
+        | ast::Form::TypeAlias(_)
+        | ast::Form::Opaque(_) => form.syntax().text().to_string(),
+        _ => return None,
+    };
+    Some(CompletionDocs {
+        documentation: Some(Doc::new(format!("```erlang\n{}\n```", definition.trim()))),
+        detail: None,
+    })
+}
+
+fn function_completion_docs(
+    db: &RootDatabase,
+    sema: &Semantic,
+    position: FilePosition,
+    fun: &ast::FunDecl,
+) -> Option<CompletionDocs> {
+    let name = fun.clauses().find_map(|clause| match clause {
+        ast::FunctionOrMacroClause::FunctionClause(clause) => clause.name(),
+        ast::FunctionOrMacroClause::MacroCallExpr(_) => None,
+    })?;
+    let token = name.syntax().first_token()?;
+    let docs = Documentation::new(db, sema);
+    let documentation = Doc::from_reference(&docs, &InFile::new(position.file_id, token));
+
+    let def_map = sema.def_map(position.file_id);
+    let detail = def_map
+        .get_functions()
+        .values()
+        .find(|def| {
+            def.file.file_id == position.file_id
+                && def.source(sema.db.upcast()).syntax() == fun.syntax()
+        })
+        .and_then(|def| def_map.get_spec(&def.function.name))
+        .map(|spec| {
+            spec.source(sema.db.upcast())
+                .syntax()
+                .text()
+                .to_string()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+    Some(CompletionDocs {
+        documentation,
+        detail,
+    })
+}
+
+/// Show the meaning and precedence of a unary or binary operator, and the
+/// BIF it corresponds to
+fn operator_doc(token: &SyntaxToken) -> Option<Doc> {
+    let op = token.text();
+    let unary = match token.parent()?.kind() {
+        SyntaxKind::UNARY_OP_EXPR => true,
+        SyntaxKind::BINARY_OP_EXPR => false,
+        _ => return None,
+    };
+    // Precedence levels follow the Erlang reference manual, 1 binds tightest
+    let (meaning, precedence) = match (op, unary) {
+        ("+", true) => ("Unary plus", 1),
+        ("-", true) => ("Unary minus", 1),
+        ("bnot", true) => ("Bitwise complement of an integer", 1),
+        ("not", true) => ("Boolean negation", 1),
+        ("*", false) => ("Multiplication", 2),
+        ("/", false) => ("Floating point division, always returns a float", 2),
+        ("div", false) => ("Integer division, truncated towards zero", 2),
+        ("rem", false) => ("Integer remainder, with the sign of the dividend", 2),
+        ("band", false) => ("Bitwise and of two integers", 2),
+        ("and", false) => ("Boolean and, evaluating both operands", 2),
+        ("+", false) => ("Addition", 3),
+        ("-", false) => ("Subtraction", 3),
+        ("bor", false) => ("Bitwise or of two integers", 3),
+        ("bxor", false) => ("Bitwise exclusive or of two integers", 3),
+        ("bsl", false) => ("Arithmetic bit shift left", 3),
+        ("bsr", false) => ("Arithmetic bit shift right", 3),
+        ("or", false) => ("Boolean or, evaluating both operands", 3),
+        ("xor", false) => ("Boolean exclusive or", 3),
+        ("++", false) => (
+            "List concatenation, copies the left operand so its cost is linear in its length",
+            4,
+        ),
+        ("--", false) => (
+            "List subtraction, removes the first occurrence of each element of the right \
+             operand, its cost is proportional to the product of the lengths",
+            4,
+        ),
+        ("==", false) => ("Equal to, `1 == 1.0` is true", 5),
+        ("/=", false) => ("Not equal to, `1 /= 1.0` is false", 5),
+        ("=<", false) => ("Less than or equal to", 5),
+        ("<", false) => ("Less than", 5),
+        (">=", false) => ("Greater than or equal to", 5),
+        (">", false) => ("Greater than", 5),
+        ("=:=", false) => ("Exactly equal to, `1 =:= 1.0` is false", 5),
+        ("=/=", false) => ("Exactly not equal to, `1 =/= 1.0` is true", 5),
+        ("andalso", false) => ("Short-circuit boolean and", 6),
+        ("orelse", false) => ("Short-circuit boolean or", 7),
+        ("!", false) => (
+            "Message send, returns the message. Sending to a registered name which \
+             does not exist raises `badarg`",
+            8,
+        ),
+        _ => return None,
+    };
+    let (code, bif) = match (op, unary) {
+        (_, true) => (format!("{op} A"), format!("BIF `erlang:'{op}'/1`")),
+        ("andalso" | "orelse", false) => (format!("A {op} B"), "Not a function".to_string()),
+        ("!", false) => ("Dest ! Msg".to_string(), "BIF `erlang:send/2`".to_string()),
+        (_, false) => (format!("A {op} B"), format!("BIF `erlang:'{op}'/2`")),
+    };
+    Some(Doc::new(format!(
+        "```erlang\n{code}\n```\n\n{meaning}.\n\n{bif}, precedence {precedence} (1 binds tightest)"
+    )))
+}
+
+/// Describe a runtime error reason raised by the runtime system, such as
+/// `badarg` or `{badmatch, V}`, given as the first argument of
+/// `error/1,2` or `exit/1`. Other reasons are left alone, as their meaning
+/// is up to the application.
+fn error_reason_doc(token: &SyntaxToken) -> Option<Doc> {
+    let atom = ast::Atom::cast(token.parent()?)?;
+    let name = atom.text()?;
+    let (tagged, description) = runtime_error_reason(&name)?;
+    let reason = if tagged {
+        let tuple = ast::Tuple::cast(atom.syntax().parent()?)?;
+        if tuple.expr().next()?.syntax() != atom.syntax() {
+            return None;
+        }
+        tuple.syntax().clone()
+    } else {
+        atom.syntax().clone()
+    };
+    let args = ast::ExprArgs::cast(reason.parent()?)?;
+    if args.args().next()?.syntax() != &reason {
+        return None;
+    }
+    let arity = args.args().count();
+    let call = ast::Call::cast(args.syntax().parent()?)?;
+    let atom_text = |expr: Option<ast::ExprMax>| match expr? {
+        ast::ExprMax::Atom(atom) => atom.text(),
+        _ => None,
+    };
+    let fun = match call.expr()? {
+        ast::Expr::ExprMax(ast::ExprMax::Atom(fun)) => fun.text()?,
+        ast::Expr::Remote(remote) => {
+            if atom_text(remote.module()?.module()).as_deref() != Some("erlang") {
+                return None;
+            }
+            atom_text(remote.fun())?
+        }
+        _ => return None,
+    };
+    match (fun.as_str(), arity) {
+        ("error", 1 | 2) | ("exit", 1) => {}
+        _ => return None,
+    }
+    let code = if tagged {
+        format!("{{{name}, _}}")
+    } else {
+        name
+    };
+    Some(Doc::new(format!("```erlang\n{code}\n```\n\n{description}")))
+}
+
+/// Whether the reason is a tuple tagged with the atom, and its description,
+/// for the error reasons documented in the Errors and Error Handling chapter
+/// of the Erlang reference manual, and common exit reasons
+fn runtime_error_reason(name: &str) -> Option<(bool, &'static str)> {
+    let res = match name {
+        "badarg" => (
+            false,
+            "Bad argument. The argument is of wrong data type, or is otherwise badly formed.",
+        ),
+        "badarith" => (false, "Bad argument in an arithmetic expression."),
+        "function_clause" => (
+            false,
+            "No matching function clause is found when evaluating a function call.",
+        ),
+        "if_clause" => (
+            false,
+            "No true branch is found when evaluating an `if` expression.",
+        ),
+        "undef" => (
+            false,
+            "The function cannot be found when evaluating a function call.",
+        ),
+        "timeout_value" => (
+            false,
+            "The timeout value in a `receive ... after` expression is evaluated to \
+             something else than an integer or `infinity`.",
+        ),
+        "system_limit" => (false, "A system limit has been reached."),
+        "noproc" => (
+            false,
+            "The process does not exist, for example when calling a process which is \
+             not alive.",
+        ),
+        "normal" => (
+            false,
+            "The process terminated normally. Linked processes do not exit when \
+             receiving it as an exit signal.",
+        ),
+        "killed" => (
+            false,
+            "The process was killed by an untrappable `kill` exit signal.",
+        ),
+        "badmatch" => (
+            true,
+            "Evaluation of a match expression failed. The value did not match.",
+        ),
+        "case_clause" => (
+            true,
+            "No matching branch is found when evaluating a `case` expression. The value \
+             did not match.",
+        ),
+        "try_clause" => (
+            true,
+            "No matching branch is found when evaluating the of-section of a `try` \
+             expression. The value did not match.",
+        ),
+        "badfun" => (
+            true,
+            "Bad type of a fun. The value was expected to be a fun.",
+        ),
+        "badarity" => (true, "A fun is applied to the wrong number of arguments."),
+        "badkey" => (true, "The key does not exist in the map."),
+        "badmap" => (true, "The value was expected to be a map."),
+        "badrecord" => (
+            true,
+            "The value was expected to be a record of another type.",
+        ),
+        _ => return None,
+    };
+    Some(res)
+}
+
+/// Show where the header of an `-include` or `-include_lib` attribute was
+/// found, or where it was looked for if it could not be resolved
+fn include_doc(sema: &Semantic, token: &InFile<SyntaxToken>) -> Option<Doc> {
+    let form = token.value.parent_ancestors().find_map(ast::Form::cast)?;
+    let resolved = match &form {
+        ast::Form::PreprocessorDirective(ast::PreprocessorDirective::PpInclude(include)) => {
+            sema.to_def(token.with_value(include))
+        }
+        ast::Form::PreprocessorDirective(ast::PreprocessorDirective::PpIncludeLib(include)) => {
+            sema.to_def(token.with_value(include))
+        }
+        _ => return None,
+    };
+    let text = match resolved {
+        Some(file) => {
+            let db = sema.db;
+            let path = db
+                .source_root(db.file_source_root(file.file_id))
+                .path_for_file(&file.file_id)?
+                .to_string();
+            match db.file_app_name(file.file_id) {
+                Some(app_name) => format!("Resolved to `{path}` in application `{app_name}`"),
+                None => format!("Resolved to `{path}`"),
+            }
+        }
+        None => {
+            let search_paths = sema.include_search_paths(token.with_value(&form));
+            if search_paths.is_empty() {
+                "Not found".to_string()
+            } else {
+                let search_paths = search_paths
+                    .iter()
+                    .map(|path| format!("- `{path}`"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                format!("Not found, searched:\n\n{search_paths}")
+            }
+        }
+    };
+    Some(Doc::new(text))
+}
+
+/// Show the module a function in an `-import` attribute comes from,
+/// followed by the documentation of the function if there is any
+fn import_doc(sema: &Semantic, docs: &Documentation, token: &InFile<SyntaxToken>) -> Option<Doc> {
+    let fa = token.value.parent_ancestors().find_map(ast::Fa::cast)?;
+    let imported = sema.imported_function(token.with_value(&fa))?;
+    let mut text = format!("```erlang\n{}:{}\n```", imported.module, imported.name);
+    if imported.module_file.is_none() {
+        text.push_str(&format!("\n\nModule `{}` not found", imported.module));
+    } else if imported.def.is_none() {
+        text.push_str(&format!(
+            "\n\nFunction `{}` not found in module `{}`",
+            imported.name, imported.module
+        ));
+    } else if let Some(doc) = Doc::from_reference(docs, token) {
+        text.push_str("\n\n");
+        text.push_str(doc.markdown_text());
+    }
+    Some(Doc::new(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_ide_db::elp_base_db::fixture::WithFixture;
+    use elp_ide_db::RootDatabase;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use super::get_completion_docs;
+    use super::get_doc_at_position;
+    use super::get_hover_doc_at_position;
+
+    #[track_caller]
+    fn check(fixture: &str, expect: Expect) {
+        let (db, position) = RootDatabase::with_position(fixture);
+        let actual = get_hover_doc_at_position(&db, position)
+            .map(|(doc, _range)| doc.markdown_text().to_string())
+            .unwrap_or_default();
+        expect.assert_eq(&actual);
+    }
+
+    #[track_caller]
+    fn check_completion_docs(fixture: &str, expect: Expect) {
+        let (db, position) = RootDatabase::with_position(fixture);
+        let actual = match get_completion_docs(&db, position) {
+            Some(docs) => format!(
+                "detail: {:?}\n{}",
+                docs.detail,
+                docs.documentation
+                    .map(|doc| doc.markdown_text().to_string())
+                    .unwrap_or_default()
+            ),
+            None => String::new(),
+        };
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn hover_module_macro() {
+        check(
+            r#"
+-module(main).
+foo() -> ?MOD~ULE.
+"#,
+            expect![[r#"
+                ```erlang
+                ?MODULE = main
+                ```"#]],
+        );
+    }
+
+    #[test]
+    fn hover_line_macro() {
+        check(
+            r#"
+-module(main).
+
+foo() -> ?LI~NE.
+"#,
+            expect![[r#"
+                ```erlang
+                ?LINE = 3
+                ```"#]],
+        );
+    }
+
+    #[test]
+    fn hover_file_macro() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+foo() -> ?FI~LE.
+"#,
+            expect![[r#"
+                ```erlang
+                ?FILE = "/src/main.erl"
+                ```"#]],
+        );
+    }
+
+    #[test]
+    fn hover_otp_release_macro() {
+        check(
+            r#"
+-module(main).
+foo() -> ?OTP_REL~EASE.
+"#,
+            expect![[r#"
+                ```erlang
+                ?OTP_RELEASE
+                ```
+
+                The major version of the OTP release the module is compiled with"#]],
+        );
+    }
+
+    #[test]
+    fn hover_function_arity_macro() {
+        check(
+            r#"
+-module(main).
+foo(A, B) -> ?FUNCTION_AR~ITY.
+"#,
+            expect![[r#"
+                ```erlang
+                ?FUNCTION_ARITY = 2
+                ```"#]],
+        );
+    }
+
+    #[test]
+    fn hover_include() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-include("fo~o.hrl").
+//- /src/foo.hrl
+-define(FOO, 1).
+"#,
+            expect!["Resolved to `/src/foo.hrl` in application `test-fixture`"],
+        );
+    }
+
+    #[test]
+    fn hover_include_lib() {
+        check(
+            r#"
+//- /main/src/main.erl app:main
+-module(main).
+-include_lib("another/include/head~er.hrl").
+//- /another-app/include/header.hrl app:another
+-define(FOO, 1).
+"#,
+            expect!["Resolved to `/another-app/include/header.hrl` in application `another`"],
+        );
+    }
+
+    #[test]
+    fn hover_include_unresolved() {
+        let (db, position) = RootDatabase::with_position(
+            r#"
+//- /src/main.erl
+-module(main).
+-include("miss~ing.hrl").
+"#,
+        );
+        let (doc, _range) = get_doc_at_position(&db, position).unwrap();
+        let text = doc.markdown_text();
+        assert!(
+            text.starts_with("Not found, searched:\n\n- `/src/missing.hrl`"),
+            "{}",
+            text
+        );
+    }
+
+    #[test]
+    fn hover_import() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-import(another, [fo~o/1]).
+//- /src/another.erl
+-module(another).
+-export([foo/1]).
+foo(_) -> ok.
+"#,
+            expect![[r#"
+                ```erlang
+                another:foo/1
+                ```"#]],
+        );
+    }
+
+    #[test]
+    fn hover_import_unresolved() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-import(another, [foo/1]).
+-import(missing, [ba~r/0]).
+//- /src/another.erl
+-module(another).
+"#,
+            expect![[r#"
+                ```erlang
+                missing:bar/0
+                ```
+
+                Module `missing` not found"#]],
+        );
+    }
+
+    #[test]
+    fn hover_function_summary() {
+        check(
+            r#"
+-module(main).
+-define(CLAUSE, foo(undefined) -> error).
+-spec foo(atom()) -> ok | error.
+foo(a) -> ok;
+?CLAUSE;
+foo(_) -> ok.
+foo(A, B) -> {A, B}.
+bar() -> f~oo(a).
+"#,
+            expect![[r#"
+                ```erlang
+                -spec foo(atom()) -> ok | error.
+                ```
+
+                -----
+
+                3 clauses, arities: foo/1, foo/2"#]],
+        );
+    }
+
+    #[test]
+    fn hover_function_summary_without_docs() {
+        check(
+            r#"
+-module(main).
+foo(_) -> ok.
+bar() -> f~oo(a).
+"#,
+            expect!["1 clause"],
+        );
+    }
+
+    #[test]
+    fn hover_remote_ref() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+foo() ->
+    F = another:b~ar,
+    F().
+//- /src/another.erl
+-module(another).
+-export([bar/0]).
+-spec bar() -> ok.
+bar() -> ok.
+"#,
+            expect![[r#"
+                ```erlang
+                -spec bar() -> ok.
+                ```
+
+                -----
+
+                1 clause"#]],
+        );
+    }
+
+    #[test]
+    fn hover_binary_operator() {
+        check(
+            r#"
+-module(main).
+foo(X) -> X bs~l 2.
+"#,
+            expect![[r#"
+                ```erlang
+                A bsl B
+                ```
+
+                Arithmetic bit shift left.
+
+                BIF `erlang:'bsl'/2`, precedence 3 (1 binds tightest)"#]],
+        );
+    }
+
+    #[test]
+    fn hover_unary_operator() {
+        check(
+            r#"
+-module(main).
+foo(X) -> ~-X.
+"#,
+            expect![[r#"
+                ```erlang
+                - A
+                ```
+
+                Unary minus.
+
+                BIF `erlang:'-'/1`, precedence 1 (1 binds tightest)"#]],
+        );
+    }
+
+    #[test]
+    fn hover_list_operator() {
+        check(
+            r#"
+-module(main).
+foo(X, Y) -> X +~+ Y.
+"#,
+            expect![[r#"
+                ```erlang
+                A ++ B
+                ```
+
+                List concatenation, copies the left operand so its cost is linear in its length.
+
+                BIF `erlang:'++'/2`, precedence 4 (1 binds tightest)"#]],
+        );
+    }
+
+    #[test]
+    fn hover_send_operator() {
+        check(
+            r#"
+-module(main).
+foo(Pid) -> Pid ~! hello.
+"#,
+            expect![[r#"
+                ```erlang
+                Dest ! Msg
+                ```
+
+                Message send, returns the message. Sending to a registered name which does not exist raises `badarg`.
+
+                BIF `erlang:send/2`, precedence 8 (1 binds tightest)"#]],
+        );
+    }
+
+    #[test]
+    fn hover_andalso() {
+        check(
+            r#"
+-module(main).
+foo(X, Y) -> X and~also Y.
+"#,
+            expect![[r#"
+                ```erlang
+                A andalso B
+                ```
+
+                Short-circuit boolean and.
+
+                Not a function, precedence 6 (1 binds tightest)"#]],
+        );
+    }
+
+    #[test]
+    fn hover_error_reason() {
+        check(
+            r#"
+-module(main).
+foo(X) -> erlang:error(bad~arg, [X]).
+"#,
+            expect![[r#"
+                ```erlang
+                badarg
+                ```
+
+                Bad argument. The argument is of wrong data type, or is otherwise badly formed."#]],
+        );
+        check(
+            r#"
+-module(main).
+foo(X) -> exit({case_cl~ause, X}).
+"#,
+            expect![[r#"
+                ```erlang
+                {case_clause, _}
+                ```
+
+                No matching branch is found when evaluating a `case` expression. The value did not match."#]],
+        );
+    }
+
+    #[test]
+    fn hover_error_reason_not_runtime() {
+        // User defined reasons and runtime reasons used differently are
+        // not described
+        check(
+            r#"
+-module(main).
+foo(X) -> error({badarg~s, X}).
+"#,
+            expect![""],
+        );
+        check(
+            r#"
+-module(main).
+foo(X) -> error({bad~arg, X}).
+"#,
+            expect![""],
+        );
+        check(
+            r#"
+-module(main).
+foo(X) -> throw(bad~arg).
+"#,
+            expect![""],
+        );
+    }
+
+    #[test]
+    fn completion_docs_function_spec() {
+        check_completion_docs(
+            r#"
+-module(main).
+-spec foo(Arg :: integer()) ->
+    ok.
+~foo(_) -> ok.
+"#,
+            expect![[r#"
+                detail: Some("-spec foo(Arg :: integer()) -> ok.")
+            "#]],
+        );
+    }
+
+    #[test]
+    fn completion_docs_record() {
+        check_completion_docs(
+            r#"
+-module(main).
+~-record(rec, {field = 1 :: integer()}).
+"#,
+            expect![[r#"
+                detail: None
+                ```erlang
+                -record(rec, {field = 1 :: integer()}).
+                ```"#]],
+        );
+    }
+
+    #[test]
+    fn completion_docs_macro() {
+        check_completion_docs(
+            r#"
+-module(main).
+~-define(TIMEOUT(X), X * 1000).
+"#,
+            expect![[r#"
+                detail: None
+                ```erlang
+                -define(TIMEOUT(X), X * 1000).
+                ```"#]],
+        );
+    }
+}
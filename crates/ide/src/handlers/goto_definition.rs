@@ -639,6 +639,26 @@ baz() -> ko().
         )
     }
 
+    #[test]
+    fn remote_type_from_spec_to_header() {
+        check(
+            r#"
+//- /src/mod1.erl
+-module(mod1).
+-include("mod1.hrl").
+
+//- /src/mod1.hrl
+-type foo(T) :: [T].
+%%    ^^^^^^
+
+//- /src/mod2.erl
+-module(mod2).
+-spec bar() -> mod1:f~oo(atom()).
+bar() -> [].
+"#,
+        )
+    }
+
     #[test]
     fn local_type_alias_from_record_def() {
         check(
@@ -818,6 +838,35 @@ foo() -> Another:b~ar().
         )
     }
 
+    #[test]
+    fn imported_call() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-import(another, [bar/0]).
+
+foo() -> b~ar().
+
+//- /src/another.erl
+-module(another).
+-export([bar/0]).
+  bar() -> ok.
+%%^^^
+"#,
+        );
+
+        check_unresolved(
+            r#"
+//- /src/main.erl
+-module(main).
+-import(missing, [bar/0]).
+
+foo() -> b~ar().
+"#,
+        )
+    }
+
     #[test]
     fn remote_call_to_header() {
         check(
@@ -943,6 +992,94 @@ foo() -> fun a~nother:foo/0.
         )
     }
 
+    #[test]
+    fn external_fun_otp() {
+        check(
+            r#"
+//- /opt/lib/stdlib-3.17/src/lists.erl otp_app:/opt/lib/stdlib-3.17
+-module(lists).
+-export([map/2]).
+  map(F, L) -> [F(X) || X <- L].
+%%^^^
+//- /src/main.erl
+-module(main).
+
+foo() -> fun lists:m~ap/2.
+"#,
+        )
+    }
+
+    #[test]
+    fn external_fun_variable_module() {
+        check_unresolved(
+            r#"
+//- /opt/lib/stdlib-3.17/src/lists.erl otp_app:/opt/lib/stdlib-3.17
+-module(lists).
+-export([map/2]).
+map(F, L) -> [F(X) || X <- L].
+//- /src/main.erl
+-module(main).
+
+foo(M) -> fun M:m~ap/2.
+"#,
+        )
+    }
+
+    #[test]
+    fn remote_ref() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+
+foo() ->
+    F = another:b~ar,
+    F().
+
+//- /src/another.erl
+-module(another).
+  bar() -> ok.
+%%^^^
+"#,
+        );
+
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+
+foo() ->
+    F = another:b~ar,
+    F(1).
+
+//- /src/another.erl
+-module(another).
+  bar() -> ok.
+%%^^^
+  bar(X) -> X.
+%%^^^
+"#,
+        );
+    }
+
+    #[test]
+    fn remote_ref_variable_module() {
+        check_unresolved(
+            r#"
+//- /src/main.erl
+-module(main).
+
+foo(M) ->
+    F = M:b~ar,
+    F().
+
+//- /src/another.erl
+-module(another).
+bar() -> ok.
+"#,
+        );
+    }
+
     #[test]
     fn spec() {
         check(
@@ -1044,6 +1181,61 @@ foo(#r~ec.field) -> ok.
         );
     }
 
+    #[test]
+    fn record_in_record_field_default() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+
+-record(inner, {value}).
+%%      ^^^^^
+
+-record(outer, {inner = #in~ner{} }).
+"#,
+        );
+
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+
+-record(inner, {value}).
+%%              ^^^^^
+
+-record(outer, {inner = #inner{val~ue = 1}, other = #inner.value}).
+"#,
+        );
+
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+
+-record(inner, {value}).
+%%      ^^^^^
+
+-record(outer, {inner = #inner{}, index = #in~ner.value}).
+"#,
+        );
+    }
+
+    #[test]
+    fn record_in_record_field_default_after_macro_field() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-define(FIELD, first).
+
+-record(inner, {value}).
+%%      ^^^^^
+
+-record(outer, {?FIELD = 1, second = #in~ner{}}).
+"#,
+        );
+    }
+
     #[test]
     fn record_name_to_header() {
         check(
@@ -1168,6 +1360,23 @@ foo() -> #rec{field1 = 1, f~ield3 = ok, field2 = ""}.
         );
     }
 
+    #[test]
+    fn record_field_name_to_header() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-include("header.hrl").
+
+foo(X) -> X#rec.f~ield2.
+
+//- /src/header.hrl
+-record(rec, {field1, field2 = 1 :: integer()}).
+%%                    ^^^^^^
+"#,
+        );
+    }
+
     #[test]
     fn export_entry() {
         check(
@@ -1183,6 +1392,37 @@ foo() -> #rec{field1 = 1, f~ield3 = ok, field2 = ""}.
         )
     }
 
+    #[test]
+    fn dialyzer_function() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+
+-dialyzer({nowarn_function, [bar/0, f~oo/1]}).
+
+  foo(_) -> ok.
+%%^^^
+bar() -> ok.
+"#,
+        )
+    }
+
+    #[test]
+    fn dialyzer_function_tuple() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+
+-dialyzer([no_return, {[no_match], {f~oo, 1}}]).
+
+  foo(_) -> ok.
+%%^^^
+"#,
+        )
+    }
+
     #[test]
     fn import_entry() {
         check(
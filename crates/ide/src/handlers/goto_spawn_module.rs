@@ -0,0 +1,205 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Heuristic navigation to the callback module passed to well-known
+//! functions starting processes, e.g. `gen_server:start_link(?MODULE, Args, [])`
+//! or `spawn(mod, fun, Args)`.
+
+use elp_ide_db::elp_base_db::FilePosition;
+use elp_ide_db::elp_base_db::ModuleName;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_ide_db::find_best_token;
+use elp_ide_db::RootDatabase;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use hir::Semantic;
+
+use crate::navigation_target::module_nav_target;
+use crate::navigation_target::NavigationTarget;
+use crate::RangeInfo;
+
+pub(crate) fn goto_spawn_module(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let sema = Semantic::new(db);
+    let token = find_best_token(&sema, position)?;
+    let arg = token.value.parent_ancestors().find(|node| {
+        node.parent()
+            .map_or(false, |parent| parent.kind() == SyntaxKind::EXPR_ARGS)
+    })?;
+    let args = ast::ExprArgs::cast(arg.parent()?)?;
+    let call = ast::Call::cast(args.syntax().parent()?)?;
+    let arg = ast::Expr::cast(arg)?;
+    let (module, function) = call_target(&call)?;
+    let index = module_arg_index(module.as_deref(), &function, args.args().count())?;
+    if args.args().nth(index)?.syntax() != arg.syntax() {
+        return None;
+    }
+
+    let module_name = match &arg {
+        ast::Expr::ExprMax(ast::ExprMax::Atom(atom)) => ModuleName::new(&atom.text()?),
+        ast::Expr::ExprMax(ast::ExprMax::MacroCallExpr(macro_call))
+            if macro_call.args().is_none() && macro_call.name()?.text()? == "MODULE" =>
+        {
+            sema.module_name(position.file_id)?
+        }
+        // Non-literal modules can't be resolved
+        _ => return None,
+    };
+    let project_id = db
+        .app_data(db.file_source_root(position.file_id))?
+        .project_id;
+    let target = module_nav_target(db, project_id, &module_name)?;
+    Some(RangeInfo::new(arg.syntax().text_range(), vec![target]))
+}
+
+/// The module (if qualified) and function called, when they are literal atoms.
+fn call_target(call: &ast::Call) -> Option<(Option<String>, String)> {
+    let atom_text = |expr: ast::ExprMax| match expr {
+        ast::ExprMax::Atom(atom) => atom.text(),
+        _ => None,
+    };
+    match call.expr()? {
+        ast::Expr::Remote(remote) => {
+            let module = atom_text(remote.module()?.module()?)?;
+            let function = atom_text(remote.fun()?)?;
+            Some((Some(module), function))
+        }
+        ast::Expr::ExprMax(fun) => Some((None, atom_text(fun)?)),
+        _ => None,
+    }
+}
+
+/// Position of the callback module argument of the functions we recognise.
+/// Unqualified calls are the auto-imported `erlang` BIFs.
+fn module_arg_index(module: Option<&str>, function: &str, arity: usize) -> Option<usize> {
+    match (module, function, arity) {
+        (Some("gen_server" | "gen_statem" | "gen_event" | "gen_fsm"), _, _)
+            if matches!(function, "start" | "start_link" | "start_monitor") =>
+        {
+            match arity {
+                3 => Some(0),
+                4 => Some(1),
+                _ => None,
+            }
+        }
+        (Some("supervisor" | "supervisor_bridge"), "start_link", 2) => Some(0),
+        (Some("supervisor" | "supervisor_bridge"), "start_link", 3) => Some(1),
+        (Some("proc_lib"), "spawn" | "spawn_link", 3) => Some(0),
+        (Some("proc_lib"), "spawn" | "spawn_link", 4) => Some(1),
+        (Some("proc_lib"), "start" | "start_link" | "start_monitor", 3..=5) => Some(0),
+        (None | Some("erlang"), "spawn" | "spawn_link" | "spawn_monitor", 3) => Some(0),
+        (None | Some("erlang"), "spawn" | "spawn_link", 4) => Some(1),
+        (None | Some("erlang"), "spawn_opt", 4) => Some(0),
+        (None | Some("erlang"), "spawn_opt", 5) => Some(1),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+    use crate::tests::check_navs;
+
+    #[track_caller]
+    fn check(fixture: &str) {
+        let (analysis, position, expected) = fixture::annotations(fixture);
+        let navs = analysis
+            .goto_spawn_module(position)
+            .unwrap()
+            .expect("no definition found")
+            .info;
+        check_navs(navs, expected);
+    }
+
+    #[track_caller]
+    fn check_unresolved(fixture: &str) {
+        let (analysis, position) = fixture::position(fixture);
+        assert!(analysis.goto_spawn_module(position).unwrap().is_none());
+    }
+
+    #[test]
+    fn gen_server_start_link() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+start() -> gen_server:start_link({local, srv}, my_s~erver, [], []).
+//- /src/my_server.erl
+-module(my_server).
+%%      ^^^^^^^^^
+"#,
+        );
+    }
+
+    #[test]
+    fn gen_server_start_link_current_module() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+%%      ^^^^
+start() -> gen_server:start_link(?MOD~ULE, [], []).
+"#,
+        );
+    }
+
+    #[test]
+    fn spawn() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+start() ->
+    spawn(wor~ker, loop, []),
+    proc_lib:spawn_link(node(), worker, loop, []).
+//- /src/worker.erl
+-module(worker).
+%%      ^^^^^^
+"#,
+        );
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+start() ->
+    proc_lib:spawn_link(node(), wor~ker, loop, []).
+//- /src/worker.erl
+-module(worker).
+%%      ^^^^^^
+"#,
+        );
+    }
+
+    #[test]
+    fn not_module_position() {
+        check_unresolved(
+            r#"
+//- /src/main.erl
+-module(main).
+start() -> gen_server:start_link(worker, wor~ker, []).
+//- /src/worker.erl
+-module(worker).
+"#,
+        );
+    }
+
+    #[test]
+    fn non_literal_module() {
+        check_unresolved(
+            r#"
+//- /src/main.erl
+-module(main).
+start(Mod) -> gen_server:start_link(M~od, [], []).
+"#,
+        );
+    }
+}
@@ -452,6 +452,43 @@ foo~(2) -> error.
         );
     }
 
+    #[test]
+    fn test_function_remote_ref() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+foo() ->
+    F = another:ba~r,
+%%              ^^^
+    F(),
+    another:bar().
+%%          ^^^
+//- /src/another.erl
+-module(another).
+-export([bar/0]).
+  bar() -> ok.
+%%^^^def
+"#,
+        );
+
+        check(
+            r#"
+//- /src/another.erl
+-module(another).
+-export([bar/0]).
+  ba~r() -> ok.
+%%^^^def
+//- /src/main.erl
+-module(main).
+foo() ->
+    F = another:bar,
+%%              ^^^
+    F().
+"#,
+        );
+    }
+
     #[test]
     fn test_functions_import_1() {
         check(
@@ -540,6 +577,37 @@ foo() -> ?FOO.
 //- /src/another_macro.erl
 -define(FOO, 2).
 
+foo() -> ?FOO.
+"#,
+        );
+    }
+
+    #[test]
+    fn test_macro_transitive_include() {
+        check(
+            r#"
+//- /src/main.hrl
+-define(FOO~, 1).
+%%      ^^^def
+
+//- /src/wrapper.hrl
+-include("main.hrl").
+
+bar() -> ?FOO.
+%%        ^^^
+
+//- /src/main.erl
+-include("wrapper.hrl").
+
+foo() -> ?FOO.
+%%        ^^^
+
+//- /src/shadowed.hrl
+-define(FOO, 2).
+
+//- /src/shadowing.erl
+-include("shadowed.hrl").
+
 foo() -> ?FOO.
 "#,
         );
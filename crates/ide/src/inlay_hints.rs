@@ -17,16 +17,19 @@ use hir::Semantic;
 use itertools::Itertools;
 use smallvec::smallvec;
 use smallvec::SmallVec;
+mod macro_expansion;
 mod param_name;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct InlayHintsConfig {
     pub parameter_hints: bool,
+    pub macro_expansion_hints: bool,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum InlayKind {
     Parameter,
+    MacroExpansion,
 }
 
 #[derive(Debug)]
@@ -190,6 +193,7 @@ impl fmt::Debug for InlayHintLabelPart {
 // Available hints are:
 //
 // * names of function arguments
+// * expansions of short macros
 pub(crate) fn inlay_hints(
     db: &RootDatabase,
     file_id: FileId,
@@ -202,6 +206,7 @@ pub(crate) fn inlay_hints(
     let mut acc = Vec::new();
 
     param_name::hints(&mut acc, &sema, config, file_id, range_limit);
+    macro_expansion::hints(&mut acc, &sema, config, file_id, range_limit);
 
     acc
 }
@@ -216,6 +221,7 @@ mod tests {
 
     pub(super) const DISABLED_CONFIG: InlayHintsConfig = InlayHintsConfig {
         parameter_hints: false,
+        macro_expansion_hints: false,
     };
 
     #[track_caller]
@@ -0,0 +1,158 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use hir::BuiltInMacro;
+use hir::InFile;
+use hir::ResolvedMacro;
+use hir::Semantic;
+
+use crate::InlayHint;
+use crate::InlayHintLabel;
+use crate::InlayHintsConfig;
+use crate::InlayKind;
+
+/// Expansions longer than this many characters are truncated
+const MAX_EXPANSION_LEN: usize = 30;
+
+pub(super) fn hints(
+    res: &mut Vec<InlayHint>,
+    sema: &Semantic,
+    config: &InlayHintsConfig,
+    file_id: FileId,
+    range_limit: Option<TextRange>,
+) -> Option<()> {
+    if !config.macro_expansion_hints {
+        return None;
+    }
+    let source_file = sema.parse(file_id);
+    for call in source_file
+        .value
+        .syntax()
+        .descendants()
+        .filter_map(ast::MacroCallExpr::cast)
+    {
+        let range = call.syntax().text_range();
+        if let Some(range_limit) = range_limit {
+            if !range_limit.contains_range(range) {
+                continue;
+            }
+        }
+        if let Some(expansion) = expansion(sema, InFile::new(file_id, &call)) {
+            res.push(InlayHint {
+                range,
+                kind: InlayKind::MacroExpansion,
+                label: InlayHintLabel::simple(truncate(&expansion), None, None),
+            });
+        }
+    }
+    Some(())
+}
+
+/// The text to show for a macro call, using the resolution recorded when
+/// lowering the enclosing form. Calls which were not lowered, such as the
+/// ones in other macro definitions, get no hint.
+fn expansion(sema: &Semantic, call: InFile<&ast::MacroCallExpr>) -> Option<String> {
+    let (_body, body_source) = sema.find_body(call.file_id, call.value.syntax())?;
+    match body_source.resolved_macro(call)? {
+        // The lowered value is a placeholder
+        ResolvedMacro::BuiltIn(BuiltInMacro::LINE) => {
+            let text = sema.db.file_text(call.file_id);
+            let offset = usize::from(call.value.syntax().text_range().start());
+            Some((text[..offset].matches('\n').count() + 1).to_string())
+        }
+        ResolvedMacro::BuiltIn(BuiltInMacro::OTP_RELEASE) => None,
+        ResolvedMacro::BuiltIn(_) | ResolvedMacro::Predefined(_) => {
+            let (_name, expansion) = sema.expand(call)?;
+            Some(expansion)
+        }
+        // For user macros, show the replacement text rather than a specific
+        // expansion, with the parameters if there are any
+        ResolvedMacro::User(def) => {
+            let form_list = sema.db.file_form_list(def.file_id);
+            let source = sema.parse(def.file_id);
+            let define = form_list[def.value].form_id.get(&source.value);
+            let replacement = define.replacement()?.syntax().text().to_string();
+            match define.lhs()?.args() {
+                Some(_) => {
+                    let args: Vec<String> = define.args().map(|arg| arg.to_string()).collect();
+                    Some(format!("({}) -> {}", args.join(", "), replacement))
+                }
+                None => Some(replacement),
+            }
+        }
+    }
+}
+
+fn truncate(expansion: &str) -> String {
+    let expansion = expansion.split_whitespace().collect::<Vec<_>>().join(" ");
+    if expansion.chars().count() > MAX_EXPANSION_LEN {
+        let truncated: String = expansion.chars().take(MAX_EXPANSION_LEN).collect();
+        format!("{}…", truncated.trim_end())
+    } else {
+        expansion
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::inlay_hints::tests::check_with_config;
+    use crate::inlay_hints::tests::DISABLED_CONFIG;
+    use crate::inlay_hints::InlayHintsConfig;
+
+    #[track_caller]
+    fn check_expansions(fixture: &str) {
+        check_with_config(
+            InlayHintsConfig {
+                macro_expansion_hints: true,
+                ..DISABLED_CONFIG
+            },
+            fixture,
+        );
+    }
+
+    #[test]
+    fn macro_expansion_hints_user() {
+        check_expansions(
+            r#"
+-module(main).~
+-define(TIMEOUT, 5000).
+-define(ADD(X, Y), X + Y).
+-define(LONG, {a_long_atom, another_long_atom, [1, 2, 3]}).
+main() ->
+    {?TIMEOUT,
+  %% ^^^^^^^^ 5000
+     ?ADD(1, 2),
+  %% ^^^^^^^^^^ (X, Y) -> X + Y
+     ?LONG}.
+  %% ^^^^^ {a_long_atom, another_long_ato…
+"#,
+        );
+    }
+
+    #[test]
+    fn macro_expansion_hints_built_in() {
+        check_expansions(
+            r#"
+-module(main).~
+main() ->
+    {?MODULE,
+  %% ^^^^^^^ 'main'
+     ?FUNCTION_NAME,
+  %% ^^^^^^^^^^^^^^ 'main'
+     ?LINE,
+  %% ^^^^^ 7
+     ?OTP_RELEASE}.
+"#,
+        );
+    }
+}
@@ -0,0 +1,221 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The invalid forms a `TransitiveInvalid` eqWAlizer diagnostic depends
+//! on, resolved to their declarations.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::FileRange;
+use elp_ide_db::elp_base_db::ProjectId;
+use elp_ide_db::elp_base_db::SourceDatabaseExt;
+use elp_ide_db::eqwalizer_report::Location;
+use elp_ide_db::eqwalizer_report::ModuleReport;
+use elp_ide_db::EqwalizerDatabase;
+use elp_ide_db::LineIndex;
+use elp_ide_db::LineIndexDatabase;
+use elp_ide_db::RootDatabase;
+use elp_syntax::AstNode;
+use elp_syntax::SmolStr;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use hir::Name;
+use hir::NameArity;
+use hir::Semantic;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidReference {
+    /// As shown by eqWAlizer: `t/1`, `mod:t/1` or `#rec{}`
+    pub name: SmolStr,
+    /// The name of the referenced declaration, if it could be resolved
+    pub target: Option<FileRange>,
+}
+
+/// The code of the eqWAlizer diagnostics of `TransitiveInvalid` forms,
+/// the only ones with invalid references.
+pub const TRANSITIVE_INVALID_CODE: &str = "reference_to_invalid_type";
+
+/// The references of the `TransitiveInvalid` form of `file_id` whose
+/// diagnostic covers `range`.
+pub(crate) fn invalid_references(
+    db: &RootDatabase,
+    project_id: ProjectId,
+    file_id: FileId,
+    range: TextRange,
+) -> Vec<InvalidReference> {
+    let report = match db.eqwalizer_report(project_id, file_id) {
+        Some(report) => report,
+        None => return vec![],
+    };
+    let text = db.file_text(file_id);
+    let line_index = db.file_line_index(file_id);
+    let sema = Semantic::new(db);
+    references_in_range(&report, &text, &line_index, range)
+        .map(|name| InvalidReference {
+            name: name.clone(),
+            target: resolve_reference(&sema, file_id, name),
+        })
+        .collect()
+}
+
+/// The references of the `TransitiveInvalid` forms starting in `range`.
+/// Several forms can start on the same line, so their columns matter too.
+fn references_in_range<'a>(
+    report: &'a ModuleReport,
+    text: &'a str,
+    line_index: &'a LineIndex,
+    range: TextRange,
+) -> impl Iterator<Item = &'a SmolStr> {
+    report
+        .invalid_forms
+        .iter()
+        .filter(move |form| {
+            form.reason == "TransitiveInvalid"
+                && location_offset(text, line_index, &form.location)
+                    .map_or(false, |offset| range.contains(offset))
+        })
+        .flat_map(|form| form.references.iter())
+}
+
+/// The offset of a one-based report location, whose column counts
+/// characters.
+fn location_offset(text: &str, line_index: &LineIndex, location: &Location) -> Option<TextSize> {
+    let line_start = line_index.line_at(location.line.checked_sub(1)? as usize)?;
+    let column = location.column.checked_sub(1)? as usize;
+    let len: usize = text[usize::from(line_start)..]
+        .chars()
+        .take(column)
+        .map(char::len_utf8)
+        .sum();
+    Some(line_start + TextSize::from(len as u32))
+}
+
+fn resolve_reference(sema: &Semantic, file_id: FileId, reference: &str) -> Option<FileRange> {
+    if let Some(record) = reference
+        .strip_prefix('#')
+        .and_then(|it| it.strip_suffix("{}"))
+    {
+        let def = sema
+            .def_map(file_id)
+            .get_record(&Name::from_erlang_service(record))?
+            .clone();
+        let name = def.source(sema.db.upcast()).name()?;
+        return Some(FileRange {
+            file_id: def.file.file_id,
+            range: name.syntax().text_range(),
+        });
+    }
+    let (module, type_name) = match reference.split_once(':') {
+        Some((module, type_name)) => (Some(module), type_name),
+        None => (None, reference),
+    };
+    let (name, arity) = type_name.rsplit_once('/')?;
+    let name = NameArity::new(Name::from_erlang_service(name), arity.parse().ok()?);
+    let file_id = match module {
+        Some(module) => sema.resolve_module_name(file_id, module)?.file.file_id,
+        None => file_id,
+    };
+    let def = sema.def_map(file_id).get_type(&name)?.clone();
+    let type_name = def.source(sema.db.upcast()).type_name()?;
+    Some(FileRange {
+        file_id: def.file.file_id,
+        range: type_name.syntax().text_range(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_ide_db::elp_base_db::fixture::WithFixture;
+    use elp_ide_db::elp_base_db::SourceDatabase;
+    use elp_ide_db::eqwalizer_report::FormKind;
+    use elp_ide_db::eqwalizer_report::InvalidFormReport;
+    use elp_ide_db::eqwalizer_report::Location;
+    use elp_ide_db::eqwalizer_report::ModuleReport;
+    use elp_ide_db::LineIndex;
+    use elp_ide_db::RootDatabase;
+    use elp_syntax::SmolStr;
+    use elp_syntax::TextRange;
+    use hir::Semantic;
+
+    use super::references_in_range;
+    use super::resolve_reference;
+
+    #[track_caller]
+    fn check(fixture: &str, reference: &str, expected: Option<&str>) {
+        let (db, files) = RootDatabase::with_many_files(fixture);
+        let sema = Semantic::new(&db);
+        let actual = resolve_reference(&sema, files[0], reference).map(|range| {
+            let text = db.file_text(range.file_id);
+            text[range.range].to_string()
+        });
+        assert_eq!(actual.as_deref(), expected);
+    }
+
+    #[test]
+    fn local_references() {
+        let fixture = r#"
+//- /src/main.erl
+-module(main).
+-type invalid() :: unknown:t().
+-opaque opaque(X) :: {X, invalid()}.
+-record(rec, {a :: invalid()}).
+"#;
+        check(fixture, "invalid/0", Some("invalid()"));
+        check(fixture, "opaque/1", Some("opaque(X)"));
+        check(fixture, "#rec{}", Some("rec"));
+        check(fixture, "missing/0", None);
+    }
+
+    #[test]
+    fn remote_references() {
+        let fixture = r#"
+//- /src/main.erl
+-module(main).
+-type t() :: other:invalid().
+//- /src/other.erl
+-module(other).
+-type invalid() :: unknown:t().
+"#;
+        check(fixture, "other:invalid/0", Some("invalid()"));
+        check(fixture, "unknown:t/0", None);
+    }
+
+    #[test]
+    fn references_by_range() {
+        let text = "-module(main).\n-type a() :: x:t(). -type b() :: y:t().\n-type c() :: ok.\n";
+        let line_index = LineIndex::new(text);
+        let form = |id: &str, line, column, reason: &str, references: &[&str]| InvalidFormReport {
+            kind: FormKind::Type,
+            id: SmolStr::new(id),
+            location: Location { line, column },
+            reason: SmolStr::new(reason),
+            references: references.iter().map(|it| SmolStr::new(it)).collect(),
+        };
+        let report = ModuleReport {
+            module: SmolStr::new("main"),
+            valid_forms: vec![],
+            invalid_forms: vec![
+                form("a/0", 2, 1, "TransitiveInvalid", &["x:t/0"]),
+                form("b/0", 2, 21, "TransitiveInvalid", &["y:t/0"]),
+                form("c/0", 3, 1, "UnknownId", &[]),
+            ],
+            error: None,
+        };
+        let references = |form: &str| {
+            let start = text.find(form).unwrap() as u32;
+            let end = start + form.len() as u32;
+            let range = TextRange::new(start.into(), end.into());
+            references_in_range(&report, text, &line_index, range)
+                .map(|it| it.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(references("-type a() :: x:t()."), vec!["x:t/0"]);
+        assert_eq!(references("-type b() :: y:t()."), vec!["y:t/0"]);
+        assert_eq!(references("-type c() :: ok."), Vec::<String>::new());
+    }
+}
@@ -31,10 +31,12 @@ use elp_ide_db::elp_base_db::FilePosition;
 use elp_ide_db::elp_base_db::FileRange;
 use elp_ide_db::elp_base_db::ModuleIndex;
 use elp_ide_db::elp_base_db::ModuleName;
+use elp_ide_db::elp_base_db::OtpApp;
 use elp_ide_db::elp_base_db::ProjectData;
 use elp_ide_db::elp_base_db::ProjectId;
 use elp_ide_db::elp_base_db::SourceDatabase;
 use elp_ide_db::elp_base_db::SourceDatabaseExt;
+use elp_ide_db::eqwalizer_report::ModuleReport;
 use elp_ide_db::erlang_service::ParseResult;
 use elp_ide_db::label::Label;
 use elp_ide_db::rename::RenameError;
@@ -56,6 +58,7 @@ use elp_syntax::AstNode;
 use expand_macro::ExpandedMacro;
 use handlers::get_docs;
 use handlers::goto_definition;
+use handlers::goto_spawn_module;
 use handlers::references;
 use hir::db::MinDefDatabase;
 use hir::DefMap;
@@ -75,7 +78,10 @@ mod extend_selection;
 mod folding_ranges;
 mod handlers;
 mod inlay_hints;
+mod invalid_references;
+mod matching_clause;
 mod navigation_target;
+mod record_to_map;
 mod rename;
 mod runnables;
 mod signature_help;
@@ -103,17 +109,26 @@ pub use elp_syntax::TextRange;
 pub use elp_syntax::TextSize;
 pub use folding_ranges::Fold;
 pub use folding_ranges::FoldKind;
+pub use handlers::get_docs::CompletionDocs;
 pub use handlers::references::ReferenceSearchResult;
 pub use highlight_related::HighlightedRange;
+pub use hir::FileMetrics;
 pub use inlay_hints::InlayHint;
 pub use inlay_hints::InlayHintLabel;
 pub use inlay_hints::InlayHintLabelPart;
 pub use inlay_hints::InlayHintsConfig;
 pub use inlay_hints::InlayKind;
 pub use inlay_hints::InlayTooltip;
+pub use invalid_references::InvalidReference;
+pub use invalid_references::TRANSITIVE_INVALID_CODE;
+pub use matching_clause::MatchingClause;
 pub use navigation_target::NavigationTarget;
+pub use record_to_map::RecordMigrationError;
+pub use record_to_map::UnmigratableSite;
 pub use runnables::Runnable;
 pub use runnables::RunnableKind;
+pub use runnables::TestFunction;
+pub use runnables::TestFunctionKind;
 pub use signature_help::SignatureHelp;
 pub use syntax_highlighting::tags::Highlight;
 pub use syntax_highlighting::tags::HlMod;
@@ -218,6 +233,26 @@ impl Analysis {
         self.with_db(|db| db.eqwalizer_stats(project_id, file_id))
     }
 
+    /// The forms of the given module that eqWAlizer considers valid or invalid
+    pub fn eqwalizer_report(
+        &self,
+        project_id: ProjectId,
+        file_id: FileId,
+    ) -> Cancellable<Option<Arc<ModuleReport>>> {
+        self.with_db(|db| db.eqwalizer_report(project_id, file_id))
+    }
+
+    /// The invalid forms depended on by the `TransitiveInvalid` form whose
+    /// eqWAlizer diagnostic covers `range`
+    pub fn eqwalizer_invalid_references(
+        &self,
+        project_id: ProjectId,
+        file_id: FileId,
+        range: TextRange,
+    ) -> Cancellable<Vec<InvalidReference>> {
+        self.with_db(|db| invalid_references::invalid_references(db, project_id, file_id, range))
+    }
+
     /// Computes the set of EDoc diagnostics for the given file.
     pub fn edoc_diagnostics(&self, file_id: FileId) -> Cancellable<Vec<(FileId, Vec<Diagnostic>)>> {
         self.with_db(|db| diagnostics::edoc_diagnostics(db, file_id))
@@ -226,9 +261,10 @@ impl Analysis {
     /// Computes the set of parse server diagnostics for the given file.
     pub fn erlang_service_diagnostics(
         &self,
+        config: &DiagnosticsConfig,
         file_id: FileId,
     ) -> Cancellable<Vec<(FileId, Vec<Diagnostic>)>> {
-        self.with_db(|db| diagnostics::erlang_service_diagnostics(db, file_id))
+        self.with_db(|db| diagnostics::erlang_service_diagnostics(db, config, file_id))
     }
 
     /// Low-level access to eqwalizer
@@ -289,10 +325,25 @@ impl Analysis {
         self.with_db(|db| db.module_index(project_id).file_for_module(module))
     }
 
+    /// Navigation target for the `-module` attribute of the given module
+    pub fn module_nav_target(
+        &self,
+        project_id: ProjectId,
+        module: &ModuleName,
+    ) -> Cancellable<Option<NavigationTarget>> {
+        self.with_db(|db| navigation_target::module_nav_target(db, project_id, module))
+    }
+
     pub fn expand_macro(&self, position: FilePosition) -> Cancellable<Option<ExpandedMacro>> {
         self.with_db(|db| expand_macro::expand_macro(db, position))
     }
 
+    /// The clause of the function called at `position` matching its
+    /// literal arguments
+    pub fn matching_clause(&self, position: FilePosition) -> Cancellable<Option<MatchingClause>> {
+        self.with_db(|db| matching_clause::matching_clause(db, position))
+    }
+
     /// Selects the next syntactic nodes encompassing the range.
     pub fn extend_selection(&self, frange: FileRange) -> Cancellable<TextRange> {
         self.with_db(|db| extend_selection::extend_selection(db, frange))
@@ -304,6 +355,11 @@ impl Analysis {
         self.with_db(|db| document_symbols::document_symbols(db, file_id))
     }
 
+    /// Line and definition counts of a file, for project metrics
+    pub fn file_metrics(&self, file_id: FileId) -> Cancellable<FileMetrics> {
+        self.with_db(|db| db.file_metrics(file_id))
+    }
+
     /// Returns the contents of a file
     pub fn file_text(&self, file_id: FileId) -> Cancellable<Arc<String>> {
         self.with_db(|db| db.file_text(file_id))
@@ -362,6 +418,16 @@ impl Analysis {
         })
     }
 
+    /// The OTP applications available to the project, sorted by name
+    pub fn otp_apps(&self, project_id: ProjectId) -> Cancellable<Arc<Vec<OtpApp>>> {
+        self.with_db(|db| db.otp_apps(project_id))
+    }
+
+    /// Whether two files belong to the same application
+    pub fn same_app(&self, file_a: FileId, file_b: FileId) -> Cancellable<bool> {
+        self.with_db(|db| db.same_app(file_a, file_b))
+    }
+
     pub fn is_generated(&self, file_id: FileId) -> Cancellable<bool> {
         self.with_db(|db| db.is_generated(file_id))
     }
@@ -406,6 +472,15 @@ impl Analysis {
         self.with_db(|db| goto_definition::goto_definition(db, position))
     }
 
+    /// Heuristic definition of the callback module argument in calls like
+    /// `gen_server:start_link(?MODULE, Args, Opts)`
+    pub fn goto_spawn_module(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<RangeInfo<Vec<NavigationTarget>>>> {
+        self.with_db(|db| goto_spawn_module::goto_spawn_module(db, position))
+    }
+
     /// Returns the docs for the symbol at the given position
     pub fn get_docs_at_position(
         &self,
@@ -414,6 +489,24 @@ impl Analysis {
         self.with_db(|db| get_docs::get_doc_at_position(db, position))
     }
 
+    /// Returns the docs shown when hovering the given position, which for
+    /// a function include a summary of its clauses and arities
+    pub fn get_hover_docs_at_position(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<(Doc, FileRange)>> {
+        self.with_db(|db| get_docs::get_hover_doc_at_position(db, position))
+    }
+
+    /// Returns the docs for the completion of the definition at the given
+    /// position, see `Completion::position`
+    pub fn get_completion_docs(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<CompletionDocs>> {
+        self.with_db(|db| get_docs::get_completion_docs(db, position))
+    }
+
     /// Finds all usages of the reference at point.
     pub fn find_all_refs(
         &self,
@@ -444,6 +537,16 @@ impl Analysis {
         self.with_db(|db| rename::rename(db, position, new_name))
     }
 
+    /// Returns the edit rewriting the record `record` visible in `file_id`
+    /// into a map, across all the files it is used in.
+    pub fn migrate_record_to_map(
+        &self,
+        file_id: FileId,
+        record: &str,
+    ) -> Cancellable<Result<SourceChange, RecordMigrationError>> {
+        self.with_db(|db| record_to_map::record_to_map(db, file_id, record))
+    }
+
     /// Returns the set of folding ranges.
     pub fn folding_ranges(&self, file_id: FileId) -> Cancellable<Vec<Fold>> {
         self.with_db(|db| folding_ranges::folding_ranges(db, file_id))
@@ -467,6 +570,12 @@ impl Analysis {
         self.with_db(|db| call_hierarchy::outgoing_calls(db, position))
     }
 
+    /// Returns the signature of the function defined at the given position,
+    /// from its spec when it has one.
+    pub fn function_signature(&self, position: FilePosition) -> Cancellable<Option<String>> {
+        self.with_db(|db| call_hierarchy::function_signature(db, position))
+    }
+
     /// Computes parameter information at the given position.
     pub fn signature_help(
         &self,
@@ -511,6 +620,12 @@ impl Analysis {
         self.with_db(|db| runnables::runnables(db, file_id))
     }
 
+    /// Returns the tests defined in a Common Test suite or an EUnit module,
+    /// including testcases declared in a suite with no function.
+    pub fn test_functions(&self, file_id: FileId) -> Cancellable<Vec<TestFunction>> {
+        self.with_db(|db| runnables::test_functions(db, file_id))
+    }
+
     /// Return URL(s) for the documentation of the symbol under the cursor.
     pub fn external_docs(&self, position: FilePosition) -> Cancellable<Option<Vec<String>>> {
         self.with_db(|db| doc_links::external_docs(db, &position))
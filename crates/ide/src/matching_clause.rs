@@ -0,0 +1,289 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::elp_base_db::FilePosition;
+use elp_ide_db::find_best_token;
+use elp_ide_db::RootDatabase;
+use elp_ide_db::SymbolKind;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SmolStr;
+use hir::CallDef;
+use hir::Expr;
+use hir::ExprId;
+use hir::InFile;
+use hir::InFunctionBody;
+use hir::Literal;
+use hir::Pat;
+use hir::PatId;
+use hir::Semantic;
+
+use crate::NavigationTarget;
+
+#[derive(Debug)]
+pub struct MatchingClause {
+    pub target: NavigationTarget,
+    /// The clause has guards, or patterns which can't be checked
+    /// statically, so it may not be the one actually selected
+    pub ambiguous: bool,
+}
+
+// Feature: Go to Matching Clause
+//
+// For a call whose arguments are all literals, such as `foo(bar, 3)`,
+// navigates to the first clause of the callee whose patterns match them.
+// Guards are not evaluated, so a guarded clause is reported as ambiguous.
+// Available as the `elp/matchingClause` LSP request, since code actions
+// can only edit, not navigate, without client-side commands.
+//
+pub(crate) fn matching_clause(db: &RootDatabase, position: FilePosition) -> Option<MatchingClause> {
+    let sema = Semantic::new(db);
+    let token = find_best_token(&sema, position)?;
+    let call = token.value.parent_ancestors().find_map(ast::Call::cast)?;
+    let call_expr = sema.to_expr(InFile::new(
+        position.file_id,
+        &ast::Expr::Call(call.clone()),
+    ))?;
+    let args = match &call_expr[call_expr.value] {
+        Expr::Call { args, .. } if !args.is_empty() => args.clone(),
+        _ => return None,
+    };
+    if !args.iter().all(|arg| is_literal(&call_expr, *arg)) {
+        return None;
+    }
+    let def = match sema.to_def(InFile::new(position.file_id, &call))? {
+        CallDef::Function(def) => def,
+        CallDef::Type(_) => return None,
+    };
+
+    let file_id = def.file.file_id;
+    let callee = sema.to_function_body(InFile::new(file_id, def.function_id));
+    let ctx = Ctx {
+        caller: &call_expr,
+        callee: &callee,
+    };
+    let source = def.source(db);
+    for clause in source.clauses() {
+        let clause = match clause {
+            ast::FunctionOrMacroClause::FunctionClause(clause) => clause,
+            ast::FunctionOrMacroClause::MacroCallExpr(_) => continue,
+        };
+        let pats: Vec<PatId> = match clause.args() {
+            Some(pats) => pats
+                .args()
+                .map(|pat| callee.pat_id_ast(db, InFile::new(file_id, &pat)))
+                .collect::<Option<_>>()?,
+            None => continue,
+        };
+        if pats.len() != args.len() {
+            continue;
+        }
+        let res = Match::all(
+            pats.iter()
+                .zip(args.iter())
+                .map(|(pat, arg)| ctx.matches(*pat, *arg)),
+        );
+        if res == Match::No {
+            continue;
+        }
+        let name = def.function.name.name().raw();
+        return Some(MatchingClause {
+            target: NavigationTarget {
+                file_id,
+                full_range: clause.syntax().text_range(),
+                focus_range: clause.name().map(|name| name.syntax().text_range()),
+                name: SmolStr::new(format!("{name}/{}", def.function.name.arity())),
+                kind: SymbolKind::Function,
+            },
+            ambiguous: res == Match::Maybe || clause.guard().is_some(),
+        });
+    }
+    None
+}
+
+/// Literals, and tuples and lists of literals
+fn is_literal(body: &InFunctionBody<ExprId>, expr_id: ExprId) -> bool {
+    match &body[expr_id] {
+        Expr::Literal(_) => true,
+        Expr::Tuple { exprs } | Expr::List { exprs, tail: None } => {
+            exprs.iter().all(|expr| is_literal(body, *expr))
+        }
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Match {
+    Yes,
+    No,
+    /// Depends on something we don't evaluate, like a record definition or
+    /// an arithmetic expression
+    Maybe,
+}
+
+impl Match {
+    fn and(self, other: Match) -> Match {
+        match (self, other) {
+            (Match::No, _) | (_, Match::No) => Match::No,
+            (Match::Maybe, _) | (_, Match::Maybe) => Match::Maybe,
+            (Match::Yes, Match::Yes) => Match::Yes,
+        }
+    }
+
+    fn all(matches: impl Iterator<Item = Match>) -> Match {
+        matches.fold(Match::Yes, Match::and)
+    }
+}
+
+struct Ctx<'a> {
+    caller: &'a InFunctionBody<ExprId>,
+    callee: &'a InFunctionBody<()>,
+}
+
+impl Ctx<'_> {
+    /// Whether the pattern `pat_id` of the callee matches the literal
+    /// `expr_id` of the caller. Repeated variables are not checked.
+    fn matches(&self, pat_id: PatId, expr_id: ExprId) -> Match {
+        let expr = &self.caller[expr_id];
+        match &self.callee[pat_id] {
+            Pat::Var(_) => Match::Yes,
+            Pat::Missing => Match::No,
+            Pat::Literal(pat_literal) => match expr {
+                Expr::Literal(literal) if same_literal(pat_literal, literal) => Match::Yes,
+                Expr::List { .. } if matches!(pat_literal, Literal::String(_)) => Match::Maybe,
+                _ => Match::No,
+            },
+            Pat::Match { lhs, rhs } => self.matches(*lhs, expr_id).and(self.matches(*rhs, expr_id)),
+            Pat::MacroCall { expansion, .. } => self.matches(*expansion, expr_id),
+            Pat::Tuple { pats } => match expr {
+                Expr::Tuple { exprs } if exprs.len() == pats.len() => Match::all(
+                    pats.iter()
+                        .zip(exprs.iter())
+                        .map(|(pat, expr)| self.matches(*pat, *expr)),
+                ),
+                _ => Match::No,
+            },
+            Pat::List { pats, tail } => match expr {
+                Expr::List { exprs, tail: None } => {
+                    let prefix_len_ok = match tail {
+                        None => exprs.len() == pats.len(),
+                        Some(_) => exprs.len() >= pats.len(),
+                    };
+                    if !prefix_len_ok {
+                        return Match::No;
+                    }
+                    let prefix = Match::all(
+                        pats.iter()
+                            .zip(exprs.iter())
+                            .map(|(pat, expr)| self.matches(*pat, *expr)),
+                    );
+                    match tail {
+                        Some(tail) if !matches!(self.callee[*tail], Pat::Var(_)) => {
+                            prefix.and(Match::Maybe)
+                        }
+                        _ => prefix,
+                    }
+                }
+                // A string is a list of characters
+                Expr::Literal(Literal::String(_)) => Match::Maybe,
+                _ => Match::No,
+            },
+            // A record is a tuple
+            Pat::Record { .. } => match expr {
+                Expr::Tuple { .. } => Match::Maybe,
+                _ => Match::No,
+            },
+            // Literal arguments are never binaries or maps
+            Pat::Binary { .. } | Pat::Map { .. } => Match::No,
+            Pat::UnaryOp { .. } | Pat::BinaryOp { .. } | Pat::RecordIndex { .. } => Match::Maybe,
+        }
+    }
+}
+
+/// Characters are integers
+fn same_literal(lhs: &Literal, rhs: &Literal) -> bool {
+    let as_integer = |literal: &Literal| match literal {
+        Literal::Char(char) => Some(*char as i128),
+        Literal::Integer(int) => Some(*int),
+        _ => None,
+    };
+    match (as_integer(lhs), as_integer(rhs)) {
+        (Some(lhs), Some(rhs)) => lhs == rhs,
+        _ => lhs == rhs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+    use crate::tests::check_navs;
+
+    #[track_caller]
+    fn check(fixture: &str, ambiguous: bool) {
+        let (analysis, position, expected) = fixture::annotations(fixture);
+        let res = analysis
+            .matching_clause(position)
+            .unwrap()
+            .expect("no matching clause found");
+        check_navs(vec![res.target], expected);
+        assert_eq!(res.ambiguous, ambiguous);
+    }
+
+    #[track_caller]
+    fn check_none(fixture: &str) {
+        let (analysis, position) = fixture::position(fixture);
+        let res = analysis.matching_clause(position).unwrap();
+        assert!(res.is_none(), "expected no matching clause, got {res:?}");
+    }
+
+    #[test]
+    fn literal_args() {
+        check(
+            r#"
+-module(main).
+main() -> f~oo(bar, {1, "a"}).
+foo(baz, _) -> baz;
+foo(bar, {0, _}) -> zero;
+  foo(bar, {1, "a"}) -> one;
+%%^^^
+foo(_, _) -> other.
+"#,
+            false,
+        );
+    }
+
+    #[test]
+    fn guarded_clause_is_ambiguous() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+main() -> other:f~oo(3).
+//- /src/other.erl
+-module(other).
+-export([foo/1]).
+  foo(N) when N > 5 -> big;
+%%^^^
+foo(_) -> small.
+"#,
+            true,
+        );
+    }
+
+    #[test]
+    fn non_literal_args() {
+        check_none(
+            r#"
+-module(main).
+main(X) -> f~oo(bar, X).
+foo(_, _) -> ok.
+"#,
+        );
+    }
+}
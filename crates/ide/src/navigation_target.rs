@@ -13,6 +13,9 @@ use std::fmt;
 
 use elp_ide_db::elp_base_db::FileId;
 use elp_ide_db::elp_base_db::FileRange;
+use elp_ide_db::elp_base_db::ModuleName;
+use elp_ide_db::elp_base_db::ProjectId;
+use elp_ide_db::elp_base_db::SourceDatabase;
 use elp_ide_db::SymbolDefinition;
 use elp_ide_db::SymbolKind;
 use elp_syntax::ast;
@@ -77,6 +80,56 @@ impl NavigationTarget {
     }
 }
 
+/// Navigation target for the `-module` attribute of the module `name`,
+/// focused on the module name. OTP modules are found through the OTP
+/// module index folded into the project one. A module file without a
+/// `-module` attribute is targeted as a whole, starting at its beginning.
+pub fn module_nav_target(
+    db: &dyn MinDefDatabase,
+    project_id: ProjectId,
+    name: &ModuleName,
+) -> Option<NavigationTarget> {
+    let file_id = db.module_index(project_id).file_for_module(name)?;
+    let module = hir::Module {
+        file: hir::File { file_id },
+    };
+    let source = module.file.source(db.upcast());
+    let (full_range, focus_range) = match module.module_attribute(db) {
+        Some(attr) => {
+            let attr = attr.form_id.get(&source);
+            let focus_range = attr.name().map(|name| name.syntax().text_range());
+            (attr.syntax().text_range(), focus_range)
+        }
+        None => (source.syntax().text_range(), None),
+    };
+    Some(NavigationTarget {
+        file_id,
+        full_range,
+        focus_range,
+        name: SmolStr::new(name.as_str()),
+        kind: SymbolKind::Module,
+    })
+}
+
+/// Navigation target for the declaration of a record field, in the file
+/// defining the record, which may be a header. The focus range is the
+/// field name alone, without its default value or type.
+pub fn record_field_nav_target(
+    db: &dyn MinDefDatabase,
+    field: &hir::RecordFieldDef,
+) -> NavigationTarget {
+    let source = field.source(db.upcast());
+    let full_range = source.syntax().text_range();
+    let focus_range = source.name().map(|name| name.syntax().text_range());
+    NavigationTarget {
+        file_id: field.record.file.file_id,
+        full_range,
+        focus_range,
+        name: field.field.name.raw(),
+        kind: SymbolKind::RecordField,
+    }
+}
+
 pub trait ToNav {
     fn to_nav(&self, db: &dyn MinDefDatabase) -> NavigationTarget;
 }
@@ -157,17 +210,7 @@ impl ToNav for hir::RecordDef {
 
 impl ToNav for hir::RecordFieldDef {
     fn to_nav(&self, db: &dyn MinDefDatabase) -> NavigationTarget {
-        let file_id = self.record.file.file_id;
-        let source = self.source(db.upcast());
-        let full_range = source.syntax().text_range();
-        let focus_range = source.name().map(|name| name.syntax().text_range());
-        NavigationTarget {
-            file_id,
-            full_range,
-            focus_range,
-            name: self.field.name.raw(),
-            kind: SymbolKind::RecordField,
-        }
+        record_field_nav_target(db, self)
     }
 }
 
@@ -246,3 +289,80 @@ impl ToNav for hir::VarDef {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use elp_ide_db::elp_base_db::ModuleName;
+
+    use crate::fixture;
+    use crate::tests::check_navs;
+
+    #[track_caller]
+    fn check(module: &str, fixture: &str) {
+        let (analysis, position, expected) = fixture::annotations(fixture);
+        let project_id = analysis.project_id(position.file_id).unwrap().unwrap();
+        let nav = analysis
+            .module_nav_target(project_id, &ModuleName::new(module))
+            .unwrap()
+            .expect("no navigation target");
+        check_navs(vec![nav], expected);
+    }
+
+    #[test]
+    fn module_nav_target_focus_on_name() {
+        check(
+            "another",
+            r#"
+//- /src/main.erl
+-module(ma~in).
+//- /src/another.erl
+-module(another).
+%%      ^^^^^^^
+foo() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn module_nav_target_no_module_attribute() {
+        check(
+            "another",
+            r#"
+//- /src/main.erl
+-module(ma~in).
+//- /src/another.erl
+%%^file
+foo() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn module_nav_target_otp() {
+        check(
+            "lists",
+            r#"
+//- /src/main.erl
+-module(ma~in).
+//- /opt/lib/stdlib-3.17/src/lists.erl otp_app:/opt/lib/stdlib-3.17
+-module(lists).
+%%      ^^^^^
+"#,
+        );
+    }
+
+    #[test]
+    fn module_nav_target_unknown() {
+        let (analysis, position) = fixture::position(
+            r#"
+//- /src/main.erl
+-module(ma~in).
+"#,
+        );
+        let project_id = analysis.project_id(position.file_id).unwrap().unwrap();
+        let nav = analysis
+            .module_nav_target(project_id, &ModuleName::new("another"))
+            .unwrap();
+        assert!(nav.is_none());
+    }
+}
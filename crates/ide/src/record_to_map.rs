@@ -0,0 +1,565 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Project-wide migration of a record to a map. The record definition
+//! becomes a map type of the same name, and its constructions, updates,
+//! field accesses, patterns and types are rewritten into their map
+//! equivalents, in every file the record is visible in.
+//!
+//! Usages without a map equivalent, such as `#rec.field` indexes or
+//! `record_info/2` calls, block the whole migration: they are reported
+//! instead of leaving code which no longer compiles.
+
+use elp_ide_db::context_at;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::FilePosition;
+use elp_ide_db::elp_base_db::FileRange;
+use elp_ide_db::source_change::SourceChange;
+use elp_ide_db::ExprContext;
+use elp_ide_db::RootDatabase;
+use elp_ide_db::SymbolDefinition;
+use elp_syntax::ast;
+use elp_syntax::ast::AstChildren;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use elp_syntax::SyntaxToken;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use fxhash::FxHashMap;
+use hir::Name;
+use hir::NameArity;
+use hir::Semantic;
+use text_edit::TextEdit;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnmigratableSite {
+    pub range: FileRange,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordMigrationError {
+    /// No record of this name is visible in the file
+    UnknownRecord,
+    /// The usages of the record which cannot be rewritten into a map,
+    /// sorted by file and position
+    Unmigratable(Vec<UnmigratableSite>),
+}
+
+/// The changes rewriting the record `record`, visible in `file_id`, and all
+/// its usages into maps.
+pub(crate) fn record_to_map(
+    db: &RootDatabase,
+    file_id: FileId,
+    record: &str,
+) -> Result<SourceChange, RecordMigrationError> {
+    let sema = Semantic::new(db);
+    let def = sema
+        .def_map(file_id)
+        .get_record(&Name::from_erlang_service(record))
+        .cloned()
+        .ok_or(RecordMigrationError::UnknownRecord)?;
+    let decl = def.source(db);
+    let decl_range = FileRange {
+        file_id: def.file.file_id,
+        range: decl.syntax().text_range(),
+    };
+    let mut migration = Migration {
+        db,
+        record: record.to_string(),
+        type_name: def.record.name.to_quoted_string(),
+        decl_range,
+        fields: Vec::new(),
+        edits: FxHashMap::default(),
+        blocked: Vec::new(),
+    };
+    migration.definition(&sema, &decl);
+    let def = SymbolDefinition::Record(def);
+    for (file_id, names) in def.clone().usages(&sema).all().iter() {
+        for name in names {
+            migration.usage(file_id, name.syntax());
+        }
+    }
+    for (file_id, _) in def.search_scope(&sema) {
+        let source_file = sema.parse(file_id);
+        migration.unresolved_usages(file_id, source_file.value.syntax());
+    }
+    migration.finish()
+}
+
+struct Field {
+    name: String,
+    default: Option<String>,
+}
+
+/// A replacement of `range`. Replacements starting at the same offset are
+/// applied outermost first, so that the `map_get/2` calls of
+/// `X#rec.a#rec.b` nest.
+struct Edit {
+    range: TextRange,
+    depth: usize,
+    text: String,
+}
+
+struct Migration<'a> {
+    db: &'a RootDatabase,
+    record: String,
+    type_name: String,
+    decl_range: FileRange,
+    fields: Vec<Field>,
+    edits: FxHashMap<FileId, Vec<Edit>>,
+    blocked: Vec<UnmigratableSite>,
+}
+
+impl Migration<'_> {
+    /// Replaces the record definition by a map type, with the same types
+    /// for the fields
+    fn definition(&mut self, sema: &Semantic, decl: &ast::RecordDecl) {
+        let file_id = self.decl_range.file_id;
+        let type_name = NameArity::new(Name::from_erlang_service(&self.record), 0);
+        if sema.def_map(file_id).get_type(&type_name).is_some() {
+            self.block(
+                file_id,
+                decl.syntax(),
+                format!("A type `{}()` already exists", self.type_name),
+            );
+            return;
+        }
+        let mut types = Vec::new();
+        for field in decl.fields() {
+            let name = match field.name() {
+                Some(name) => name.syntax().text().to_string(),
+                None => continue,
+            };
+            let ty = field
+                .ty()
+                .and_then(|ty| ty.expr())
+                .map_or("term()".to_string(), |ty| ty.syntax().text().to_string());
+            types.push(format!("{name} := {ty}"));
+            self.fields.push(Field {
+                name,
+                default: field
+                    .expr()
+                    .and_then(|expr| expr.expr())
+                    .map(|expr| expr.syntax().text().to_string()),
+            });
+        }
+        self.edit(
+            file_id,
+            self.decl_range.range,
+            0,
+            format!("-type {}() :: #{{{}}}.", self.type_name, types.join(", ")),
+        );
+    }
+
+    /// Rewrites the usage of the record whose name is `name`
+    fn usage(&mut self, file_id: FileId, name: &SyntaxNode) {
+        if file_id == self.decl_range.file_id
+            && self.decl_range.range.contains_range(name.text_range())
+        {
+            self.block(file_id, name, "The record is used in its own definition");
+            return;
+        }
+        let node = match name.ancestors().find(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::RECORD_EXPR
+                    | SyntaxKind::RECORD_UPDATE_EXPR
+                    | SyntaxKind::RECORD_FIELD_EXPR
+                    | SyntaxKind::RECORD_INDEX_EXPR
+            )
+        }) {
+            Some(node) => node,
+            None => {
+                self.block(file_id, name, "Unsupported usage of the record");
+                return;
+            }
+        };
+        let position = FilePosition {
+            file_id,
+            offset: name.text_range().start(),
+        };
+        let context = match context_at(self.db, position) {
+            Some(context) => context,
+            None => {
+                self.block(file_id, &node, "Unsupported usage of the record");
+                return;
+            }
+        };
+        let depth = node.ancestors().count();
+        let res = match context {
+            ExprContext::Expression | ExprContext::Guard | ExprContext::Pattern => {
+                let pattern = context == ExprContext::Pattern;
+                if let Some(expr) = ast::RecordExpr::cast(node.clone()) {
+                    self.record_expr(file_id, &expr, pattern, depth)
+                } else if let Some(expr) = ast::RecordUpdateExpr::cast(node.clone()) {
+                    self.record_update_expr(file_id, &expr, depth)
+                } else if let Some(expr) = ast::RecordFieldExpr::cast(node.clone()) {
+                    self.record_field_expr(file_id, &expr, depth)
+                } else {
+                    Err("Record field indexes have no map equivalent".to_string())
+                }
+            }
+            ExprContext::Type | ExprContext::Spec => match ast::RecordExpr::cast(node.clone()) {
+                Some(expr) if expr.fields().next().is_none() => {
+                    let range = expr.syntax().text_range();
+                    self.edit(file_id, range, depth, format!("{}()", self.type_name));
+                    Ok(())
+                }
+                _ => Err("Record types with field types have no map equivalent".to_string()),
+            },
+            ExprContext::Attribute | ExprContext::MacroArg | ExprContext::Comment => {
+                Err("Usages in macro definitions or arguments cannot be rewritten".to_string())
+            }
+        };
+        if let Err(reason) = res {
+            self.block(file_id, &node, reason);
+        }
+    }
+
+    /// `#rec{a = 1}` becomes `#{a => 1, b => Default}` in expressions, and
+    /// `#{a := 1}` in patterns
+    fn record_expr(
+        &mut self,
+        file_id: FileId,
+        expr: &ast::RecordExpr,
+        pattern: bool,
+        depth: usize,
+    ) -> Result<(), String> {
+        let start = expr.syntax().text_range().start();
+        let lbrace = first_token_after(expr.syntax(), start, SyntaxKind::ANON_LBRACE)
+            .ok_or_else(|| "Incomplete record".to_string())?;
+        let op = if pattern { ":=" } else { "=>" };
+        let given = self.fields(file_id, expr.fields(), op, depth)?;
+        self.edit(
+            file_id,
+            TextRange::new(start, lbrace.text_range().end()),
+            depth,
+            "#{".to_string(),
+        );
+        if pattern {
+            return Ok(());
+        }
+        let defaults: Vec<String> = self
+            .fields
+            .iter()
+            .filter(|field| !given.contains(&field.name))
+            .map(|field| {
+                let default = field.default.as_deref().unwrap_or("undefined");
+                format!("{} => {}", field.name, default)
+            })
+            .collect();
+        if !defaults.is_empty() {
+            let rbrace = expr
+                .syntax()
+                .last_token()
+                .filter(|token| token.kind() == SyntaxKind::ANON_RBRACE)
+                .ok_or_else(|| "Incomplete record".to_string())?;
+            let separator = if given.is_empty() { "" } else { ", " };
+            let offset = rbrace.text_range().start();
+            self.edit(
+                file_id,
+                TextRange::empty(offset),
+                depth,
+                format!("{separator}{}", defaults.join(", ")),
+            );
+        }
+        Ok(())
+    }
+
+    /// `X#rec{a = 1}` becomes `X#{a := 1}`
+    fn record_update_expr(
+        &mut self,
+        file_id: FileId,
+        expr: &ast::RecordUpdateExpr,
+        depth: usize,
+    ) -> Result<(), String> {
+        let base_end = expr
+            .expr()
+            .ok_or_else(|| "Incomplete record update".to_string())?
+            .syntax()
+            .text_range()
+            .end();
+        let lbrace = first_token_after(expr.syntax(), base_end, SyntaxKind::ANON_LBRACE)
+            .ok_or_else(|| "Incomplete record update".to_string())?;
+        self.fields(file_id, expr.fields(), ":=", depth)?;
+        self.edit(
+            file_id,
+            TextRange::new(base_end, lbrace.text_range().end()),
+            depth,
+            "#{".to_string(),
+        );
+        Ok(())
+    }
+
+    /// `X#rec.a` becomes `map_get(a, X)`, which is also allowed in guards
+    fn record_field_expr(
+        &mut self,
+        file_id: FileId,
+        expr: &ast::RecordFieldExpr,
+        depth: usize,
+    ) -> Result<(), String> {
+        let incomplete = || "Incomplete record field access".to_string();
+        let base = expr.expr().ok_or_else(incomplete)?;
+        let field = expr
+            .field()
+            .and_then(|field| field.name())
+            .ok_or_else(incomplete)?;
+        let range = expr.syntax().text_range();
+        let base_end = base.syntax().text_range().end();
+        self.edit(
+            file_id,
+            TextRange::empty(range.start()),
+            depth,
+            format!("map_get({}, ", field.syntax().text()),
+        );
+        self.edit(
+            file_id,
+            TextRange::new(base_end, range.end()),
+            depth,
+            ")".to_string(),
+        );
+        Ok(())
+    }
+
+    /// Replaces the `=` of each field by `op`, returning the names of the
+    /// fields
+    fn fields(
+        &mut self,
+        file_id: FileId,
+        fields: AstChildren<ast::RecordField>,
+        op: &str,
+        depth: usize,
+    ) -> Result<Vec<String>, String> {
+        let mut names = Vec::new();
+        for field in fields {
+            let name = field
+                .name()
+                .ok_or_else(|| "Incomplete record field".to_string())?
+                .syntax()
+                .text()
+                .to_string();
+            if name == "_" {
+                return Err("Setting all fields with `_ = Value` has no map equivalent".to_string());
+            }
+            let start = field.syntax().text_range().start();
+            let eq = first_token_after(field.syntax(), start, SyntaxKind::ANON_EQ)
+                .ok_or_else(|| "Incomplete record field".to_string())?;
+            self.edit(file_id, eq.text_range(), depth, op.to_string());
+            names.push(name);
+        }
+        Ok(names)
+    }
+
+    /// Usages of the record which are not references to its definition:
+    /// `record_info/2` and `is_record/2,3` calls, which have no map
+    /// equivalent, and usages in macro definitions, which are only
+    /// resolved where the macro is used.
+    fn unresolved_usages(&mut self, file_id: FileId, syntax: &SyntaxNode) {
+        for define in syntax.descendants().filter_map(ast::PpDefine::cast) {
+            for name in define
+                .syntax()
+                .descendants()
+                .filter_map(ast::RecordName::cast)
+            {
+                let name = match name.name() {
+                    Some(name) => name,
+                    None => continue,
+                };
+                if name.syntax().text() == self.record.as_str() {
+                    let node = name.syntax().parent().and_then(|it| it.parent());
+                    self.block(
+                        file_id,
+                        node.as_ref().unwrap_or(name.syntax()),
+                        "Usages in macro definitions or arguments cannot be rewritten",
+                    );
+                }
+            }
+        }
+        let atom_text = |expr: Option<ast::ExprMax>| match expr? {
+            ast::ExprMax::Atom(atom) => atom.text(),
+            _ => None,
+        };
+        for call in syntax.descendants().filter_map(ast::Call::cast) {
+            let fun = match call.expr() {
+                Some(ast::Expr::ExprMax(ast::ExprMax::Atom(fun))) => fun.text(),
+                Some(ast::Expr::Remote(remote)) => {
+                    let module = remote
+                        .module()
+                        .and_then(|module| atom_text(module.module()));
+                    match module.as_deref() {
+                        Some("erlang") => atom_text(remote.fun()),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            let args: Vec<ast::Expr> = match call.args() {
+                Some(args) => args.args().collect(),
+                None => continue,
+            };
+            let label = match (fun.as_deref(), args.len()) {
+                (Some("record_info"), 2) => "record_info/2",
+                (Some("is_record"), 2 | 3) => "is_record",
+                _ => continue,
+            };
+            let record = match &args[1] {
+                ast::Expr::ExprMax(ast::ExprMax::Atom(atom)) => atom.text(),
+                _ => None,
+            };
+            if record.as_deref() == Some(self.record.as_str()) {
+                self.block(
+                    file_id,
+                    call.syntax(),
+                    format!("`{label}` has no map equivalent"),
+                );
+            }
+        }
+    }
+
+    fn edit(&mut self, file_id: FileId, range: TextRange, depth: usize, text: String) {
+        self.edits
+            .entry(file_id)
+            .or_default()
+            .push(Edit { range, depth, text });
+    }
+
+    fn block(&mut self, file_id: FileId, node: &SyntaxNode, reason: impl Into<String>) {
+        self.blocked.push(UnmigratableSite {
+            range: FileRange {
+                file_id,
+                range: node.text_range(),
+            },
+            reason: reason.into(),
+        });
+    }
+
+    fn finish(mut self) -> Result<SourceChange, RecordMigrationError> {
+        if !self.blocked.is_empty() {
+            self.blocked
+                .sort_by_key(|site| (site.range.file_id, site.range.range.start()));
+            // Usages in macro definitions can be found both as references
+            // and by name
+            self.blocked.dedup_by(|a, b| a.range == b.range);
+            return Err(RecordMigrationError::Unmigratable(self.blocked));
+        }
+        let mut source_change = SourceChange::default();
+        for (file_id, mut edits) in self.edits {
+            edits.sort_by_key(|edit| (edit.range.start(), edit.range.end(), edit.depth));
+            let mut builder = TextEdit::builder();
+            for edit in edits {
+                builder.replace(edit.range, edit.text);
+            }
+            source_change.insert_source_edit(file_id, builder.finish());
+        }
+        Ok(source_change)
+    }
+}
+
+/// The first token of `kind` in `node` starting at or after `offset`
+fn first_token_after(node: &SyntaxNode, offset: TextSize, kind: SyntaxKind) -> Option<SyntaxToken> {
+    node.descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .find(|token| token.kind() == kind && token.text_range().start() >= offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_ide_db::elp_base_db::fixture::WithFixture;
+    use elp_ide_db::elp_base_db::SourceDatabase;
+    use elp_ide_db::RootDatabase;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use super::record_to_map;
+    use super::RecordMigrationError;
+
+    #[track_caller]
+    fn check(fixture: &str, record: &str, expect: Expect) {
+        let (db, files) = RootDatabase::with_many_files(fixture);
+        let actual = match record_to_map(&db, files[0], record) {
+            Ok(source_change) => files
+                .iter()
+                .filter_map(|file_id| {
+                    let edit = source_change.get_source_edit(*file_id)?;
+                    let mut text = db.file_text(*file_id).to_string();
+                    edit.apply(&mut text);
+                    Some(text)
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Err(RecordMigrationError::UnknownRecord) => "unknown record".to_string(),
+            Err(RecordMigrationError::Unmigratable(sites)) => sites
+                .iter()
+                .map(|site| {
+                    let text = db.file_text(site.range.file_id);
+                    format!("{}: {}\n", &text[site.range.range], site.reason)
+                })
+                .collect(),
+        };
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn migrate_record() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-include("rec.hrl").
+-spec new(atom()) -> #rec{}.
+new(A) -> #rec{a = A}.
+get_b(#rec{b = B}) -> B.
+set_b(R, B) -> R#rec{b = B}.
+is_one(R) when R#rec.a =:= 1 -> true.
+nested(R) -> R#rec.a#rec.b.
+//- /src/rec.hrl
+-record(rec, {a :: atom() | integer(), b = 0 :: integer(), c}).
+"#,
+            "rec",
+            expect![[r#"
+                -module(main).
+                -include("rec.hrl").
+                -spec new(atom()) -> rec().
+                new(A) -> #{a => A, b => 0, c => undefined}.
+                get_b(#{b := B}) -> B.
+                set_b(R, B) -> R#{b := B}.
+                is_one(R) when map_get(a, R) =:= 1 -> true.
+                nested(R) -> map_get(b, map_get(a, R)).
+
+                -type rec() :: #{a := atom() | integer(), b := integer(), c := term()}.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn unmigratable_usages() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-record(rec, {a, b}).
+-define(NEW, #rec{}).
+fields() -> record_info(fields, rec).
+check(R) when is_record(R, rec) -> element(#rec.a, R).
+all() -> #rec{_ = 1}.
+"#,
+            "rec",
+            expect![[r#"
+                #rec{}: Usages in macro definitions or arguments cannot be rewritten
+                record_info(fields, rec): `record_info/2` has no map equivalent
+                is_record(R, rec): `is_record` has no map equivalent
+                #rec.a: Record field indexes have no map equivalent
+                #rec{_ = 1}: Setting all fields with `_ = Value` has no map equivalent
+            "#]],
+        );
+        check("-module(main).\n", "rec", expect!["unknown record"]);
+    }
+}
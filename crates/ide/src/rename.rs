@@ -29,7 +29,8 @@ use hir::Semantic;
 
 // Feature: Rename
 //
-// Renames the item below the cursor and all of its references
+// Renames the item below the cursor and all of its references.
+// Renaming a module also renames its file, so that it can still be found.
 //
 // |===
 // | Editor  | Shortcut
@@ -102,6 +103,7 @@ fn find_definitions(
                                     .map(|def| SymbolDefinition::Var(def))
                                     .collect::<Vec<_>>())),
                                 ReferenceClass::MultiMacro(_) => None,
+                                ReferenceClass::MultiFunction(_) => None,
                             },
                             None => None,
                         }
@@ -127,6 +129,7 @@ fn find_definitions(
 mod tests {
     use elp_ide_db::elp_base_db::assert_eq_text;
     use elp_ide_db::elp_base_db::test_fixture::trim_indent;
+    use elp_ide_db::source_change::FileSystemEdit;
     use text_edit::TextEdit;
 
     use crate::fixture;
@@ -1022,4 +1025,112 @@ mod tests {
              "#,
         );
     }
+
+    #[test]
+    fn rename_module() {
+        check(
+            "new_main",
+            r#"
+             //- /src/main.erl
+             -module(ma~in).
+             -export([foo/0]).
+             -callback init() -> ok.
+             foo() -> main:bar().
+             bar() -> ok.
+
+             //- /src/other.erl
+             -module(other).
+             -behaviour(main).
+             init() -> main:foo(), fun main:foo/0.
+             "#,
+            r#"
+             //- /src/main.erl
+             -module(new_main).
+             -export([foo/0]).
+             -callback init() -> ok.
+             foo() -> new_main:bar().
+             bar() -> ok.
+
+             //- /src/other.erl
+             -module(other).
+             -behaviour(new_main).
+             init() -> new_main:foo(), fun new_main:foo/0.
+             "#,
+        );
+    }
+
+    #[test]
+    fn rename_module_from_reference() {
+        check(
+            "new_main",
+            r#"
+             //- /src/main.erl
+             -module(main).
+             -export([foo/0]).
+             foo() -> ok.
+
+             //- /src/other.erl
+             -module(other).
+             bar() -> ma~in:foo().
+             "#,
+            r#"
+             //- /src/main.erl
+             -module(new_main).
+             -export([foo/0]).
+             foo() -> ok.
+
+             //- /src/other.erl
+             -module(other).
+             bar() -> new_main:foo().
+             "#,
+        );
+    }
+
+    #[test]
+    fn rename_module_moves_file() {
+        let (analysis, position) = fixture::position(r#"-module(ma~in)."#);
+        let source_change = analysis.rename(position, "new_main").unwrap().unwrap();
+        match source_change.file_system_edits.as_slice() {
+            [FileSystemEdit::MoveFile { src, dst }] => {
+                assert_eq!(*src, position.file_id);
+                assert_eq!(dst.anchor, position.file_id);
+                assert_eq!(dst.path, "new_main.erl");
+            }
+            edits => panic!("expected the module file to be moved, got {edits:?}"),
+        }
+    }
+
+    #[test]
+    fn rename_module_fails() {
+        check(
+            "other",
+            r#"
+             //- /src/main.erl
+             -module(ma~in).
+
+             //- /src/other.erl
+             -module(other).
+             "#,
+            r#"error: Module 'other' already exists"#,
+        );
+        check(
+            "Main",
+            r#"-module(ma~in)."#,
+            r#"error: Invalid new module name: 'Main'"#,
+        );
+        check(
+            "my_lists",
+            r#"
+             //- /src/main.erl
+             -module(main).
+             foo() -> li~sts:reverse([]).
+
+             //- /opt/lib/stdlib-3.17/src/lists.erl otp_app:/opt/lib/stdlib-3.17
+             -module(lists).
+             -export([reverse/1]).
+             reverse(L) -> L.
+             "#,
+            r#"error: Cannot rename OTP module 'lists'"#,
+        );
+    }
 }
@@ -8,12 +8,15 @@
  */
 
 use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::FileRange;
 use elp_ide_db::RootDatabase;
 use elp_project_model::AppName;
+use fxhash::FxHashSet;
 use hir::NameArity;
 use hir::Semantic;
 
 use crate::common_test;
+use crate::navigation_target::ToNav;
 use crate::NavigationTarget;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -133,10 +136,108 @@ pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
     }
 }
 
+/// A test function, as shown in a test explorer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestFunction {
+    pub name: NameArity,
+    pub kind: TestFunctionKind,
+    /// The range of the function definition, or `None` for a testcase
+    /// declared in `all/0` or `groups/0` without a corresponding function
+    pub range: Option<FileRange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestFunctionKind {
+    CommonTest,
+    EUnit,
+    /// An EUnit `_test_` function, returning the tests to run
+    EUnitGenerator,
+}
+
+/// The Common Test testcases of a `_SUITE` module, or the EUnit tests of
+/// any other module, in the order they are defined.
+pub(crate) fn test_functions(db: &RootDatabase, file_id: FileId) -> Vec<TestFunction> {
+    let sema = Semantic::new(db);
+    match sema.module_name(file_id) {
+        Some(module_name) if module_name.ends_with(common_test::SUFFIX) => {
+            common_test_functions(&sema, file_id)
+        }
+        Some(_) => eunit_functions(&sema, file_id),
+        None => Vec::new(),
+    }
+}
+
+fn common_test_functions(sema: &Semantic, file_id: FileId) -> Vec<TestFunction> {
+    let mut seen = FxHashSet::default();
+    let mut res: Vec<_> = common_test::runnables(sema, file_id)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|runnable| match runnable.kind {
+            // A testcase is run once per group it appears in
+            RunnableKind::Test { name, .. } if seen.insert(name.clone()) => Some(TestFunction {
+                name,
+                kind: TestFunctionKind::CommonTest,
+                range: Some(FileRange {
+                    file_id: runnable.nav.file_id,
+                    range: runnable.nav.full_range,
+                }),
+            }),
+            _ => None,
+        })
+        .collect();
+    res.sort_by_key(|test| test.range.map(|range| range.range.start()));
+
+    let def_map = sema.def_map(file_id);
+    let mut missing: Vec<_> = common_test::declared_test_names(sema, file_id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|name| NameArity::new(name, 1))
+        .filter(|name| def_map.get_function(name).is_none())
+        .collect();
+    missing.sort();
+    res.extend(missing.into_iter().map(|name| TestFunction {
+        name,
+        kind: TestFunctionKind::CommonTest,
+        range: None,
+    }));
+    res
+}
+
+fn eunit_functions(sema: &Semantic, file_id: FileId) -> Vec<TestFunction> {
+    let mut res: Vec<_> = sema
+        .def_map(file_id)
+        .get_functions()
+        .iter()
+        .filter(|(name, def)| name.arity() == 0 && def.file.file_id == file_id)
+        .filter_map(|(name, def)| {
+            let kind = if name.name().ends_with("_test") {
+                TestFunctionKind::EUnit
+            } else if name.name().ends_with("_test_") {
+                TestFunctionKind::EUnitGenerator
+            } else {
+                return None;
+            };
+            let nav = def.to_nav(sema.db);
+            Some(TestFunction {
+                name: name.clone(),
+                kind,
+                range: Some(FileRange {
+                    file_id: nav.file_id,
+                    range: nav.full_range,
+                }),
+            })
+        })
+        .collect();
+    res.sort_by_key(|test| test.range.map(|range| range.range.start()));
+    res
+}
+
 #[cfg(test)]
 mod tests {
 
     use elp_ide_db::elp_base_db::FileRange;
+    use expect_test::expect;
+    use expect_test::Expect;
     use stdx::trim_indent;
 
     use crate::fixture;
@@ -415,4 +516,67 @@ mod tests {
     "#,
         );
     }
+
+    #[track_caller]
+    fn check_test_functions(fixture: &str, expect: Expect) {
+        let (analysis, pos, _annotations) = fixture::annotations(trim_indent(fixture).as_str());
+        let actual = analysis
+            .test_functions(pos.file_id)
+            .unwrap()
+            .into_iter()
+            .map(|test| match test.range {
+                Some(range) => format!("{} {:?} {:?}\n", test.name, test.kind, range.range),
+                None => format!("{} {:?} missing\n", test.name, test.kind),
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn test_functions_suite() {
+        check_test_functions(
+            r#"
+ //- /my_app/test/my_common_test_SUITE.erl
+    ~
+    -module(my_common_test_SUITE).
+    -export([all/0, groups/0]).
+    -export([a/1, b/1, c/1]).
+    all() -> [b, a, {group, g1}, missing1].
+    groups() -> [{g1, [], [a, c, missing2]}].
+    a(_Config) ->
+      ok.
+    b(_Config) ->
+      ok.
+    c(_Config) ->
+      ok.
+    "#,
+            expect![[r#"
+                a/1 CommonTest 189..211
+                b/1 CommonTest 215..237
+                c/1 CommonTest 241..263
+                missing1/1 CommonTest missing
+                missing2/1 CommonTest missing
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_functions_eunit() {
+        check_test_functions(
+            r#"
+ //- /my_app/test/main.erl
+    ~
+    -module(main).
+    -include_lib("eunit/include/eunit.hrl").
+    foo_test() -> ok.
+    foo_test_() -> [fun foo_test/0].
+    bar_test(_) -> ok.
+    helper() -> ok.
+    "#,
+            expect![[r#"
+                foo_test/0 EUnit 69..86
+                foo_test_/0 EUnitGenerator 90..122
+            "#]],
+        );
+    }
 }
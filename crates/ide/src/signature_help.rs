@@ -603,6 +603,32 @@ main() ->
         );
     }
 
+    #[test]
+    fn test_fn_signature_without_doc() {
+        // The clause summary shown on hover is not part of the signature
+        check(
+            r#"
+-module(main).
+
+foo(N) when N > 0 -> positive;
+foo(N) -> N.
+
+foo(N, M) -> {N, M}.
+
+main() ->
+  foo(~).
+"#,
+            expect![[r#"
+                foo(N)
+                    ^
+                ======
+                foo(N, M)
+                    ^  -
+                ======
+            "#]],
+        );
+    }
+
     #[test]
     fn test_fn_signature_local_imported() {
         check(
@@ -75,6 +75,7 @@ pub(crate) fn highlight(
     bound_vars_in_pattern_highlight(&sema, file_id, range_to_highlight, &mut hl);
     functions_highlight(&sema, file_id, range_to_highlight, &mut hl);
     deprecated_func_highlight(&sema, file_id, range_to_highlight, &mut hl);
+    records_highlight(&sema, file_id, range_to_highlight, &mut hl);
     hl.to_vec()
 }
 
@@ -250,12 +251,52 @@ fn functions_highlight(
     }
 }
 
+/// Record names and field names get different highlights, in record
+/// declarations, expressions, patterns and types alike.
+fn records_highlight(
+    sema: &Semantic,
+    file_id: FileId,
+    range_to_highlight: TextRange,
+    hl: &mut Highlights,
+) {
+    let source_file = sema.parse(file_id);
+    for node in source_file.value.syntax().descendants() {
+        if range_to_highlight.intersect(node.text_range()).is_none() {
+            continue;
+        }
+        let (name, kind) = if let Some(name) = ast::RecordName::cast(node.clone()) {
+            (name.name(), SymbolKind::Record)
+        } else if let Some(decl) = ast::RecordDecl::cast(node.clone()) {
+            (decl.name(), SymbolKind::Record)
+        } else if let Some(field) = ast::RecordFieldName::cast(node.clone()) {
+            (field.name(), SymbolKind::RecordField)
+        } else if let Some(field) = ast::RecordField::cast(node.clone()) {
+            (field.name(), SymbolKind::RecordField)
+        } else {
+            continue;
+        };
+        if let Some(name) = name {
+            let range = name.syntax().text_range();
+            if range_to_highlight.intersect(range).is_some() {
+                hl.add(HlRange {
+                    range,
+                    highlight: HlTag::Symbol(kind).into(),
+                    binding_hash: None,
+                });
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use elp_base_db::fixture::WithFixture;
     use elp_ide_db::elp_base_db;
     use elp_ide_db::elp_base_db::fixture::extract_tags;
+    use elp_ide_db::elp_base_db::SourceDatabaseExt;
     use elp_ide_db::RootDatabase;
+    use expect_test::expect;
+    use expect_test::Expect;
     use itertools::Itertools;
 
     use crate::syntax_highlighting::highlight;
@@ -295,6 +336,17 @@ mod tests {
         assert_eq!(expected, ranges);
     }
 
+    #[track_caller]
+    fn check_tags(fixture: &str, expect: Expect) {
+        let (db, file_id) = RootDatabase::with_single_file(fixture);
+        let text = db.file_text(file_id);
+        let actual = highlight(&db, file_id, None)
+            .iter()
+            .map(|h| format!("{} {}\n", &text[h.range], h.highlight.tag))
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
     #[test]
     fn highlights_1() {
         check_highlights(
@@ -346,6 +398,31 @@ mod tests {
         )
     }
 
+    #[test]
+    fn record_highlights() {
+        check_tags(
+            r#"
+-record(rec, {field, other = 1}).
+f(X) -> #rec{field = X#rec.field, other = #rec.other}.
+g(#rec{field = F}) -> F.
+"#,
+            expect![[r#"
+                rec struct
+                field property
+                other property
+                rec struct
+                field property
+                rec struct
+                field property
+                other property
+                rec struct
+                other property
+                rec struct
+                field property
+            "#]],
+        )
+    }
+
     #[test]
     fn highlights_in_range() {
         check_highlights(
@@ -58,7 +58,7 @@ impl HlTag {
                 SymbolKind::Module => "module",
                 SymbolKind::Function => "function",
                 SymbolKind::Record => "struct",
-                SymbolKind::RecordField => "struct",
+                SymbolKind::RecordField => "property",
                 SymbolKind::Type => "type_parameter",
                 SymbolKind::Define => "constant",
                 SymbolKind::Variable => "variable",
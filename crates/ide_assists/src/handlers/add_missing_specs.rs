@@ -0,0 +1,163 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_syntax::ast;
+use elp_syntax::ast::edit::line_indentation;
+use elp_syntax::AstNode;
+use elp_syntax::TextSize;
+
+use crate::handlers::add_spec::type_name;
+use crate::AssistContext;
+use crate::Assists;
+
+// Assist: add_missing_specs
+//
+// Adds a spec stub above every exported function of the module which
+// doesn't already have one. The argument names of the first clause are
+// used as placeholders.
+//
+// ```
+// -module(~main).
+// -export([foo/2, bar/0]).
+// foo(Arg1, some_atom) -> ok.
+// -spec bar() -> ok.
+// bar() -> ok.
+// ```
+// ->
+// ```
+// -module(main).
+// -export([foo/2, bar/0]).
+// -spec foo(Arg1 :: type1(), type2()) -> return_type().
+// foo(Arg1, some_atom) -> ok.
+// -spec bar() -> ok.
+// bar() -> ok.
+// ```
+pub(crate) fn add_missing_specs(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    if ctx.find_node_at_offset::<ast::ModuleAttribute>().is_none()
+        && ctx.find_node_at_offset::<ast::ExportAttribute>().is_none()
+    {
+        return None;
+    }
+
+    let file_id = ctx.file_id();
+    let def_map = ctx.sema.def_map(file_id);
+    let stubs: Vec<(TextSize, String)> = def_map
+        .unspecced_exports()
+        .into_iter()
+        .filter(|def| def.file.file_id == file_id)
+        .filter_map(|def| {
+            let source = def.source(ctx.db().upcast());
+            let name_text = source.name()?.text()?;
+            let clause = source.clauses().find_map(|clause| match clause {
+                ast::FunctionOrMacroClause::FunctionClause(clause) => Some(clause),
+                ast::FunctionOrMacroClause::MacroCallExpr(_) => None,
+            })?;
+            let types = clause
+                .args()
+                .into_iter()
+                .flat_map(|args| args.args())
+                .enumerate()
+                .map(|(arg_idx, expr)| type_name(arg_idx + 1, expr))
+                .collect::<Vec<_>>()
+                .join(", ");
+            // We insert just before the function, so the spec needs to
+            // restore its indentation on the following line.
+            let indent = line_indentation(source.syntax());
+            Some((
+                source.syntax().text_range().start(),
+                format!(
+                    "-spec {}({}) -> return_type().\n{}",
+                    name_text, types, indent
+                ),
+            ))
+        })
+        .collect();
+    if stubs.is_empty() {
+        return None;
+    }
+
+    acc.add(
+        AssistId("add_missing_specs", AssistKind::Generate),
+        "Add spec stubs for all unspecced exports",
+        ctx.selection_trimmed(),
+        None,
+        |builder| {
+            builder.edit_file(file_id);
+            for (offset, text) in stubs {
+                builder.insert(offset, text);
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_unspecced_exports() {
+        check_assist(
+            add_missing_specs,
+            "Add spec stubs for all unspecced exports",
+            r#"
+-module(main).
+-export([~foo/2, bar/0, baz/1]).
+foo(Arg1, some_atom) -> ok;
+foo(_, Other) -> Other.
+-spec bar() -> ok.
+bar() -> ok.
+baz(X) -> X.
+local() -> ok.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([foo/2, bar/0, baz/1]).
+                -spec foo(Arg1 :: type1(), type2()) -> return_type().
+                foo(Arg1, some_atom) -> ok;
+                foo(_, Other) -> Other.
+                -spec bar() -> ok.
+                bar() -> ok.
+                -spec baz(X :: type1()) -> return_type().
+                baz(X) -> X.
+                local() -> ok.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn test_all_exports_specced() {
+        check_assist_not_applicable(
+            add_missing_specs,
+            r#"
+-module(~main).
+-export([bar/0]).
+-spec bar() -> ok.
+bar() -> ok.
+local() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn test_not_on_module_level_attribute() {
+        check_assist_not_applicable(
+            add_missing_specs,
+            r#"
+-module(main).
+-export([bar/0]).
+b~ar() -> ok.
+"#,
+        );
+    }
+}
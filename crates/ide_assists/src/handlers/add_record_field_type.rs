@@ -0,0 +1,155 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+
+use crate::AssistContext;
+use crate::Assists;
+
+// Assist: add_record_field_type
+//
+// Adds a type to a record field declaration that doesn't have one,
+// based on the default value if there is a literal one.
+//
+// ```
+// -record(state, {count~ = 0, name}).
+// ```
+// ->
+// ```
+// -record(state, {count = 0 :: integer(), name}).
+// ```
+pub(crate) fn add_record_field_type(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let field = ctx.find_node_at_offset::<ast::RecordField>()?;
+    ast::RecordDecl::cast(field.syntax().parent()?)?;
+    if field.ty().is_some() {
+        return None;
+    }
+    let name = field.name()?;
+    let default = field.expr();
+    let ty = default
+        .as_ref()
+        .and_then(|default| default.expr())
+        .and_then(|expr| type_of_literal(&expr))
+        .unwrap_or("term()");
+    let insert = match &default {
+        Some(default) => default.syntax().text_range().end(),
+        None => name.syntax().text_range().end(),
+    };
+
+    acc.add(
+        AssistId("add_record_field_type", AssistKind::RefactorRewrite),
+        "Add type to record field",
+        name.syntax().text_range(),
+        None,
+        |edit| edit.insert(insert, format!(" :: {ty}")),
+    )
+}
+
+fn type_of_literal(expr: &ast::Expr) -> Option<&'static str> {
+    match expr {
+        ast::Expr::ExprMax(expr_max) => match expr_max {
+            ast::ExprMax::Atom(atom) => match atom.text()?.as_str() {
+                "true" | "false" => Some("boolean()"),
+                _ => Some("atom()"),
+            },
+            ast::ExprMax::Binary(_) => Some("binary()"),
+            ast::ExprMax::Char(_) | ast::ExprMax::Integer(_) => Some("integer()"),
+            ast::ExprMax::Float(_) => Some("float()"),
+            ast::ExprMax::List(_) => Some("list()"),
+            ast::ExprMax::String(_) => Some("string()"),
+            ast::ExprMax::Tuple(_) => Some("tuple()"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_field_without_default() {
+        check_assist(
+            add_record_field_type,
+            "Add type to record field",
+            r#"
+-record(state, {count = 0, na~me}).
+"#,
+            expect![[r#"
+                -record(state, {count = 0, name :: term()}).
+            "#]],
+        )
+    }
+
+    #[test]
+    fn test_field_with_literal_default() {
+        check_assist(
+            add_record_field_type,
+            "Add type to record field",
+            r#"
+-record(state, {co~unt = 0, name}).
+"#,
+            expect![[r#"
+                -record(state, {count = 0 :: integer(), name}).
+            "#]],
+        );
+        check_assist(
+            add_record_field_type,
+            "Add type to record field",
+            r#"
+-record(state, {enab~led = false}).
+"#,
+            expect![[r#"
+                -record(state, {enabled = false :: boolean()}).
+            "#]],
+        )
+    }
+
+    #[test]
+    fn test_field_with_other_default() {
+        check_assist(
+            add_record_field_type,
+            "Add type to record field",
+            r#"
+-record(state, {sta~rted = erlang:system_time()}).
+"#,
+            expect![[r#"
+                -record(state, {started = erlang:system_time() :: term()}).
+            "#]],
+        )
+    }
+
+    #[test]
+    fn test_field_with_type() {
+        check_assist_not_applicable(
+            add_record_field_type,
+            r#"
+-record(state, {co~unt = 0 :: non_neg_integer()}).
+"#,
+        );
+    }
+
+    #[test]
+    fn test_record_expression() {
+        check_assist_not_applicable(
+            add_record_field_type,
+            r#"
+-record(state, {count = 0 :: non_neg_integer()}).
+foo() -> #state{co~unt = 1}.
+"#,
+        );
+    }
+}
@@ -12,6 +12,7 @@ use elp_ide_db::assists::AssistKind;
 use elp_ide_db::SymbolClass;
 use elp_ide_db::SymbolDefinition;
 use elp_syntax::ast;
+use elp_syntax::ast::edit::line_indentation;
 use elp_syntax::AstNode;
 
 use crate::AssistContext;
@@ -35,13 +36,7 @@ pub(crate) fn add_spec(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
         _ => None,
     }?;
 
-    let has_spec_already = ctx
-        .sema
-        .def_map(ctx.file_id())
-        .get_spec(&function_def.function.name)
-        .is_some();
-
-    if has_spec_already {
+    if function_def.spec(ctx.db()).is_some() {
         return None;
     }
 
@@ -51,6 +46,9 @@ pub(crate) fn add_spec(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
 
     let insert = source.syntax().text_range().start();
     let target = name.syntax().text_range();
+    // We insert just before the function, so the spec needs to restore
+    // its indentation on the following line.
+    let indent = line_indentation(source.syntax());
 
     acc.add(
         AssistId("add_spec", AssistKind::Generate),
@@ -88,10 +86,11 @@ pub(crate) fn add_spec(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
                         .collect::<String>();
                     snippet_idx += 1;
                     let snippet = format!(
-                        "-spec {}({}) -> ${{{}:return_type()}}.\n",
+                        "-spec {}({}) -> ${{{}:return_type()}}.\n{}",
                         name_text,
                         types_snippets.trim_end_matches(", "),
-                        snippet_idx
+                        snippet_idx,
+                        indent
                     );
                     builder.edit_file(ctx.frange.file_id);
                     builder.insert_snippet(cap, insert, snippet);
@@ -101,9 +100,10 @@ pub(crate) fn add_spec(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
                         .map(|arg_name| format!("{}, ", arg_name))
                         .collect::<String>();
                     let text = format!(
-                        "-spec {}({}) -> return_type().\n",
+                        "-spec {}({}) -> return_type().\n{}",
                         name_text,
-                        types_text.trim_end_matches(", ")
+                        types_text.trim_end_matches(", "),
+                        indent
                     );
                     builder.edit_file(ctx.frange.file_id);
                     builder.insert(insert, text)
@@ -167,6 +167,25 @@ f~oo() -> ok.
         )
     }
 
+    #[test]
+    fn test_indented_function() {
+        check_assist_expect_parse_error(
+            add_spec,
+            "Add spec stub",
+            r#"
+-ifdef(TEST).
+    ~foo(Foo) -> ok.
+-endif.
+"#,
+            expect![[r#"
+                -ifdef(TEST).
+                    -spec foo(${1:Foo :: type1()}) -> ${2:return_type()}.
+                    foo(Foo) -> ok.
+                -endif.
+            "#]],
+        )
+    }
+
     #[test]
     fn test_already_has_spec_above() {
         check_assist_not_applicable(
@@ -184,6 +203,21 @@ f~oo() -> ok.
             add_spec,
             r#"
 ~foo(Foo, some_atom) -> ok.
+-spec foo(x(), y()) -> ok.
+    "#,
+        );
+    }
+
+    #[test]
+    fn test_already_has_spec_in_header() {
+        check_assist_not_applicable(
+            add_spec,
+            r#"
+//- /src/main.erl
+-module(main).
+-include("main.hrl").
+~foo(Foo, some_atom) -> ok.
+//- /src/main.hrl
 -spec foo(x(), y()) -> ok.
     "#,
         );
@@ -0,0 +1,220 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_ide_db::assists::AssistUserInput;
+use elp_ide_db::assists::AssistUserInputType;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_syntax::ast;
+use elp_syntax::ast::BinaryOp;
+use elp_syntax::ast::ListOp;
+use elp_syntax::AstNode;
+use hir::Expr;
+use hir::ExprId;
+use hir::InFile;
+use hir::InFunctionBody;
+use hir::Literal;
+
+use crate::AssistContext;
+use crate::Assists;
+
+/// The user input selecting the binary form, any other value gives an
+/// iolist
+const BINARY: &str = "binary";
+
+// Assist: convert_string_concatenation
+//
+// Converts a chain of string concatenations into an iolist, or into a
+// binary if the user asks for one. Only offered if at least one operand is
+// a string literal, and the others are string literals, variables, calls
+// or macros, so that `++` on arbitrary lists is left alone.
+//
+// ```
+// foo(Name) -> "Hello, " ~++ Name ++ "!".
+// ```
+// ->
+// ```
+// foo(Name) -> ["Hello, ", Name, "!"].
+// ```
+//
+// Non-literal operands of the binary form are converted with
+// `unicode:characters_to_binary/1`, since they are strings rather than
+// binaries:
+// ```
+// foo(Name) -> <<"Hello, ", (unicode:characters_to_binary(Name))/binary, "!">>.
+// ```
+pub(crate) fn convert_string_concatenation(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let mut concat: ast::BinaryOpExpr = ctx.find_node_at_offset()?;
+    if !is_append(&concat) {
+        return None;
+    }
+    // `A ++ B ++ C` is `A ++ (B ++ C)`, go to the outermost `++`, looking
+    // through parentheses since `++` is associative
+    let mut node = concat.syntax().clone();
+    while let Some(parent) = node.parent() {
+        if ast::ParenExpr::can_cast(parent.kind()) {
+            node = parent;
+            continue;
+        }
+        match ast::BinaryOpExpr::cast(parent) {
+            Some(parent) if is_append(&parent) => {
+                node = parent.syntax().clone();
+                concat = parent;
+            }
+            _ => break,
+        }
+    }
+
+    let expr = ctx.sema.to_expr(InFile::new(
+        ctx.file_id(),
+        &ast::Expr::BinaryOpExpr(concat.clone()),
+    ))?;
+    let mut operands = Vec::new();
+    flatten(&expr, expr.value, &mut operands);
+    let kinds: Vec<Operand> = operands
+        .iter()
+        .map(|expr_id| operand(&expr, *expr_id))
+        .collect::<Option<_>>()?;
+    if !kinds
+        .iter()
+        .any(|kind| matches!(kind, Operand::String { .. }))
+    {
+        return None;
+    }
+    let file_text = ctx.db().file_text(ctx.file_id());
+    let texts: Vec<String> = operands
+        .iter()
+        .map(|expr_id| {
+            let range = expr.range_for_expr(ctx.db(), *expr_id)?;
+            Some(file_text[range].to_string())
+        })
+        .collect::<Option<_>>()?;
+
+    let target = concat.syntax().text_range();
+    acc.add(
+        AssistId("convert_string_concatenation", AssistKind::RefactorRewrite),
+        "Convert string concatenation",
+        target,
+        Some(AssistUserInput {
+            input_type: AssistUserInputType::Atom,
+            value: "iolist".to_string(),
+        }),
+        |builder| {
+            let replacement = if ctx.user_input_or(|| "iolist".to_string()) == BINARY {
+                let segments: Vec<String> = kinds
+                    .iter()
+                    .zip(texts)
+                    .map(|(kind, text)| match kind {
+                        Operand::String { ascii: true } => text,
+                        Operand::String { ascii: false } => format!("{text}/utf8"),
+                        Operand::Other => {
+                            format!("(unicode:characters_to_binary({text}))/binary")
+                        }
+                    })
+                    .collect();
+                format!("<<{}>>", segments.join(", "))
+            } else {
+                format!("[{}]", texts.join(", "))
+            };
+            builder.replace(target, replacement)
+        },
+    )
+}
+
+fn is_append(expr: &ast::BinaryOpExpr) -> bool {
+    matches!(expr.op(), Some((BinaryOp::ListOp(ListOp::Append), _)))
+}
+
+/// The operands of nested `++`, in source order, so that evaluation order
+/// is preserved.
+fn flatten(expr: &InFunctionBody<ExprId>, expr_id: ExprId, acc: &mut Vec<ExprId>) {
+    match &expr[expr_id] {
+        Expr::BinaryOp {
+            lhs,
+            rhs,
+            op: BinaryOp::ListOp(ListOp::Append),
+        } => {
+            flatten(expr, *lhs, acc);
+            flatten(expr, *rhs, acc);
+        }
+        _ => acc.push(expr_id),
+    }
+}
+
+enum Operand {
+    String {
+        ascii: bool,
+    },
+    /// A value which could be a string
+    Other,
+}
+
+fn operand(expr: &InFunctionBody<ExprId>, expr_id: ExprId) -> Option<Operand> {
+    match &expr[expr_id] {
+        Expr::Literal(Literal::String(string)) => Some(Operand::String {
+            ascii: string.is_ascii(),
+        }),
+        Expr::Var(_) | Expr::Call { .. } | Expr::MacroCall { .. } => Some(Operand::Other),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn to_iolist() {
+        check_assist(
+            convert_string_concatenation,
+            "Convert string concatenation",
+            r#"
+foo(Name, N) -> "Hello, " ++ Name ~++ integer_to_list(N) ++ "!".
+"#,
+            expect![[r#"
+                foo(Name, N) -> ["Hello, ", Name, integer_to_list(N), "!"].
+            "#]],
+        )
+    }
+
+    #[test]
+    fn to_binary() {
+        check_assist_with_user_input(
+            convert_string_concatenation,
+            "Convert string concatenation",
+            "binary",
+            r#"
+foo(Name) -> ("Hello, " ~++ Name) ++ "été".
+"#,
+            expect![[r#"
+                foo(Name) -> <<"Hello, ", (unicode:characters_to_binary(Name))/binary, "été"/utf8>>.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn not_applicable_to_lists() {
+        check_assist_not_applicable(
+            convert_string_concatenation,
+            r#"
+foo(L) -> [1, 2] ~++ L.
+"#,
+        );
+        check_assist_not_applicable(
+            convert_string_concatenation,
+            r#"
+foo(A, B) -> A ~++ B.
+"#,
+        );
+    }
+}
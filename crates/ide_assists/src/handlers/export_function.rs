@@ -9,11 +9,11 @@
 
 use elp_ide_db::assists::AssistId;
 use elp_ide_db::assists::AssistKind;
+use elp_ide_db::helpers::ExportBuilder;
 use elp_ide_db::SymbolClass;
 use elp_ide_db::SymbolDefinition;
 use elp_syntax::AstNode;
 
-use crate::helpers;
 use crate::AssistContext;
 use crate::Assists;
 
@@ -38,13 +38,8 @@ pub(crate) fn export_function(acc: &mut Assists, ctx: &AssistContext) -> Option<
             let id = AssistId("export_function", AssistKind::QuickFix);
             let message = format!("Export the function `{function_name_arity}`");
             acc.add(id, message, function_range, None, |builder| {
-                helpers::ExportBuilder::new(
-                    &ctx.sema,
-                    ctx.file_id(),
-                    &[function_name_arity],
-                    builder,
-                )
-                .finish();
+                ExportBuilder::new(&ctx.sema, ctx.file_id(), &[function_name_arity], builder)
+                    .finish();
             });
         }
     }
@@ -0,0 +1,308 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_ide_db::assists::AssistUserInput;
+use elp_ide_db::assists::AssistUserInputType;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use fxhash::FxHashSet;
+use hir::FormIdx;
+use hir::PPDirective;
+
+use crate::AssistContext;
+use crate::Assists;
+
+// Assist: extract_macro
+//
+// Extract a literal that is used more than once into a `-define` macro,
+// replacing either this occurrence or all occurrences in the module.
+//
+// ```
+// -module(main).
+// foo() -> ~timeout.
+// bar() -> timeout.
+// ```
+// ->
+// ```
+// -module(main).
+// -define(TIMEOUT, timeout).
+// foo() -> ?TIMEOUT.
+// bar() -> ?TIMEOUT.
+// ```
+pub(crate) fn extract_macro(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let literal = ctx.find_node_at_offset::<ast::ExprMax>()?;
+    let literal = literal.syntax();
+    if !is_literal(literal.kind()) || !is_value_position(literal) {
+        return None;
+    }
+    literal
+        .ancestors()
+        .find(|node| node.kind() == SyntaxKind::FUN_DECL)?;
+
+    let source = ctx.sema.parse(ctx.file_id()).value;
+    let occurrences: Vec<TextRange> = source
+        .syntax()
+        .descendants()
+        .filter(|node| {
+            node.kind() == literal.kind()
+                && node.text() == literal.text()
+                && is_value_position(node)
+                && node
+                    .ancestors()
+                    .any(|node| node.kind() == SyntaxKind::FUN_DECL)
+        })
+        .map(|node| node.text_range())
+        .collect();
+    if occurrences.len() < 2 {
+        return None;
+    }
+
+    let (insert_at, existing) = define_location(ctx, literal.text_range().start());
+    let suggested = suggest_macro_name(&literal.text().to_string());
+    let value = literal.text().to_string();
+    let target = literal.text_range();
+
+    for (id, label, ranges) in [
+        ("extract_macro", "Extract into macro", vec![target]),
+        (
+            "extract_macro_all",
+            "Extract all occurrences into macro",
+            occurrences,
+        ),
+    ] {
+        let value = value.clone();
+        let suggested = suggested.clone();
+        let existing = existing.clone();
+        acc.add(
+            AssistId(id, AssistKind::RefactorExtract),
+            label,
+            target,
+            Some(AssistUserInput {
+                input_type: AssistUserInputType::Variable,
+                value: suggested.clone(),
+            }),
+            move |builder| {
+                let name = freshen_macro_name(ctx.user_input_or(|| suggested), &existing);
+                let define = format!("-define({name}, {value}).");
+                match insert_at {
+                    Some(offset) => builder.insert(offset, format!("\n{define}")),
+                    None => builder.insert(TextSize::from(0), format!("{define}\n")),
+                }
+                for range in ranges {
+                    builder.replace(range, format!("?{name}"));
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+fn is_literal(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::ATOM
+            | SyntaxKind::INTEGER
+            | SyntaxKind::FLOAT
+            | SyntaxKind::CHAR
+            | SyntaxKind::STRING
+    )
+}
+
+/// Whether `node` is used as a value, rather than, say, as the name of a
+/// called function or of a record.
+fn is_value_position(node: &SyntaxNode) -> bool {
+    let parent = match node.parent() {
+        Some(parent) => parent,
+        None => return false,
+    };
+    match parent.kind() {
+        SyntaxKind::CALL => ast::Call::cast(parent)
+            .and_then(|call| call.expr())
+            .map_or(false, |callee| callee.syntax() != node),
+        SyntaxKind::BINARY_OP_EXPR
+        | SyntaxKind::CLAUSE_BODY
+        | SyntaxKind::CR_CLAUSE
+        | SyntaxKind::EXPR_ARGS
+        | SyntaxKind::GUARD_CLAUSE
+        | SyntaxKind::LIST
+        | SyntaxKind::MAP_FIELD
+        | SyntaxKind::MATCH_EXPR
+        | SyntaxKind::PAREN_EXPR
+        | SyntaxKind::RECORD_FIELD
+        | SyntaxKind::TUPLE
+        | SyntaxKind::UNARY_OP_EXPR => true,
+        _ => false,
+    }
+}
+
+/// Where to insert the new `-define`: after the last define or include
+/// preceding `offset`, or else after the module attribute. Also returns
+/// the names of the macros already defined in the file.
+fn define_location(ctx: &AssistContext, offset: TextSize) -> (Option<TextSize>, FxHashSet<String>) {
+    let form_list = ctx.db().file_form_list(ctx.file_id());
+    let mut existing = FxHashSet::default();
+    let mut after_module = None;
+    let mut after_pp = None;
+    for form in form_list.forms() {
+        let range = match form {
+            FormIdx::ModuleAttribute(idx) => {
+                let range = ctx.form_ast(form_list[*idx].form_id).syntax().text_range();
+                after_module = Some(range.end());
+                continue;
+            }
+            FormIdx::PPDirective(idx) => match &form_list[*idx] {
+                PPDirective::Define(define) => {
+                    let define = &form_list[*define];
+                    existing.insert(define.name.name().to_string());
+                    ctx.form_ast(define.form_id).syntax().text_range()
+                }
+                PPDirective::Include(include) => ctx
+                    .form_ast(form_list[*include].form_id())
+                    .syntax()
+                    .text_range(),
+                PPDirective::Undef { .. } => continue,
+            },
+            _ => continue,
+        };
+        if range.end() <= offset {
+            after_pp = Some(range.end());
+        }
+    }
+    (after_pp.or(after_module), existing)
+}
+
+fn suggest_macro_name(literal: &str) -> String {
+    let name = literal
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    let name = name.trim_matches('_');
+    match name.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => name.to_string(),
+        _ => "VALUE".to_string(),
+    }
+}
+
+fn freshen_macro_name(name: String, existing: &FxHashSet<String>) -> String {
+    if !existing.contains(&name) {
+        return name;
+    }
+    (1..)
+        .map(|n| format!("{name}_{n}"))
+        .find(|candidate| !existing.contains(candidate))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn extract_one_occurrence() {
+        check_assist(
+            extract_macro,
+            "Extract into macro",
+            r#"
+-module(main).
+foo() -> {~timeout, 1}.
+bar() -> timeout.
+"#,
+            expect![[r#"
+                -module(main).
+                -define(TIMEOUTEdited, timeout).
+                foo() -> {?TIMEOUTEdited, 1}.
+                bar() -> timeout.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn extract_all_occurrences() {
+        check_assist_with_user_input(
+            extract_macro,
+            "Extract all occurrences into macro",
+            "MAX",
+            r#"
+-module(main).
+-include("some.hrl").
+-define(OTHER, 1).
+foo(X) when X > 1~00 -> 100;
+foo(_) -> [100, 10].
+"#,
+            expect![[r#"
+                -module(main).
+                -include("some.hrl").
+                -define(OTHER, 1).
+                -define(MAX, 100).
+                foo(X) when X > ?MAX -> ?MAX;
+                foo(_) -> [?MAX, 10].
+            "#]],
+        )
+    }
+
+    #[test]
+    fn existing_macro_name() {
+        check_assist_with_user_input(
+            extract_macro,
+            "Extract all occurrences into macro",
+            "NAME",
+            r#"
+-module(main).
+-define(NAME, other).
+foo() -> "na~me".
+bar() -> "name".
+"#,
+            expect![[r#"
+                -module(main).
+                -define(NAME, other).
+                -define(NAME_1, "name").
+                foo() -> ?NAME_1.
+                bar() -> ?NAME_1.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn not_applicable_to_single_occurrence() {
+        check_assist_not_applicable(
+            extract_macro,
+            r#"
+-module(main).
+foo() -> ~timeout.
+"#,
+        )
+    }
+
+    #[test]
+    fn not_applicable_to_function_name() {
+        check_assist_not_applicable(
+            extract_macro,
+            r#"
+-module(main).
+foo() -> b~ar(), bar().
+bar() -> ok.
+"#,
+        )
+    }
+}
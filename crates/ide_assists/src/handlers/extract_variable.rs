@@ -12,11 +12,19 @@ use elp_ide_db::assists::AssistKind;
 use elp_ide_db::assists::AssistUserInput;
 use elp_ide_db::assists::AssistUserInputType;
 use elp_syntax::ast;
+use elp_syntax::ast::BinaryOp;
 use elp_syntax::AstNode;
 use elp_syntax::NodeOrToken;
 use elp_syntax::SyntaxKind;
 use elp_syntax::SyntaxNode;
+use elp_syntax::TextRange;
+use elp_syntax::WalkEvent;
+use hir::AnyExprId;
+use hir::Expr;
+use hir::ExprId;
 use hir::InFile;
+use hir::InFunctionBody;
+use hir::Strategy;
 use stdx::format_to;
 
 use crate::assist_context::AssistContext;
@@ -39,6 +47,27 @@ use crate::helpers::suggest_name_for_variable;
 //     VarName * 4.
 // ```
 // Note: $0 is the snippet language encoding of cursor ranges and positions.
+//
+// If the subexpression occurs several times in the clause, it is also
+// offered to replace all of them, binding the variable before the first
+// one:
+//
+// ```
+// foo(X) ->
+//     bar(X + 1),
+//     baz($0X + 1$0).
+// ```
+// ->
+// ```
+// foo(X) ->
+//     $0VarName = X + 1,
+//     bar(VarName),
+//     baz(VarName).
+// ```
+//
+// Only occurrences referring to the same variable bindings are replaced,
+// and expressions which may have side effects, such as calls, are never
+// replaced more than once.
 pub(crate) fn extract_variable(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
     if ctx.has_empty_selection() {
         return None;
@@ -65,25 +94,57 @@ pub(crate) fn extract_variable(acc: &mut Assists, ctx: &AssistContext) -> Option
 
     let anchor = Anchor::from(&to_extract)?;
     let target = to_extract.syntax().text_range();
-    let indent = anchor.syntax().prev_sibling_or_token()?.as_token()?.clone();
-    acc.add(
+    add_extraction(
+        acc,
+        ctx,
         AssistId("extract_variable", AssistKind::RefactorExtract),
         "Extract into variable",
+        &to_extract,
+        &anchor,
+        vec![target],
+    );
+    if let Some((first_anchor, occurrences)) = all_occurrences(ctx, &to_extract) {
+        add_extraction(
+            acc,
+            ctx,
+            AssistId("extract_variable_all", AssistKind::RefactorExtract),
+            "Extract into variable, replacing all occurrences",
+            &to_extract,
+            &first_anchor,
+            occurrences,
+        );
+    }
+    Some(())
+}
+
+fn add_extraction(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+    id: AssistId,
+    label: &str,
+    to_extract: &ast::Expr,
+    anchor: &Anchor,
+    occurrences: Vec<TextRange>,
+) -> Option<()> {
+    let target = to_extract.syntax().text_range();
+    let indent = anchor.syntax().prev_sibling_or_token()?.as_token()?.clone();
+    acc.add(
+        id,
+        label,
         target,
         Some(AssistUserInput {
             input_type: AssistUserInputType::Variable,
-            value: suggest_name_for_variable(&to_extract, &ctx.sema),
+            value: suggest_name_for_variable(to_extract, &ctx.sema),
         }),
         move |edit| {
             let vars_in_clause = ctx
                 .sema
-                .find_vars_in_clause_ast(&InFile::new(ctx.file_id(), &to_extract));
+                .find_vars_in_clause_ast(&InFile::new(ctx.file_id(), to_extract));
             let var_name = freshen_variable_name(
                 &ctx.sema,
-                ctx.user_input_or(|| suggest_name_for_variable(&to_extract, &ctx.sema)),
+                ctx.user_input_or(|| suggest_name_for_variable(to_extract, &ctx.sema)),
                 &vars_in_clause,
             );
-            let expr_range = to_extract.syntax().text_range();
 
             let mut buf = String::new();
             format_to!(buf, "{} = {}", var_name, to_extract.syntax());
@@ -101,7 +162,9 @@ pub(crate) fn extract_variable(acc: &mut Assists, ctx: &AssistContext) -> Option
                 buf.push_str(text);
             }
 
-            edit.replace(expr_range, var_name.clone());
+            for range in occurrences {
+                edit.replace(range, var_name.clone());
+            }
             let offset = anchor.syntax().text_range().start();
             match ctx.config.snippet_cap {
                 Some(cap) => {
@@ -114,6 +177,127 @@ pub(crate) fn extract_variable(acc: &mut Assists, ctx: &AssistContext) -> Option
     )
 }
 
+/// The ranges of the expressions structurally identical to `to_extract` in
+/// its function clause, if there are several, together with the anchor
+/// before which to bind them.  Occurrences are only included if
+/// their variables resolve to the same bindings as the ones of
+/// `to_extract`, and are all bound before the new anchor, so that the
+/// extracted value is the same everywhere.
+fn all_occurrences(
+    ctx: &AssistContext,
+    to_extract: &ast::Expr,
+) -> Option<(Anchor, Vec<TextRange>)> {
+    let file_id = ctx.file_id();
+    let expr = ctx.sema.to_expr(InFile::new(file_id, to_extract))?;
+    if may_have_effects(&expr) {
+        return None;
+    }
+    let body = expr.body();
+    let printed = body.print_any_expr(ctx.db().upcast(), AnyExprId::Expr(expr.value));
+    let defs = var_defs(ctx, to_extract)?;
+    // Variables can only be used after all of their definitions
+    let bound_at = defs
+        .iter()
+        .flatten()
+        .map(|range| range.end())
+        .max()
+        .unwrap_or_default();
+
+    // The body of the function clause, rather than of a nested case or
+    // fun clause
+    let clause_body = to_extract
+        .syntax()
+        .ancestors()
+        .filter(|it| ast::ClauseBody::can_cast(it.kind()))
+        .last()?;
+    let mut occurrences = Vec::new();
+    let mut preorder = clause_body.preorder();
+    while let Some(event) = preorder.next() {
+        let node = match event {
+            WalkEvent::Enter(node) => node,
+            WalkEvent::Leave(_) => continue,
+        };
+        let candidate = match ast::Expr::cast(node) {
+            Some(candidate) => candidate,
+            None => continue,
+        };
+        let is_occurrence = ctx
+            .sema
+            .to_expr(InFile::new(file_id, &candidate))
+            .map_or(false, |it| {
+                body.print_any_expr(ctx.db().upcast(), AnyExprId::Expr(it.value)) == printed
+            });
+        if is_occurrence && var_defs(ctx, &candidate).as_ref() == Some(&defs) {
+            occurrences.push(candidate.syntax().clone());
+            // Nested occurrences are replaced along with this one
+            preorder.skip_subtree();
+        }
+    }
+
+    let target = to_extract.syntax().text_range();
+    let statement = |node: &SyntaxNode| {
+        node.ancestors()
+            .find(|it| it.parent().as_ref() == Some(&clause_body))
+    };
+    let first_anchor = occurrences
+        .iter()
+        .filter_map(statement)
+        .filter(|it| it.text_range().start() >= bound_at)
+        .min_by_key(|it| it.text_range().start())?;
+    let from = first_anchor.text_range().start();
+    let occurrences: Vec<TextRange> = occurrences
+        .iter()
+        .map(|node| node.text_range())
+        .filter(|range| range.start() >= from)
+        .collect();
+    if occurrences.len() < 2 || !occurrences.contains(&target) {
+        return None;
+    }
+    Some((Anchor(first_anchor), occurrences))
+}
+
+/// The ranges of the definitions of each variable used in `expr`, in
+/// order, or `None` if `expr` binds a variable itself.
+fn var_defs(ctx: &AssistContext, expr: &ast::Expr) -> Option<Vec<Vec<TextRange>>> {
+    expr.syntax()
+        .descendants()
+        .filter_map(ast::Var::cast)
+        .map(|var| {
+            let defs = ctx
+                .sema
+                .to_def(InFile::new(ctx.file_id(), &var))?
+                .to_reference()?;
+            Some(
+                defs.iter()
+                    .map(|def| def.source(ctx.db().upcast()).syntax().text_range())
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Calls, sends and receives may have side effects, so the value of an
+/// expression containing them can't be reused.
+fn may_have_effects(expr: &InFunctionBody<ExprId>) -> bool {
+    expr.fold_expr(
+        Strategy::TopDown,
+        expr.value,
+        false,
+        &mut |acc, ctx| {
+            acc || matches!(
+                ctx.expr,
+                Expr::Call { .. }
+                    | Expr::Receive { .. }
+                    | Expr::BinaryOp {
+                        op: BinaryOp::Send,
+                        ..
+                    }
+            )
+        },
+        &mut |acc, _| acc,
+    )
+}
+
 /// Check whether the node is a valid expression which can be
 /// extracted to a variable.  In general that's true for any
 /// expression, but in some cases that would produce invalid code.
@@ -168,6 +352,7 @@ mod tests {
     use super::*;
     use crate::tests::check_assist;
     use crate::tests::check_assist_not_applicable;
+    use crate::tests::check_assist_not_applicable_with_label;
     use crate::tests::check_assist_with_user_input;
 
     #[test]
@@ -338,4 +523,67 @@ foo(X) ->
             "#]],
         );
     }
+
+    #[test]
+    fn test_extract_var_all_occurrences() {
+        check_assist(
+            extract_variable,
+            "Extract into variable, replacing all occurrences",
+            r#"
+foo(X) ->
+  bar(X + 1),
+  F = fun(X) -> X + 1 end,
+  case X of
+    1 -> baz(~X + 1~);
+    _ -> (X + 1) * 2
+  end.
+"#,
+            expect![[r#"
+                foo(X) ->
+                  $0VarNameEdited = X + 1,
+                  bar(VarNameEdited),
+                  F = fun(X) -> X + 1 end,
+                  case X of
+                    1 -> baz(VarNameEdited);
+                    _ -> VarNameEdited * 2
+                  end.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_extract_var_all_after_binding() {
+        check_assist(
+            extract_variable,
+            "Extract into variable, replacing all occurrences",
+            r#"
+foo(X) ->
+  bar(X + 1),
+  Y = X,
+  baz(Y + 1),
+  baz(~Y + 1~).
+"#,
+            expect![[r#"
+                foo(X) ->
+                  bar(X + 1),
+                  Y = X,
+                  $0VarNameEdited = Y + 1,
+                  baz(VarNameEdited),
+                  baz(VarNameEdited).
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_extract_var_all_not_for_calls() {
+        check_assist_not_applicable_with_label(
+            extract_variable,
+            "Extract into variable, replacing all occurrences",
+            r#"
+foo() ->
+  bar(make_ref()),
+  bar(~make_ref()~).
+"#,
+        );
+    }
 }
@@ -0,0 +1,364 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::ops::Range;
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxNode;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use hir::InFile;
+
+use crate::AssistContext;
+use crate::Assists;
+
+// Assist: flatten_nested_case
+//
+// Flattens a `case` whose single clause has no guard and only contains
+// another `case` on a variable. If the variable is bound by the pattern of
+// the outer clause, the patterns of the inner clauses are substituted for
+// it.
+//
+// ```
+// foo(X) ->
+//     ~case X of
+//         {ok, V} ->
+//             case V of
+//                 1 -> one;
+//                 _ -> other
+//             end
+//     end.
+// ```
+// ->
+// ```
+// foo(X) ->
+//     case X of
+//         {ok, 1} -> one;
+//         {ok, _} -> other
+//     end.
+// ```
+//
+// If the variable was bound before, both values are matched together:
+// ```
+//     case {X, Y} of
+//         {{ok, _}, 1} -> one;
+//         {{ok, _}, _} -> other
+//     end.
+// ```
+//
+// The variable is bound before the inner `case` is reached, so nothing is
+// evaluated in a different order. Only the value of the `case_clause`
+// error raised when no clause matches can differ.
+pub(crate) fn flatten_nested_case(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let case: ast::CaseExpr = ctx.find_node_at_offset()?;
+    let (outer, clause, inner) = match nested_case(&case) {
+        Some((clause, inner)) => (case, clause, inner),
+        None => {
+            // The cursor is on the inner `case`
+            let outer = case
+                .syntax()
+                .ancestors()
+                .skip(1)
+                .find_map(ast::CaseExpr::cast)?;
+            let (clause, inner) = nested_case(&outer)?;
+            if inner.syntax() != case.syntax() {
+                return None;
+            }
+            (outer, clause, inner)
+        }
+    };
+    let replacement = flattened(ctx, &outer, &clause, &inner)?;
+    let target = outer.syntax().text_range();
+    acc.add(
+        AssistId("flatten_nested_case", AssistKind::RefactorRewrite),
+        "Flatten nested case",
+        target,
+        None,
+        |builder| builder.replace(target, replacement),
+    )
+}
+
+/// The single clause of `case` and the `case` it contains, if the clause
+/// has no guard and nothing else in its body
+fn nested_case(case: &ast::CaseExpr) -> Option<(ast::CrClause, ast::CaseExpr)> {
+    let mut clauses = case.clauses();
+    let clause = match (clauses.next()?, clauses.next()) {
+        (ast::CrClauseOrMacro::CrClause(clause), None) => clause,
+        _ => return None,
+    };
+    if clause.guard().is_some() {
+        return None;
+    }
+    let mut exprs = clause.body()?.exprs();
+    let inner = match (exprs.next()?, exprs.next()) {
+        (expr, None) => ast::CaseExpr::cast(expr.syntax().clone())?,
+        _ => return None,
+    };
+    Some((clause, inner))
+}
+
+fn flattened(
+    ctx: &AssistContext,
+    outer: &ast::CaseExpr,
+    clause: &ast::CrClause,
+    inner: &ast::CaseExpr,
+) -> Option<String> {
+    let scrutinee = outer.expr()?.syntax().text().to_string();
+    let pat = clause.pat()?;
+    let var = match inner.expr()? {
+        ast::Expr::ExprMax(ast::ExprMax::Var(var)) => var,
+        _ => return None,
+    };
+    let var_name = var.syntax().text().to_string();
+    let inner_clauses: Vec<ast::CrClause> = inner
+        .clauses()
+        .map(|clause| match clause {
+            ast::CrClauseOrMacro::CrClause(clause) => Some(clause),
+            ast::CrClauseOrMacro::MacroCallExpr(_) => None,
+        })
+        .collect::<Option<_>>()?;
+    let defs = ctx
+        .sema
+        .to_def(InFile::new(ctx.file_id(), &var))?
+        .to_reference()?;
+    let def_ranges: Vec<TextRange> = defs
+        .iter()
+        .map(|def| def.source(ctx.db().upcast()).syntax().text_range())
+        .collect();
+    let pat_range = pat.syntax().text_range();
+    let bound_in_pat: Vec<TextRange> = def_ranges
+        .iter()
+        .copied()
+        .filter(|range| pat_range.contains_range(*range))
+        .collect();
+
+    let mut edits = Vec::new();
+    if bound_in_pat.is_empty() {
+        // Bound before the outer `case`, so it can be matched together
+        // with the outer value
+        edits.push((
+            var.syntax().text_range(),
+            format!("{{{scrutinee}, {var_name}}}"),
+        ));
+        for inner_clause in &inner_clauses {
+            let inner_pat = inner_clause.pat()?;
+            edits.push((
+                inner_pat.syntax().text_range(),
+                format!("{{{}, {}}}", pat.syntax().text(), inner_pat.syntax().text()),
+            ));
+        }
+    } else {
+        let def_range = match def_ranges.as_slice() {
+            [def_range] if bound_in_pat.len() == 1 => *def_range,
+            _ => return None,
+        };
+        let in_binary = pat
+            .syntax()
+            .descendants()
+            .filter(|node| node.text_range().contains_range(def_range))
+            .any(|node| ast::BinElement::can_cast(node.kind()));
+        if in_binary {
+            return None;
+        }
+        edits.push((var.syntax().text_range(), scrutinee));
+        for inner_clause in &inner_clauses {
+            let inner_pat = inner_clause.pat()?;
+            if uses_var(inner_pat.syntax(), &var_name) {
+                return None;
+            }
+            let used = inner_clause
+                .guard()
+                .map_or(false, |guard| uses_var(guard.syntax(), &var_name))
+                || inner_clause
+                    .body()
+                    .map_or(false, |body| uses_var(body.syntax(), &var_name));
+            let value = match (used, def_range == pat_range) {
+                (false, _) => inner_pat.syntax().text().to_string(),
+                (true, true) => format!("{} = {var_name}", inner_pat.syntax().text()),
+                (true, false) => format!("({} = {var_name})", inner_pat.syntax().text()),
+            };
+            let mut text = pat.syntax().text().to_string();
+            text.replace_range(relative(def_range, pat_range.start()), &value);
+            edits.push((inner_pat.syntax().text_range(), text));
+        }
+    }
+
+    let inner_range = inner.syntax().text_range();
+    let mut text = inner.syntax().text().to_string();
+    edits.sort_by_key(|(range, _)| range.start());
+    for (range, replacement) in edits.iter().rev() {
+        text.replace_range(relative(*range, inner_range.start()), replacement);
+    }
+
+    // The inner `case` takes the place of the outer one, so is unindented
+    // by the difference between their columns
+    let file_text = ctx.db().file_text(ctx.file_id());
+    let column = |offset: TextSize| {
+        let offset = usize::from(offset);
+        offset - file_text[..offset].rfind('\n').map_or(0, |it| it + 1)
+    };
+    let shift =
+        column(inner_range.start()).saturating_sub(column(outer.syntax().text_range().start()));
+    let lines: Vec<&str> = text
+        .split('\n')
+        .enumerate()
+        .map(|(idx, line)| {
+            if idx == 0 {
+                return line;
+            }
+            let indent = line.len() - line.trim_start_matches(' ').len();
+            &line[indent.min(shift)..]
+        })
+        .collect();
+    Some(lines.join("\n"))
+}
+
+fn uses_var(node: &SyntaxNode, name: &str) -> bool {
+    node.descendants()
+        .filter_map(ast::Var::cast)
+        .any(|var| var.syntax().text() == name)
+}
+
+fn relative(range: TextRange, start: TextSize) -> Range<usize> {
+    (range - start).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn bound_by_outer_pattern() {
+        check_assist(
+            flatten_nested_case,
+            "Flatten nested case",
+            r#"
+foo(X) ->
+    ~case X of
+        {ok, V} ->
+            case V of
+                1 -> one;
+                N when N > 1 -> many;
+                _ -> other
+            end
+    end.
+"#,
+            expect![[r#"
+                foo(X) ->
+                    case X of
+                        {ok, 1} -> one;
+                        {ok, N} when N > 1 -> many;
+                        {ok, _} -> other
+                    end.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn bound_by_outer_pattern_and_used() {
+        check_assist(
+            flatten_nested_case,
+            "Flatten nested case",
+            r#"
+foo(X) ->
+    case X of
+        {ok, V} ->
+            ~case V of
+                {a, _} -> V;
+                _ -> other
+            end
+    end.
+"#,
+            expect![[r#"
+                foo(X) ->
+                    case X of
+                        {ok, ({a, _} = V)} -> V;
+                        {ok, _} -> other
+                    end.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn bound_before() {
+        check_assist(
+            flatten_nested_case,
+            "Flatten nested case",
+            r#"
+foo(X, Y) ->
+    ~case X of
+        {ok, Z} ->
+            case Y of
+                Z -> same;
+                _ -> other
+            end
+    end.
+"#,
+            expect![[r#"
+                foo(X, Y) ->
+                    case {X, Y} of
+                        {{ok, Z}, Z} -> same;
+                        {{ok, Z}, _} -> other
+                    end.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn not_applicable() {
+        // More than one outer clause
+        check_assist_not_applicable(
+            flatten_nested_case,
+            r#"
+foo(X, Y) ->
+    ~case X of
+        ok -> case Y of _ -> ok end;
+        _ -> error
+    end.
+"#,
+        );
+        // Something is evaluated before the inner case
+        check_assist_not_applicable(
+            flatten_nested_case,
+            r#"
+foo(X, Y) ->
+    ~case X of
+        ok -> bar(), case Y of _ -> ok end
+    end.
+"#,
+        );
+        // The inner case is not on a variable
+        check_assist_not_applicable(
+            flatten_nested_case,
+            r#"
+foo(X) ->
+    ~case X of
+        ok -> case bar() of _ -> ok end
+    end.
+"#,
+        );
+        // The outer clause has a guard
+        check_assist_not_applicable(
+            flatten_nested_case,
+            r#"
+foo(X, Y) ->
+    ~case X of
+        V when V > 0 -> case Y of _ -> ok end
+    end.
+"#,
+        );
+    }
+}
@@ -11,6 +11,7 @@ use std::cmp::max;
 
 use elp_ide_db::assists::AssistId;
 use elp_ide_db::assists::AssistKind;
+use elp_ide_db::helpers::ExportBuilder;
 use elp_syntax::ast::BehaviourAttribute;
 use elp_syntax::AstNode;
 use hir::Callback;
@@ -23,7 +24,6 @@ use text_edit::TextSize;
 
 use crate::assist_context::AssistContext;
 use crate::assist_context::Assists;
-use crate::helpers;
 
 // Assist: implement_behaviour
 //
@@ -180,7 +180,7 @@ impl<'a> ImplementCallbacks<'a> {
         let (funs, texts) = build_assist(self.ctx, self.behaviour, additions);
         self.acc.add(id, message, self.attr_range, None, |builder| {
             let mut export_builder =
-                helpers::ExportBuilder::new(&self.ctx.sema, self.ctx.file_id(), &funs, builder)
+                ExportBuilder::new(&self.ctx.sema, self.ctx.file_id(), &funs, builder)
                     .insert_at(self.insert_at)
                     .with_comment(comment);
             if let Some(existing) = existing_callback {
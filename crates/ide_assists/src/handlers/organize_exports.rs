@@ -0,0 +1,377 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::NodeOrToken;
+use elp_syntax::SyntaxKind;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use hir::FormIdx;
+use hir::NameArity;
+
+use crate::AssistContext;
+use crate::Assists;
+
+// Assist: organize_exports
+//
+// Sort the entries of an export attribute by name and arity, removing
+// duplicates.
+//
+// ```
+// -export([foo/1, ~bar/0, foo/1]).
+// ```
+// ->
+// ```
+// -export([bar/0, foo/1]).
+// ```
+//
+// Adjacent export attributes not separated by conditional compilation
+// can also be merged into one.
+//
+// ```
+// -export([foo/1]).
+// -export([~bar/0]).
+// ```
+// ->
+// ```
+// -export([bar/0, foo/1]).
+// ```
+pub(crate) fn organize_exports(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let attr = ctx.find_node_at_offset::<ast::ExportAttribute>()?;
+    let exports = adjacent_exports(ctx, &attr)?;
+    let idx = exports.iter().position(|export| export.attr == attr)?;
+
+    let current = &exports[idx];
+    let (entries, dangling) = organize(std::slice::from_ref(current));
+    if entries.iter().map(|entry| &entry.name).ne(current.names()) {
+        let text = render(&entries, &dangling, current.is_multiline());
+        acc.add(
+            AssistId("organize_exports", AssistKind::Refactor),
+            "Sort and deduplicate export list",
+            current.attr.syntax().text_range(),
+            None,
+            |builder| builder.replace(current.attr.syntax().text_range(), text),
+        );
+    }
+
+    if exports.len() > 1 {
+        let (entries, dangling) = organize(&exports);
+        let multiline = exports.iter().any(|export| export.is_multiline());
+        let text = render(&entries, &dangling, multiline);
+        let target = exports
+            .iter()
+            .map(|export| export.attr.syntax().text_range())
+            .reduce(|acc, range| acc.cover(range))?;
+        acc.add(
+            AssistId("merge_exports", AssistKind::Refactor),
+            "Merge adjacent export attributes",
+            target,
+            None,
+            |builder| {
+                builder.replace(exports[0].attr.syntax().text_range(), text);
+                for export in &exports[1..] {
+                    builder.delete(removal_range(&export.attr));
+                }
+            },
+        );
+    }
+
+    Some(())
+}
+
+/// An entry of an export attribute, together with the comments that belong
+/// to it.
+#[derive(Clone)]
+struct Entry {
+    name: NameArity,
+    leading: Vec<String>,
+    trailing: Option<String>,
+}
+
+struct Export {
+    attr: ast::ExportAttribute,
+    entries: Vec<Entry>,
+    /// Comments after the last entry
+    dangling: Vec<String>,
+}
+
+impl Export {
+    fn names(&self) -> impl Iterator<Item = &NameArity> {
+        self.entries.iter().map(|entry| &entry.name)
+    }
+
+    fn is_multiline(&self) -> bool {
+        self.attr.syntax().text().contains_char('\n')
+            || !self.dangling.is_empty()
+            || self
+                .entries
+                .iter()
+                .any(|entry| !entry.leading.is_empty() || entry.trailing.is_some())
+    }
+}
+
+/// The export attribute `attr`, together with the export attributes it can
+/// be merged with: those in the same run of exports under the same
+/// pre-processor condition, without a conditional compilation directive in
+/// between.
+fn adjacent_exports(ctx: &AssistContext, attr: &ast::ExportAttribute) -> Option<Vec<Export>> {
+    let form_list = ctx.db().file_form_list(ctx.file_id());
+    let source = ctx.sema.parse(ctx.file_id()).value;
+
+    let mut runs: Vec<Vec<Export>> = vec![Vec::new()];
+    let mut run_cond = None;
+    for form in form_list.forms() {
+        match form {
+            FormIdx::PPCondition(_) => runs.push(Vec::new()),
+            FormIdx::Export(idx) => {
+                let export = &form_list[*idx];
+                let last = runs.last_mut()?;
+                if !last.is_empty() && run_cond != export.cond {
+                    runs.push(Vec::new());
+                }
+                run_cond = export.cond;
+                let export_attr = export.form_id.get(&source);
+                let names = export
+                    .entries
+                    .clone()
+                    .map(|entry| form_list[entry].name.clone())
+                    .collect::<Vec<_>>();
+                let (entries, dangling) = entries_with_comments(&export_attr, names)?;
+                runs.last_mut()?.push(Export {
+                    attr: export_attr,
+                    entries,
+                    dangling,
+                });
+            }
+            _ => {}
+        }
+    }
+    runs.into_iter()
+        .find(|run| run.iter().any(|export| &export.attr == attr))
+}
+
+/// Pair up the entries of `attr` with their names, and collect the comments
+/// inside the attribute. A comment on the same line as an entry belongs to
+/// it, as do comments on their own line before it.
+fn entries_with_comments(
+    attr: &ast::ExportAttribute,
+    names: Vec<NameArity>,
+) -> Option<(Vec<Entry>, Vec<String>)> {
+    if names.len() != attr.funs().count() {
+        // Some entries could not be lowered, leave the attribute alone
+        return None;
+    }
+    let mut names = names.into_iter();
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut pending = Vec::new();
+    let mut seen_newline = true;
+    let mut in_list = false;
+    for element in attr.syntax().children_with_tokens() {
+        match element.kind() {
+            SyntaxKind::ANON_LBRACK => in_list = true,
+            SyntaxKind::ANON_RBRACK => break,
+            _ if !in_list => {}
+            SyntaxKind::WHITESPACE => seen_newline |= element.to_string().contains('\n'),
+            SyntaxKind::COMMENT => {
+                let comment = element.to_string().trim_end().to_string();
+                match entries.last_mut() {
+                    Some(entry) if !seen_newline && entry.trailing.is_none() => {
+                        entry.trailing = Some(comment)
+                    }
+                    _ => pending.push(comment),
+                }
+            }
+            SyntaxKind::ANON_COMMA => seen_newline = false,
+            _ => {
+                if let NodeOrToken::Node(node) = element {
+                    if ast::Fa::can_cast(node.kind()) {
+                        entries.push(Entry {
+                            name: names.next()?,
+                            leading: std::mem::take(&mut pending),
+                            trailing: None,
+                        });
+                        seen_newline = false;
+                    }
+                }
+            }
+        }
+    }
+    Some((entries, pending))
+}
+
+/// Sort the entries of all of `exports` by name and arity and drop the
+/// duplicates, keeping the comments of the dropped entries.
+fn organize(exports: &[Export]) -> (Vec<Entry>, Vec<String>) {
+    let mut all: Vec<Entry> = Vec::new();
+    let mut dangling = Vec::new();
+    for export in exports {
+        all.extend(export.entries.iter().cloned());
+        dangling.extend(export.dangling.iter().cloned());
+    }
+    // Stable, so the first occurrence of a duplicate comes first
+    all.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut entries: Vec<Entry> = Vec::new();
+    for entry in all {
+        match entries.last_mut() {
+            Some(last) if last.name == entry.name => {
+                last.leading.extend(entry.leading);
+                match (&last.trailing, entry.trailing) {
+                    (None, trailing) => last.trailing = trailing,
+                    (Some(_), Some(trailing)) => last.leading.push(trailing),
+                    (Some(_), None) => {}
+                }
+            }
+            _ => entries.push(entry),
+        }
+    }
+    (entries, dangling)
+}
+
+fn render(entries: &[Entry], dangling: &[String], multiline: bool) -> String {
+    if !multiline {
+        let names = entries
+            .iter()
+            .map(|entry| entry.name.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return format!("-export([{names}]).");
+    }
+    let mut text = "-export([\n".to_string();
+    for (idx, entry) in entries.iter().enumerate() {
+        for comment in &entry.leading {
+            text.push_str(&format!("    {comment}\n"));
+        }
+        text.push_str(&format!("    {}", entry.name));
+        if idx + 1 < entries.len() {
+            text.push(',');
+        }
+        if let Some(comment) = &entry.trailing {
+            text.push_str(&format!(" {comment}"));
+        }
+        text.push('\n');
+    }
+    for comment in dangling {
+        text.push_str(&format!("    {comment}\n"));
+    }
+    text.push_str("]).");
+    text
+}
+
+/// The range to delete to remove `attr`, including the end of its line.
+fn removal_range(attr: &ast::ExportAttribute) -> TextRange {
+    let range = attr.syntax().text_range();
+    match attr.syntax().next_sibling_or_token() {
+        Some(NodeOrToken::Token(token))
+            if token.kind() == SyntaxKind::WHITESPACE && token.text().starts_with('\n') =>
+        {
+            TextRange::new(range.start(), range.end() + TextSize::of('\n'))
+        }
+        _ => range,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn sort_and_dedup() {
+        check_assist(
+            organize_exports,
+            "Sort and deduplicate export list",
+            r#"
+-module(main).
+-export([foo/1, ~bar/0, foo/0, foo/1]).
+"#,
+            expect![[r#"
+                -module(main).
+                -export([bar/0, foo/0, foo/1]).
+            "#]],
+        )
+    }
+
+    #[test]
+    fn already_organized() {
+        check_assist_not_applicable(
+            organize_exports,
+            r#"
+-module(main).
+-export([~bar/0, foo/0, foo/1]).
+"#,
+        )
+    }
+
+    #[test]
+    fn preserves_comments() {
+        check_assist(
+            organize_exports,
+            "Sort and deduplicate export list",
+            r#"
+-module(main).
+-export([
+    %% The main entry point
+    ~foo/1, % not bar
+    bar/0
+]).
+"#,
+            expect![[r#"
+                -module(main).
+                -export([
+                    bar/0,
+                    %% The main entry point
+                    foo/1 % not bar
+                ]).
+            "#]],
+        )
+    }
+
+    #[test]
+    fn merge_adjacent() {
+        check_assist(
+            organize_exports,
+            "Merge adjacent export attributes",
+            r#"
+-module(main).
+-export([foo/1]).
+-export([~bar/0, foo/1]).
+
+foo(_) -> ok.
+bar() -> ok.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([bar/0, foo/1]).
+
+                foo(_) -> ok.
+                bar() -> ok.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn no_merge_across_conditional_compilation() {
+        check_assist_not_applicable(
+            organize_exports,
+            r#"
+-module(main).
+-export([foo/1]).
+-ifdef(TEST).
+-export([~bar/0]).
+-endif.
+"#,
+        )
+    }
+}
@@ -31,7 +31,6 @@ use hir::CompileOption;
 use hir::FormList;
 use hir::InFileAstPtr;
 use hir::InFunctionBody;
-use hir::NameArity;
 use hir::Semantic;
 use hir::Var;
 use text_edit::TextSize;
@@ -127,14 +126,6 @@ pub(crate) fn skip_trailing_separator(node: &SyntaxNode) -> Option<TextRange> {
     None
 }
 
-pub(crate) fn find_next_token(node: &SyntaxNode, delimiter: SyntaxKind) -> Option<TextRange> {
-    node.children_with_tokens()
-        .filter_map(|it| it.into_token())
-        .filter(|it| it.kind() == delimiter)
-        .next()
-        .map(|t| t.text_range())
-}
-
 pub(crate) fn skip_trailing_newline(node: &SyntaxNode) -> Option<TextRange> {
     let elements = iter::successors(node.next_sibling_or_token(), |n| {
         (*n).next_sibling_or_token()
@@ -402,146 +393,3 @@ fn add_to_compile_attribute<'a>(
     };
     Some(())
 }
-
-// ---------------------------------------------------------------------
-
-pub(crate) struct ExportBuilder<'a> {
-    sema: &'a Semantic<'a>,
-    file_id: FileId,
-    funs: &'a [NameArity],
-    // `group_with`: Add `funs` to the same export as this, if found.
-    // If it is added to the existing export, the comment is not used.
-    group_with: Option<NameArity>,
-    insert_at: Option<TextSize>,
-    with_comment: Option<String>,
-    builder: &'a mut SourceChangeBuilder,
-}
-
-impl<'a> ExportBuilder<'a> {
-    pub(crate) fn new(
-        sema: &'a Semantic<'a>,
-        file_id: FileId,
-        funs: &'a [NameArity],
-        builder: &'a mut SourceChangeBuilder,
-    ) -> ExportBuilder<'a> {
-        ExportBuilder {
-            sema,
-            file_id,
-            funs,
-            group_with: None,
-            insert_at: None,
-            with_comment: None,
-            builder,
-        }
-    }
-
-    pub(crate) fn group_with(mut self, name: NameArity) -> ExportBuilder<'a> {
-        self.group_with = Some(name);
-        self
-    }
-
-    pub(crate) fn insert_at(mut self, location: TextSize) -> ExportBuilder<'a> {
-        self.insert_at = Some(location);
-        self
-    }
-
-    pub(crate) fn with_comment(mut self, comment: String) -> ExportBuilder<'a> {
-        self.with_comment = Some(comment);
-        self
-    }
-
-    pub(crate) fn finish(&mut self) {
-        let source = self.sema.parse(self.file_id).value;
-        let form_list = self.sema.db.file_form_list(self.file_id);
-        let export_text = self
-            .funs
-            .iter()
-            .map(|function_name_arity| format!("{function_name_arity}"))
-            .collect::<Vec<_>>()
-            .join(", ");
-
-        let (insert, text) = if form_list.exports().count() == 0 {
-            self.new_export(form_list, source, export_text)
-        } else {
-            // Top priority: group_with
-            if let Some(group_with) = &self.group_with {
-                if let Some((insert, text)) = || -> Option<_> {
-                    let (_, export) = form_list.exports().find(|(_, e)| {
-                        e.entries
-                            .clone()
-                            .into_iter()
-                            .any(|fa| &form_list[fa].name == group_with)
-                    })?;
-                    add_to_export(export, &source, &export_text)
-                }() {
-                    (insert, text)
-                } else {
-                    self.new_export(form_list, source, export_text)
-                }
-            } else {
-                if self.with_comment.is_some() {
-                    // Preceding comment for export, always make a fresh one
-                    self.new_export(form_list, source, export_text)
-                } else {
-                    if let Some((insert, text)) = || -> Option<_> {
-                        if form_list.exports().count() == 1 {
-                            // One existing export, add the function to it.
-
-                            let (_, export) = form_list.exports().next()?;
-                            add_to_export(export, &source, &export_text)
-                        } else {
-                            // Multiple
-                            None
-                        }
-                    }() {
-                        (insert, text)
-                    } else {
-                        // Zero or multiple existing exports, create a fresh one
-                        self.new_export(form_list, source, export_text)
-                    }
-                }
-            }
-        };
-
-        self.builder.edit_file(self.file_id);
-        self.builder.insert(insert, text)
-    }
-
-    fn new_export(
-        &self,
-        form_list: std::sync::Arc<hir::FormList>,
-        source: elp_syntax::SourceFile,
-        export_text: String,
-    ) -> (TextSize, String) {
-        let insert = self.insert_at.unwrap_or_else(|| {
-            if let Some(module_attr) = form_list.module_attribute() {
-                let module_attr_range = module_attr.form_id.get(&source).syntax().text_range();
-                TextSize::from(module_attr_range.end() + TextSize::from(1))
-            } else {
-                TextSize::from(0)
-            }
-        });
-        match &self.with_comment {
-            Some(comment) => (
-                insert,
-                format!("\n%% {comment}\n-export([{export_text}]).\n"),
-            ),
-            None => (insert, format!("\n-export([{export_text}]).\n")),
-        }
-    }
-}
-
-fn add_to_export(
-    export: &hir::Export,
-    source: &elp_syntax::SourceFile,
-    export_text: &String,
-) -> Option<(TextSize, String)> {
-    let export_ast = export.form_id.get(source);
-    if let Some(fa) = export_ast.funs().last() {
-        Some((fa.syntax().text_range().end(), format!(", {export_text}")))
-    } else {
-        // Empty export list
-        let range = find_next_token(export_ast.syntax(), SyntaxKind::ANON_LBRACK)?;
-        Some((range.end(), export_text.clone()))
-    }
-}
@@ -68,18 +68,24 @@ mod handlers {
     mod add_edoc;
     mod add_format;
     mod add_impl;
+    mod add_missing_specs;
+    mod add_record_field_type;
     mod add_spec;
     mod bump_variables;
+    mod convert_string_concatenation;
     mod create_function;
     mod delete_function;
     mod export_function;
     mod extract_function;
+    mod extract_macro;
     mod extract_variable;
+    mod flatten_nested_case;
     mod flip_sep;
     mod ignore_variable;
     mod implement_behaviour;
     mod inline_function;
     mod inline_local_variable;
+    mod organize_exports;
 
     pub(crate) fn all() -> &'static [Handler] {
         &[
@@ -87,18 +93,24 @@ mod handlers {
             add_edoc::add_edoc,
             add_format::add_format,
             add_impl::add_impl,
+            add_missing_specs::add_missing_specs,
+            add_record_field_type::add_record_field_type,
             add_spec::add_spec,
             bump_variables::bump_variables,
+            convert_string_concatenation::convert_string_concatenation,
             create_function::create_function,
             delete_function::delete_function,
             export_function::export_function,
             extract_function::extract_function,
+            extract_macro::extract_macro,
             extract_variable::extract_variable,
+            flatten_nested_case::flatten_nested_case,
             flip_sep::flip_sep,
             ignore_variable::ignore_variable,
             implement_behaviour::implement_behaviour,
             inline_function::inline_function,
             inline_local_variable::inline_local_variable,
+            organize_exports::organize_exports,
             // These are manually sorted for better priorities. By default,
             // priority is determined by the size of the target range (smaller
             // target wins). If the ranges are equal, position in this list is
@@ -21,6 +21,7 @@ use elp_ide_db::elp_base_db::fixture::WithFixture;
 use elp_ide_db::elp_base_db::FileRange;
 use elp_ide_db::elp_base_db::SourceDatabase;
 use elp_ide_db::elp_base_db::SourceDatabaseExt;
+use elp_ide_db::helpers::ExportBuilder;
 use elp_ide_db::helpers::SnippetCap;
 use elp_ide_db::source_change::FileSystemEdit;
 use elp_ide_db::RootDatabase;
@@ -36,7 +37,6 @@ use hir::InFile;
 use stdx::format_to;
 
 use crate::handlers::Handler;
-use crate::helpers;
 use crate::AssistConfig;
 use crate::AssistContext;
 use crate::AssistResolveStrategy;
@@ -113,6 +113,22 @@ pub(crate) fn check_assist_not_applicable(assist: Handler, ra_fixture: &str) {
     );
 }
 
+#[track_caller]
+pub(crate) fn check_assist_not_applicable_with_label(
+    assist: Handler,
+    assist_label: &str,
+    ra_fixture: &str,
+) {
+    check(
+        assist,
+        ra_fixture,
+        ExpectedResult::NotApplicable,
+        Some(assist_label),
+        true,
+        None,
+    );
+}
+
 enum ExpectedResult {
     NotApplicable,
     After(Expect),
@@ -394,7 +410,7 @@ fn export_no_pre_existing() {
                 let id = AssistId("export_function", AssistKind::QuickFix);
                 let message = format!("Export the function `{function_name_arity}`");
                 acc.add(id, message, function_range, None, |builder| {
-                    helpers::ExportBuilder::new(
+                    ExportBuilder::new(
                         &ctx.sema,
                         ctx.file_id(),
                         &[function_name_arity],
@@ -437,7 +453,7 @@ fn export_single_pre_existing() {
                 let id = AssistId("export_function", AssistKind::QuickFix);
                 let message = format!("Export the function `{function_name_arity}`");
                 acc.add(id, message, function_range, None, |builder| {
-                    helpers::ExportBuilder::new(
+                    ExportBuilder::new(
                         &ctx.sema,
                         ctx.file_id(),
                         &[function_name_arity],
@@ -486,7 +502,7 @@ fn export_single_pre_existing_with_comment() {
                 let id = AssistId("export_function", AssistKind::QuickFix);
                 let message = format!("Export the function `{function_name_arity}`");
                 acc.add(id, message, function_range, None, |builder| {
-                    helpers::ExportBuilder::new(
+                    ExportBuilder::new(
                         &ctx.sema,
                         ctx.file_id(),
                         &[function_name_arity],
@@ -544,7 +560,7 @@ fn export_single_group_with_overrides_comment() {
                 let id = AssistId("export_function", AssistKind::QuickFix);
                 let message = format!("Export the function `{function_name_arity}`");
                 acc.add(id, message, function_range, None, |builder| {
-                    helpers::ExportBuilder::new(
+                    ExportBuilder::new(
                         &ctx.sema,
                         ctx.file_id(),
                         &[function_name_arity],
@@ -600,7 +616,7 @@ fn export_into_specific_pre_existing_1() {
                 let id = AssistId("export_function", AssistKind::QuickFix);
                 let message = format!("Export the function `{function_name_arity}`");
                 acc.add(id, message, function_range, None, |builder| {
-                    helpers::ExportBuilder::new(
+                    ExportBuilder::new(
                         &ctx.sema,
                         ctx.file_id(),
                         &[function_name_arity],
@@ -659,7 +675,7 @@ fn export_into_specific_pre_existing_2() {
                 let id = AssistId("export_function", AssistKind::QuickFix);
                 let message = format!("Export the function `{function_name_arity}`");
                 acc.add(id, message, function_range, None, |builder| {
-                    helpers::ExportBuilder::new(
+                    ExportBuilder::new(
                         &ctx.sema,
                         ctx.file_id(),
                         &[function_name_arity],
@@ -7,6 +7,12 @@
  * of this source tree.
  */
 
+use elp_base_db::FileId;
+use elp_base_db::ModuleName;
+use hir::File;
+use hir::FileKind;
+use hir::Semantic;
+
 use crate::Args;
 use crate::Completion;
 use crate::Contents;
@@ -50,6 +56,7 @@ pub(crate) fn add_completions(
                             position: None,
                             sort_text: None,
                             deprecated: false,
+                            detail: None,
                         })
                     }
                 } else {
@@ -64,7 +71,7 @@ pub(crate) fn add_completions(
 
         [.., (K::ANON_DASH, _), (K::ATOM, attr_name)] if matches!(trigger, Some('-') | None) => {
             if "module".starts_with(attr_name.text()) {
-                if let Some(module) = sema.module_name(file_position.file_id) {
+                if let Some(module) = module_name_for_attribute(sema, file_position.file_id) {
                     acc.push(Completion {
                         kind: Kind::Attribute,
                         label: format!("-module({}).", module.to_quoted_string()),
@@ -75,6 +82,7 @@ pub(crate) fn add_completions(
                         position: None,
                         sort_text: None,
                         deprecated: false,
+                        detail: None,
                     });
                     true
                 } else {
@@ -88,6 +96,7 @@ pub(crate) fn add_completions(
                     position: None,
                     sort_text: None,
                     deprecated: false,
+                    detail: None,
                 });
                 true
             } else {
@@ -99,6 +108,24 @@ pub(crate) fn add_completions(
     }
 }
 
+/// The module name to complete a `-module` attribute with, based on the
+/// file name. Files that already have a `-module` attribute get none.
+fn module_name_for_attribute(sema: &Semantic, file_id: FileId) -> Option<ModuleName> {
+    if sema.db.file_form_list(file_id).module_attribute().is_some() {
+        return None;
+    }
+    sema.module_name(file_id).or_else(|| {
+        let file = File { file_id };
+        match file.kind(sema.db.upcast()) {
+            FileKind::Module => {
+                let name = file.name(sema.db.upcast());
+                Some(ModuleName::new(name.strip_suffix(".erl")?))
+            }
+            _ => None,
+        }
+    })
+}
+
 #[cfg(test)]
 mod test {
     use expect_test::expect;
@@ -216,6 +243,19 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_module_attribute_already_present() {
+        check(
+            r#"
+        //- /src/my_module.erl
+        -module(my_module).
+        -mod~
+        "#,
+            None,
+            expect![""],
+        );
+    }
+
     #[test]
     fn test_module_attribute_hyphen() {
         check(
@@ -10,7 +10,9 @@
 use elp_base_db::FileId;
 use elp_base_db::FilePosition;
 use elp_syntax::AstNode;
+use hir::DefMap;
 use hir::FunctionDef;
+use hir::Name;
 use hir::NameArity;
 use hir::Semantic;
 
@@ -126,6 +128,7 @@ pub(crate) fn add_completions(
                         }),
                         sort_text: None,
                         deprecated,
+                        detail: arities_detail(&def_map, function_name),
                     }
                 });
 
@@ -166,6 +169,28 @@ fn complete_remote_function_call<'a>(
     }();
 }
 
+/// Lists all arities of `name` when there is more than one, e.g.
+/// "foo/1 (exported), foo/2"
+fn arities_detail(def_map: &DefMap, name: &Name) -> Option<String> {
+    let arities = def_map.get_function_arities(name);
+    if arities.len() < 2 {
+        return None;
+    }
+    let detail = arities
+        .iter()
+        .map(|def| {
+            let na = &def.function.name;
+            if def_map.is_function_exported(na) {
+                format!("{na} (exported)")
+            } else {
+                na.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(detail)
+}
+
 fn name_arity_to_call_completion(
     def: Option<&FunctionDef>,
     na: &NameArity,
@@ -192,6 +217,7 @@ fn name_arity_to_call_completion(
             position,
             sort_text: None,
             deprecated,
+            detail: None,
         })
     } else {
         None
@@ -453,12 +479,34 @@ mod test {
     "#,
             None,
             expect![[r#"
-                {label:bar/0, kind:Function, contents:Snippet("bar()"), position:Some(FilePosition { file_id: FileId(0), offset: 46 })}
-                {label:bar/1, kind:Function, contents:Snippet("bar(${1:X})"), position:Some(FilePosition { file_id: FileId(0), offset: 59 })}
+                {label:bar/0, kind:Function, contents:Snippet("bar()"), position:Some(FilePosition { file_id: FileId(0), offset: 46 }), detail:"bar/0, bar/1"}
+                {label:bar/1, kind:Function, contents:Snippet("bar(${1:X})"), position:Some(FilePosition { file_id: FileId(0), offset: 59 }), detail:"bar/0, bar/1"}
                 {label:baz/2, kind:Function, contents:Snippet("baz(${1:X}, ${2:Arg2})"), position:Some(FilePosition { file_id: FileId(0), offset: 72 })}"#]],
         );
     }
 
+    #[test]
+    fn test_local_calls_arities() {
+        check(
+            r#"
+    //- /src/sample1.erl
+    -module(sample1).
+    -export([bar/1]).
+    -include("sample1.hrl").
+    foo() -> b~.
+    bar(X) -> X.
+    //- /src/sample1.hrl
+    bar() -> ok.
+    bar(X, Y) -> {X, Y}.
+    "#,
+            None,
+            expect![[r#"
+                {label:bar/0, kind:Function, contents:Snippet("bar()"), position:Some(FilePosition { file_id: FileId(1), offset: 0 }), detail:"bar/0, bar/1 (exported), bar/2"}
+                {label:bar/1, kind:Function, contents:Snippet("bar(${1:X})"), position:Some(FilePosition { file_id: FileId(0), offset: 73 }), detail:"bar/0, bar/1 (exported), bar/2"}
+                {label:bar/2, kind:Function, contents:Snippet("bar(${1:X}, ${2:Y})"), position:Some(FilePosition { file_id: FileId(1), offset: 13 }), detail:"bar/0, bar/1 (exported), bar/2"}"#]],
+        );
+    }
+
     #[test]
     fn test_local_calls_2() {
         check(
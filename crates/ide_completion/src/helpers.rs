@@ -67,6 +67,7 @@ pub(crate) fn name_slash_arity_completion(
             position: None,
             sort_text: None,
             deprecated: false,
+            detail: None,
         })
     } else {
         None
@@ -0,0 +1,180 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use hir::IncludeCandidate;
+use hir::IncludeSource;
+
+use crate::Args;
+use crate::Completion;
+use crate::Contents;
+use crate::DoneFlag;
+use crate::Kind;
+
+/// -include("prefix~") or -include_lib("prefix~"), offering the headers
+/// in the order the compiler searches for them, so that the first one
+/// offered for a path is the one it resolves to
+pub(crate) fn add_completions(
+    acc: &mut Vec<Completion>,
+    Args {
+        sema,
+        parsed,
+        file_position,
+        ..
+    }: &Args,
+) -> DoneFlag {
+    let token = match parsed
+        .value
+        .syntax()
+        .token_at_offset(file_position.offset)
+        .left_biased()
+    {
+        Some(token) if token.kind() == SyntaxKind::STRING => token,
+        _ => return false,
+    };
+    let is_lib = match token.parent().and_then(include_kind) {
+        Some(is_lib) => is_lib,
+        None => return false,
+    };
+    // Only within the quotes
+    let text = token.text();
+    let len: usize = (file_position.offset - token.text_range().start()).into();
+    if len == 0 || (len >= text.len() && text.len() > 1 && text.ends_with('"')) {
+        return false;
+    }
+    let prefix = &text[1..len.min(text.len())];
+
+    let candidates = sema.include_candidates(file_position.file_id, is_lib);
+    let width = candidates.len().to_string().len();
+    acc.extend(
+        candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, candidate)| candidate.path.starts_with(prefix))
+            .map(|(idx, candidate)| Completion {
+                label: candidate.path.clone(),
+                kind: Kind::File,
+                contents: Contents::SameAsLabel,
+                position: None,
+                // Keeps the search order, which the client would otherwise
+                // replace by the alphabetical one
+                sort_text: Some(format!("{idx:0width$}")),
+                deprecated: false,
+                detail: Some(detail(candidate)),
+            }),
+    );
+    true
+}
+
+/// Whether `node` is within an `-include_lib`, or an `-include`
+fn include_kind(node: SyntaxNode) -> Option<bool> {
+    node.ancestors().find_map(|node| {
+        if ast::PpInclude::can_cast(node.kind()) {
+            Some(false)
+        } else if ast::PpIncludeLib::can_cast(node.kind()) {
+            Some(true)
+        } else {
+            None
+        }
+    })
+}
+
+fn detail(candidate: &IncludeCandidate) -> String {
+    let mut res = describe(candidate.source).to_string();
+    if !candidate.shadowed.is_empty() {
+        let shadowed = candidate
+            .shadowed
+            .iter()
+            .map(|source| describe(*source))
+            .collect::<Vec<_>>()
+            .join(", ");
+        res.push_str(&format!(", also in {shadowed}"));
+    }
+    res
+}
+
+fn describe(source: IncludeSource) -> &'static str {
+    match source {
+        IncludeSource::Relative => "same directory",
+        IncludeSource::IncludePath => "include path",
+        IncludeSource::ProjectApp => "project application",
+        IncludeSource::OtpApp => "OTP application",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::tests::get_completions;
+    use crate::tests::render_completions;
+    use crate::Kind;
+
+    fn check(code: &str, expect: Expect) {
+        let mut completions: Vec<_> = get_completions(code, None)
+            .into_iter()
+            .filter(|c| c.kind == Kind::File)
+            .collect();
+        completions.sort_by(|c1, c2| c1.sort_text.cmp(&c2.sort_text));
+        let actual = &render_completions(completions);
+        expect.assert_eq(actual);
+    }
+
+    #[test]
+    fn test_include_search_order() {
+        check(
+            r#"
+//- /main/src/module.erl app:main include_path:/main/include
+-module(module).
+-include("~").
+//- /main/src/local.hrl app:main
+//- /main/include/local.hrl app:main
+//- /main/include/api.hrl app:main
+"#,
+            expect![[r#"
+                {label:local.hrl, kind:File, contents:SameAsLabel, position:None, detail:"same directory, also in include path"}
+                {label:main/include/api.hrl, kind:File, contents:SameAsLabel, position:None, detail:"include path"}
+                {label:main/include/local.hrl, kind:File, contents:SameAsLabel, position:None, detail:"include path"}
+                {label:main/src/local.hrl, kind:File, contents:SameAsLabel, position:None, detail:"include path"}
+                {label:api.hrl, kind:File, contents:SameAsLabel, position:None, detail:"include path"}"#]],
+        );
+    }
+
+    #[test]
+    fn test_include_lib_prefix() {
+        check(
+            r#"
+//- /main/src/module.erl app:main
+-module(module).
+-include_lib("s~").
+//- /another/include/state.hrl app:another
+//- /opt/lib/stdlib-3.17/include/assert.hrl otp_app:/opt/lib/stdlib-3.17
+"#,
+            expect![[r#"
+                {label:stdlib/include/assert.hrl, kind:File, contents:SameAsLabel, position:None, detail:"OTP application"}"#]],
+        );
+    }
+
+    #[test]
+    fn test_not_outside_include() {
+        check(
+            r#"
+//- /main/src/module.erl app:main
+-module(module).
+foo() -> "~".
+//- /main/src/local.hrl app:main
+"#,
+            expect![""],
+        );
+    }
+}
@@ -45,7 +45,7 @@ lazy_static! {
         "try",
         "when",
         "xor"
-    ].iter().map(|label| Completion{ label: label.to_string(), kind: crate::Kind::Keyword, contents: Contents::SameAsLabel, position: None, sort_text: None, deprecated: false}).collect();
+    ].iter().map(|label| Completion{ label: label.to_string(), kind: crate::Kind::Keyword, contents: Contents::SameAsLabel, position: None, sort_text: None, deprecated: false, detail: None}).collect();
 }
 
 pub(crate) fn add_completions(acc: &mut Vec<Completion>, Args { trigger, .. }: &Args) -> DoneFlag {
@@ -164,7 +164,7 @@ mod test {
     "#,
             None,
             expect![[r#"
-                {label:foo/0, kind:Type, contents:Snippet("foo()"), position:None}
+                {label:foo/0, kind:Type, contents:Snippet("foo()"), position:Some(FilePosition { file_id: FileId(0), offset: 12 })}
                 {label:main, kind:Module, contents:SameAsLabel, position:None}"#]],
         );
     }
@@ -32,8 +32,10 @@ mod export_functions;
 mod export_types;
 mod functions;
 mod helpers;
+mod includes;
 mod keywords;
 mod macros;
+mod maps;
 // @fb-only: mod meta_only;
 mod modules;
 mod records;
@@ -54,22 +56,23 @@ pub struct Completion {
     pub position: Option<FilePosition>,
     pub sort_text: Option<String>,
     pub deprecated: bool,
+    pub detail: Option<String>,
 }
 
 impl fmt::Display for Completion {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self.deprecated {
-            true => write!(
-                f,
-                "{{label:{}, kind:{:?}, contents:{:?}, position:{:?}, deprecated:{}}}",
-                self.label, self.kind, self.contents, self.position, self.deprecated
-            ),
-            false => write!(
-                f,
-                "{{label:{}, kind:{:?}, contents:{:?}, position:{:?}}}",
-                self.label, self.kind, self.contents, self.position
-            ),
+        write!(
+            f,
+            "{{label:{}, kind:{:?}, contents:{:?}, position:{:?}",
+            self.label, self.kind, self.contents, self.position
+        )?;
+        if self.deprecated {
+            write!(f, ", deprecated:{}", self.deprecated)?;
         }
+        if let Some(detail) = &self.detail {
+            write!(f, ", detail:{:?}", detail)?;
+        }
+        write!(f, "}}")
     }
 }
 
@@ -86,6 +89,7 @@ pub enum Kind {
     Function,
     Keyword,
     Module,
+    MapKey,
     RecordField,
     Type,
     Behavior,
@@ -96,6 +100,7 @@ pub enum Kind {
     Record,
     Variable,
     Attribute,
+    File,
     AiAssist,
 }
 
@@ -143,7 +148,9 @@ pub fn completions(
 
     match ctx {
         Ctx::Expr => {
-            let _ = macros::add_completions(&mut acc, args)
+            maps::add_completions(&mut acc, args);
+            let _ = includes::add_completions(&mut acc, args)
+                || macros::add_completions(&mut acc, args)
                 || records::add_completions(&mut acc, args)
                 || functions::add_completions(&mut acc, args)
                 || vars::add_completions(&mut acc, args)
@@ -162,7 +169,8 @@ pub fn completions(
             export_types::add_completions(&mut acc, args);
         }
         Ctx::Other => {
-            let _ = attributes::add_completions(&mut acc, args)
+            let _ = includes::add_completions(&mut acc, args)
+                || attributes::add_completions(&mut acc, args)
                 // @fb-only: || meta_only::add_completions(&mut acc, args)
                 || vars::add_completions(&mut acc, args);
         }
@@ -7,6 +7,7 @@
  * of this source tree.
  */
 
+use elp_base_db::FilePosition;
 use elp_syntax::algo;
 use elp_syntax::ast;
 use elp_syntax::AstNode;
@@ -44,9 +45,15 @@ pub(crate) fn add_completions(
             let def_map = sema.def_map(file_position.file_id);
             let user_defined = def_map
                 .get_macros()
-                .keys()
-                .filter(|macro_name| macro_name.name().starts_with(prefix))
-                .map(macro_name_to_completion);
+                .iter()
+                .filter(|(macro_name, _)| macro_name.name().starts_with(prefix))
+                .map(|(macro_name, def)| {
+                    let position = FilePosition {
+                        file_id: def.file.file_id,
+                        offset: def.source(sema.db.upcast()).syntax().text_range().start(),
+                    };
+                    macro_name_to_completion(macro_name, position)
+                });
 
             acc.extend(user_defined);
 
@@ -62,7 +69,7 @@ pub(crate) fn add_completions(
     }
 }
 
-fn macro_name_to_completion(macro_name: &MacroName) -> Completion {
+fn macro_name_to_completion(macro_name: &MacroName, position: FilePosition) -> Completion {
     match macro_name.arity() {
         Some(arity) => {
             let label = macro_name.to_string();
@@ -71,18 +78,20 @@ fn macro_name_to_completion(macro_name: &MacroName) -> Completion {
                 label,
                 kind: Kind::Macro,
                 contents,
-                position: None,
+                position: Some(position),
                 sort_text: None,
                 deprecated: false,
+                detail: None,
             }
         }
         None => Completion {
             label: macro_name.to_string(),
             kind: Kind::Macro,
             contents: Contents::SameAsLabel,
-            position: None,
+            position: Some(position),
             sort_text: None,
             deprecated: false,
+            detail: None,
         },
     }
 }
@@ -95,6 +104,7 @@ fn built_in_macro_name_to_completion(name: &Name) -> Completion {
         position: None,
         sort_text: None,
         deprecated: false,
+        detail: None,
     }
 }
 
@@ -139,10 +149,10 @@ mod test {
     "#,
             Some('?'),
             expect![[r#"
-                {label:FOB, kind:Macro, contents:SameAsLabel, position:None}
-                {label:FOO, kind:Macro, contents:SameAsLabel, position:None}
-                {label:FOO/0, kind:Macro, contents:Snippet("FOO()"), position:None}
-                {label:FOO/3, kind:Macro, contents:Snippet("FOO(${1:Arg1}, ${2:Arg2}, ${3:Arg3})"), position:None}"#]],
+                {label:FOB, kind:Macro, contents:SameAsLabel, position:Some(FilePosition { file_id: FileId(0), offset: 88 })}
+                {label:FOO, kind:Macro, contents:SameAsLabel, position:Some(FilePosition { file_id: FileId(0), offset: 18 })}
+                {label:FOO/0, kind:Macro, contents:Snippet("FOO()"), position:Some(FilePosition { file_id: FileId(0), offset: 35 })}
+                {label:FOO/3, kind:Macro, contents:Snippet("FOO(${1:Arg1}, ${2:Arg2}, ${3:Arg3})"), position:Some(FilePosition { file_id: FileId(0), offset: 54 })}"#]],
         );
 
         check(
@@ -153,7 +163,7 @@ mod test {
     foo() -> ?FO~
     "#,
             None,
-            expect!["{label:FOO, kind:Macro, contents:SameAsLabel, position:None}"],
+            expect!["{label:FOO, kind:Macro, contents:SameAsLabel, position:Some(FilePosition { file_id: FileId(0), offset: 18 })}"],
         );
 
         check(
@@ -165,7 +175,7 @@ mod test {
     foo() -> ok.
     "#,
             None,
-            expect!["{label:FOO, kind:Macro, contents:SameAsLabel, position:None}"],
+            expect!["{label:FOO, kind:Macro, contents:SameAsLabel, position:Some(FilePosition { file_id: FileId(0), offset: 18 })}"],
         );
     }
 
@@ -183,7 +193,7 @@ mod test {
                 {label:FILE, kind:Macro, contents:SameAsLabel, position:None}
                 {label:FUNCTION_ARITY, kind:Macro, contents:SameAsLabel, position:None}
                 {label:FUNCTION_NAME, kind:Macro, contents:SameAsLabel, position:None}
-                {label:F_USER_DEFINED, kind:Macro, contents:SameAsLabel, position:None}"#]],
+                {label:F_USER_DEFINED, kind:Macro, contents:SameAsLabel, position:Some(FilePosition { file_id: FileId(0), offset: 18 })}"#]],
         );
 
         check(
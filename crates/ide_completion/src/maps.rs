@@ -0,0 +1,214 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::ast::AstChildren;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxNode;
+use fxhash::FxHashSet;
+use hir::Expr;
+use hir::ExprId;
+use hir::InFile;
+use hir::Name;
+use hir::Pat;
+use hir::Semantic;
+
+use crate::Args;
+use crate::Completion;
+use crate::Contents;
+use crate::Kind;
+
+/// Modules using many maps could otherwise flood the completion list
+const MAX_MAP_KEYS: usize = 50;
+
+/// #{key_prefix~} or X#{key_prefix~}, offering the atom keys of the other
+/// maps of the module. These are offered alongside the other expression
+/// completions, as the key being typed may as well be a variable or a call.
+pub(crate) fn add_completions(
+    acc: &mut Vec<Completion>,
+    Args {
+        sema,
+        file_position,
+        parsed,
+        previous_tokens,
+        trigger,
+        ..
+    }: &Args,
+) {
+    use elp_syntax::SyntaxKind as K;
+    if trigger.is_some() {
+        return;
+    }
+    let default = vec![];
+    let previous_tokens: &[_] = previous_tokens.as_ref().unwrap_or(&default);
+    let (separator, prefix) = match previous_tokens {
+        [.., (K::ANON_LBRACE | K::ANON_COMMA, separator), (K::ATOM, key)]
+            if key.text_range().end() == file_position.offset =>
+        {
+            (separator, key.text())
+        }
+        [.., (K::ANON_LBRACE | K::ANON_COMMA, separator)] => (separator, ""),
+        _ => return,
+    };
+    // The separator must belong to the map itself, not to a call or a
+    // list within one of its values
+    let fields = match separator.parent().and_then(map_fields) {
+        Some(fields) => fields,
+        None => return,
+    };
+    // Keys already in the map being edited, except the one being typed
+    let present: FxHashSet<String> = fields
+        .filter(|field| {
+            !field
+                .syntax()
+                .text_range()
+                .contains_inclusive(file_position.offset)
+        })
+        .filter_map(|field| match field.key()? {
+            ast::Expr::ExprMax(ast::ExprMax::Atom(atom)) => Some(atom.syntax().text().to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let mut keys: Vec<String> = module_map_keys(sema, file_position.file_id)
+        .into_iter()
+        .map(|key| key.to_quoted_string())
+        .filter(|key| key.starts_with(prefix) && key != prefix && !present.contains(key))
+        .collect();
+    keys.sort();
+    keys.truncate(MAX_MAP_KEYS);
+    acc.extend(keys.into_iter().map(|key| Completion {
+        label: key,
+        kind: Kind::MapKey,
+        contents: Contents::SameAsLabel,
+        position: None,
+        sort_text: None,
+        deprecated: false,
+        detail: None,
+    }));
+}
+
+fn map_fields(node: SyntaxNode) -> Option<AstChildren<ast::MapField>> {
+    match ast::MapExpr::cast(node.clone()) {
+        Some(map) => Some(map.fields()),
+        None => ast::MapExprUpdate::cast(node).map(|update| update.fields()),
+    }
+}
+
+/// The atom keys of the maps built, updated or matched in the functions of
+/// the module
+fn module_map_keys(sema: &Semantic, file_id: FileId) -> FxHashSet<Name> {
+    let form_list = sema.db.file_form_list(file_id);
+    let mut keys = FxHashSet::default();
+    for (function_id, _) in form_list.functions() {
+        let function_id = InFile::new(file_id, function_id);
+        let function_body = sema.db.function_body(function_id);
+        let body = &function_body.body;
+        let atom_key = |key: &ExprId| body[*key].as_atom().map(|atom| sema.db.lookup_atom(atom));
+        keys = sema.fold_function(
+            function_id,
+            keys,
+            &mut |mut acc, _clause_id, ctx| {
+                match &ctx.expr {
+                    Expr::Map { fields } => {
+                        acc.extend(fields.iter().filter_map(|(key, _)| atom_key(key)))
+                    }
+                    Expr::MapUpdate { fields, .. } => {
+                        acc.extend(fields.iter().filter_map(|(key, _, _)| atom_key(key)))
+                    }
+                    _ => {}
+                }
+                acc
+            },
+            &mut |mut acc, _clause_id, ctx| {
+                if let Pat::Map { fields } = &ctx.pat {
+                    acc.extend(fields.iter().filter_map(|(key, _)| atom_key(key)));
+                }
+                acc
+            },
+        );
+    }
+    keys
+}
+
+#[cfg(test)]
+mod test {
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::tests::get_completions;
+    use crate::tests::render_completions;
+    use crate::Kind;
+
+    fn check(code: &str, expect: Expect) {
+        let completions = get_completions(code, None)
+            .into_iter()
+            .filter(|c| c.kind == Kind::MapKey)
+            .collect();
+        let actual = &render_completions(completions);
+        expect.assert_eq(actual);
+    }
+
+    #[test]
+    fn test_map_keys() {
+        check(
+            r#"
+        -module(sample).
+        foo(#{name := N}) -> #{name => N, age => 1, "dynamic" => 2}.
+        bar(M) -> M#{address := x}.
+        baz() -> #{a~}.
+        "#,
+            expect![[r#"
+                {label:address, kind:MapKey, contents:SameAsLabel, position:None}
+                {label:age, kind:MapKey, contents:SameAsLabel, position:None}"#]],
+        );
+    }
+
+    #[test]
+    fn test_map_keys_exclude_present() {
+        check(
+            r#"
+        -module(sample).
+        foo(#{name := N}) -> #{name => N, age => 1}.
+        baz(M) -> M#{age => 2, ~}.
+        "#,
+            expect![[r#"
+                {label:name, kind:MapKey, contents:SameAsLabel, position:None}"#]],
+        );
+    }
+
+    #[test]
+    fn test_map_keys_with_other_completions() {
+        let completions = get_completions(
+            r#"
+        -module(sample).
+        foo() -> #{name => 1}.
+        nested() -> ok.
+        baz() -> #{n~}.
+        "#,
+            None,
+        );
+        let labels: Vec<&str> = completions.iter().map(|c| c.label.as_str()).collect();
+        assert!(labels.contains(&"name"));
+        assert!(labels.contains(&"nested/0"));
+    }
+
+    #[test]
+    fn test_map_keys_not_outside_maps() {
+        check(
+            r#"
+        -module(sample).
+        foo() -> #{name => 1}.
+        baz() -> {n~}.
+        "#,
+            expect![""],
+        );
+    }
+}
@@ -7,6 +7,7 @@
  * of this source tree.
  */
 
+use elp_base_db::FilePosition;
 use elp_syntax::algo;
 use elp_syntax::ast;
 use elp_syntax::AstNode;
@@ -84,13 +85,17 @@ fn add_token_based_completions(
             .get_records()
             .iter()
             .filter(|(name, _)| name.starts_with(name_prefix))
-            .map(|(name, _)| Completion {
+            .map(|(name, def)| Completion {
                 label: name.to_string(),
                 kind: Kind::Record,
                 contents: Contents::SameAsLabel,
-                position: None,
+                position: Some(FilePosition {
+                    file_id: def.file.file_id,
+                    offset: def.source(sema.db.upcast()).syntax().text_range().start(),
+                }),
                 sort_text: None,
                 deprecated: false,
+                detail: None,
             });
         acc.extend(completions);
         true
@@ -161,6 +166,7 @@ fn field_name_to_completion_with_equals(field_name: Name) -> Completion {
         position: None,
         sort_text: None,
         deprecated: false,
+        detail: None,
     }
 }
 
@@ -172,6 +178,7 @@ fn field_name_to_completion(field_name: Name) -> Completion {
         position: None,
         sort_text: None,
         deprecated: false,
+        detail: None,
     }
 }
 
@@ -317,8 +324,8 @@ mod test {
         "#,
             None,
             expect![[r#"
-                {label:that_record, kind:Record, contents:SameAsLabel, position:None}
-                {label:this_record, kind:Record, contents:SameAsLabel, position:None}"#]],
+                {label:that_record, kind:Record, contents:SameAsLabel, position:Some(FilePosition { file_id: FileId(0), offset: 61 })}
+                {label:this_record, kind:Record, contents:SameAsLabel, position:Some(FilePosition { file_id: FileId(0), offset: 17 })}"#]],
         );
 
         check(
@@ -331,9 +338,9 @@ mod test {
         "#,
             None,
             expect![[r#"
-                {label:another, kind:Record, contents:SameAsLabel, position:None}
-                {label:that_record, kind:Record, contents:SameAsLabel, position:None}
-                {label:this_record, kind:Record, contents:SameAsLabel, position:None}"#]],
+                {label:another, kind:Record, contents:SameAsLabel, position:Some(FilePosition { file_id: FileId(0), offset: 87 })}
+                {label:that_record, kind:Record, contents:SameAsLabel, position:Some(FilePosition { file_id: FileId(0), offset: 61 })}
+                {label:this_record, kind:Record, contents:SameAsLabel, position:Some(FilePosition { file_id: FileId(0), offset: 17 })}"#]],
         );
     }
 
@@ -371,7 +378,7 @@ mod test {
             ok -> #r~
         "#,
             None,
-            expect!["{label:rec, kind:Record, contents:SameAsLabel, position:None}"],
+            expect!["{label:rec, kind:Record, contents:SameAsLabel, position:Some(FilePosition { file_id: FileId(0), offset: 17 })}"],
         );
 
         check(
@@ -7,12 +7,15 @@
  * of this source tree.
  */
 
+use elp_base_db::FilePosition;
 use elp_syntax::algo;
 use elp_syntax::ast;
 use elp_syntax::ast::Atom;
 use elp_syntax::AstNode;
 use hir::InFile;
 use hir::NameArity;
+use hir::Semantic;
+use hir::TypeAliasDef;
 
 use crate::helpers;
 use crate::Args;
@@ -70,7 +73,7 @@ fn complete_remote_name(
         .get_exported_types()
         .into_iter()
         .filter(|na| na.name().starts_with(fun_prefix))
-        .map(create_call_completion);
+        .map(|na| create_call_completion(sema, na, def_map.get_type(na)));
     acc.extend(completions);
     Some(())
 }
@@ -93,9 +96,9 @@ pub(crate) fn add_local(
     let completions = def_map
         .get_types()
         .into_iter()
-        .filter_map(|(name_arity, _)| {
+        .filter_map(|(name_arity, def)| {
             if name_arity.name().starts_with(prefix) {
-                Some(create_call_completion(name_arity))
+                Some(create_call_completion(sema, name_arity, Some(def)))
             } else {
                 None
             }
@@ -104,15 +107,24 @@ pub(crate) fn add_local(
     false
 }
 
-fn create_call_completion(name_arity: &NameArity) -> Completion {
+fn create_call_completion(
+    sema: &Semantic,
+    name_arity: &NameArity,
+    def: Option<&TypeAliasDef>,
+) -> Completion {
     let contents = helpers::format_call(name_arity.name(), name_arity.arity());
+    let position = def.map(|def| FilePosition {
+        file_id: def.file.file_id,
+        offset: def.source(sema.db.upcast()).syntax().text_range().start(),
+    });
     Completion {
         label: name_arity.to_string(),
         kind: Kind::Type,
         contents,
-        position: None,
+        position,
         sort_text: None,
         deprecated: false,
+        detail: None,
     }
 }
 
@@ -150,10 +162,10 @@ mod test {
         "#,
             None,
             expect![[r#"
-                {label:alias/0, kind:Type, contents:Snippet("alias()"), position:None}
-                {label:alias/1, kind:Type, contents:Snippet("alias(${1:Arg1})"), position:None}
-                {label:alias_opaque/0, kind:Type, contents:Snippet("alias_opaque()"), position:None}
-                {label:alias_opaque/1, kind:Type, contents:Snippet("alias_opaque(${1:Arg1})"), position:None}
+                {label:alias/0, kind:Type, contents:Snippet("alias()"), position:Some(FilePosition { file_id: FileId(0), offset: 17 })}
+                {label:alias/1, kind:Type, contents:Snippet("alias(${1:Arg1})"), position:Some(FilePosition { file_id: FileId(0), offset: 38 })}
+                {label:alias_opaque/0, kind:Type, contents:Snippet("alias_opaque()"), position:Some(FilePosition { file_id: FileId(0), offset: 59 })}
+                {label:alias_opaque/1, kind:Type, contents:Snippet("alias_opaque(${1:Arg1})"), position:Some(FilePosition { file_id: FileId(0), offset: 93 })}
                 {label:another_module, kind:Module, contents:SameAsLabel, position:None}"#]],
         );
     }
@@ -181,9 +193,9 @@ mod test {
         "#,
             Some(':'),
             expect![[r#"
-                {label:alias2/0, kind:Type, contents:Snippet("alias2()"), position:None}
-                {label:alias_opaque2/0, kind:Type, contents:Snippet("alias_opaque2()"), position:None}
-                {label:alias_opaque2/1, kind:Type, contents:Snippet("alias_opaque2(${1:Arg1})"), position:None}"#]],
+                {label:alias2/0, kind:Type, contents:Snippet("alias2()"), position:Some(FilePosition { file_id: FileId(1), offset: 86 })}
+                {label:alias_opaque2/0, kind:Type, contents:Snippet("alias_opaque2()"), position:Some(FilePosition { file_id: FileId(1), offset: 108 })}
+                {label:alias_opaque2/1, kind:Type, contents:Snippet("alias_opaque2(${1:Arg1})"), position:Some(FilePosition { file_id: FileId(1), offset: 143 })}"#]],
         );
 
         check(
@@ -205,9 +217,9 @@ mod test {
         "#,
             None,
             expect![[r#"
-                {label:alias2/0, kind:Type, contents:Snippet("alias2()"), position:None}
-                {label:alias_opaque2/0, kind:Type, contents:Snippet("alias_opaque2()"), position:None}
-                {label:alias_opaque2/1, kind:Type, contents:Snippet("alias_opaque2(${1:Arg1})"), position:None}"#]],
+                {label:alias2/0, kind:Type, contents:Snippet("alias2()"), position:Some(FilePosition { file_id: FileId(1), offset: 86 })}
+                {label:alias_opaque2/0, kind:Type, contents:Snippet("alias_opaque2()"), position:Some(FilePosition { file_id: FileId(1), offset: 108 })}
+                {label:alias_opaque2/1, kind:Type, contents:Snippet("alias_opaque2(${1:Arg1})"), position:Some(FilePosition { file_id: FileId(1), offset: 143 })}"#]],
         );
 
         check(
@@ -223,7 +235,7 @@ mod test {
         "#,
             None,
             expect![[
-                r#"{label:alias2/0, kind:Type, contents:Snippet("alias2()"), position:None}"#
+                r#"{label:alias2/0, kind:Type, contents:Snippet("alias2()"), position:Some(FilePosition { file_id: FileId(1), offset: 44 })}"#
             ]],
         );
     }
@@ -59,6 +59,9 @@ pub enum ReferenceClass {
     MultiVar(Vec<VarDef>),
     /// An arity-less reference to a macro, can refer to multiple definitions
     MultiMacro(Vec<DefineDef>),
+    /// An arity-less reference to a function, such as `mod:fun` as a
+    /// value, can refer to all its arities
+    MultiFunction(Vec<FunctionDef>),
 }
 
 impl ReferenceClass {
@@ -68,9 +71,12 @@ impl ReferenceClass {
             ReferenceClass::MultiVar(vars) => {
                 Either::Right(Either::Left(vars.into_iter().map(SymbolDefinition::Var)))
             }
-            ReferenceClass::MultiMacro(defs) => Either::Right(Either::Right(
+            ReferenceClass::MultiMacro(defs) => Either::Right(Either::Right(Either::Left(
                 defs.into_iter().map(SymbolDefinition::Define),
-            )),
+            ))),
+            ReferenceClass::MultiFunction(defs) => Either::Right(Either::Right(Either::Right(
+                defs.into_iter().map(SymbolDefinition::Function),
+            ))),
         }
     }
 }
@@ -193,7 +199,8 @@ impl SymbolClass {
                     if let Some(call) = sema.to_def(token.with_value(&remote)) {
                         reference_direct(Some(call))
                     } else {
-                        classify_var(sema, token.file_id, wrapper)
+                        classify_remote_ref(sema, token.with_value(&remote))
+                            .or_else(|| classify_var(sema, token.file_id, wrapper))
                     }
                 },
                 ast::Call(call) => {
@@ -214,7 +221,8 @@ impl SymbolClass {
                         .or_else(|| from_wrapper(sema, &token, wrapper))
                 },
                 _ => {
-                    from_wrapper(sema, &token, wrapper)
+                    classify_dialyzer_function(sema, &token, &wrapper, &parent)
+                        .or_else(|| from_wrapper(sema, &token, wrapper))
                 }
             }
         }
@@ -358,6 +366,18 @@ fn classify_var(sema: &Semantic, file_id: FileId, wrapper: SyntaxNode) -> Option
     }
 }
 
+/// The name of `foo/1` or `{foo, 1}` in a `-dialyzer` attribute
+fn classify_dialyzer_function(
+    sema: &Semantic,
+    token: &InFile<SyntaxToken>,
+    wrapper: &SyntaxNode,
+    parent: &SyntaxNode,
+) -> Option<SymbolClass> {
+    ast::Atom::cast(wrapper.clone())?;
+    let fa = ast::Expr::cast(parent.clone())?;
+    reference_direct(sema.resolve_dialyzer_function(token.with_value(&fa)))
+}
+
 fn classify_macro_name(
     sema: &Semantic,
     file_id: FileId,
@@ -378,6 +398,22 @@ fn classify_macro_name(
     }
 }
 
+/// `mod:fun` as a value rather than as the target of a call
+fn classify_remote_ref(sema: &Semantic, remote: InFile<&ast::Remote>) -> Option<SymbolClass> {
+    let mut defs = sema.resolve_remote_ref(remote)?;
+    if defs.len() == 1 {
+        Some(SymbolClass::Reference {
+            refs: ReferenceClass::Definition(SymbolDefinition::Function(defs.swap_remove(0))),
+            typ: ReferenceType::Direct,
+        })
+    } else {
+        Some(SymbolClass::Reference {
+            refs: ReferenceClass::MultiFunction(defs),
+            typ: ReferenceType::Direct,
+        })
+    }
+}
+
 fn reference_direct<Def: Into<SymbolDefinition>>(def: Option<Def>) -> Option<SymbolClass> {
     def.map(|def| SymbolClass::Reference {
         refs: ReferenceClass::Definition(def.into()),
@@ -129,7 +129,21 @@ impl ToDoc for InFile<&ast::Remote> {
                 CallDef::Type(_) => None,
             }
         } else {
-            None
+            // `mod:fun` as a value, documented by every arity it refers to
+            let function_docs: Vec<_> = docs
+                .sema
+                .resolve_remote_ref(ast)?
+                .into_iter()
+                .filter_map(|fun_def| {
+                    docs.function_doc(fun_def.file.file_id, fun_def.function.name)
+                })
+                .map(|doc| doc.markdown_text)
+                .collect();
+            if function_docs.is_empty() {
+                None
+            } else {
+                Some(Doc::new(function_docs.join("\n\n")))
+            }
         }
     }
 }
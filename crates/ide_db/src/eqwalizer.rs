@@ -19,6 +19,7 @@ use elp_base_db::SourceDatabase;
 use elp_base_db::SourceRootId;
 use elp_eqwalizer::ast::db::EqwalizerASTDatabase;
 use elp_eqwalizer::ast::db::EqwalizerErlASTStorage;
+use elp_eqwalizer::ast::report::ModuleReport;
 use elp_eqwalizer::ast::Error;
 use elp_eqwalizer::ipc::IpcHandle;
 use elp_eqwalizer::EqwalizerDiagnostics;
@@ -73,6 +74,8 @@ pub trait EqwalizerDatabase:
         project_id: ProjectId,
         file_id: FileId,
     ) -> Option<Arc<EqwalizerStats>>;
+    fn eqwalizer_report(&self, project_id: ProjectId, file_id: FileId)
+        -> Option<Arc<ModuleReport>>;
     fn has_eqwalizer_app_marker(&self, source_root_id: SourceRootId) -> bool;
     fn has_eqwalizer_module_marker(&self, file_id: FileId) -> bool;
     fn has_eqwalizer_ignore_marker(&self, file_id: FileId) -> bool;
@@ -106,6 +109,16 @@ fn eqwalizer_stats(
     db.compute_eqwalizer_stats(project_id, ModuleName::new(module_name))
 }
 
+fn eqwalizer_report(
+    db: &dyn EqwalizerDatabase,
+    project_id: ProjectId,
+    file_id: FileId,
+) -> Option<Arc<ModuleReport>> {
+    let module_index = db.module_index(project_id);
+    let module_name: &str = module_index.module_for_file(file_id)?.as_str();
+    Some(db.module_report(project_id, ModuleName::new(module_name)))
+}
+
 fn is_eqwalizer_enabled(
     db: &dyn EqwalizerDatabase,
     file_id: FileId,
@@ -0,0 +1,174 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Classifies a position by its syntactic context, for providers which
+//! behave differently in patterns, guards, types and so on.
+
+use elp_base_db::FilePosition;
+use elp_base_db::SourceDatabase;
+use elp_syntax::ast;
+use elp_syntax::match_ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+
+use crate::RootDatabase;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExprContext {
+    Expression,
+    Pattern,
+    Guard,
+    /// Type definitions and record field types
+    Type,
+    /// Specs and callbacks
+    Spec,
+    /// Any form other than a function, type, spec or callback, including
+    /// record and macro definitions
+    Attribute,
+    Comment,
+    /// An argument of a macro call, which could expand into any context
+    MacroArg,
+}
+
+/// The context of the token at `position`, computed from its syntax
+/// ancestry and the kind of its form. Returns `None` outside of any form.
+pub fn context_at(db: &RootDatabase, position: FilePosition) -> Option<ExprContext> {
+    let source_file = db.parse(position.file_id).tree();
+    let tokens = source_file.syntax().token_at_offset(position.offset);
+    let token = tokens
+        .clone()
+        .left_biased()
+        .or_else(|| tokens.right_biased())?;
+    if token.kind() == SyntaxKind::COMMENT {
+        return Some(ExprContext::Comment);
+    }
+    let ancestors: Vec<SyntaxNode> = token
+        .parent_ancestors()
+        .take_while(|node| node.kind() != SyntaxKind::SOURCE_FILE)
+        .collect();
+    if ancestors
+        .iter()
+        .any(|node| ast::MacroCallArgs::can_cast(node.kind()))
+    {
+        return Some(ExprContext::MacroArg);
+    }
+    for node in &ancestors {
+        if ast::Guard::can_cast(node.kind()) {
+            return Some(ExprContext::Guard);
+        }
+        if is_pattern(node) {
+            return Some(ExprContext::Pattern);
+        }
+        let context = match_ast! {
+            match node {
+                ast::FieldType(_) => ExprContext::Type,
+                ast::TypeAlias(_) => ExprContext::Type,
+                ast::Opaque(_) => ExprContext::Type,
+                ast::Spec(_) => ExprContext::Spec,
+                ast::Callback(_) => ExprContext::Spec,
+                // Record field defaults
+                ast::FieldExpr(_) => ExprContext::Expression,
+                ast::FunDecl(_) => ExprContext::Expression,
+                _ => continue,
+            }
+        };
+        return Some(context);
+    }
+    // The outermost ancestor is the form
+    ancestors.last().map(|_| ExprContext::Attribute)
+}
+
+/// Whether `node` is the pattern of a clause, match or generator
+fn is_pattern(node: &SyntaxNode) -> bool {
+    let parent = match node.parent() {
+        Some(parent) => parent,
+        None => return false,
+    };
+    let is_node = |it: Option<&SyntaxNode>| it == Some(node);
+    match_ast! {
+        match parent {
+            ast::FunctionClause(clause) => is_node(clause.args().as_ref().map(|it| it.syntax())),
+            ast::FunClause(clause) => is_node(clause.args().as_ref().map(|it| it.syntax())),
+            ast::CrClause(clause) => is_node(clause.pat().as_ref().map(|it| it.syntax())),
+            ast::CatchClause(clause) => is_node(clause.pat().as_ref().map(|it| it.syntax())),
+            ast::MatchExpr(expr) => is_node(expr.lhs().as_ref().map(|it| it.syntax())),
+            ast::Generator(generator) => is_node(generator.lhs().as_ref().map(|it| it.syntax())),
+            ast::BGenerator(generator) => is_node(generator.lhs().as_ref().map(|it| it.syntax())),
+            ast::MapGenerator(generator) => is_node(generator.lhs().as_ref().map(|it| it.syntax())),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+
+    use super::*;
+
+    #[track_caller]
+    fn check(fixture: &str, expected: Option<ExprContext>) {
+        let (db, position) = RootDatabase::with_position(fixture);
+        assert_eq!(context_at(&db, position), expected);
+    }
+
+    #[test]
+    fn function_contexts() {
+        check("-module(main).\nfoo(~X) -> X.", Some(ExprContext::Pattern));
+        check(
+            "-module(main).\nfoo(X) when is_atom(~X) -> X.",
+            Some(ExprContext::Guard),
+        );
+        check(
+            "-module(main).\nfoo(X) -> {ok, ~Y} = X, Y.",
+            Some(ExprContext::Pattern),
+        );
+        check(
+            "-module(main).\nfoo(X) -> [Y || {~Y} <- X].",
+            Some(ExprContext::Pattern),
+        );
+        check(
+            "-module(main).\nfoo(X) -> bar(~X).",
+            Some(ExprContext::Expression),
+        );
+        check(
+            "-module(main).\nfoo(X) -> ?assert(~X).",
+            Some(ExprContext::MacroArg),
+        );
+        check(
+            "-module(main).\nfoo(X) -> % a ~comment\n  X.",
+            Some(ExprContext::Comment),
+        );
+    }
+
+    #[test]
+    fn form_contexts() {
+        check(
+            "-module(main).\n-spec foo(~atom()) -> ok.",
+            Some(ExprContext::Spec),
+        );
+        check(
+            "-module(main).\n-type t() :: ~atom().",
+            Some(ExprContext::Type),
+        );
+        check(
+            "-module(main).\n-record(r, {a = ~1 :: integer()}).",
+            Some(ExprContext::Expression),
+        );
+        check(
+            "-module(main).\n-record(r, {a = 1 :: ~integer()}).",
+            Some(ExprContext::Type),
+        );
+        check(
+            "-module(main).\n-export([~foo/0]).",
+            Some(ExprContext::Attribute),
+        );
+    }
+}
@@ -9,9 +9,25 @@
 
 //! A module with ide helpers for high-level ide features.
 
+use std::iter;
+
+use elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::Direction;
+use elp_syntax::NodeOrToken;
+use elp_syntax::SyntaxElement;
 use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
 use elp_syntax::SyntaxToken;
+use elp_syntax::TextSize;
 use elp_syntax::TokenAtOffset;
+use hir::FunctionDef;
+use hir::NameArity;
+use hir::Semantic;
+
+use crate::source_change::SourceChange;
+use crate::source_change::SourceChangeBuilder;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SnippetCap {
@@ -35,3 +51,331 @@ pub fn pick_best_token(
 ) -> Option<SyntaxToken> {
     tokens.max_by_key(move |t| f(t.kind()))
 }
+
+/// The minimal edit exporting `def`, or `None` if it is already exported.
+///
+/// The function is added to the `-export` attribute listing a function
+/// with the same name, or else the one listing the closest exported
+/// function defined before it, or else the last one in the module. If the
+/// module has no `-export` attribute, one is added after the module
+/// attribute.
+pub fn add_export(sema: &Semantic, def: &FunctionDef) -> Option<SourceChange> {
+    if def.exported {
+        return None;
+    }
+    let file_id = def.file.file_id;
+    let form_list = sema.db.file_form_list(file_id);
+    let name = &def.function.name;
+
+    let exported = |export: &hir::Export| {
+        export
+            .entries
+            .clone()
+            .into_iter()
+            .map(|fa| form_list[fa].name.clone())
+            .collect::<Vec<_>>()
+    };
+    let same_name = form_list
+        .exports()
+        .flat_map(|(_, export)| exported(export))
+        .find(|fa| fa.name() == name.name());
+    let start = def.source(sema.db.upcast()).syntax().text_range().start();
+    let preceding = || {
+        sema.def_map(file_id)
+            .get_functions()
+            .values()
+            .filter(|other| other.exported && other.file.file_id == file_id)
+            .map(|other| {
+                let range = other.source(sema.db.upcast()).syntax().text_range();
+                (range.start(), other.function.name.clone())
+            })
+            .filter(|(other_start, _)| *other_start < start)
+            .max_by_key(|(other_start, _)| *other_start)
+            .map(|(_, other)| other)
+    };
+    let last = || {
+        form_list
+            .exports()
+            .last()
+            .and_then(|(_, export)| exported(export).pop())
+    };
+
+    let funs = [name.clone()];
+    let mut builder = SourceChangeBuilder::new(file_id);
+    let mut export_builder = ExportBuilder::new(sema, file_id, &funs, &mut builder);
+    if let Some(group_with) = same_name.or_else(preceding).or_else(last) {
+        export_builder = export_builder.group_with(group_with);
+    }
+    export_builder.finish();
+    Some(builder.finish())
+}
+
+// ---------------------------------------------------------------------
+
+pub struct ExportBuilder<'a> {
+    sema: &'a Semantic<'a>,
+    file_id: FileId,
+    funs: &'a [NameArity],
+    // `group_with`: Add `funs` to the same export as this, if found.
+    // If it is added to the existing export, the comment is not used.
+    group_with: Option<NameArity>,
+    insert_at: Option<TextSize>,
+    with_comment: Option<String>,
+    builder: &'a mut SourceChangeBuilder,
+}
+
+impl<'a> ExportBuilder<'a> {
+    pub fn new(
+        sema: &'a Semantic<'a>,
+        file_id: FileId,
+        funs: &'a [NameArity],
+        builder: &'a mut SourceChangeBuilder,
+    ) -> ExportBuilder<'a> {
+        ExportBuilder {
+            sema,
+            file_id,
+            funs,
+            group_with: None,
+            insert_at: None,
+            with_comment: None,
+            builder,
+        }
+    }
+
+    pub fn group_with(mut self, name: NameArity) -> ExportBuilder<'a> {
+        self.group_with = Some(name);
+        self
+    }
+
+    pub fn insert_at(mut self, location: TextSize) -> ExportBuilder<'a> {
+        self.insert_at = Some(location);
+        self
+    }
+
+    pub fn with_comment(mut self, comment: String) -> ExportBuilder<'a> {
+        self.with_comment = Some(comment);
+        self
+    }
+
+    pub fn finish(&mut self) {
+        let source = self.sema.parse(self.file_id).value;
+        let form_list = self.sema.db.file_form_list(self.file_id);
+        let export_text = self
+            .funs
+            .iter()
+            .map(|function_name_arity| format!("{function_name_arity}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let (insert, text) = if form_list.exports().count() == 0 {
+            self.new_export(form_list, source, export_text)
+        } else {
+            // Top priority: group_with
+            if let Some(group_with) = &self.group_with {
+                if let Some((insert, text)) = || -> Option<_> {
+                    let (_, export) = form_list.exports().find(|(_, e)| {
+                        e.entries
+                            .clone()
+                            .into_iter()
+                            .any(|fa| &form_list[fa].name == group_with)
+                    })?;
+                    add_to_export(&export.form_id.get(&source), &export_text)
+                }() {
+                    (insert, text)
+                } else {
+                    self.new_export(form_list, source, export_text)
+                }
+            } else {
+                if self.with_comment.is_some() {
+                    // Preceding comment for export, always make a fresh one
+                    self.new_export(form_list, source, export_text)
+                } else {
+                    if let Some((insert, text)) = || -> Option<_> {
+                        if form_list.exports().count() == 1 {
+                            // One existing export, add the function to it.
+
+                            let (_, export) = form_list.exports().next()?;
+                            add_to_export(&export.form_id.get(&source), &export_text)
+                        } else {
+                            // Multiple
+                            None
+                        }
+                    }() {
+                        (insert, text)
+                    } else {
+                        // Zero or multiple existing exports, create a fresh one
+                        self.new_export(form_list, source, export_text)
+                    }
+                }
+            }
+        };
+
+        self.builder.edit_file(self.file_id);
+        self.builder.insert(insert, text)
+    }
+
+    fn new_export(
+        &self,
+        form_list: std::sync::Arc<hir::FormList>,
+        source: elp_syntax::SourceFile,
+        export_text: String,
+    ) -> (TextSize, String) {
+        let insert = self.insert_at.unwrap_or_else(|| {
+            if let Some(module_attr) = form_list.module_attribute() {
+                let module_attr_range = module_attr.form_id.get(&source).syntax().text_range();
+                TextSize::from(module_attr_range.end() + TextSize::from(1))
+            } else {
+                TextSize::from(0)
+            }
+        });
+        match &self.with_comment {
+            Some(comment) => (
+                insert,
+                format!("\n%% {comment}\n-export([{export_text}]).\n"),
+            ),
+            None => (insert, format!("\n-export([{export_text}]).\n")),
+        }
+    }
+}
+
+/// Keeps the layout of the attribute: entries on separate lines get a new
+/// line, and a trailing comma stays trailing.
+fn add_to_export(export: &ast::ExportAttribute, export_text: &str) -> Option<(TextSize, String)> {
+    let last = match export.funs().last() {
+        Some(last) => last,
+        None => {
+            // Empty export list
+            let lbrack = export
+                .syntax()
+                .children_with_tokens()
+                .find(|it| it.kind() == SyntaxKind::ANON_LBRACK)?;
+            return Some((lbrack.text_range().end(), export_text.to_string()));
+        }
+    };
+    let separator = match line_indent(last.syntax()) {
+        Some(indent) => format!("\n{indent}"),
+        None => " ".to_string(),
+    };
+    let trailing_comma = last
+        .syntax()
+        .siblings_with_tokens(Direction::Next)
+        .skip(1)
+        .find(|it| !is_trivia(it))
+        .filter(|it| it.to_string().trim() == ",");
+    match trailing_comma {
+        Some(comma) => Some((
+            comma.text_range().end(),
+            format!("{separator}{export_text},"),
+        )),
+        None => Some((
+            last.syntax().text_range().end(),
+            format!(",{separator}{export_text}"),
+        )),
+    }
+}
+
+/// The indentation of the line `node` is on, if it is not the first line
+/// of its parent.
+fn line_indent(node: &SyntaxNode) -> Option<String> {
+    iter::successors(node.prev_sibling_or_token(), |it| {
+        it.prev_sibling_or_token()
+    })
+    .find_map(|it| match it {
+        NodeOrToken::Token(token) if token.kind() == SyntaxKind::WHITESPACE => {
+            let text = token.text();
+            text.rfind('\n').map(|idx| text[idx + 1..].to_string())
+        }
+        _ => None,
+    })
+}
+
+fn is_trivia(element: &SyntaxElement) -> bool {
+    matches!(element.kind(), SyntaxKind::WHITESPACE | SyntaxKind::COMMENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use elp_base_db::SourceDatabaseExt;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use super::*;
+    use crate::RootDatabase;
+
+    #[track_caller]
+    fn check_add_export(function: &str, fixture: &str, expect: Expect) {
+        let (db, file_id) = RootDatabase::with_single_file(fixture);
+        let sema = Semantic::new(&db);
+        let def_map = sema.def_map(file_id);
+        let def = def_map
+            .get_functions()
+            .values()
+            .find(|def| def.function.name.to_string() == function)
+            .unwrap();
+        let actual = match add_export(&sema, def) {
+            Some(change) => {
+                let mut text = db.file_text(file_id).to_string();
+                change.get_source_edit(file_id).unwrap().apply(&mut text);
+                text
+            }
+            None => "no change".to_string(),
+        };
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn add_export_without_existing_export() {
+        check_add_export(
+            "baz/0",
+            r#"
+-module(main).
+baz() -> ok.
+"#,
+            expect![[r#"
+
+                -module(main).
+
+                -export([baz/0]).
+                baz() -> ok.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn add_export_already_exported() {
+        check_add_export(
+            "baz/0",
+            r#"
+-module(main).
+-export([baz/0]).
+baz() -> ok.
+"#,
+            expect![[r#"no change"#]],
+        );
+    }
+
+    #[test]
+    fn add_export_after_preceding_function() {
+        check_add_export(
+            "bar/0",
+            r#"
+-module(main).
+-export([foo/0]).
+-export([baz/0]).
+foo() -> ok.
+bar() -> ok.
+baz() -> ok.
+"#,
+            expect![[r#"
+
+                -module(main).
+                -export([foo/0, bar/0]).
+                -export([baz/0]).
+                foo() -> ok.
+                bar() -> ok.
+                baz() -> ok.
+            "#]],
+        );
+    }
+}
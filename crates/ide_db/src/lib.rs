@@ -45,6 +45,7 @@ mod defs;
 pub mod docs;
 pub mod eqwalizer;
 mod erl_ast;
+mod expr_context;
 mod fixmes;
 mod line_index;
 mod search;
@@ -62,6 +63,7 @@ pub use defs::SymbolClass;
 pub use defs::SymbolDefinition;
 pub use elp_base_db;
 pub use elp_base_db::impl_intern_key;
+pub use elp_eqwalizer::ast::report as eqwalizer_report;
 pub use elp_eqwalizer::Eqwalizer;
 pub use elp_eqwalizer::EqwalizerDiagnostic;
 pub use elp_eqwalizer::EqwalizerDiagnostics;
@@ -69,6 +71,8 @@ pub use elp_eqwalizer::EqwalizerStats;
 pub use elp_erlang_service as erlang_service;
 pub use eqwalizer::EqwalizerDatabase;
 pub use erl_ast::ErlAstDatabase;
+pub use expr_context::context_at;
+pub use expr_context::ExprContext;
 pub use line_index::LineCol;
 pub use line_index::LineIndex;
 pub use search::FindUsages;
@@ -14,6 +14,7 @@
 use std::fmt;
 use std::iter::once;
 
+use elp_base_db::AnchoredPathBuf;
 use elp_base_db::FileId;
 use elp_base_db::FileRange;
 use elp_syntax::ast;
@@ -24,6 +25,7 @@ use hir::Semantic;
 use text_edit::TextEdit;
 
 use crate::search::NameLike;
+use crate::source_change::FileSystemEdit;
 use crate::source_change::SourceChange;
 use crate::SymbolDefinition;
 
@@ -77,6 +79,18 @@ pub fn is_valid_function_name(new_name: String) -> bool {
     }
 }
 
+// Delegate checking name validity to the parser
+pub fn is_valid_module_name(new_name: String) -> bool {
+    let parse = ast::SourceFile::parse_text(format!("-module({}).", new_name).as_str());
+    match parse.tree().forms().next() {
+        Some(ast::Form::ModuleAttribute(attr)) => match attr.name() {
+            Some(ast::Name::Atom(atom)) => atom.syntax().text().to_string() == new_name,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SafetyChecks {
     Yes,
@@ -91,8 +105,23 @@ impl SymbolDefinition {
         safety_check: SafetyChecks,
     ) -> RenameResult<SourceChange> {
         match self.clone() {
-            SymbolDefinition::Module(_) => {
-                rename_error!("Cannot rename module")
+            SymbolDefinition::Module(module) => {
+                if safety_check == SafetyChecks::Yes {
+                    let new_name = get_name(None);
+                    if !is_valid_module_name(new_name.clone()) {
+                        rename_error!("Invalid new module name: '{}'", new_name);
+                    }
+                    if module.is_in_otp(sema.db) {
+                        rename_error!("Cannot rename OTP module '{}'", module.name(sema.db));
+                    }
+                    if sema
+                        .resolve_module_name(module.file.file_id, &new_name)
+                        .is_some()
+                    {
+                        rename_error!("Module '{}' already exists", new_name);
+                    }
+                }
+                self.rename_reference(sema, get_name, safety_check)
             }
             SymbolDefinition::Function(fun) => {
                 if safety_check == SafetyChecks::Yes {
@@ -217,6 +246,32 @@ impl SymbolDefinition {
                 source_edit_from_usages(&mut source_change, usages, get_name);
                 Ok(source_change)
             }
+            SymbolDefinition::Module(module) => {
+                let usages = self.clone().usages(sema).all();
+                let source_file = sema.parse(file_id);
+                let def_usages: Vec<_> = module
+                    .module_attribute(sema.db)
+                    .and_then(|attr| attr.form_id.get(&source_file.value).name())
+                    .map(NameLike::Name)
+                    .into_iter()
+                    .collect();
+                let usages: Vec<_> = usages
+                    .iter()
+                    .chain(once((file_id, &def_usages[..])))
+                    .collect();
+                source_edit_from_usages(&mut source_change, usages, get_name);
+
+                // The file has to follow the module, so that it can be found
+                let new_name = get_name(None);
+                source_change.push_file_system_edit(FileSystemEdit::MoveFile {
+                    src: file_id,
+                    dst: AnchoredPathBuf {
+                        anchor: file_id,
+                        path: format!("{}.erl", new_name.trim_matches('\'')),
+                    },
+                });
+                Ok(source_change)
+            }
             SymbolDefinition::Var(var) => {
                 let usages = sema
                     .find_local_usages(InFile {
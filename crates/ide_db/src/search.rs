@@ -125,6 +125,20 @@ impl SearchScope {
     }
 }
 
+/// All files in the project of `header` that include it, either directly
+/// or through other headers.
+fn includers(db: &dyn MinDefDatabase, header: FileId) -> Vec<FileId> {
+    let project_id = match db.app_data(db.file_source_root(header)) {
+        Some(app_data) => app_data.project_id,
+        None => return Vec::new(),
+    };
+    SearchScope::project(db, project_id)
+        .into_iter()
+        .map(|(file_id, _)| file_id)
+        .filter(|&file_id| db.include_closure(file_id).contains(&header))
+        .collect()
+}
+
 impl IntoIterator for SearchScope {
     type Item = (FileId, Option<TextRange>);
     type IntoIter = std::collections::hash_map::IntoIter<FileId, Option<TextRange>>;
@@ -135,7 +149,9 @@ impl IntoIterator for SearchScope {
 }
 
 impl SymbolDefinition {
-    fn search_scope(&self, sema: &Semantic) -> SearchScope {
+    /// The files, or parts of files, references to the definition can be
+    /// found in
+    pub fn search_scope(&self, sema: &Semantic) -> SearchScope {
         if let SymbolDefinition::Var(var) = self {
             let range = var
                 .source(sema.db.upcast())
@@ -161,9 +177,7 @@ impl SymbolDefinition {
                 FileKind::Header => {
                     let def_map = file.def_map(sema.db);
                     let included = def_map.get_included_files();
-                    let header = SymbolDefinition::Header(file.clone());
-                    let usages = header.usages(sema).all();
-                    let includers = usages.iter().map(|(file_id, _)| file_id);
+                    let includers = includers(sema.db, file.file_id);
                     SearchScope::files(iter::once(file.file_id).chain(included).chain(includers))
                 }
                 FileKind::Other => SearchScope::single_file(self.file().file_id, None),
@@ -32,6 +32,7 @@ use parking_lot::Mutex;
 use paths::AbsPath;
 use paths::AbsPathBuf;
 use paths::RelPath;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde::Serialize;
 use tempfile::NamedTempFile;
@@ -58,6 +59,13 @@ pub const ELP_CONFIG_FILE: &str = ".elp.toml";
 //
 // [eqwalizer]
 // enable_all = true
+//
+// [generated]
+// markers = [ "@generated", "@autogenerated" ]
+// scan_bytes = 4000
+//
+// [escript]
+// modules = true
 //```
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Deserialize)]
 pub struct ElpConfig {
@@ -66,6 +74,10 @@ pub struct ElpConfig {
     pub buck: BuckConfig,
     #[serde(default)]
     pub eqwalizer: EqwalizerConfig,
+    #[serde(default)]
+    pub generated: GeneratedConfig,
+    #[serde(default)]
+    pub escript: EscriptConfig,
 }
 
 impl ElpConfig {
@@ -168,6 +180,80 @@ pub struct EqwalizerConfig {
     pub enable_all: bool,
 }
 
+/// How files produced by code generators are recognised: a file is
+/// generated if one of the markers occurs in its first `scan_bytes` bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Deserialize)]
+pub struct GeneratedConfig {
+    #[serde(default = "GeneratedConfig::default_markers")]
+    pub markers: Vec<String>,
+    #[serde(default = "GeneratedConfig::default_scan_bytes")]
+    pub scan_bytes: usize,
+}
+
+impl GeneratedConfig {
+    // Split, so that this file is not itself considered generated
+    const DEFAULT_MARKER: &'static str = concat!("@", "generated");
+
+    /// Read the `[generated]` section of the `.elp.toml` in `dir`, if any.
+    pub fn from_project_dir(dir: &AbsPath) -> GeneratedConfig {
+        read_config_section(dir, "generated")
+    }
+
+    fn default_markers() -> Vec<String> {
+        vec![Self::DEFAULT_MARKER.to_string()]
+    }
+
+    fn default_scan_bytes() -> usize {
+        2001
+    }
+}
+
+impl Default for GeneratedConfig {
+    fn default() -> Self {
+        GeneratedConfig {
+            markers: Self::default_markers(),
+            scan_bytes: Self::default_scan_bytes(),
+        }
+    }
+}
+
+/// Whether the escripts of the project applications are indexed as
+/// modules, see `ProjectAppData::escript_modules`.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Ord,
+    PartialOrd,
+    Deserialize,
+    Default
+)]
+pub struct EscriptConfig {
+    #[serde(default)]
+    pub modules: bool,
+}
+
+impl EscriptConfig {
+    /// Read the `[escript]` section of the `.elp.toml` in `dir`, if any.
+    pub fn from_project_dir(dir: &AbsPath) -> EscriptConfig {
+        read_config_section(dir, "escript")
+    }
+}
+
+/// Read a single section of the `.elp.toml` in `dir`, falling back to
+/// its default when the file or the section is missing or invalid.
+/// Unlike `ElpConfig::try_parse` this does not need a `[buck]` section,
+/// so that it also applies to rebar projects.
+fn read_config_section<T: DeserializeOwned + Default>(dir: &AbsPath, section: &str) -> T {
+    fs::read_to_string(dir.join(ELP_CONFIG_FILE))
+        .ok()
+        .and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+        .and_then(|config| config.get(section)?.clone().try_into().ok())
+        .unwrap_or_default()
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct TargetInfo {
     pub targets: FxHashMap<TargetFullName, Target>,
@@ -186,7 +272,10 @@ impl BuckProject {
         config: &ElpConfig,
     ) -> Result<(BuckProject, BuildInfoFile, PathBuf), anyhow::Error> {
         let target_info = load_buck_targets(&config.buck)?;
-        let project_app_data = targets_to_project_data(&target_info.targets);
+        let mut project_app_data = targets_to_project_data(&target_info.targets);
+        for app in &mut project_app_data {
+            app.escript_modules = app.app_type == AppType::App && config.escript.modules;
+        }
         let otp_root = Otp::find_otp()?;
         let build_info_term = build_info(&config.buck, &project_app_data, &otp_root);
         let build_info = save_build_info(build_info_term)?;
@@ -836,6 +925,7 @@ impl From<ProjectAppDataAcc> for ProjectAppData {
                 .collect(),
             macros: acc.macros,
             parse_transforms: vec![],
+            compile_options: vec![],
             abs_src_dirs: acc.abs_src_dirs.into_iter().collect(),
             app_type: acc.app_type.unwrap(),
             // we sort to speed up parse-server
@@ -855,6 +945,9 @@ impl From<ProjectAppDataAcc> for ProjectAppData {
                     len1.cmp(&len2)
                 })
                 .collect(),
+            // Set from the `[escript]` section of the config once loaded
+            escript_modules: false,
+            version: None,
         }
     }
 }
@@ -12,6 +12,8 @@ use std::borrow::Cow;
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
+use std::fs;
+use std::io::Read;
 use std::iter;
 use std::mem;
 use std::ops::Deref;
@@ -25,6 +27,7 @@ use anyhow::bail;
 use anyhow::Context;
 use anyhow::Result;
 use buck::EqwalizerConfig;
+use buck::GeneratedConfig;
 use elp_log::timeit;
 use lazy_static::lazy_static;
 use parking_lot::MutexGuard;
@@ -325,6 +328,14 @@ impl Project {
             ProjectBuildData::Rebar(_) => EqwalizerConfig::default(),
         }
     }
+
+    pub fn generated_config(&self) -> GeneratedConfig {
+        match &self.project_build_data {
+            ProjectBuildData::Buck(buck) => buck.config.generated.clone(),
+            ProjectBuildData::Otp => GeneratedConfig::from_project_dir(&self.otp.lib_dir),
+            ProjectBuildData::Rebar(rebar) => rebar.generated.clone(),
+        }
+    }
 }
 
 impl fmt::Debug for Project {
@@ -375,10 +386,21 @@ pub struct ProjectAppData {
     pub abs_src_dirs: Vec<AbsPathBuf>,
     pub macros: Vec<eetf::Term>,
     pub parse_transforms: Vec<eetf::Term>,
+    /// The `erl_opts` of the application, which apply to each of its
+    /// modules like a `-compile` attribute
+    pub compile_options: Vec<eetf::Term>,
     pub app_type: AppType,
     //list of directories required by module to compile
     //usually includes all dependencies include paths and otp
     pub include_path: Vec<AbsPathBuf>,
+    /// Whether escripts, either `.escript` files or files without an
+    /// extension starting with an escript shebang, are indexed as modules
+    /// named after their file stem. Set by `modules` in the `[escript]`
+    /// section of `.elp.toml`, for the project applications only
+    pub escript_modules: bool,
+    /// The version of the application, as given by the `vsn` of its
+    /// `.app` file or by its versioned directory for OTP applications
+    pub version: Option<String>,
 }
 
 impl ProjectAppData {
@@ -397,16 +419,20 @@ impl ProjectAppData {
             dir,
             macros: vec![],
             parse_transforms: vec![],
+            compile_options: vec![],
             app_type: AppType::App,
             include_path: vec![],
             abs_src_dirs: src_dirs,
+            escript_modules: false,
+            version: None,
         }
     }
 
     pub fn otp_app_data(versioned_name: &str, dir: AbsPathBuf) -> Self {
-        let name = versioned_name
-            .split_once('-')
-            .map_or(versioned_name, |(base, _version)| base);
+        let (name, version) = match versioned_name.split_once('-') {
+            Some((base, version)) => (base, Some(version.to_string())),
+            None => (versioned_name, None),
+        };
         let parent = dir.parent().unwrap_or(dir.as_path()).to_path_buf();
         let src = dir.join(PathBuf::from("src"));
         let include = dir.join("include");
@@ -420,9 +446,12 @@ impl ProjectAppData {
             dir,
             macros: vec![],
             parse_transforms: vec![],
+            compile_options: vec![],
             app_type: AppType::Otp,
             include_path: vec![include, src, parent],
             abs_src_dirs: vec![abs_src_dir],
+            escript_modules: false,
+            version,
         }
     }
 
@@ -445,6 +474,42 @@ impl ProjectAppData {
             .collect()
     }
 
+    /// Files without an extension in the source directories of the
+    /// application that start with an escript shebang. These are not
+    /// picked up when loading the source directories by extension, so
+    /// they are listed separately. Empty unless `escript_modules` is set.
+    pub fn extensionless_escripts(&self) -> Vec<AbsPathBuf> {
+        if !self.escript_modules {
+            return vec![];
+        }
+        let mut escripts = Vec::new();
+        let mut dirs = self.all_source_dirs();
+        while let Some(dir) = dirs.pop() {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for entry in entries.flatten() {
+                let path = dir.join(entry.file_name());
+                match entry.file_type() {
+                    Ok(file_type) if file_type.is_dir() => dirs.push(path),
+                    Ok(file_type) if file_type.is_file() && path.extension().is_none() => {
+                        let mut header = Vec::new();
+                        let read = fs::File::open(&path)
+                            .and_then(|file| file.take(256).read_to_end(&mut header));
+                        if read.is_ok() && is_escript_shebang(&header) {
+                            escripts.push(path);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        escripts.sort();
+        escripts.dedup();
+        escripts
+    }
+
     /// Combine the info from the other ProjectAppData into this one
     pub fn combine(&mut self, other: ProjectAppData) {
         self.abs_src_dirs.extend(other.abs_src_dirs);
@@ -457,6 +522,12 @@ impl ProjectAppData {
         self.macros.dedup();
         self.parse_transforms.extend(other.parse_transforms);
         self.parse_transforms.dedup();
+        self.compile_options.extend(other.compile_options);
+        self.compile_options.dedup();
+        self.escript_modules |= other.escript_modules;
+        if self.version.is_none() {
+            self.version = other.version;
+        }
     }
 }
 
@@ -537,6 +608,16 @@ impl Project {
     }
 }
 
+/// Whether the first line of `text` is a shebang running `escript`, as
+/// in `#!/usr/bin/env escript`.
+pub fn is_escript_shebang(text: &[u8]) -> bool {
+    let first_line = text.split(|byte| *byte == b'\n').next().unwrap_or_default();
+    first_line.starts_with(b"#!")
+        && first_line
+            .split(|byte| *byte == b'/' || byte.is_ascii_whitespace())
+            .any(|word| word == b"escript")
+}
+
 fn path_ends_with(path: &AbsPath, ending: impl AsRef<Path>) -> bool {
     path.ends_with(paths::RelPath::new_unchecked(ending.as_ref()))
 }
@@ -584,4 +665,34 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn escript_shebang() {
+        assert!(is_escript_shebang(
+            b"#!/usr/bin/env escript\nmain(_) -> ok."
+        ));
+        assert!(is_escript_shebang(b"#!/usr/local/bin/escript"));
+        assert!(!is_escript_shebang(b"#!/bin/sh\nexec escript foo\n"));
+        assert!(!is_escript_shebang(b"%% escript\n"));
+    }
+
+    #[test]
+    fn config_sections_without_buck() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = AbsPathBuf::assert(dir.path().to_path_buf());
+        assert_eq!(
+            GeneratedConfig::from_project_dir(&root),
+            GeneratedConfig::default()
+        );
+
+        fs::write(
+            root.join(buck::ELP_CONFIG_FILE),
+            "[generated]\nmarkers = [\"@autogenerated\"]\n\n[escript]\nmodules = true\n",
+        )
+        .unwrap();
+        let generated = GeneratedConfig::from_project_dir(&root);
+        assert_eq!(generated.markers, vec!["@autogenerated".to_string()]);
+        assert_eq!(generated.scan_bytes, GeneratedConfig::default().scan_bytes);
+        assert!(buck::EscriptConfig::from_project_dir(&root).modules);
+    }
 }
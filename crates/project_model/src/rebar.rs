@@ -25,6 +25,8 @@ use parking_lot::Mutex;
 use paths::AbsPath;
 use paths::AbsPathBuf;
 
+use crate::buck::EscriptConfig;
+use crate::buck::GeneratedConfig;
 use crate::AppName;
 use crate::AppType;
 use crate::CommandProxy;
@@ -36,6 +38,9 @@ pub struct RebarProject {
     pub deps: Vec<ProjectAppData>,
     pub root: AbsPathBuf,
     pub(crate) rebar_config: RebarConfig,
+    /// The `[generated]` section of the `.elp.toml` next to the rebar
+    /// config, if any
+    pub generated: GeneratedConfig,
 }
 
 /// corresponds to rebar profile
@@ -125,6 +130,7 @@ impl RebarProject {
             deps,
             root,
             rebar_config,
+            generated: GeneratedConfig::default(),
         }
     }
 
@@ -142,23 +148,33 @@ impl RebarProject {
         let data = fs::read(path)?;
         let build_info = eetf::Term::decode(&*data)?;
         let otp_root = to_abs_path(map_get(&build_info, "otp_lib_dir")?)?;
+        let (escript, generated) = match rebar_config.config_file.parent() {
+            Some(dir) => (
+                EscriptConfig::from_project_dir(dir),
+                GeneratedConfig::from_project_dir(dir),
+            ),
+            None => Default::default(),
+        };
 
         let apps = to_vec(map_get(&build_info, "apps")?)?
             .iter()
-            .map(|term| to_app_data(term, AppType::App))
+            .map(|term| to_app_data(term, AppType::App, escript.modules))
             .collect::<Result<_>>()?;
         let deps = to_vec(map_get(&build_info, "deps")?)?
             .iter()
-            .map(|term| to_app_data(term, AppType::Dep))
+            .map(|term| to_app_data(term, AppType::Dep, false))
             .collect::<Result<_>>()?;
         let root = to_abs_path(map_get(&build_info, "source_root")?)?;
 
-        return Ok((
-            RebarProject::new(apps, deps, root, rebar_config, &otp_root),
-            otp_root.into(),
-        ));
+        let mut project = RebarProject::new(apps, deps, root, rebar_config, &otp_root);
+        project.generated = generated;
+        return Ok((project, otp_root.into()));
 
-        fn to_app_data(term: &eetf::Term, is_dep: AppType) -> Result<ProjectAppData> {
+        fn to_app_data(
+            term: &eetf::Term,
+            is_dep: AppType,
+            escript_modules: bool,
+        ) -> Result<ProjectAppData> {
             let dir = to_abs_path(map_get(term, "dir")?)?;
             let src_dirs: Vec<String> = to_vec(map_get(term, "src_dirs")?)?
                 .iter()
@@ -179,9 +195,19 @@ impl RebarProject {
                     .collect::<Result<_>>()?,
                 macros: to_vec(map_get(term, "macros")?)?.to_owned(),
                 parse_transforms: to_vec(map_get(term, "parse_transforms")?)?.to_owned(),
+                // Only given by recent versions of the build_info plugin
+                compile_options: match map_get(term, "erl_opts") {
+                    Ok(erl_opts) => to_vec(erl_opts)?.to_owned(),
+                    Err(_) => vec![],
+                },
                 app_type: is_dep,
                 include_path: vec![],
                 abs_src_dirs,
+                escript_modules,
+                version: map_get(term, "vsn")
+                    .ok()
+                    .and_then(|vsn| to_string(vsn).ok())
+                    .map(|vsn| vsn.to_string()),
             })
         }
     }
@@ -208,6 +234,7 @@ impl Default for RebarProject {
             deps: vec![],
             root: AbsPathBuf::assert("/".into()).normalize(),
             rebar_config: Default::default(),
+            generated: Default::default(),
         }
     }
 }
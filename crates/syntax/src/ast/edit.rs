@@ -137,6 +137,27 @@ impl IndentLevel {
     }
 }
 
+/// The leading whitespace of the line `node` starts on, taken verbatim so
+/// that tabs are preserved. Empty for nodes on a line starting at column 0.
+pub fn line_indentation(node: &SyntaxNode) -> String {
+    let mut line = String::new();
+    if let Some(first) = node.first_token() {
+        for token in prev_tokens(first).skip(1) {
+            let text = token.text();
+            match text.rfind('\n') {
+                Some(pos) => {
+                    line.insert_str(0, &text[pos + 1..]);
+                    break;
+                }
+                None => line.insert_str(0, text),
+            }
+        }
+    }
+    line.chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect()
+}
+
 fn prev_tokens(token: SyntaxToken) -> impl Iterator<Item = SyntaxToken> {
     iter::successors(Some(token), |token| token.prev_token())
 }
@@ -178,8 +199,10 @@ impl<N: AstNode + Clone> AstNodeEdit for N {}
 #[cfg(test)]
 mod tests {
 
+    use super::line_indentation;
     use super::AstNodeEdit;
     use super::IndentLevel;
+    use crate::ast;
     use crate::ast::AstNode;
     use crate::SourceFile;
 
@@ -216,4 +239,38 @@ mod tests {
             indented.syntax().to_string().as_str()
         );
     }
+
+    fn clause_indentations(text: &str) -> Vec<String> {
+        let source = SourceFile::parse_text(text);
+        source
+            .tree()
+            .syntax()
+            .descendants()
+            .filter_map(ast::FunctionClause::cast)
+            .map(|clause| line_indentation(clause.syntax()))
+            .collect()
+    }
+
+    #[test]
+    fn test_line_indentation_column_zero() {
+        assert_eq!(vec![""], clause_indentations("foo() -> ok.\n"));
+    }
+
+    #[test]
+    fn test_line_indentation_spaces_and_tabs() {
+        assert_eq!(
+            vec!["  ", "\t"],
+            clause_indentations("  foo() -> ok.\n\tbar() -> ok.\n")
+        );
+    }
+
+    #[test]
+    fn test_line_indentation_mid_line() {
+        // The second clause does not start a line, the indentation is the
+        // one of the line it is on.
+        assert_eq!(
+            vec!["    ", "    "],
+            clause_indentations("    foo(1) -> ok; foo(_) -> error.\n")
+        );
+    }
 }
@@ -199,6 +199,55 @@ pub fn is_erlang_fun(f: &str, a: usize) -> bool {
     }
 }
 
+/// The functions of the `erlang` module which are allowed in guards, as
+/// listed by `erl_internal:guard_bif/2` and `erl_internal:type_test/2`.
+pub fn is_guard_bif(f: &str, a: usize) -> bool {
+    match (f, a) {
+        ("abs", 1) => true,
+        ("binary_part", 2) => true,
+        ("binary_part", 3) => true,
+        ("bit_size", 1) => true,
+        ("byte_size", 1) => true,
+        ("ceil", 1) => true,
+        ("element", 2) => true,
+        ("float", 1) => true,
+        ("floor", 1) => true,
+        ("hd", 1) => true,
+        ("is_map_key", 2) => true,
+        ("length", 1) => true,
+        ("map_get", 2) => true,
+        ("map_size", 1) => true,
+        ("max", 2) => true,
+        ("min", 2) => true,
+        ("node", 0) => true,
+        ("node", 1) => true,
+        ("round", 1) => true,
+        ("self", 0) => true,
+        ("size", 1) => true,
+        ("tl", 1) => true,
+        ("trunc", 1) => true,
+        ("tuple_size", 1) => true,
+        ("is_atom", 1) => true,
+        ("is_binary", 1) => true,
+        ("is_bitstring", 1) => true,
+        ("is_boolean", 1) => true,
+        ("is_float", 1) => true,
+        ("is_function", 1) => true,
+        ("is_function", 2) => true,
+        ("is_integer", 1) => true,
+        ("is_list", 1) => true,
+        ("is_map", 1) => true,
+        ("is_number", 1) => true,
+        ("is_pid", 1) => true,
+        ("is_port", 1) => true,
+        ("is_record", 2) => true,
+        ("is_record", 3) => true,
+        ("is_reference", 1) => true,
+        ("is_tuple", 1) => true,
+        _ => false,
+    }
+}
+
 pub fn is_erlang_type(f: &str, a: usize) -> bool {
     match (f, a) {
         // This part is from https://www.erlang.org/doc/reference_manual/typespec.html
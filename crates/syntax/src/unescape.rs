@@ -97,6 +97,62 @@ pub fn unescape_string(s_in: &str) -> Option<Cow<str>> {
     Some(Cow::Owned(s))
 }
 
+/// Sigil prefixes for string literals, introduced in OTP 27
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sigil {
+    /// `~s"..."`: a string, escapes are processed as for plain strings
+    String,
+    /// `~S"..."`: a string taken verbatim, escapes are not processed
+    Verbatim,
+    /// `~b"..."`: a utf8 binary, escapes are processed as for plain strings
+    Binary,
+    /// `~B"..."` and `~"..."`: a utf8 binary taken verbatim
+    VerbatimBinary,
+}
+
+impl Sigil {
+    pub fn is_binary(&self) -> bool {
+        matches!(self, Sigil::Binary | Sigil::VerbatimBinary)
+    }
+
+    pub fn is_verbatim(&self) -> bool {
+        matches!(self, Sigil::Verbatim | Sigil::VerbatimBinary)
+    }
+}
+
+/// Like `unescape_string`, but also understands an optional sigil
+/// prefix and the delimiters allowed after it, such as `~s(...)`.
+/// Plain strings are returned without a sigil.
+pub fn unescape_sigil_string(s_in: &str) -> Option<(Option<Sigil>, Cow<str>)> {
+    let rest = match s_in.strip_prefix('~') {
+        Some(rest) => rest,
+        None => return unescape_string(s_in).map(|s| (None, s)),
+    };
+    let (sigil, rest) = match rest.chars().next()? {
+        's' => (Sigil::String, &rest[1..]),
+        'S' => (Sigil::Verbatim, &rest[1..]),
+        'b' => (Sigil::Binary, &rest[1..]),
+        'B' => (Sigil::VerbatimBinary, &rest[1..]),
+        _ => (Sigil::VerbatimBinary, rest),
+    };
+    let open = rest.chars().next()?;
+    let close = match open {
+        '(' => ')',
+        '[' => ']',
+        '{' => '}',
+        '<' => '>',
+        '/' | '|' | '\'' | '"' | '`' | '#' => open,
+        _ => return None,
+    };
+    let content = rest.strip_prefix(open)?.strip_suffix(close)?;
+    if sigil.is_verbatim() {
+        Some((Some(sigil), Cow::Borrowed(content)))
+    } else {
+        let value = unescape_string(&format!("\"{}\"", content))?.into_owned();
+        Some((Some(sigil), Cow::Owned(value)))
+    }
+}
+
 fn unescape_octal(c: char, queue: &mut VecDeque<char>) -> Option<char> {
     let mut s = String::new();
     s.push(c);
@@ -172,7 +228,9 @@ fn unescape_control(queue: &mut VecDeque<char>) -> Option<char> {
 mod tests {
     use expect_test::expect;
 
+    use super::unescape_sigil_string;
     use super::unescape_string;
+    use super::Sigil;
 
     #[test]
     fn unescape_string_plain() {
@@ -248,4 +306,66 @@ mod tests {
             &unescape_string(r#"'a\Gb\%cd\'\"\\"#).unwrap()
         );
     }
+
+    #[test]
+    fn unescape_sigil_string_plain() {
+        assert_eq!(
+            Some((None, "a\nb".into())),
+            unescape_sigil_string(r#""a\nb""#)
+        );
+    }
+
+    #[test]
+    fn unescape_sigil_string_verbatim() {
+        assert_eq!(
+            Some((Some(Sigil::VerbatimBinary), r#"a\nb"#.into())),
+            unescape_sigil_string(r#"~"a\nb""#)
+        );
+    }
+
+    #[test]
+    fn unescape_sigil_string_binary() {
+        assert_eq!(
+            Some((Some(Sigil::Binary), "a\nb".into())),
+            unescape_sigil_string(r#"~b"a\nb""#)
+        );
+    }
+
+    #[test]
+    fn unescape_sigil_string_verbatim_binary() {
+        assert_eq!(
+            Some((Some(Sigil::VerbatimBinary), r#"a\nb"#.into())),
+            unescape_sigil_string(r#"~B"a\nb""#)
+        );
+    }
+
+    #[test]
+    fn unescape_sigil_string_string() {
+        assert_eq!(
+            Some((Some(Sigil::String), "a\nb".into())),
+            unescape_sigil_string(r#"~s"a\nb""#)
+        );
+        assert_eq!(
+            Some((Some(Sigil::Verbatim), r#"a\nb"#.into())),
+            unescape_sigil_string(r#"~S"a\nb""#)
+        );
+    }
+
+    #[test]
+    fn unescape_sigil_string_delimiters() {
+        assert_eq!(
+            Some((Some(Sigil::String), "a\"b".into())),
+            unescape_sigil_string(r#"~s(a"b)"#)
+        );
+        assert_eq!(
+            Some((Some(Sigil::Verbatim), "a)b".into())),
+            unescape_sigil_string(r#"~S[a)b]"#)
+        );
+    }
+
+    #[test]
+    fn unescape_sigil_string_unknown() {
+        assert_eq!(None, unescape_sigil_string(r#"~x"a""#));
+        assert_eq!(None, unescape_sigil_string(r#"~s"a)"#));
+    }
 }